@@ -1,5 +1,5 @@
 import * as path from "path";
-import { ExtensionContext, workspace } from "vscode";
+import { commands, ExtensionContext, workspace } from "vscode";
 
 import {
     LanguageClient,
@@ -31,6 +31,16 @@ export function activate(context: ExtensionContext) {
             fileEvents: workspace.createFileSystemWatcher("**"),
         },
         progressOnInitialization: true,
+        initializationOptions: {
+            renderExclude: workspace.getConfiguration("vue-property-decorator").get("render.exclude"),
+            incrementalRender: workspace.getConfiguration("vue-property-decorator").get("render.incremental"),
+            maxFileSize: workspace.getConfiguration("vue-property-decorator").get("render.maxFileSize"),
+            componentDecorators: workspace.getConfiguration("vue-property-decorator").get("render.componentDecorators"),
+            pullDiagnostics: workspace.getConfiguration("vue-property-decorator").get("diagnostics.pull"),
+            cssEnabled: workspace.getConfiguration("vue-property-decorator").get("css.enabled"),
+            tsserverPath: workspace.getConfiguration("vue-property-decorator").get("tsserver.path"),
+            nodePath: workspace.getConfiguration("vue-property-decorator").get("node.path"),
+        },
     };
 
     // Create the language client and start the client.
@@ -41,6 +51,19 @@ export function activate(context: ExtensionContext) {
         clientOptions
     );
 
+    // Convert LSP locations from the server before calling editor.action.showReferences
+    context.subscriptions.push(
+        commands.registerCommand("vue-property-decorator-extension.show.references", (uri: string, position: any, locations: any[]) => {
+            const converter = client.protocol2CodeConverter;
+            return commands.executeCommand(
+                "editor.action.showReferences",
+                converter.asUri(uri),
+                converter.asPosition(position),
+                locations.map((location) => converter.asLocation(location))
+            );
+        })
+    );
+
     // Start the client. This will also launch the server
     client.start();
 }
@@ -59391,6 +59391,10 @@ function doValidateCss(document) {
     const sheet = sheetMap$2.get(document.uri);
     return cssServer.doValidation(document, sheet);
 }
+function getSelectionRangesOnCss(document, positions) {
+    const sheet = sheetMap$2.get(document.uri);
+    return cssServer.getSelectionRanges(document, positions, sheet);
+}
 
 let scssServer = null;
 const sheetMap$1 = new Map();
@@ -59418,6 +59422,10 @@ function doValidateScss(document) {
     const sheet = sheetMap$1.get(document.uri);
     return scssServer.doValidation(document, sheet);
 }
+function getSelectionRangesOnScss(document, positions) {
+    const sheet = sheetMap$1.get(document.uri);
+    return scssServer.getSelectionRanges(document, positions, sheet);
+}
 
 let lessServer = null;
 const sheetMap = new Map();
@@ -59445,6 +59453,10 @@ function doValidateLess(document) {
     const sheet = sheetMap.get(document.uri);
     return lessServer.doValidation(document, sheet);
 }
+function getSelectionRangesOnLess(document, positions) {
+    const sheet = sheetMap.get(document.uri);
+    return lessServer.getSelectionRanges(document, positions, sheet);
+}
 
 // Create a connection for the server, using Node's IPC as a transport.
 // Also include all preview / proposed LSP features.
@@ -59456,6 +59468,7 @@ connection.onInitialize((params) => {
         capabilities: {
             textDocumentSync: nodeExports.TextDocumentSyncKind.Incremental,
             hoverProvider: true,
+            selectionRangeProvider: true,
             // Tell the client that this server supports code completion.
             completionProvider: {
                 resolveProvider: false,
@@ -59584,6 +59597,22 @@ connection.onCompletion((params) => {
     }
     return null;
 });
+connection.onSelectionRanges((params) => {
+    const document = documents.get(params.textDocument.uri);
+    if (!document) {
+        return null;
+    }
+    if (document.languageId === "css") {
+        return getSelectionRangesOnCss(document, params.positions);
+    }
+    else if (document.languageId === "scss") {
+        return getSelectionRangesOnScss(document, params.positions);
+    }
+    else if (document.languageId === "less") {
+        return getSelectionRangesOnLess(document, params.positions);
+    }
+    return null;
+});
 documents.listen(connection);
 connection.listen();
 //# sourceMappingURL=css-lsp-server.js.map
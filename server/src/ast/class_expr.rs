@@ -1,11 +1,18 @@
 use html_languageservice::html_data::Description;
-use swc_common::{comments::Comments, BytePos};
-use swc_ecma_ast::{ClassExpr, Expr};
+use swc_common::{comments::Comments, source_map::SmallPos, BytePos, Spanned};
+use swc_ecma_ast::{
+    BlockStmt, BlockStmtOrExpr, Callee, ClassExpr, Expr, Lit, Module, ObjectLit, Prop, PropName,
+    PropOrSpread, Stmt,
+};
 use tower_lsp::lsp_types::{MarkupContent, MarkupKind};
 
 use crate::renderer::multi_threaded_comment::MultiThreadedComments;
 
-use super::comment::get_markdown;
+use super::{
+    _expr_is_true, comment::get_markdown, get_decorator_args, get_name_form_prop_name,
+    get_name_span_from_prop_name, get_object_props_with_spread, get_super_class_ident,
+    get_value_of_specified_prop, is_component_decorator,
+};
 
 pub fn get_class_expr_pos(class: &ClassExpr) -> BytePos {
     let class = &class.class;
@@ -17,6 +24,26 @@ pub fn get_class_expr_pos(class: &ClassExpr) -> BytePos {
     pos
 }
 
+/// 获取类体左花括号的位置
+///
+/// 从类名、泛型参数、父类和实现的接口中最后结束的位置开始查找
+pub fn get_class_body_start(class: &ClassExpr, source: &str) -> usize {
+    let lo = class
+        .class
+        .decorators
+        .iter()
+        .map(|v| v.span.hi)
+        .chain(class.ident.as_ref().map(|v| v.span.hi))
+        .chain(class.class.type_params.as_ref().map(|v| v.span.hi))
+        .chain(class.class.super_class.as_ref().map(|v| v.span().hi))
+        .chain(class.class.super_type_params.as_ref().map(|v| v.span.hi))
+        .chain(class.class.implements.iter().map(|v| v.span.hi))
+        .max()
+        .unwrap_or(class.class.span.lo)
+        .to_usize();
+    source[lo..].find('{').map_or(lo, |v| lo + v)
+}
+
 pub fn get_class_expr_name(class: &ClassExpr) -> String {
     if let Some(ident) = &class.ident {
         return ident.sym.to_string();
@@ -52,9 +79,250 @@ pub fn get_class_expr_description(
 
 pub fn get_extends_component(class: &ClassExpr) -> Option<String> {
     let supper_class = class.class.super_class.as_ref()?;
-    if let Expr::Ident(ident) = supper_class.as_ref() {
-        Some(ident.sym.to_string())
-    } else {
-        None
+    get_super_class_ident(supper_class).map(|ident| ident.sym.to_string())
+}
+
+/// 获取混入的组件，即 `extends Mixins(A, B)` 中的 `A`, `B`
+pub fn get_mixins_components(class: &ClassExpr) -> Option<Vec<String>> {
+    let supper_class = class.class.super_class.as_ref()?;
+    if let Expr::Call(call) = supper_class.as_ref() {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Expr::Ident(ident) = callee.as_ref() {
+                if ident.sym == "Mixins" {
+                    return Some(
+                        call.args
+                            .iter()
+                            .filter_map(|arg| match arg.expr.as_ref() {
+                                Expr::Ident(ident) => Some(ident.sym.to_string()),
+                                _ => None,
+                            })
+                            .collect(),
+                    );
+                }
+            }
+        }
     }
+    None
+}
+
+/// 获取装饰器参数中混入的组件，即 `@Component({ mixins: [A, B] })` 中的 `A`, `B`
+pub fn get_options_mixins(module: &Module, class: &ClassExpr, names: &[String]) -> Vec<String> {
+    class
+        .class
+        .decorators
+        .iter()
+        .filter(|decorator| is_component_decorator(module, decorator, names))
+        .filter_map(get_decorator_args)
+        .filter_map(|args| args.first())
+        .flat_map(|arg| get_object_props_with_spread(module, &arg.expr))
+        .filter_map(|prop| get_value_of_specified_prop(prop, "mixins"))
+        .filter_map(|value| match value {
+            Expr::Array(array) => Some(array),
+            _ => None,
+        })
+        .flat_map(|array| array.elems.iter().flatten())
+        .filter_map(|elem| match elem.expr.as_ref() {
+            Expr::Ident(ident) => Some(ident.sym.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 是否为函数式组件，即 `@Component({ functional: true })`
+pub fn is_functional_component(module: &Module, class: &ClassExpr, names: &[String]) -> bool {
+    class
+        .class
+        .decorators
+        .iter()
+        .filter(|decorator| is_component_decorator(module, decorator, names))
+        .filter_map(get_decorator_args)
+        .filter_map(|args| args.first())
+        .flat_map(|arg| get_object_props_with_spread(module, &arg.expr))
+        .any(|prop| get_value_of_specified_prop(prop, "functional").is_some_and(_expr_is_true))
+}
+
+/// 获取装饰器参数中的 `model` 选项，即 `@Component({ model: { prop: 'checked', event: 'change' } })`
+/// 返回: (属性名称, 事件名称)，未指定时分别为 `value` 和 `input`
+pub fn get_options_model(
+    module: &Module,
+    class: &ClassExpr,
+    names: &[String],
+) -> Option<(String, String)> {
+    let options = get_component_options(module, class, names);
+    let model = options
+        .iter()
+        .find_map(|prop| get_value_of_specified_prop(prop, "model"))?;
+    let props = get_object_props_with_spread(module, model);
+    let get_str_value = |key: &str| {
+        props
+            .iter()
+            .find_map(|prop| get_value_of_specified_prop(prop, key))
+            .and_then(|value| match value {
+                Expr::Lit(Lit::Str(value)) => Some(value.value.to_string()),
+                _ => None,
+            })
+    };
+    Some((
+        get_str_value("prop").unwrap_or("value".to_string()),
+        get_str_value("event").unwrap_or("input".to_string()),
+    ))
+}
+
+/// 获取装饰器参数中 `provide` 提供的 key 及 key 的范围
+///
+/// 支持 `provide: { foo: 1 }` 和 `provide() { return { foo: 1 } }`
+pub fn get_options_provide_keys(
+    module: &Module,
+    class: &ClassExpr,
+    names: &[String],
+) -> Vec<(String, (usize, usize))> {
+    let mut keys = vec![];
+    for prop in get_component_options(module, class, names) {
+        let PropOrSpread::Prop(prop) = prop else {
+            continue;
+        };
+        let object = match prop.as_ref() {
+            Prop::KeyValue(prop) if get_name_form_prop_name(&prop.key) == "provide" => {
+                match prop.value.as_ref() {
+                    Expr::Object(object) => Some(object),
+                    Expr::Fn(expr) => get_returned_object(expr.function.body.as_ref()),
+                    Expr::Arrow(expr) => match expr.body.as_ref() {
+                        BlockStmtOrExpr::BlockStmt(body) => get_returned_object(Some(body)),
+                        BlockStmtOrExpr::Expr(expr) => match expr.as_ref() {
+                            Expr::Paren(expr) => match expr.expr.as_ref() {
+                                Expr::Object(object) => Some(object),
+                                _ => None,
+                            },
+                            _ => None,
+                        },
+                    },
+                    _ => None,
+                }
+            }
+            Prop::Method(prop) if get_name_form_prop_name(&prop.key) == "provide" => {
+                get_returned_object(prop.function.body.as_ref())
+            }
+            _ => None,
+        };
+        let Some(object) = object else {
+            continue;
+        };
+        for prop in &object.props {
+            let PropOrSpread::Prop(prop) = prop else {
+                continue;
+            };
+            let key = match prop.as_ref() {
+                Prop::KeyValue(prop) => &prop.key,
+                Prop::Method(prop) => &prop.key,
+                Prop::Getter(prop) => &prop.key,
+                Prop::Shorthand(ident) => {
+                    keys.push((
+                        ident.sym.to_string(),
+                        (ident.span.lo.to_usize(), ident.span.hi.to_usize()),
+                    ));
+                    continue;
+                }
+                _ => continue,
+            };
+            if let PropName::Computed(_) = key {
+                continue;
+            }
+            let span = get_name_span_from_prop_name(key);
+            keys.push((
+                get_name_form_prop_name(key),
+                (span.lo.to_usize(), span.hi.to_usize()),
+            ));
+        }
+    }
+    keys
+}
+
+/// 获取装饰器参数中 `inject` 注入的 key 及其在源码中的范围
+///
+/// 支持 `inject: ['foo']`、`inject: { bar: 'foo' }` 和 `inject: { bar: { from: 'foo' } }`，
+/// 数组中的范围为字符串的内容，对象中的范围为属性名，未指定 `from` 时 key 为属性名
+pub fn get_options_inject_keys(
+    module: &Module,
+    class: &ClassExpr,
+    names: &[String],
+) -> Vec<(String, (usize, usize))> {
+    let mut keys = vec![];
+    for value in get_component_options(module, class, names)
+        .into_iter()
+        .filter_map(|prop| get_value_of_specified_prop(prop, "inject"))
+    {
+        match value {
+            Expr::Array(array) => {
+                for elem in array.elems.iter().flatten() {
+                    if let Expr::Lit(Lit::Str(value)) = elem.expr.as_ref() {
+                        keys.push((
+                            value.value.to_string(),
+                            (value.span.lo.to_usize() + 1, value.span.hi.to_usize() - 1),
+                        ));
+                    }
+                }
+            }
+            Expr::Object(object) => {
+                for prop in &object.props {
+                    let PropOrSpread::Prop(prop) = prop else {
+                        continue;
+                    };
+                    let Prop::KeyValue(prop) = prop.as_ref() else {
+                        continue;
+                    };
+                    if let PropName::Computed(_) = prop.key {
+                        continue;
+                    }
+                    let name = get_name_form_prop_name(&prop.key);
+                    let from = match prop.value.as_ref() {
+                        Expr::Lit(Lit::Str(value)) => Some(value.value.to_string()),
+                        Expr::Object(object) => object
+                            .props
+                            .iter()
+                            .filter_map(|prop| get_value_of_specified_prop(prop, "from"))
+                            .find_map(|value| match value {
+                                Expr::Lit(Lit::Str(value)) => Some(value.value.to_string()),
+                                _ => None,
+                            }),
+                        _ => None,
+                    };
+                    let span = get_name_span_from_prop_name(&prop.key);
+                    keys.push((
+                        from.unwrap_or(name),
+                        (span.lo.to_usize(), span.hi.to_usize()),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    keys
+}
+
+/// 获取 `@Component` 装饰器参数对象的属性，展开其中的 `...spread`
+fn get_component_options<'a>(
+    module: &'a Module,
+    class: &'a ClassExpr,
+    names: &[String],
+) -> Vec<&'a PropOrSpread> {
+    class
+        .class
+        .decorators
+        .iter()
+        .filter(|decorator| is_component_decorator(module, decorator, names))
+        .filter_map(get_decorator_args)
+        .filter_map(|args| args.first())
+        .flat_map(|arg| get_object_props_with_spread(module, &arg.expr))
+        .collect()
+}
+
+/// 获取函数体中最后一个 `return` 返回的对象字面量
+fn get_returned_object(body: Option<&BlockStmt>) -> Option<&ObjectLit> {
+    body?.stmts.iter().rev().find_map(|stmt| match stmt {
+        Stmt::Return(stmt) => match stmt.arg.as_deref()? {
+            Expr::Object(object) => Some(object),
+            _ => None,
+        },
+        _ => None,
+    })
 }
@@ -1,15 +1,22 @@
 use html_languageservice::html_data::Description;
-use swc_common::{comments::Comments, source_map::SmallPos, BytePos};
-use swc_ecma_ast::{ClassMember, ClassProp};
+use swc_common::{comments::Comments, source_map::SmallPos, BytePos, Spanned};
+use swc_ecma_ast::{ClassMember, ClassProp, Decorator, MethodKind, Prop, PropOrSpread, TsTypeAnn};
 use tower_lsp::lsp_types::{MarkupContent, MarkupKind};
 
-use crate::renderer::{multi_threaded_comment::MultiThreadedComments, RenderCachePropType};
+use crate::{
+    renderer::{multi_threaded_comment::MultiThreadedComments, RenderCachePropType},
+    util,
+};
 
 use super::{
     comment::get_markdown,
-    decorator::is_specified_decorator,
-    get_class_prop_pos, get_decorator_prop_params,
+    decorator::{get_decorator_args, get_decorator_name, is_specified_decorator},
+    expr::get_object_props,
+    get_class_prop_pos, get_decorator_emit_name, get_decorator_inject_params,
+    get_decorator_model_params, get_decorator_prop_default_type, get_decorator_prop_params,
+    get_decorator_provide_key, get_decorator_ref_name,
     prop_name::{get_name_form_prop_name, get_name_span_from_prop_name},
+    ts_type_ann::is_array_ts_type,
 };
 
 pub fn _filter_specified_prop<'a>(prop: &'a ClassMember, name: &str) -> Option<&'a ClassProp> {
@@ -118,41 +125,259 @@ pub fn get_class_member_name_pos(member: &ClassMember) -> BytePos {
     match member {
         ClassMember::ClassProp(prop) => get_name_span_from_prop_name(&prop.key).lo,
         ClassMember::PrivateProp(prop) => prop.key.span.lo,
+        ClassMember::Method(method) => get_name_span_from_prop_name(&method.key).lo,
+        ClassMember::PrivateMethod(method) => method.key.span.lo,
         _ => BytePos(0),
     }
 }
 
+/// 获取成员中的嵌套结构的范围，包括装饰器以及名称之后的参数、方法体、类型注解和初始值
+pub fn get_class_member_nested_ranges(member: &ClassMember) -> Vec<(usize, usize)> {
+    let span = member.span();
+    let key_hi = match member {
+        ClassMember::ClassProp(prop) => get_name_span_from_prop_name(&prop.key).hi,
+        ClassMember::PrivateProp(prop) => prop.key.span.hi,
+        ClassMember::Method(method) => get_name_span_from_prop_name(&method.key).hi,
+        ClassMember::PrivateMethod(method) => method.key.span.hi,
+        ClassMember::Constructor(constructor) => get_name_span_from_prop_name(&constructor.key).hi,
+        ClassMember::Empty(_) => return vec![],
+        _ => span.lo,
+    };
+    let mut ranges = get_class_member_decorator_list(member)
+        .iter()
+        .map(|v| (v.span.lo.to_usize(), v.span.hi.to_usize()))
+        .collect::<Vec<_>>();
+    if key_hi < span.hi {
+        ranges.push((key_hi.to_usize(), span.hi.to_usize()));
+    }
+    ranges
+}
+
+/// 获取成员上所有装饰器的名称
+pub fn get_class_member_decorators(member: &ClassMember) -> Vec<String> {
+    get_class_member_decorator_list(member)
+        .iter()
+        .filter_map(get_decorator_name)
+        .collect()
+}
+
+/// 获取成员上所有装饰器的范围，没有装饰器时返回 None
+pub fn get_class_member_decorators_range(member: &ClassMember) -> Option<(usize, usize)> {
+    let decorators = get_class_member_decorator_list(member);
+    Some((
+        decorators.first()?.span.lo.to_usize(),
+        decorators.last()?.span.hi.to_usize(),
+    ))
+}
+
+/// 获取 `@Prop`, `@Model` 等装饰器选项对象中的键及其范围
+pub fn get_class_member_decorator_option_keys(
+    member: &ClassMember,
+) -> Vec<(String, (usize, usize))> {
+    let mut keys = vec![];
+    let decorators = get_class_member_decorator_list(member)
+        .iter()
+        .filter(|decorator| {
+            ["Prop", "PropSync", "Model", "ModelSync", "VModel"]
+                .iter()
+                .any(|name| is_specified_decorator(decorator, name))
+        });
+    for decorator in decorators {
+        let Some(args) = get_decorator_args(decorator) else {
+            continue;
+        };
+        for props in args.iter().filter_map(|arg| get_object_props(&arg.expr)) {
+            for prop in props {
+                let PropOrSpread::Prop(prop) = prop else {
+                    continue;
+                };
+                let (name, span) = match prop.as_ref() {
+                    Prop::Shorthand(ident) => (ident.sym.to_string(), ident.span),
+                    Prop::KeyValue(prop) => (
+                        get_name_form_prop_name(&prop.key),
+                        get_name_span_from_prop_name(&prop.key),
+                    ),
+                    Prop::Method(prop) => (
+                        get_name_form_prop_name(&prop.key),
+                        get_name_span_from_prop_name(&prop.key),
+                    ),
+                    _ => continue,
+                };
+                keys.push((name, (span.lo.to_usize(), span.hi.to_usize())));
+            }
+        }
+    }
+    keys
+}
+
+fn get_class_member_decorator_list(member: &ClassMember) -> &[Decorator] {
+    match member {
+        ClassMember::ClassProp(prop) => &prop.decorators,
+        ClassMember::PrivateProp(prop) => &prop.decorators,
+        ClassMember::Method(method) => &method.function.decorators,
+        ClassMember::PrivateMethod(method) => &method.function.decorators,
+        _ => &[],
+    }
+}
+
+/// 属性是否存在初始值
+pub fn has_class_member_value(member: &ClassMember) -> bool {
+    match member {
+        ClassMember::ClassProp(prop) => prop.value.is_some(),
+        ClassMember::PrivateProp(prop) => prop.value.is_some(),
+        _ => false,
+    }
+}
+
 pub fn get_class_member_prop_type(member: &ClassMember) -> RenderCachePropType {
     match member {
-        ClassMember::Method(_) => RenderCachePropType::Method,
-        ClassMember::PrivateMethod(_) => RenderCachePropType::Method,
+        ClassMember::Method(method) => match method.kind {
+            MethodKind::Getter => RenderCachePropType::Computed { writable: false },
+            MethodKind::Setter => RenderCachePropType::Computed { writable: true },
+            MethodKind::Method => RenderCachePropType::Method,
+        },
+        ClassMember::PrivateMethod(method) => match method.kind {
+            MethodKind::Getter => RenderCachePropType::Computed { writable: false },
+            MethodKind::Setter => RenderCachePropType::Computed { writable: true },
+            MethodKind::Method => RenderCachePropType::Method,
+        },
         _ => RenderCachePropType::Property,
     }
 }
 
+/// 获取成员的 TS 类型注解，计算属性使用 getter 的返回值类型
+pub fn get_class_member_type_ann(member: &ClassMember, source: &str) -> Option<String> {
+    match member {
+        ClassMember::ClassProp(prop) => get_type_ann_string(&prop.type_ann, source),
+        ClassMember::PrivateProp(prop) => get_type_ann_string(&prop.type_ann, source),
+        ClassMember::Method(method) if method.kind == MethodKind::Getter => {
+            get_type_ann_string(&method.function.return_type, source)
+        }
+        ClassMember::PrivateMethod(method) if method.kind == MethodKind::Getter => {
+            get_type_ann_string(&method.function.return_type, source)
+        }
+        _ => None,
+    }
+}
+
 /// 获取属性参数
-/// 返回: (typ, default, required)
+/// 返回: (typ, default, required, validator)，typ 为装饰器参数中的 type
 pub fn get_class_member_prop_params(
     member: &ClassMember,
     source: &str,
-) -> Option<(Option<String>, bool, bool)> {
+) -> Option<(Option<String>, bool, bool, bool)> {
+    let decorators = get_class_member_prop_decorators(member)?;
+    if decorators.len() == 1 {
+        get_decorator_prop_params(&decorators[0], source)
+    } else {
+        None
+    }
+}
+
+/// 从 `@Prop` 装饰器参数中的 default 推断属性的 TS 类型
+pub fn get_class_member_prop_default_type(member: &ClassMember, source: &str) -> Option<String> {
+    let decorators = get_class_member_prop_decorators(member)?;
+    if decorators.len() == 1 {
+        get_decorator_prop_default_type(&decorators[0], source)
+    } else {
+        None
+    }
+}
+
+fn get_class_member_prop_decorators(member: &ClassMember) -> Option<&[Decorator]> {
     match member {
-        ClassMember::ClassProp(prop) => {
-            if prop.decorators.len() == 1 {
-                let decorator = &prop.decorators[0];
-                get_decorator_prop_params(decorator, source)
-            } else {
-                None
-            }
-        }
-        ClassMember::PrivateProp(prop) => {
-            if prop.decorators.len() == 1 {
-                let decorator = &prop.decorators[0];
-                get_decorator_prop_params(decorator, source)
-            } else {
-                None
-            }
-        }
+        ClassMember::ClassProp(prop) => Some(&prop.decorators),
+        ClassMember::PrivateProp(prop) => Some(&prop.decorators),
         _ => None,
     }
 }
+
+/// 获取类型注解的源码
+fn get_type_ann_string(type_ann: &Option<Box<TsTypeAnn>>, source: &str) -> Option<String> {
+    let span = type_ann.as_ref()?.type_ann.span();
+    Some(source[span.lo.to_usize()..span.hi.to_usize()].to_string())
+}
+
+/// 获取注入参数
+/// 返回: (from, default, key 是否为字符串字面量)，如果未指定注入的 key，那么 from 为成员名称
+pub fn get_class_member_inject_params(
+    member: &ClassMember,
+    source: &str,
+) -> Option<(String, bool, bool)> {
+    let decorators = match member {
+        ClassMember::ClassProp(prop) => &prop.decorators,
+        ClassMember::PrivateProp(prop) => &prop.decorators,
+        _ => return None,
+    };
+    let (from, default, is_literal_key) = decorators
+        .iter()
+        .find_map(|decorator| get_decorator_inject_params(decorator, source))?;
+    Some((
+        from.unwrap_or(get_class_member_name(member)),
+        default,
+        is_literal_key,
+    ))
+}
+
+/// 获取 `@Model`, `@ModelSync`, `@VModel` 装饰器的参数
+/// 返回: (model 属性名称, 事件名称, 类型注解, 是否可写)，如果未指定属性名称，那么为成员名称
+pub fn get_class_member_model_params(
+    member: &ClassMember,
+    source: &str,
+) -> Option<(String, String, Option<String>, bool)> {
+    let (decorators, type_ann) = match member {
+        ClassMember::ClassProp(prop) => (&prop.decorators, &prop.type_ann),
+        ClassMember::PrivateProp(prop) => (&prop.decorators, &prop.type_ann),
+        _ => return None,
+    };
+    let (prop, event, writable) = decorators.iter().find_map(get_decorator_model_params)?;
+    Some((
+        prop.unwrap_or(get_class_member_name(member)),
+        event,
+        get_type_ann_string(type_ann, source),
+        writable,
+    ))
+}
+
+/// 获取 `@Emit` 装饰器触发的事件名称，未指定时为方法名称的短横线形式
+pub fn get_class_member_emit_name(member: &ClassMember) -> Option<String> {
+    let name = get_class_member_decorator_list(member)
+        .iter()
+        .find_map(get_decorator_emit_name)?;
+    Some(name.unwrap_or_else(|| util::to_kebab_case(&get_class_member_name(member))))
+}
+
+/// 获取 `@Provide`, `@ProvideReactive` 装饰器提供的 key 及其范围，未指定时为成员名称
+pub fn get_class_member_provide_key(member: &ClassMember) -> Option<(String, (usize, usize))> {
+    let key = get_class_member_decorator_list(member)
+        .iter()
+        .find_map(get_decorator_provide_key)?;
+    Some(key.unwrap_or_else(|| {
+        let name = get_class_member_name(member);
+        let start = get_class_member_name_pos(member).to_usize();
+        let end = start + name.len();
+        (name, (start, end))
+    }))
+}
+
+/// 获取 `@Ref` 装饰器的参数
+/// 返回: (ref 名称, 类型注解是否为数组, 类型注解)，如果未指定 ref 名称，那么为成员名称
+pub fn get_class_member_ref_params(
+    member: &ClassMember,
+    source: &str,
+) -> Option<(String, bool, Option<String>)> {
+    let (decorators, type_ann) = match member {
+        ClassMember::ClassProp(prop) => (&prop.decorators, &prop.type_ann),
+        ClassMember::PrivateProp(prop) => (&prop.decorators, &prop.type_ann),
+        _ => return None,
+    };
+    let name = decorators.iter().find_map(get_decorator_ref_name)?;
+    let is_array = type_ann
+        .as_ref()
+        .is_some_and(|type_ann| is_array_ts_type(&type_ann.type_ann));
+    Some((
+        name.unwrap_or(get_class_member_name(member)),
+        is_array,
+        get_type_ann_string(type_ann, source),
+    ))
+}
@@ -1,7 +1,62 @@
+use std::collections::HashMap;
+
 use swc_common::{source_map::SmallPos, Spanned};
-use swc_ecma_ast::{Callee, Decorator, Expr, ExprOrSpread, Lit, Prop, PropOrSpread};
+use swc_ecma_ast::{
+    BlockStmtOrExpr, Callee, Class, ClassMember, Decl, Decorator, DefaultDecl, Expr, ExprOrSpread,
+    Ident, ImportSpecifier, Lit, MemberProp, Module, ModuleDecl, ModuleItem, Prop, PropOrSpread,
+    Stmt, TsEntityName, TsType,
+};
+
+use super::{
+    get_local_from_import_specifier, get_orig_name_from_import_specifier,
+    get_value_of_specified_prop, prop_name::get_name_form_prop_name,
+};
+
+/// 声明组件的装饰器的默认名称，包括 vue-class-component v8 的 `@Options`
+pub fn get_default_component_decorators() -> Vec<String> {
+    vec!["Component".to_string(), "Options".to_string()]
+}
+
+/// 导出装饰器的包
+const DECORATOR_PACKAGES: [&str; 4] = [
+    "vue-property-decorator",
+    "vue-class-component",
+    "vue-facing-decorator",
+    "nuxt-property-decorator",
+];
+
+/// 是否是声明组件的装饰器，如 `@Component` 和 `@Options`
+///
+/// `names` 为声明组件的装饰器名称，装饰器需要从已知的包中导入
+pub fn is_component_decorator(module: &Module, decorator: &Decorator, names: &[String]) -> bool {
+    get_decorator_name(decorator).is_some_and(|name| {
+        names.contains(&name) && is_imported_from_decorator_packages(module, &name)
+    })
+}
 
-use super::prop_name::get_name_form_prop_name;
+/// 指定的装饰器是否从已知的包中导入
+///
+/// 导入时的别名和命名空间已在解析时还原，vue-class-component 的默认导出为 `Component`
+fn is_imported_from_decorator_packages(module: &Module, name: &str) -> bool {
+    module.body.iter().any(|item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item else {
+            return false;
+        };
+        let src = &import_decl.src.value[..];
+        DECORATOR_PACKAGES.contains(&src)
+            && import_decl
+                .specifiers
+                .iter()
+                .any(|specifier| match specifier {
+                    ImportSpecifier::Namespace(_) => true,
+                    ImportSpecifier::Default(_) => {
+                        src == "vue-class-component" && name == "Component"
+                    }
+                    ImportSpecifier::Named(_) => get_orig_name_from_import_specifier(specifier)
+                        .is_some_and(|v| v.is_some_and(|v| v == name)),
+                })
+    })
+}
 
 pub fn is_specified_decorator(decorator: &Decorator, name: &str) -> bool {
     match decorator.expr.as_ref() {
@@ -17,6 +72,21 @@ pub fn is_specified_decorator(decorator: &Decorator, name: &str) -> bool {
     }
 }
 
+/// 获取装饰器名称，支持 `@Name` 和 `@Name(...)` 两种形式
+pub fn get_decorator_name(decorator: &Decorator) -> Option<String> {
+    match decorator.expr.as_ref() {
+        Expr::Call(expr) => match &expr.callee {
+            Callee::Expr(expr) => match expr.as_ref() {
+                Expr::Ident(ident) => Some(ident.sym.to_string()),
+                _ => None,
+            },
+            _ => None,
+        },
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        _ => None,
+    }
+}
+
 pub fn get_decorator_args(decorator: &Decorator) -> Option<&Vec<ExprOrSpread>> {
     if let Expr::Call(expr) = decorator.expr.as_ref() {
         return Some(&expr.args);
@@ -27,7 +97,7 @@ pub fn get_decorator_args(decorator: &Decorator) -> Option<&Vec<ExprOrSpread>> {
 pub fn get_decorator_prop_params(
     decorator: &Decorator,
     source: &str,
-) -> Option<(Option<String>, bool, bool)> {
+) -> Option<(Option<String>, bool, bool, bool)> {
     if is_specified_decorator(decorator, "Prop") {
         let args = get_decorator_args(decorator)?;
         if args.len() == 1 {
@@ -36,29 +106,410 @@ pub fn get_decorator_prop_params(
                 let mut typ = None;
                 let mut default = false;
                 let mut required = false;
+                let mut validator = false;
                 for prop in &obj.props {
                     if let PropOrSpread::Prop(prop) = prop {
+                        // validator(value) { ... }
+                        if let Prop::Method(prop) = prop.as_ref() {
+                            if get_name_form_prop_name(&prop.key) == "validator" {
+                                validator = true;
+                            }
+                        }
                         if let Prop::KeyValue(prop) = prop.as_ref() {
                             let key = get_name_form_prop_name(&prop.key);
                             if key == "type" {
-                                typ = Some(
-                                    source[prop.value.span().lo.to_usize()
-                                        ..prop.value.span().hi.to_usize()]
-                                        .to_string(),
-                                )
+                                typ = get_prop_type_arg(&prop.value, source)
+                                    .or_else(|| get_prop_type_thunk(&prop.value, source))
+                                    .or(Some(
+                                        source[prop.value.span().lo.to_usize()
+                                            ..prop.value.span().hi.to_usize()]
+                                            .to_string(),
+                                    ))
                             } else if key == "default" {
                                 default = true;
                             } else if key == "required" {
                                 if let Expr::Lit(Lit::Bool(value)) = &prop.value.as_ref() {
                                     required = value.value;
                                 }
+                            } else if key == "validator" {
+                                validator = true;
                             }
                         }
                     }
                 }
-                return Some((typ, default, required));
+                return Some((typ, default, required, validator));
             }
         }
     }
     None
 }
+
+/// 获取 `Object as PropType<User>` 中 `PropType` 的类型参数，如 `User`
+fn get_prop_type_arg(expr: &Expr, source: &str) -> Option<String> {
+    let Expr::TsAs(expr) = expr else {
+        return None;
+    };
+    let TsType::TsTypeRef(type_ref) = expr.type_ann.as_ref() else {
+        return None;
+    };
+    let name = match &type_ref.type_name {
+        TsEntityName::Ident(ident) => ident.sym.to_string(),
+        TsEntityName::TsQualifiedName(name) => name.right.sym.to_string(),
+    };
+    if name != "PropType" {
+        return None;
+    }
+    let span = type_ref.type_params.as_ref()?.params.first()?.span();
+    Some(source[span.lo.to_usize()..span.hi.to_usize()].to_string())
+}
+
+/// 获取 `() => Foo` 形式的类型中返回的构造函数，如 `Foo`
+///
+/// 用于避免循环引用时使用函数包装构造函数
+fn get_prop_type_thunk(expr: &Expr, source: &str) -> Option<String> {
+    let Expr::Arrow(arrow) = expr else {
+        return None;
+    };
+    if !arrow.params.is_empty() {
+        return None;
+    }
+    let BlockStmtOrExpr::Expr(body) = arrow.body.as_ref() else {
+        return None;
+    };
+    let mut body = body.as_ref();
+    while let Expr::Paren(expr) = body {
+        body = expr.expr.as_ref();
+    }
+    match body {
+        Expr::Ident(_) | Expr::Member(_) => {
+            let span = body.span();
+            Some(source[span.lo.to_usize()..span.hi.to_usize()].to_string())
+        }
+        _ => None,
+    }
+}
+
+/// 获取 `@Prop` 装饰器参数中 default 推断的 TS 类型，指定了 type 时返回 None
+pub fn get_decorator_prop_default_type(decorator: &Decorator, source: &str) -> Option<String> {
+    if !is_specified_decorator(decorator, "Prop") {
+        return None;
+    }
+    let args = get_decorator_args(decorator)?;
+    let Expr::Object(obj) = args.first()?.expr.as_ref() else {
+        return None;
+    };
+    let mut default_typ = None;
+    for prop in &obj.props {
+        if let PropOrSpread::Prop(prop) = prop {
+            if let Prop::KeyValue(prop) = prop.as_ref() {
+                let key = get_name_form_prop_name(&prop.key);
+                if key == "type" {
+                    return None;
+                } else if key == "default" {
+                    default_typ = get_expr_type_name(&prop.value, source);
+                }
+            }
+        }
+    }
+    default_typ
+}
+
+/// 从默认值推断类型名称
+///
+/// 例如 `'a'` 为 `string`，枚举成员 `MySize.Large` 为 `MySize`，无法推断时返回 None
+///
+/// 对象和数组的默认值需要使用工厂函数，如 `() => []` 为 `any[]`，`() => ({})` 为 `Record<string, any>`
+fn get_expr_type_name(expr: &Expr, source: &str) -> Option<String> {
+    match expr {
+        Expr::Lit(Lit::Str(_)) => Some("string".to_string()),
+        Expr::Lit(Lit::Num(_)) => Some("number".to_string()),
+        Expr::Lit(Lit::Bool(_)) => Some("boolean".to_string()),
+        Expr::Member(member) => {
+            let span = member.obj.span();
+            Some(source[span.lo.to_usize()..span.hi.to_usize()].to_string())
+        }
+        Expr::Arrow(arrow) => {
+            let ret = match arrow.body.as_ref() {
+                BlockStmtOrExpr::Expr(expr) => expr.as_ref(),
+                BlockStmtOrExpr::BlockStmt(block) => match block.stmts.last() {
+                    Some(Stmt::Return(stmt)) => stmt.arg.as_deref()?,
+                    _ => return None,
+                },
+            };
+            get_factory_type_name(ret)
+        }
+        _ => None,
+    }
+}
+
+/// 从工厂函数的返回值推断对象或数组类型
+fn get_factory_type_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Paren(expr) => get_factory_type_name(&expr.expr),
+        Expr::Array(_) => Some("any[]".to_string()),
+        Expr::Object(_) => Some("Record<string, any>".to_string()),
+        _ => None,
+    }
+}
+
+/// 获取 `@Model`, `@ModelSync`, `@VModel` 装饰器的参数
+/// 返回: (model 属性名称, 事件名称, 是否可写)，属性名称为 None 时为成员名称
+///
+/// `@ModelSync` 和 `@VModel` 生成带有 setter 的计算属性，因此可写
+pub fn get_decorator_model_params(decorator: &Decorator) -> Option<(Option<String>, String, bool)> {
+    let get_str_arg = |index: usize| {
+        let arg = get_decorator_args(decorator)?.get(index)?;
+        if let Expr::Lit(Lit::Str(value)) = arg.expr.as_ref() {
+            Some(value.value.to_string())
+        } else {
+            None
+        }
+    };
+    if is_specified_decorator(decorator, "Model") {
+        // @Model(event, options)
+        let event = get_str_arg(0).unwrap_or("input".to_string());
+        Some((None, event, false))
+    } else if is_specified_decorator(decorator, "ModelSync") {
+        // @ModelSync(propName, event, options)
+        let event = get_str_arg(1).unwrap_or("input".to_string());
+        Some((get_str_arg(0), event, true))
+    } else if is_specified_decorator(decorator, "VModel") {
+        // @VModel(options)，属性名称为 options.name，默认为 value
+        let name = get_decorator_args(decorator)
+            .and_then(|args| args.first())
+            .and_then(|arg| match arg.expr.as_ref() {
+                Expr::Object(object) => object.props.iter().find_map(|prop| {
+                    match get_value_of_specified_prop(prop, "name")? {
+                        Expr::Lit(Lit::Str(value)) => Some(value.value.to_string()),
+                        _ => None,
+                    }
+                }),
+                _ => None,
+            });
+        Some((
+            Some(name.unwrap_or("value".to_string())),
+            "input".to_string(),
+            true,
+        ))
+    } else {
+        None
+    }
+}
+
+/// 获取 `@Ref` 装饰器指定的 ref 名称
+/// 返回: 如果不是 `@Ref` 装饰器，返回 None；未指定 ref 名称时返回 Some(None)
+pub fn get_decorator_ref_name(decorator: &Decorator) -> Option<Option<String>> {
+    if !is_specified_decorator(decorator, "Ref") {
+        return None;
+    }
+    let args = get_decorator_args(decorator);
+    match args
+        .and_then(|args| args.first())
+        .map(|arg| arg.expr.as_ref())
+    {
+        Some(Expr::Lit(Lit::Str(value))) => Some(Some(value.value.to_string())),
+        _ => Some(None),
+    }
+}
+
+/// 获取 `@Emit` 装饰器指定的事件名称
+/// 返回: 如果不是 `@Emit` 装饰器，返回 None；未指定事件名称时返回 Some(None)
+pub fn get_decorator_emit_name(decorator: &Decorator) -> Option<Option<String>> {
+    if !is_specified_decorator(decorator, "Emit") {
+        return None;
+    }
+    let args = get_decorator_args(decorator);
+    match args
+        .and_then(|args| args.first())
+        .map(|arg| arg.expr.as_ref())
+    {
+        Some(Expr::Lit(Lit::Str(value))) => Some(Some(value.value.to_string())),
+        _ => Some(None),
+    }
+}
+
+/// 获取 `@Provide`, `@ProvideReactive` 装饰器指定的 key 及其范围（不含引号）
+/// 返回: 如果不是这两个装饰器，返回 None；未指定 key 时返回 Some(None)
+pub fn get_decorator_provide_key(
+    decorator: &Decorator,
+) -> Option<Option<(String, (usize, usize))>> {
+    if !is_specified_decorator(decorator, "Provide")
+        && !is_specified_decorator(decorator, "ProvideReactive")
+    {
+        return None;
+    }
+    let args = get_decorator_args(decorator);
+    match args
+        .and_then(|args| args.first())
+        .map(|arg| arg.expr.as_ref())
+    {
+        Some(Expr::Lit(Lit::Str(value))) => Some(Some((
+            value.value.to_string(),
+            (value.span.lo.to_usize() + 1, value.span.hi.to_usize() - 1),
+        ))),
+        _ => Some(None),
+    }
+}
+
+/// 获取 `@Inject` 装饰器的参数
+/// 返回: (from, default, key 是否为字符串字面量)，如果未指定注入的 key，那么 from 为 None
+pub fn get_decorator_inject_params(
+    decorator: &Decorator,
+    source: &str,
+) -> Option<(Option<String>, bool, bool)> {
+    if is_specified_decorator(decorator, "Inject") {
+        let args = match get_decorator_args(decorator) {
+            Some(args) => args,
+            None => return Some((None, false, true)),
+        };
+        if args.len() == 0 {
+            return Some((None, false, true));
+        }
+        match args[0].expr.as_ref() {
+            // @Inject('key')
+            Expr::Lit(Lit::Str(value)) => Some((Some(value.value.to_string()), false, true)),
+            // @Inject({ from: 'key', default: () => [] })
+            Expr::Object(obj) => {
+                let mut from = None;
+                let mut default = false;
+                let mut is_literal_key = true;
+                for prop in &obj.props {
+                    if let PropOrSpread::Prop(prop) = prop {
+                        if let Prop::KeyValue(prop) = prop.as_ref() {
+                            let key = get_name_form_prop_name(&prop.key);
+                            if key == "from" {
+                                from = Some(match prop.value.as_ref() {
+                                    Expr::Lit(Lit::Str(value)) => value.value.to_string(),
+                                    value => {
+                                        is_literal_key = false;
+                                        source
+                                            [value.span().lo.to_usize()..value.span().hi.to_usize()]
+                                            .to_string()
+                                    }
+                                });
+                            } else if key == "default" {
+                                default = true;
+                            }
+                        }
+                    }
+                }
+                Some((from, default, is_literal_key))
+            }
+            // @Inject(SomeSymbol)
+            expr => Some((
+                Some(source[expr.span().lo.to_usize()..expr.span().hi.to_usize()].to_string()),
+                false,
+                false,
+            )),
+        }
+    } else {
+        None
+    }
+}
+
+/// 将装饰器中导入时使用的别名还原为原始名称
+///
+/// * `import { Component as Comp } from 'vue-property-decorator'` 时 `@Comp` 还原为 `@Component`
+/// * `import * as VPD from 'vue-property-decorator'` 时 `@VPD.Component` 还原为 `@Component`
+/// * `import Comp from 'vue-class-component'` 时 `@Comp` 还原为 `@Component`
+///
+/// 只处理从 `vue-property-decorator` 和 `vue-class-component` 等已知的包导入的名称
+pub fn normalize_decorators(module: &mut Module) {
+    // 别名 -> 原始名称
+    let mut aliases = HashMap::new();
+    let mut namespaces = vec![];
+    for item in &module.body {
+        if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item {
+            if !DECORATOR_PACKAGES.contains(&&import_decl.src.value[..]) {
+                continue;
+            }
+            for specifier in &import_decl.specifiers {
+                let local = get_local_from_import_specifier(specifier);
+                let orig_name = match specifier {
+                    ImportSpecifier::Namespace(_) => {
+                        namespaces.push(local);
+                        continue;
+                    }
+                    // vue-class-component 默认导出 Component
+                    ImportSpecifier::Default(_)
+                        if &import_decl.src.value[..] == "vue-class-component" =>
+                    {
+                        "Component".to_string()
+                    }
+                    _ => match get_orig_name_from_import_specifier(specifier) {
+                        Some(Some(orig_name)) => orig_name,
+                        _ => continue,
+                    },
+                };
+                if orig_name != local {
+                    aliases.insert(local, orig_name);
+                }
+            }
+        }
+    }
+    if aliases.is_empty() && namespaces.is_empty() {
+        return;
+    }
+    for item in &mut module.body {
+        let class = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(decl)) => match &mut decl.decl {
+                DefaultDecl::Class(class) => &mut class.class,
+                _ => continue,
+            },
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(decl)) => match &mut decl.decl {
+                Decl::Class(class) => &mut class.class,
+                _ => continue,
+            },
+            ModuleItem::Stmt(Stmt::Decl(Decl::Class(class))) => &mut class.class,
+            _ => continue,
+        };
+        normalize_class_decorators(class, &aliases, &namespaces);
+    }
+}
+
+fn normalize_class_decorators(
+    class: &mut Class,
+    aliases: &HashMap<String, String>,
+    namespaces: &Vec<String>,
+) {
+    let mut decorators = class.decorators.iter_mut().collect::<Vec<_>>();
+    for member in &mut class.body {
+        match member {
+            ClassMember::ClassProp(prop) => decorators.extend(prop.decorators.iter_mut()),
+            ClassMember::PrivateProp(prop) => decorators.extend(prop.decorators.iter_mut()),
+            ClassMember::Method(method) => decorators.extend(method.function.decorators.iter_mut()),
+            ClassMember::PrivateMethod(method) => {
+                decorators.extend(method.function.decorators.iter_mut())
+            }
+            _ => {}
+        }
+    }
+    for decorator in decorators {
+        let expr = match decorator.expr.as_mut() {
+            Expr::Call(expr) => match &mut expr.callee {
+                Callee::Expr(expr) => expr.as_mut(),
+                _ => continue,
+            },
+            expr => expr,
+        };
+        match expr {
+            Expr::Ident(ident) => {
+                if let Some(orig_name) = aliases.get(ident.sym.as_str()) {
+                    ident.sym = orig_name.as_str().into();
+                }
+            }
+            Expr::Member(member) => {
+                let ident = match (member.obj.as_ref(), &member.prop) {
+                    (Expr::Ident(obj), MemberProp::Ident(prop))
+                        if namespaces.iter().any(|v| v == obj.sym.as_str()) =>
+                    {
+                        Ident::new_no_ctxt(prop.sym.clone(), member.span)
+                    }
+                    _ => continue,
+                };
+                *expr = Expr::Ident(ident);
+            }
+            _ => {}
+        }
+    }
+}
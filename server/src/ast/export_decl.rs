@@ -1,21 +1,23 @@
-use swc_ecma_ast::{ClassDecl, Decl, ExportDecl};
+use swc_ecma_ast::{ClassDecl, Decl, ExportDecl, Module};
 
-use super::{get_ident_from_decl, is_specified_decorator};
+use super::{get_ident_from_decl, is_component_decorator};
 
 pub fn get_ident_from_export_decl(export_decl: &ExportDecl) -> String {
     get_ident_from_decl(&export_decl.decl)
 }
 
 /// 获取导出的 class 组件
-pub fn _get_export_class_component_from_export_decl(
-    export_decl: &ExportDecl,
-) -> Option<&ClassDecl> {
+pub fn _get_export_class_component_from_export_decl<'a>(
+    module: &Module,
+    export_decl: &'a ExportDecl,
+    names: &[String],
+) -> Option<&'a ClassDecl> {
     if let Decl::Class(class) = &export_decl.decl {
         if class
             .class
             .decorators
             .iter()
-            .find(|d| is_specified_decorator(d, "Component"))
+            .find(|d| is_component_decorator(module, d, names))
             .is_some()
         {
             return Some(class);
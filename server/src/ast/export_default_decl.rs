@@ -1,17 +1,19 @@
-use swc_ecma_ast::{ClassExpr, DefaultDecl, ExportDefaultDecl};
+use swc_ecma_ast::{ClassExpr, DefaultDecl, ExportDefaultDecl, Module};
 
-use crate::ast::is_specified_decorator;
+use crate::ast::is_component_decorator;
 
 /// 获取导出的 class 组件
-pub fn _get_export_class_component_from_export_default_decl(
-    export_decl: &ExportDefaultDecl,
-) -> Option<&ClassExpr> {
+pub fn _get_export_class_component_from_export_default_decl<'a>(
+    module: &Module,
+    export_decl: &'a ExportDefaultDecl,
+    names: &[String],
+) -> Option<&'a ClassExpr> {
     if let DefaultDecl::Class(class) = &export_decl.decl {
         if class
             .class
             .decorators
             .iter()
-            .find(|d| is_specified_decorator(d, "Component"))
+            .find(|d| is_component_decorator(module, d, names))
             .is_some()
         {
             return Some(class);
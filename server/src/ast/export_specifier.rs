@@ -1,19 +1,22 @@
 use swc_ecma_ast::ExportSpecifier;
 
-/// 获取导出表示，第一层 Option 为 None, 则值无效
+/// 获取导出表示，默认导出为 None，第一层 Option 为 None, 则值无效
+///
+/// 未重命名时使用原始表示，如 `export { default } from './Foo.vue'` 也是默认导出
 pub fn get_export_from_export_specifier(specifier: &ExportSpecifier) -> Option<Option<String>> {
     match specifier {
-        ExportSpecifier::Default(specifier) => Some(Some(specifier.exported.to_string())),
+        ExportSpecifier::Default(specifier) => Some(Some(specifier.exported.sym.to_string())),
         ExportSpecifier::Named(specifier) => {
-            if let Some(exported) = &specifier.exported {
-                let exported = exported.atom().to_string();
-                if &exported == "default" {
-                    Some(None)
-                } else {
-                    Some(Some(exported))
-                }
+            let exported = specifier
+                .exported
+                .as_ref()
+                .unwrap_or(&specifier.orig)
+                .atom()
+                .to_string();
+            if &exported == "default" {
+                Some(None)
             } else {
-                Some(Some(specifier.orig.atom().to_string()))
+                Some(Some(exported))
             }
         }
         ExportSpecifier::Namespace(_) => None,
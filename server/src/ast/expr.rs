@@ -1,4 +1,8 @@
-use swc_ecma_ast::{Expr, Lit, PropOrSpread};
+use swc_ecma_ast::{
+    Decl, Expr, Ident, Lit, Module, ModuleDecl, ModuleItem, Pat, Prop, PropName, PropOrSpread, Stmt,
+};
+
+use super::get_name_form_prop_name;
 
 pub fn get_object_props(expr: &Expr) -> Option<&Vec<PropOrSpread>> {
     if let Expr::Object(expr) = expr {
@@ -7,6 +11,116 @@ pub fn get_object_props(expr: &Expr) -> Option<&Vec<PropOrSpread>> {
     None
 }
 
+/// 获取继承的类对应的标识符
+///
+/// 会展开括号、类型断言及泛型实例化，如 `(Base<Props> as VueClass<Base>)` 中的 `Base`
+pub fn get_super_class_ident(expr: &Expr) -> Option<&Ident> {
+    match expr {
+        Expr::Ident(ident) => Some(ident),
+        Expr::Paren(expr) => get_super_class_ident(&expr.expr),
+        Expr::TsAs(expr) => get_super_class_ident(&expr.expr),
+        Expr::TsTypeAssertion(expr) => get_super_class_ident(&expr.expr),
+        Expr::TsNonNull(expr) => get_super_class_ident(&expr.expr),
+        Expr::TsInstantiation(expr) => get_super_class_ident(&expr.expr),
+        Expr::TsSatisfies(expr) => get_super_class_ident(&expr.expr),
+        _ => None,
+    }
+}
+
+/// 获取对象的属性，并展开其中的 `...spread`
+///
+/// 标识符指向当前模块中以对象字面量初始化的变量时，使用该对象的属性，无法解析的展开会被忽略
+///
+/// 与对象字面量的语义一致，重复的键以后出现的为准
+pub fn get_object_props_with_spread<'a>(
+    module: &'a Module,
+    expr: &'a Expr,
+) -> Vec<&'a PropOrSpread> {
+    let mut result = vec![];
+    collect_object_props(module, expr, &mut result, 0);
+    let keys = result.iter().map(|v| get_prop_key(v)).collect::<Vec<_>>();
+    result
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            keys[*i].as_ref().map_or(true, |key| {
+                !keys[i + 1..].iter().any(|v| v.as_ref() == Some(key))
+            })
+        })
+        .map(|(_, prop)| prop)
+        .collect()
+}
+
+/// 获取属性的键，无法静态确定时返回 None
+fn get_prop_key(prop: &PropOrSpread) -> Option<String> {
+    let PropOrSpread::Prop(prop) = prop else {
+        return None;
+    };
+    let key = match prop.as_ref() {
+        Prop::Shorthand(ident) => return Some(ident.sym.to_string()),
+        Prop::KeyValue(prop) => &prop.key,
+        Prop::Method(prop) => &prop.key,
+        _ => return None,
+    };
+    if matches!(key, PropName::Computed(_) | PropName::BigInt(_)) {
+        return None;
+    }
+    Some(get_name_form_prop_name(key))
+}
+
+fn collect_object_props<'a>(
+    module: &'a Module,
+    expr: &'a Expr,
+    result: &mut Vec<&'a PropOrSpread>,
+    depth: usize,
+) {
+    // 避免循环引用
+    if depth > 8 {
+        return;
+    }
+    match expr {
+        Expr::Object(object) => {
+            for prop in &object.props {
+                if let PropOrSpread::Spread(spread) = prop {
+                    collect_object_props(module, &spread.expr, result, depth + 1);
+                } else {
+                    result.push(prop);
+                }
+            }
+        }
+        Expr::Ident(ident) => {
+            if let Some(init) = get_local_var_init(module, ident.sym.as_str()) {
+                collect_object_props(module, init, result, depth + 1);
+            }
+        }
+        Expr::Paren(expr) => collect_object_props(module, &expr.expr, result, depth),
+        Expr::TsAs(expr) => collect_object_props(module, &expr.expr, result, depth),
+        _ => {}
+    }
+}
+
+/// 获取当前模块中顶层变量的初始值
+fn get_local_var_init<'a>(module: &'a Module, name: &str) -> Option<&'a Expr> {
+    for item in &module.body {
+        let var = match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => var,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(decl)) => match &decl.decl {
+                Decl::Var(var) => var,
+                _ => continue,
+            },
+            _ => continue,
+        };
+        for decl in &var.decls {
+            if let Pat::Ident(ident) = &decl.name {
+                if ident.id.sym.as_str() == name {
+                    return decl.init.as_deref();
+                }
+            }
+        }
+    }
+    None
+}
+
 pub fn _expr_is_true(expr: &Expr) -> bool {
     if let Expr::Lit(Lit::Bool(expr)) = expr {
         expr.value
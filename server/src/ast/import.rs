@@ -1,4 +1,5 @@
-use swc_ecma_ast::{ImportDecl, ImportSpecifier};
+use swc_common::source_map::SmallPos;
+use swc_ecma_ast::{ImportDecl, ImportSpecifier, Module, ModuleDecl, ModuleItem};
 
 pub fn get_specified_import<'a>(
     imports: &'a Vec<&ImportDecl>,
@@ -23,3 +24,24 @@ pub fn get_specified_import<'a>(
     }
     None
 }
+
+/// 获取所有导入路径及路径字符串的范围，包括 `export ... from` 重新导出的路径
+pub fn get_import_paths(module: &Module) -> Vec<(String, (usize, usize))> {
+    let mut result = vec![];
+    for item in &module.body {
+        let src = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => &import_decl.src,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) => match &export.src {
+                Some(src) => src,
+                None => continue,
+            },
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => &export.src,
+            _ => continue,
+        };
+        result.push((
+            src.value.to_string(),
+            (src.span.lo.to_usize(), src.span.hi.to_usize()),
+        ));
+    }
+    result
+}
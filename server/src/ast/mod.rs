@@ -16,6 +16,7 @@ mod prop_name;
 mod prop_or_spread;
 mod string;
 mod ts_type_ann;
+mod ts_type_element;
 
 pub(super) use class_decl::*;
 pub(super) use class_expr::*;
@@ -27,7 +28,10 @@ pub(super) use export_decl::*;
 pub(super) use export_default_decl::*;
 pub(super) use export_specifier::*;
 pub(super) use expr::*;
+pub(super) use import::*;
 pub(super) use import_specifier::*;
 pub(super) use module::*;
+pub(super) use prop_name::*;
 pub(super) use prop_or_spread::*;
 pub(super) use string::*;
+pub(super) use ts_type_element::*;
@@ -1,13 +1,14 @@
 use swc_ecma_ast::{
     ClassDecl, ClassExpr, Decl, DefaultDecl, ExportAll, ExportSpecifier, Expr, ImportDecl,
-    ImportSpecifier, Module, ModuleDecl, ModuleExportName, ModuleItem, Prop, PropOrSpread,
+    ImportSpecifier, Module, ModuleDecl, ModuleExportName, ModuleItem, Prop, PropOrSpread, Stmt,
+    TsImportEqualsDecl, TsModuleRef,
 };
 
 use super::{
     _get_export_class_component_from_export_decl,
     _get_export_class_component_from_export_default_decl,
-    decorator::{get_decorator_args, is_specified_decorator},
-    expr::get_object_props,
+    decorator::{get_decorator_args, is_component_decorator},
+    expr::get_object_props_with_spread,
     get_export_from_export_specifier, get_ident_from_export_decl, get_local_from_import_specifier,
     get_orig_name_from_export_specifier, get_orig_name_from_import_specifier,
     import::get_specified_import,
@@ -20,6 +21,7 @@ use super::{
 pub fn get_registered_components(
     module: &Module,
     class: &ClassExpr,
+    names: &[String],
 ) -> Option<Vec<(String, Option<String>, Option<String>, String)>> {
     // import
     let imports = get_import_expr(&module);
@@ -28,7 +30,7 @@ pub fn get_registered_components(
         .class
         .decorators
         .iter()
-        .find(|decorator| is_specified_decorator(decorator, "Component"));
+        .find(|decorator| is_component_decorator(module, decorator, names));
     if component_decorator.is_none() {
         return None;
     }
@@ -40,27 +42,45 @@ pub fn get_registered_components(
         return None;
     }
     let arg = &args[0];
-    let props = get_object_props(arg.expr.as_ref())?;
+    let props = get_object_props_with_spread(module, arg.expr.as_ref());
     for prop in props {
         let value = get_value_of_specified_prop(prop, "components");
         if value.is_none() {
             continue;
         }
         let value = value.unwrap();
-        let props = get_object_props(value)?;
+        let props = get_object_props_with_spread(module, value);
         for prop in props {
             if let PropOrSpread::Prop(prop) = prop {
+                // 注册的名称，导入的本地名称以及导出对象的属性
                 let name;
+                let local;
+                let mut member_prop = None;
                 match prop.as_ref() {
                     Prop::Shorthand(prop) => {
                         name = prop.sym.to_string();
+                        local = name.clone();
                     }
                     Prop::KeyValue(prop) => {
+                        // `Alias: RealComp` 或 `Alias: Select.Option`
                         name = get_name_form_prop_name(&prop.key);
+                        match prop.value.as_ref() {
+                            Expr::Ident(ident) => local = ident.sym.to_string(),
+                            Expr::Member(member) => {
+                                let (Expr::Ident(obj), Some(prop)) =
+                                    (member.obj.as_ref(), member.prop.as_ident())
+                                else {
+                                    continue;
+                                };
+                                local = obj.sym.to_string();
+                                member_prop = Some(prop.sym.to_string());
+                            }
+                            _ => continue,
+                        }
                     }
-                    _ => name = "unknown".to_string(),
+                    _ => continue,
                 }
-                if let Some((import, raw_path)) = get_specified_import(&imports, &name) {
+                if let Some((import, raw_path)) = get_specified_import(&imports, &local) {
                     let export;
                     match import {
                         ImportSpecifier::Default(_) => {
@@ -80,7 +100,7 @@ pub fn get_registered_components(
                             continue;
                         }
                     }
-                    registers.push((name, export, None, raw_path.to_string()));
+                    registers.push((name, export, member_prop, raw_path.to_string()));
                 }
             }
         }
@@ -263,6 +283,36 @@ pub struct _ModuleReference {
 
 // ---- new ----
 
+/// 获取模块中导入的标识符，以及顶层声明的类、接口、类型别名和枚举的名称
+pub fn get_local_type_names(module: &Module) -> Vec<String> {
+    let mut names = vec![];
+    for item in &module.body {
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
+                names.extend(
+                    import_decl
+                        .specifiers
+                        .iter()
+                        .map(get_local_from_import_specifier),
+                );
+                continue;
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => &export_decl.decl,
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            _ => continue,
+        };
+        let ident = match decl {
+            Decl::Class(class) => &class.ident,
+            Decl::TsInterface(interface) => &interface.id,
+            Decl::TsTypeAlias(alias) => &alias.id,
+            Decl::TsEnum(ts_enum) => &ts_enum.id,
+            _ => continue,
+        };
+        names.push(ident.sym.to_string());
+    }
+    names
+}
+
 /// 从 module 获取导入的原始名称和路径
 pub fn get_import_from_module(module: &Module, name: &String) -> Option<(Option<String>, String)> {
     for body in &module.body {
@@ -283,6 +333,7 @@ pub fn get_import_from_module(module: &Module, name: &String) -> Option<(Option<
 pub fn _get_export_from_module(
     module: &Module,
     export_name: &Option<String>,
+    names: &[String],
 ) -> TsFileExportResult {
     // (export_name, orig_name, path)
     let mut imports: Vec<(String, Option<String>, String)> = vec![];
@@ -305,7 +356,7 @@ pub fn _get_export_from_module(
                     if let Some(export_name) = export_name {
                         if export_name == &get_ident_from_export_decl(export_decl) {
                             let class_decl =
-                                _get_export_class_component_from_export_decl(export_decl);
+                                _get_export_class_component_from_export_decl(module, export_decl, names);
                             if class_decl.is_some() {
                                 return TsFileExportResult::_Current;
                             } else {
@@ -351,7 +402,7 @@ pub fn _get_export_from_module(
                 }
                 ModuleDecl::ExportDefaultDecl(export_default_decl) => {
                     if export_name == &None {
-                        if _get_export_class_component_from_export_default_decl(export_default_decl)
+                        if _get_export_class_component_from_export_default_decl(module, export_default_decl, names)
                             .is_some()
                         {
                             return TsFileExportResult::_Current;
@@ -378,8 +429,30 @@ pub fn _get_export_from_module(
                 ModuleDecl::ExportAll(export_all) => {
                     export_all_list.push(export_all.src.value.to_string());
                 }
-                ModuleDecl::TsImportEquals(_) => {}
-                ModuleDecl::TsExportAssignment(_) => {}
+                ModuleDecl::TsImportEquals(import_equals) => {
+                    if let Some(import) = _get_import_from_import_equals(import_equals) {
+                        imports.push(import);
+                    }
+                }
+                ModuleDecl::TsExportAssignment(export_assignment) => {
+                    // `export = Ident` 视为默认导出
+                    if export_name == &None {
+                        if let Expr::Ident(indent) = export_assignment.expr.as_ref() {
+                            let ident = indent.sym.to_string();
+                            let target = imports.iter().find(|v| v.0 == ident);
+                            if let Some((_, orig_name, path)) = target {
+                                return TsFileExportResult::_Other(
+                                    path.to_string(),
+                                    orig_name.clone(),
+                                );
+                            }
+                            if _get_class_component_from_module(module, &ident, names).is_some() {
+                                return TsFileExportResult::_Current;
+                            }
+                        }
+                        return TsFileExportResult::_None;
+                    }
+                }
                 ModuleDecl::TsNamespaceExport(_) => {}
             }
         }
@@ -479,8 +552,23 @@ pub fn get_local_exports_and_transfers(
                         transfers.push((None, None, export_all.src.value.to_string(), true));
                     }
                 }
-                ModuleDecl::TsImportEquals(_) => {}
-                ModuleDecl::TsExportAssignment(_) => {}
+                ModuleDecl::TsImportEquals(import_equals) => {
+                    if let Some(import) = _get_import_from_import_equals(import_equals) {
+                        imports.push(import);
+                    }
+                }
+                ModuleDecl::TsExportAssignment(export_assignment) => {
+                    // `export = Ident` 视为默认导出
+                    if let Expr::Ident(indent) = export_assignment.expr.as_ref() {
+                        let ident = indent.sym.to_string();
+                        let import = imports.iter().find(|v| v.0 == ident);
+                        if let Some((_, orig_name, path)) = import {
+                            transfers.push((None, orig_name.clone(), path.clone(), false));
+                            continue;
+                        }
+                    }
+                    local_exports.push(None);
+                }
                 ModuleDecl::TsNamespaceExport(_) => {}
             }
         }
@@ -489,6 +577,49 @@ pub fn get_local_exports_and_transfers(
     (local_exports, transfers)
 }
 
+/// 从 `import Foo = require("xxx")` 获取导入
+/// 返回值: (local, orig_name, path)，orig_name 视为默认导出
+fn _get_import_from_import_equals(
+    import_equals: &TsImportEqualsDecl,
+) -> Option<(String, Option<String>, String)> {
+    if let TsModuleRef::TsExternalModuleRef(module_ref) = &import_equals.module_ref {
+        Some((
+            import_equals.id.sym.to_string(),
+            None,
+            module_ref.expr.value.to_string(),
+        ))
+    } else {
+        None
+    }
+}
+
+/// 从 module 获取指定名称的 class 组件定义
+fn _get_class_component_from_module<'a>(module: &'a Module, name: &str, names: &[String]) -> Option<&'a ClassDecl> {
+    for item in &module.body {
+        let class = match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Class(class))) => class,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                if let Decl::Class(class) = &export_decl.decl {
+                    class
+                } else {
+                    continue;
+                }
+            }
+            _ => continue,
+        };
+        if &*class.ident.sym == name
+            && class
+                .class
+                .decorators
+                .iter()
+                .any(|decorator| is_component_decorator(module, decorator, names))
+        {
+            return Some(class);
+        }
+    }
+    None
+}
+
 /// ts 文件导出解析结果
 #[derive(PartialEq, Debug)]
 pub enum TsFileExportResult {
@@ -506,7 +637,7 @@ pub enum TsFileExportResult {
 mod tests {
     use crate::ast;
 
-    use super::{TsFileExportResult, _get_export_from_module};
+    use super::{get_local_exports_and_transfers, TsFileExportResult, _get_export_from_module};
 
     fn assert_export_result(
         source: &str,
@@ -515,7 +646,11 @@ mod tests {
     ) {
         let (module, _) = ast::parse_source(source, 0, source.len());
         let module = module.unwrap();
-        let result = _get_export_from_module(&module, export_name);
+        let result = _get_export_from_module(
+            &module,
+            export_name,
+            &ast::get_default_component_decorators(),
+        );
         assert_eq!(result, expected);
     }
 
@@ -569,6 +704,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn export_assignment() {
+        assert_export_result(
+            &[
+                "import { Component } from 'vue-property-decorator';",
+                "@Component",
+                "class MyComponent {}",
+                "export = MyComponent;",
+            ]
+            .join("\n"),
+            &None,
+            TsFileExportResult::_Current,
+        );
+        assert_export_result(
+            &[
+                "import { MyComponent } from 'xxx';",
+                "export = MyComponent;",
+            ]
+            .join("\n"),
+            &None,
+            TsFileExportResult::_Other("xxx".to_string(), Some("MyComponent".to_string())),
+        );
+        assert_export_result(
+            &[
+                "import MyComponent = require('xxx');",
+                "export = MyComponent;",
+            ]
+            .join("\n"),
+            &None,
+            TsFileExportResult::_Other("xxx".to_string(), None),
+        );
+        assert_export_result(
+            &["class MyComponent {}", "export = MyComponent;"].join("\n"),
+            &None,
+            TsFileExportResult::_None,
+        );
+        assert_export_result(
+            &[
+                "import { MyComponent } from 'xxx';",
+                "export = MyComponent;",
+            ]
+            .join("\n"),
+            &Some("MyComponent".to_string()),
+            TsFileExportResult::_None,
+        );
+    }
+
+    #[test]
+    fn export_assignment_transfers() {
+        let source = [
+            "import MyComponent = require('xxx');",
+            "export = MyComponent;",
+        ]
+        .join("\n");
+        let (module, _) = ast::parse_source(&source, 0, source.len());
+        let (local_exports, transfers) = get_local_exports_and_transfers(&module.unwrap());
+        assert_eq!(local_exports, vec![]);
+        assert_eq!(transfers, vec![(None, None, "xxx".to_string(), false)]);
+
+        let source = ["class MyComponent {}", "export = MyComponent;"].join("\n");
+        let (module, _) = ast::parse_source(&source, 0, source.len());
+        let (local_exports, transfers) = get_local_exports_and_transfers(&module.unwrap());
+        assert_eq!(local_exports, vec![None]);
+        assert_eq!(transfers, vec![]);
+    }
+
     #[test]
     fn other_export_some() {
         assert_export_result(
@@ -4,6 +4,8 @@ use swc_ecma_parser::{error::Error, lexer::Lexer, Parser, StringInput, Syntax, T
 
 use crate::renderer::multi_threaded_comment::MultiThreadedComments;
 
+use super::normalize_decorators;
+
 pub fn parse_source(
     source: &str,
     start_pos: usize,
@@ -24,7 +26,10 @@ pub fn parse_source(
     let comments = MultiThreadedComments::default();
     let lexer = Lexer::new(syntax, EsVersion::EsNext, input, Some(&comments));
     let mut parser = Parser::new_from(lexer);
-    let module = parser.parse_module();
+    let module = parser.parse_module().map(|mut module| {
+        normalize_decorators(&mut module);
+        module
+    });
 
     (module, comments)
 }
@@ -1,4 +1,4 @@
-use swc_ecma_ast::{TsType, TsTypeAnn};
+use swc_ecma_ast::{TsEntityName, TsType, TsTypeAnn, TsTypeOperatorOp, TsUnionOrIntersectionType};
 
 pub fn _get_ts_type_string(ts_type: &Option<Box<TsTypeAnn>>) -> String {
     if let Some(ts_type) = ts_type {
@@ -16,3 +16,22 @@ pub fn _get_ts_type_string(ts_type: &Option<Box<TsTypeAnn>>) -> String {
         "unknown".to_string()
     }
 }
+
+/// 类型是否为数组，联合类型中存在数组时也视为数组
+pub fn is_array_ts_type(ts_type: &TsType) -> bool {
+    match ts_type {
+        TsType::TsArrayType(_) | TsType::TsTupleType(_) => true,
+        TsType::TsTypeRef(type_ref) => match &type_ref.type_name {
+            TsEntityName::Ident(ident) => ["Array", "ReadonlyArray"].contains(&&ident.sym[..]),
+            _ => false,
+        },
+        TsType::TsParenthesizedType(ts_type) => is_array_ts_type(&ts_type.type_ann),
+        TsType::TsTypeOperator(ts_type) => {
+            ts_type.op == TsTypeOperatorOp::ReadOnly && is_array_ts_type(&ts_type.type_ann)
+        }
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(ts_type)) => {
+            ts_type.types.iter().any(|v| is_array_ts_type(v))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,29 @@
+use html_languageservice::html_data::Description;
+use swc_common::comments::Comments;
+use swc_ecma_ast::TsTypeElement;
+use tower_lsp::lsp_types::{MarkupContent, MarkupKind};
+
+use crate::renderer::multi_threaded_comment::MultiThreadedComments;
+
+use super::comment::get_markdown;
+
+/// 获取类型成员的注释，不存在注释时返回 None
+pub fn get_ts_type_element_description(
+    element: &TsTypeElement,
+    comments: &MultiThreadedComments,
+) -> Option<Description> {
+    let pos = match element {
+        TsTypeElement::TsPropertySignature(prop) => prop.span.lo,
+        TsTypeElement::TsMethodSignature(method) => method.span.lo,
+        _ => return None,
+    };
+    let comments = comments.get_leading(pos)?;
+    Some(Description::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value: comments
+            .iter()
+            .map(get_markdown)
+            .collect::<Vec<String>>()
+            .join("\n"),
+    }))
+}
@@ -14,10 +14,17 @@ pub trait ConvertBack {
     async fn convert_back(self, options: &ConvertOptions) -> Self;
 }
 
+/// 生成与 ts 服务器悬停信息一致的模块路径
+pub fn get_module_markdown(path: &str) -> String {
+    format!("\n```typescript\nmodule \"{}\"\n```\n", path)
+}
+
 impl ConvertBack for Uri {
     /// 必须 root_uri, target_uri
     async fn convert_back(mut self, options: &ConvertOptions<'_>) -> Self {
-        let (root_uri, target_uri) = options.root_uri_target_uri();
+        let Some((root_uri, target_uri)) = options.root_uri_target_uri() else {
+            return self;
+        };
         let dest_path = util::to_file_path(&self);
         let src_dir = util::to_file_path(root_uri);
         let dest_dir = util::to_file_path(target_uri);
@@ -26,9 +33,10 @@ impl ConvertBack for Uri {
             // 转换为原路径
             let src_path = src_dir.join(&rel_path);
             let mut src_path = src_path.to_str().unwrap();
-            // 移除 .ts 扩展名
-            if dest_path.to_str().unwrap().ends_with(".vue.ts") {
-                src_path = &src_path[..src_path.len() - 3]; // .ts 总是3个字符
+            // 移除 .ts 或 .js 扩展名
+            let dest_path = dest_path.to_str().unwrap();
+            if dest_path.ends_with(".vue.ts") || dest_path.ends_with(".vue.js") {
+                src_path = &src_path[..src_path.len() - 3]; // .ts 和 .js 总是3个字符
             }
             self = util::create_uri_from_str(src_path);
         }
@@ -70,7 +78,9 @@ impl<A: ConvertBack, B: ConvertBack> ConvertBack for OneOf<A, B> {
 impl ConvertBack for HoverContents {
     /// 必须 root_uri, target_uri
     async fn convert_back(self, options: &ConvertOptions<'_>) -> Self {
-        let (root_uri, target_uri) = options.root_uri_target_uri();
+        let Some((root_uri, target_uri)) = options.root_uri_target_uri() else {
+            return self;
+        };
         fn convert_back_module(s: String, root_uri: &Uri, target_uri: &Uri) -> String {
             if s.contains("```typescript\nmodule") {
                 if let Some(caps) = REG_TYPESCRIPT_MODULE.captures(&s) {
@@ -81,8 +91,7 @@ impl ConvertBack for HoverContents {
                             root_uri,
                             target_uri,
                         );
-                        let src_path = src_path.to_str().unwrap();
-                        format!("\n```typescript\nmodule \"{}\"\n```\n", src_path)
+                        get_module_markdown(src_path.to_str().unwrap())
                     } else {
                         s
                     }
@@ -132,8 +141,11 @@ impl ConvertBack for Hover {
         if let Some(range) = self.range {
             if let Some(range) = renderer.get_original_range(uri, &range) {
                 let contents = if let HoverContents::Markup(markup) = self.contents {
-                    let prefix = "\n```typescript\nlet ";
-                    if markup.value.starts_with(prefix) && markup.value.contains(":") {
+                    // prop 使用 `const` 声明，其他属性使用 `let` 声明
+                    let prefix = ["\n```typescript\nlet ", "\n```typescript\nconst "]
+                        .into_iter()
+                        .find(|prefix| markup.value.starts_with(prefix));
+                    if let Some(prefix) = prefix.filter(|_| markup.value.contains(":")) {
                         let prop =
                             &markup.value[prefix.len()..markup.value.find(|v| v == ':').unwrap()];
                         HoverContents::Markup(MarkupContent {
@@ -220,8 +232,10 @@ impl ConvertBack for CompletionItem {
         let uri = options.uri.unwrap();
         let renderer = options.renderer.unwrap();
         let detail = if let Some(detail) = self.detail {
-            let prefix = "let ";
-            if detail.starts_with(prefix) && detail.contains(":") {
+            let prefix = ["let ", "const "]
+                .into_iter()
+                .find(|prefix| detail.starts_with(prefix));
+            if let Some(prefix) = prefix.filter(|_| detail.contains(":")) {
                 let prop = &detail[prefix.len()..detail.find(|v| v == ':').unwrap()];
                 Some(format!(
                     "({}) {}.{}",
@@ -291,23 +305,42 @@ impl ConvertBack for CompletionResponse {
 }
 
 impl ConvertBack for Location {
-    /// 必须 root_uri, target_uri
+    /// 必须 root_uri, target_uri, renderer
     async fn convert_back(self, options: &ConvertOptions<'_>) -> Self {
-        let uri = options.uri.unwrap();
         let renderer = options.renderer.unwrap();
-        let range = renderer.get_original_range(uri, &self.range);
+        // 位置可能位于其他文件（如混入组件），需要按目标文件进行映射
+        let uri = self.uri.convert_back(options).await;
+        let range = renderer.get_original_location_range(&uri, &self.range);
         Location {
-            uri: self.uri.convert_back(options).await,
+            uri,
             range: range.unwrap_or(self.range),
         }
     }
 }
 
 impl ConvertBack for LocationLink {
-    /// 必须 root_uri, target_uri
+    /// 必须 uri, root_uri, target_uri, renderer
     async fn convert_back(self, options: &ConvertOptions<'_>) -> Self {
+        let uri = options.uri.unwrap();
+        let renderer = options.renderer.unwrap();
         let target_uri = self.target_uri.convert_back(options).await;
-        LocationLink { target_uri, ..self }
+        let origin_selection_range = self.origin_selection_range.map(|range| {
+            renderer
+                .get_original_location_range(uri, &range)
+                .unwrap_or(range)
+        });
+        let target_range = renderer
+            .get_original_location_range(&target_uri, &self.target_range)
+            .unwrap_or(self.target_range);
+        let target_selection_range = renderer
+            .get_original_location_range(&target_uri, &self.target_selection_range)
+            .unwrap_or(self.target_selection_range);
+        LocationLink {
+            origin_selection_range,
+            target_uri,
+            target_range,
+            target_selection_range,
+        }
     }
 }
 
@@ -388,7 +421,9 @@ impl ConvertBack for Vec<DocumentSymbol> {
 impl ConvertBack for Vec<SymbolInformation> {
     /// 必须 root_uri, renderer
     async fn convert_back(self, options: &ConvertOptions<'_>) -> Self {
-        let (root_uri, _) = options.root_uri_target_uri();
+        let Some((root_uri, _)) = options.root_uri_target_uri() else {
+            return self;
+        };
         let renderer = options.renderer.unwrap();
         let mut result = vec![];
         for mut item in self {
@@ -692,6 +727,35 @@ impl ConvertBack for DiagnosticRelatedInformation {
     }
 }
 
+/// 转换诊断的相关信息
+///
+/// 位于 vue 文件渲染结果中的位置需要映射回原文件，位于插入的 render 区域中无法映射的相关信息被丢弃
+async fn convert_back_related_information(
+    related_information: Vec<DiagnosticRelatedInformation>,
+    options: &ConvertOptions<'_>,
+) -> Vec<DiagnosticRelatedInformation> {
+    let renderer = options.renderer.unwrap();
+    let mut result = vec![];
+    for item in related_information {
+        let uri = item.location.uri.convert_back(options).await;
+        let mut range = item.location.range;
+        if renderer.get_document(&uri).is_some() {
+            let Some(original) = renderer
+                .get_original_range(&uri, &range)
+                .or_else(|| renderer.get_original_script_range(&uri, &range))
+            else {
+                continue;
+            };
+            range = original;
+        }
+        result.push(DiagnosticRelatedInformation {
+            location: Location { uri, range },
+            message: item.message,
+        });
+    }
+    result
+}
+
 impl ConvertBack for Vec<Diagnostic> {
     /// 必须 uri, root_uri, target_uri, renderer
     async fn convert_back(self, options: &ConvertOptions<'_>) -> Self {
@@ -706,12 +770,14 @@ impl ConvertBack for Vec<Diagnostic> {
                     continue;
                 }
                 diag.range = range;
-                diag.related_information = diag.related_information.convert_back(options).await;
-                diags.push(diag);
-            } else if renderer.is_position_valid(&uri, &diag.range.start) {
-                diag.related_information = diag.related_information.convert_back(options).await;
-                diags.push(diag);
+            } else if !renderer.is_position_valid(&uri, &diag.range.start) {
+                continue;
+            }
+            if let Some(related_information) = diag.related_information {
+                diag.related_information =
+                    Some(convert_back_related_information(related_information, options).await);
             }
+            diags.push(diag);
         }
         diags
     }
@@ -775,7 +841,10 @@ impl ConvertBack for ApplyWorkspaceEditParams {
 mod tests {
     use std::str::FromStr;
 
-    use tower_lsp::lsp_types::Uri;
+    use lsp_textdocument::FullTextDocument;
+    use tower_lsp::lsp_types::{
+        Diagnostic, DiagnosticRelatedInformation, Location, NumberOrString, Position, Range, Uri,
+    };
 
     use crate::{convert::ConvertOptions, renderer::Renderer};
 
@@ -805,10 +874,239 @@ mod tests {
             "file:///home/user/project/src/a.vue",
         )
         .await;
+        assert_uri(
+            "file:///home/user/.~%24project/src/a.vue.js",
+            "file:///home/user/project/src/a.vue",
+        )
+        .await;
         assert_uri(
             "file:///home/user/.~%24project/src/a.ts",
             "file:///home/user/project/src/a.ts",
         )
         .await;
     }
+
+    #[tokio::test]
+    async fn related_information() {
+        let mut renderer = Renderer::new();
+        renderer.set_root_uri_target_uri(
+            Uri::from_str("file:///home/user/project").unwrap(),
+            Uri::from_str("file:///home/user/.~$project").unwrap(),
+        );
+        let a = Uri::from_str("file:///home/user/project/src/a.vue").unwrap();
+        let b = Uri::from_str("file:///home/user/project/src/b.vue").unwrap();
+        for (uri, template, name) in [
+            (&a, "<div></div>", "A"),
+            (&b, "<div>{{ title }}</div>", "B"),
+        ] {
+            renderer.create_node_from_document(
+                uri,
+                FullTextDocument::new(
+                    "vue".to_string(),
+                    0,
+                    [
+                        "<template>",
+                        format!("  {}", template).as_str(),
+                        "</template>",
+                        "<script lang=\"ts\">",
+                        "import { Component, Vue } from 'vue-property-decorator';",
+                        "@Component",
+                        format!("export default class {} extends Vue {{", name).as_str(),
+                        "  title = '';",
+                        "}",
+                        "</script>",
+                    ]
+                    .join("\n"),
+                ),
+            );
+        }
+        // `title` 在 b.vue.ts 中的位置
+        let start = renderer
+            .get_mapping_position(&b, &Position::new(1, 10))
+            .unwrap();
+        let end = renderer
+            .get_mapping_position(&b, &Position::new(1, 15))
+            .unwrap();
+        let target_b = Uri::from_str("file:///home/user/.~%24project/src/b.vue.ts").unwrap();
+        let related_information = |range: Range| DiagnosticRelatedInformation {
+            location: Location {
+                uri: target_b.clone(),
+                range,
+            },
+            message: "related".to_string(),
+        };
+        let diags = vec![Diagnostic {
+            range: Range::new(Position::new(4, 0), Position::new(4, 6)),
+            message: "error".to_string(),
+            related_information: Some(vec![
+                related_information(Range::new(start, end)),
+                // 位于插入的 render 区域
+                related_information(Range::new(
+                    Position::new(start.line, 0),
+                    Position::new(start.line, 1),
+                )),
+            ]),
+            ..Default::default()
+        }]
+        .convert_back(&ConvertOptions {
+            uri: Some(&a),
+            renderer: Some(&renderer),
+        })
+        .await;
+        assert_eq!(
+            diags[0].related_information,
+            Some(vec![DiagnosticRelatedInformation {
+                location: Location {
+                    uri: b.clone(),
+                    range: Range::new(Position::new(1, 10), Position::new(1, 15)),
+                },
+                message: "related".to_string(),
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn readonly_prop_assignment() {
+        let mut renderer = Renderer::new();
+        renderer.set_root_uri_target_uri(
+            Uri::from_str("file:///home/user/project").unwrap(),
+            Uri::from_str("file:///home/user/.~$project").unwrap(),
+        );
+        let uri = Uri::from_str("file:///home/user/project/src/a.vue").unwrap();
+        renderer.create_node_from_document(
+            &uri,
+            FullTextDocument::new(
+                "vue".to_string(),
+                0,
+                [
+                    "<template>",
+                    "  <div @click=\"title = 'x'\"></div>",
+                    "</template>",
+                    "<script lang=\"ts\">",
+                    "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                    "@Component",
+                    "export default class A extends Vue {",
+                    "  @Prop(String) title!: string;",
+                    "}",
+                    "</script>",
+                ]
+                .join("\n"),
+            ),
+        );
+        // tsserver 报告对 `const` 声明的 prop 赋值的错误
+        let start = renderer
+            .get_mapping_position(&uri, &Position::new(1, 15))
+            .unwrap();
+        let end = renderer
+            .get_mapping_position(&uri, &Position::new(1, 20))
+            .unwrap();
+        let diags = vec![Diagnostic {
+            range: Range::new(start, end),
+            code: Some(NumberOrString::Number(2588)),
+            message: "Cannot assign to 'title' because it is a constant.".to_string(),
+            ..Default::default()
+        }]
+        .convert_back(&ConvertOptions {
+            uri: Some(&uri),
+            renderer: Some(&renderer),
+        })
+        .await;
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].range,
+            Range::new(Position::new(1, 15), Position::new(1, 20))
+        );
+    }
+
+    #[tokio::test]
+    async fn location_in_render_region() {
+        let mut renderer = Renderer::new();
+        renderer.set_root_uri_target_uri(
+            Uri::from_str("file:///home/user/project").unwrap(),
+            Uri::from_str("file:///home/user/.~$project").unwrap(),
+        );
+        let uri = Uri::from_str("file:///home/user/project/src/a.vue").unwrap();
+        renderer.create_node_from_document(
+            &uri,
+            FullTextDocument::new(
+                "vue".to_string(),
+                0,
+                [
+                    "<template>",
+                    "  <div>{{ title }}</div>",
+                    "</template>",
+                    "<script lang=\"ts\">",
+                    "import { Component, Vue } from 'vue-property-decorator';",
+                    "@Component",
+                    "export default class A extends Vue {",
+                    "  title = '';",
+                    "}",
+                    "</script>",
+                ]
+                .join("\n"),
+            ),
+        );
+        // `title` 在 a.vue.ts 中的位置
+        let start = renderer
+            .get_mapping_position(&uri, &Position::new(1, 10))
+            .unwrap();
+        let end = renderer
+            .get_mapping_position(&uri, &Position::new(1, 15))
+            .unwrap();
+        let target = Uri::from_str("file:///home/user/.~%24project/src/a.vue.ts").unwrap();
+        let options = ConvertOptions {
+            uri: Some(&uri),
+            renderer: Some(&renderer),
+        };
+        let location = |range: Range| Location {
+            uri: target.clone(),
+            range,
+        };
+        // 模版表达式
+        assert_eq!(
+            location(Range::new(start, end))
+                .convert_back(&options)
+                .await,
+            Location {
+                uri: uri.clone(),
+                range: Range::new(Position::new(1, 10), Position::new(1, 15)),
+            }
+        );
+        // render 区域中无法映射的位置定位到组件类名
+        assert_eq!(
+            location(Range::new(
+                Position::new(start.line, 0),
+                Position::new(start.line, 1),
+            ))
+            .convert_back(&options)
+            .await,
+            Location {
+                uri: uri.clone(),
+                range: Range::new(Position::new(6, 21), Position::new(6, 22)),
+            }
+        );
+        // render 区域之前的脚本
+        assert_eq!(
+            location(Range::new(Position::new(7, 2), Position::new(7, 7)))
+                .convert_back(&options)
+                .await,
+            Location {
+                uri: uri.clone(),
+                range: Range::new(Position::new(7, 2), Position::new(7, 7)),
+            }
+        );
+        // render 区域之后的内容向上移动一行
+        assert_eq!(
+            location(Range::new(
+                Position::new(start.line + 1, 0),
+                Position::new(start.line + 1, 9),
+            ))
+            .convert_back(&options)
+            .await,
+            Location {
+                uri: uri.clone(),
+                range: Range::new(Position::new(9, 0), Position::new(9, 9)),
+            }
+        );
+    }
 }
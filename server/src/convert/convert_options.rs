@@ -9,8 +9,9 @@ pub struct ConvertOptions<'a> {
 }
 
 impl ConvertOptions<'_> {
-    pub fn root_uri_target_uri(&self) -> (&Uri, &Uri) {
-        let (root_uri, dest_uri) = self.renderer.unwrap().root_uri_target_uri();
-        (root_uri, dest_uri)
+    /// 渲染器尚未初始化时返回 None，此时不进行转换
+    pub fn root_uri_target_uri(&self) -> Option<(&Uri, &Uri)> {
+        let (root_uri, dest_uri) = self.renderer?.root_uri_target_uri()?;
+        Some((root_uri, dest_uri))
     }
 }
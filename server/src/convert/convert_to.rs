@@ -13,19 +13,23 @@ pub trait ConvertTo {
 impl ConvertTo for Uri {
     /// 必须 root_uri, target_uri
     async fn convert_to(mut self, options: &ConvertOptions<'_>) -> Self {
-        let (root_uri, target_uri) = options.root_uri_target_uri();
+        let Some((root_uri, target_uri)) = options.root_uri_target_uri() else {
+            return self;
+        };
+        let is_js = options.renderer.unwrap().is_js_component(&self);
 
         let src_path = util::to_file_path(&self);
         let src_dir = util::to_file_path(root_uri);
         let dest_dir = util::to_file_path(target_uri);
-        // 计算相对路径
-        let rel_path = src_path
-            .strip_prefix(&format!("{}/", src_dir.to_string_lossy()))
-            .unwrap();
+        // 计算相对路径，不在项目中的文件保持不变
+        let Ok(rel_path) = src_path.strip_prefix(&format!("{}/", src_dir.to_string_lossy())) else {
+            return self;
+        };
         // 转换为目标路径
         let dest_path = dest_dir.join(&rel_path);
         if dest_path.to_string_lossy().ends_with(".vue") {
-            self = util::create_uri_from_str(&format!("{}{}", dest_path.to_string_lossy(), ".ts"));
+            let suffix = if is_js { ".js" } else { ".ts" };
+            self = util::create_uri_from_str(&format!("{}{}", dest_path.to_string_lossy(), suffix));
         } else {
             self = util::create_uri_from_path(&dest_path);
         }
@@ -87,9 +91,15 @@ impl ConvertTo for VersionedTextDocumentIdentifier {
 impl ConvertTo for TextDocumentItem {
     /// 必须 root_uri, target_uri
     async fn convert_to(self, options: &ConvertOptions<'_>) -> Self {
+        let uri = self.uri.convert_to(options).await;
+        let language_id = if uri.as_str().ends_with(".vue.js") {
+            "javascript"
+        } else {
+            "typescript"
+        };
         TextDocumentItem {
-            uri: self.uri.convert_to(options).await,
-            language_id: "typescript".to_string(),
+            uri,
+            language_id: language_id.to_string(),
             ..self
         }
     }
@@ -199,6 +209,16 @@ impl ConvertTo for DocumentSymbolParams {
     }
 }
 
+impl ConvertTo for DocumentFormattingParams {
+    /// 必须 root_uri, target_uri
+    async fn convert_to(self, options: &ConvertOptions<'_>) -> Self {
+        DocumentFormattingParams {
+            text_document: self.text_document.convert_to(options).await,
+            ..self
+        }
+    }
+}
+
 impl ConvertTo for SemanticTokensParams {
     /// 必须 root_uri, target_uri
     async fn convert_to(self, options: &ConvertOptions<'_>) -> Self {
@@ -209,6 +229,16 @@ impl ConvertTo for SemanticTokensParams {
     }
 }
 
+impl ConvertTo for SemanticTokensDeltaParams {
+    /// 必须 root_uri, target_uri
+    async fn convert_to(self, options: &ConvertOptions<'_>) -> Self {
+        SemanticTokensDeltaParams {
+            text_document: self.text_document.convert_to(options).await,
+            ..self
+        }
+    }
+}
+
 impl ConvertTo for SemanticTokensRangeParams {
     /// 必须 uri, root_uri, target_uri, renderer
     async fn convert_to(self, options: &ConvertOptions<'_>) -> Self {
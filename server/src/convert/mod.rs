@@ -2,6 +2,6 @@ mod convert_back;
 mod convert_options;
 mod convert_to;
 
-pub use convert_back::ConvertBack;
+pub use convert_back::{get_module_markdown, ConvertBack};
 pub use convert_options::ConvertOptions;
 pub use convert_to::ConvertTo;
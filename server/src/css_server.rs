@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use async_lsp_client::{LspServer, ServerMessage};
-use html_languageservice::parser::html_document::HTMLDocument;
+use html_languageservice::parser::html_document::{HTMLDocument, Node};
 use lsp_textdocument::FullTextDocument;
 use tokio::sync::mpsc::Sender;
 use tower_lsp::{
@@ -11,21 +11,24 @@ use tower_lsp::{
             DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, LogMessage,
             Notification, PublishDiagnostics,
         },
-        request::{ApplyWorkspaceEdit, Completion, HoverRequest, Request},
+        request::{
+            ApplyWorkspaceEdit, Completion, Formatting, HoverRequest, Request,
+            SelectionRangeRequest,
+        },
         ApplyWorkspaceEditParams, ChangeAnnotation, ChangeAnnotationIdentifier, CompletionParams,
         CompletionResponse, Diagnostic, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
-        DidOpenTextDocumentParams, DocumentChanges, Hover, HoverParams, InitializeParams,
-        InitializeResult, InitializedParams, LogMessageParams,
-        OptionalVersionedTextDocumentIdentifier, PublishDiagnosticsParams,
-        TextDocumentContentChangeEvent, TextDocumentEdit, TextDocumentIdentifier, TextDocumentItem,
-        TextDocumentPositionParams, TextEdit, Uri, VersionedTextDocumentIdentifier,
-        WorkDoneProgressParams, WorkspaceEdit,
+        DidOpenTextDocumentParams, DocumentChanges, DocumentFormattingParams, Hover, HoverParams,
+        InitializeParams, InitializeResult, InitializedParams, LogMessageParams,
+        OptionalVersionedTextDocumentIdentifier, PublishDiagnosticsParams, SelectionRange,
+        SelectionRangeParams, TextDocumentContentChangeEvent, TextDocumentEdit,
+        TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, TextEdit, Uri,
+        VersionedTextDocumentIdentifier, WorkDoneProgressParams, WorkspaceEdit,
     },
     Client,
 };
 use tracing::warn;
 
-use crate::{renderer, util};
+use crate::{lazy::REG_CSS_CLASS_NAME, renderer, util};
 
 /// # CssServer
 /// 将请求转换格式后，发送到 css-lsp-server，并处理响应
@@ -160,6 +163,53 @@ impl CssServer {
             .convert_back(suffix)
     }
 
+    pub async fn selection_range(
+        &self,
+        params: SelectionRangeParams,
+        html_document: &HTMLDocument,
+    ) -> Result<Option<Vec<SelectionRange>>> {
+        let suffix = get_suffix_from_html(html_document);
+        self.server
+            .send_request::<SelectionRangeRequest>(params.convert_to(suffix))
+            .await
+            .convert_back(suffix)
+    }
+
+    /// 格式化样式，只保留样式范围内的修改
+    pub async fn formatting(
+        &self,
+        params: DocumentFormattingParams,
+        document: &FullTextDocument,
+        html_document: &HTMLDocument,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let suffix = get_suffix_from_html(html_document);
+        let ranges = get_style_ranges(html_document);
+        if ranges.is_empty() {
+            return Ok(None);
+        }
+        let edits = self
+            .server
+            .send_request::<Formatting>(params.convert_to(suffix))
+            .await?;
+        Ok(edits.map(|edits| {
+            edits
+                .into_iter()
+                .filter(|edit| {
+                    let start = document.offset_at(edit.range.start) as usize;
+                    let end = document.offset_at(edit.range.end) as usize;
+                    ranges.iter().any(|v| v.0 <= start && end <= v.1)
+                })
+                .collect()
+        }))
+    }
+
+    pub async fn shutdown(&self) {
+        if let Err(err) = self.server.shutdown().await {
+            warn!("css server shutdown error: {:?}", err);
+        }
+        self.server.exit().await;
+    }
+
     async fn process_message(
         client: &Client,
         server: &LspServer,
@@ -240,14 +290,18 @@ fn get_suffix_from_html(html_document: &HTMLDocument) -> &'static str {
         .iter()
         .find(|v| v.tag.as_ref().is_some_and(|tag| tag == "style"));
     if let Some(style) = style {
-        if let Some(v) = style.attributes.get("lang") {
-            if v.value.as_ref().is_some_and(|v| v == "\"scss\"") {
-                ".scss"
-            } else if v.value.as_ref().is_some_and(|v| v == "\"less\"") {
-                ".less"
-            } else {
-                ".css"
-            }
+        get_suffix_from_style(style)
+    } else {
+        ".css"
+    }
+}
+
+fn get_suffix_from_style(style: &Node) -> &'static str {
+    if let Some(v) = style.attributes.get("lang") {
+        if v.value.as_ref().is_some_and(|v| v == "\"scss\"") {
+            ".scss"
+        } else if v.value.as_ref().is_some_and(|v| v == "\"less\"") {
+            ".less"
         } else {
             ".css"
         }
@@ -256,25 +310,87 @@ fn get_suffix_from_html(html_document: &HTMLDocument) -> &'static str {
     }
 }
 
+/// 获取与第一个样式块语言相同的所有样式块的内容范围
+fn get_style_ranges(html_document: &HTMLDocument) -> Vec<(usize, usize)> {
+    let suffix = get_suffix_from_html(html_document);
+    html_document
+        .roots
+        .iter()
+        .filter(|v| v.tag.as_ref().is_some_and(|tag| tag == "style"))
+        .filter(|v| get_suffix_from_style(v) == suffix)
+        .filter_map(|v| Some((v.start_tag_end?, v.end_tag_start?)))
+        .collect()
+}
+
 /// 获取 vue 文件中的 css 部分，其余部分填充为空格或换行符
 /// 返回转换后的内容和 文件后缀
 fn get_css_source(source: &str, html_document: &HTMLDocument) -> (String, &'static str) {
-    let style = html_document
-        .roots
-        .iter()
-        .find(|v| v.tag.as_ref().is_some_and(|v| v == "style"));
-    if let Some(style) = style {
-        if style.start_tag_end.is_some() && style.end_tag_start.is_some() {
-            let start_tag_end = style.start_tag_end.unwrap();
-            let end_tag_start = style.end_tag_start.unwrap();
-            let content = renderer::get_fill_space_source(source, start_tag_end, end_tag_start);
-            (content, get_suffix_from_html(html_document))
-        } else {
-            ("".to_string(), ".css")
+    let ranges = get_style_ranges(html_document);
+    if ranges.is_empty() {
+        return ("".to_string(), ".css");
+    }
+    // 填充后的内容均为单字节字符，与原文件的字节偏移一致
+    let mut content = renderer::get_fill_space_source(source, 0, 0);
+    for (start, end) in ranges {
+        content.replace_range(start..end, &source[start..end]);
+    }
+    (content, get_suffix_from_html(html_document))
+}
+
+/// 使用 CSS Modules 的样式块
+#[derive(Debug, PartialEq, Clone)]
+pub struct StyleModule {
+    /// 模版中访问的名称，默认为 `$style`
+    pub name: String,
+    /// 类名及其在文档中的偏移量
+    pub classes: Vec<(String, usize)>,
+}
+
+/// 获取存在 `module` 属性的样式块及其中的类名
+pub fn get_style_modules(source: &str, styles: &[Node]) -> Vec<StyleModule> {
+    let mut modules = vec![];
+    for style in styles {
+        let Some(attr) = style.attributes.get("module") else {
+            continue;
+        };
+        let (Some(start), Some(end)) = (style.start_tag_end, style.end_tag_start) else {
+            continue;
+        };
+        let name = match &attr.value {
+            Some(value) if value.len() > 2 => value[1..value.len() - 1].to_string(),
+            _ => "$style".to_string(),
+        };
+        let mut classes: Vec<(String, usize)> = vec![];
+        for caps in REG_CSS_CLASS_NAME.captures_iter(&source[start..end]) {
+            let class = caps.get(1).unwrap();
+            if classes.iter().all(|v| v.0 != class.as_str()) {
+                classes.push((class.as_str().to_string(), start + class.start()));
+            }
         }
-    } else {
-        ("".to_string(), ".css")
+        modules.push(StyleModule { name, classes });
     }
+    modules
+}
+
+/// 获取 CSS Modules 在渲染文件中的声明
+///
+/// 脚本为 JavaScript 时通过 JSDoc 声明类型
+pub fn get_style_modules_declaration(modules: &[StyleModule], is_js: bool) -> String {
+    modules
+        .iter()
+        .map(|module| {
+            let typ = module
+                .classes
+                .iter()
+                .map(|v| format!("\"{}\":string;", v.0))
+                .collect::<String>();
+            if is_js {
+                format!("/** @type {{{{{}}}}} */const {} = {{}};", typ, module.name)
+            } else {
+                format!("const {}:{{{}}} = {{}} as any;", module.name, typ)
+            }
+        })
+        .collect()
 }
 
 trait ConvertTo {
@@ -308,6 +424,31 @@ impl ConvertTo for HoverParams {
     }
 }
 
+impl ConvertTo for DocumentFormattingParams {
+    fn convert_to(self, suffix: &str) -> Self {
+        DocumentFormattingParams {
+            text_document: TextDocumentIdentifier {
+                uri: self.text_document.uri.convert_to(suffix),
+            },
+            options: self.options,
+            work_done_progress_params: self.work_done_progress_params,
+        }
+    }
+}
+
+impl ConvertTo for SelectionRangeParams {
+    fn convert_to(self, suffix: &str) -> Self {
+        SelectionRangeParams {
+            text_document: TextDocumentIdentifier {
+                uri: self.text_document.uri.convert_to(suffix),
+            },
+            positions: self.positions,
+            work_done_progress_params: self.work_done_progress_params,
+            partial_result_params: self.partial_result_params,
+        }
+    }
+}
+
 impl ConvertTo for CompletionParams {
     fn convert_to(self, suffix: &str) -> Self {
         CompletionParams {
@@ -413,3 +554,85 @@ impl ConvertBack for CompletionResponse {
         self
     }
 }
+
+impl ConvertBack for Vec<SelectionRange> {
+    fn convert_back(self, _suffix: &str) -> Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use html_languageservice::{parser::html_parse, HTMLDataManager};
+
+    use super::StyleModule;
+
+    #[test]
+    fn style_modules() {
+        let source = [
+            "<template><div></div></template>",
+            "<style module>",
+            ".root { margin: 0.5em; }",
+            ".root-item:hover { color: red; }",
+            "</style>",
+            "<style module=\"styles\">",
+            ".active {}",
+            "</style>",
+            "<style>",
+            ".plain {}",
+            "</style>",
+        ]
+        .join("\n");
+        let html_document =
+            html_parse::parse_html_document(&source, "vue", &HTMLDataManager::default(), true);
+        let modules = super::get_style_modules(&source, &html_document.roots[1..]);
+        assert_eq!(
+            modules,
+            vec![
+                StyleModule {
+                    name: "$style".to_string(),
+                    classes: vec![("root".to_string(), 49), ("root-item".to_string(), 74)],
+                },
+                StyleModule {
+                    name: "styles".to_string(),
+                    classes: vec![("active".to_string(), 140)],
+                },
+            ]
+        );
+        assert_eq!(
+            super::get_style_modules_declaration(&modules, false),
+            r#"const $style:{"root":string;"root-item":string;} = {} as any;const styles:{"active":string;} = {} as any;"#
+        );
+        assert_eq!(
+            super::get_style_modules_declaration(&modules[1..], true),
+            r#"/** @type {{"active":string;}} */const styles = {};"#
+        );
+    }
+
+    #[test]
+    fn css_source() {
+        let source = [
+            "<template><div></div></template>",
+            "<style lang=\"scss\">",
+            ".a {}",
+            "</style>",
+            "<style>",
+            ".b {}",
+            "</style>",
+            "<style lang=\"scss\">",
+            ".c {}",
+            "</style>",
+        ]
+        .join("\n");
+        let html_document =
+            html_parse::parse_html_document(&source, "vue", &HTMLDataManager::default(), true);
+        let (content, suffix) = super::get_css_source(&source, &html_document);
+        assert_eq!(suffix, ".scss");
+        // 保留所有语言相同的样式块，其余部分填充空白
+        assert_eq!(
+            content.split('\n').map(|v| v.trim()).collect::<Vec<_>>(),
+            vec!["", "", ".a {}", "", "", "", "", "", ".c {}", ""]
+        );
+        assert_eq!(content.len(), source.len());
+    }
+}
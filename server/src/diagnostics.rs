@@ -1,9 +1,19 @@
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
 
-use tokio::sync::mpsc::{self, Sender};
+use tokio::{
+    sync::{
+        mpsc::{self, Sender},
+        Notify,
+    },
+    time,
+};
 use tower_lsp::{
     lsp_types::{Diagnostic, Uri},
     Client,
@@ -13,14 +23,68 @@ pub struct DiagnosticsManager {
     client: Client,
     count: usize,
     diags: Arc<Mutex<HashMap<Uri, Vec<Vec<Diagnostic>>>>>,
+    /// 客户端通过 `textDocument/diagnostic` 拉取诊断时，只缓存诊断并通知客户端刷新
+    pull_mode: Arc<AtomicBool>,
+    /// 通知客户端刷新诊断，间隔内的多次通知合并为一次
+    refresh: Arc<Notify>,
 }
 
+/// 合并刷新诊断通知的间隔
+const REFRESH_DELAY: Duration = Duration::from_millis(200);
+
 impl DiagnosticsManager {
     pub fn new(client: Client) -> Self {
         DiagnosticsManager {
             client,
             count: 0,
             diags: Arc::new(Mutex::new(HashMap::new())),
+            pull_mode: Arc::new(AtomicBool::new(false)),
+            refresh: Arc::new(Notify::new()),
+        }
+    }
+
+    /// 设置拉取模式，客户端支持 `workspace/diagnostic/refresh` 时诊断变化后通知客户端刷新
+    pub fn set_pull_mode(&self, pull_mode: bool, refresh_support: bool) {
+        self.pull_mode.store(pull_mode, Ordering::Relaxed);
+        if pull_mode && refresh_support {
+            let client = self.client.clone();
+            let refresh = Arc::clone(&self.refresh);
+            tokio::spawn(async move {
+                loop {
+                    refresh.notified().await;
+                    time::sleep(REFRESH_DELAY).await;
+                    let _ = client.workspace_diagnostic_refresh().await;
+                }
+            });
+        }
+    }
+
+    /// 已注册的诊断来源数量，下一个注册的来源序号与之相同
+    pub fn registered_count(&self) -> usize {
+        self.count
+    }
+
+    /// 获取缓存的诊断，排除序号为 `exclude` 的来源
+    pub fn get_diagnostics(&self, uri: &Uri, exclude: usize) -> Vec<Diagnostic> {
+        let diags_guard = self.diags.lock().unwrap();
+        DiagnosticsManager::get_diags_except(&diags_guard, uri, exclude)
+    }
+
+    /// 清除序号为 `index` 的来源的所有诊断，并重新发布受影响文件的诊断
+    pub async fn clear(&self, index: usize) {
+        let cleared = {
+            let mut diags_guard = self.diags.lock().unwrap();
+            DiagnosticsManager::clear_diags(&mut diags_guard, index)
+        };
+        if cleared.is_empty() {
+            return;
+        }
+        if self.pull_mode.load(Ordering::Relaxed) {
+            self.refresh.notify_one();
+        } else {
+            for (uri, all_diags) in cleared {
+                self.client.publish_diagnostics(uri, all_diags, None).await;
+            }
         }
     }
 
@@ -30,13 +94,19 @@ impl DiagnosticsManager {
         let diags = Arc::clone(&self.diags);
         let count = self.count;
         let client = self.client.clone();
+        let pull_mode = Arc::clone(&self.pull_mode);
+        let refresh = Arc::clone(&self.refresh);
         tokio::spawn(async move {
             while let Some((uri, version, msg)) = rx.recv().await {
                 let all_diags = {
                     let mut diags_guard = diags.lock().unwrap();
                     DiagnosticsManager::get_all_diags(&mut diags_guard, &uri, msg, count)
                 };
-                client.publish_diagnostics(uri, all_diags, version).await;
+                if pull_mode.load(Ordering::Relaxed) {
+                    refresh.notify_one();
+                } else {
+                    client.publish_diagnostics(uri, all_diags, version).await;
+                }
             }
         });
         self.count += 1;
@@ -78,6 +148,39 @@ impl DiagnosticsManager {
             all_diags
         }
     }
+
+    /// 返回被清除的文件及其剩余的诊断
+    fn clear_diags<T: Clone>(
+        diags_guard: &mut HashMap<Uri, Vec<Vec<T>>>,
+        index: usize,
+    ) -> Vec<(Uri, Vec<T>)> {
+        diags_guard
+            .iter_mut()
+            .filter(|(_, diags)| diags.get(index).is_some_and(|v| !v.is_empty()))
+            .map(|(uri, diags)| {
+                diags[index].clear();
+                (uri.clone(), diags.iter().flatten().cloned().collect())
+            })
+            .collect()
+    }
+
+    fn get_diags_except<T: Clone>(
+        diags_guard: &HashMap<Uri, Vec<Vec<T>>>,
+        uri: &Uri,
+        exclude: usize,
+    ) -> Vec<T> {
+        diags_guard
+            .get(uri)
+            .map(|diags| {
+                diags
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != exclude)
+                    .flat_map(|(_, v)| v.iter().cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
@@ -106,4 +209,49 @@ mod tests {
         );
         assert_value(vec![(2, vec![0, 1, 2]), (2, vec![3, 4, 5])], vec![3, 4, 5]);
     }
+
+    #[test]
+    fn diags_except() {
+        let mut diags = HashMap::new();
+        let uri = Uri::from_str("file:///test.ts").unwrap();
+        DiagnosticsManager::get_all_diags(&mut diags, &uri, vec![0, 1], 0);
+        DiagnosticsManager::get_all_diags(&mut diags, &uri, vec![2], 2);
+        assert_eq!(
+            DiagnosticsManager::get_diags_except(&diags, &uri, 2),
+            vec![0, 1]
+        );
+        assert_eq!(
+            DiagnosticsManager::get_diags_except(&diags, &uri, 1),
+            vec![0, 1, 2]
+        );
+        let other = Uri::from_str("file:///other.ts").unwrap();
+        assert_eq!(
+            DiagnosticsManager::get_diags_except(&diags, &other, 0),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn clear_diags() {
+        let mut diags = HashMap::new();
+        let uri = Uri::from_str("file:///test.vue").unwrap();
+        let other = Uri::from_str("file:///other.vue").unwrap();
+        DiagnosticsManager::get_all_diags(&mut diags, &uri, vec![0, 1], 0);
+        DiagnosticsManager::get_all_diags(&mut diags, &uri, vec![2], 1);
+        DiagnosticsManager::get_all_diags(&mut diags, &other, vec![3], 0);
+        assert_eq!(
+            DiagnosticsManager::clear_diags(&mut diags, 1),
+            vec![(uri.clone(), vec![0, 1])]
+        );
+        assert_eq!(
+            DiagnosticsManager::get_diags_except(&diags, &uri, 2),
+            vec![0, 1]
+        );
+        assert_eq!(
+            DiagnosticsManager::get_diags_except(&diags, &other, 2),
+            vec![3]
+        );
+        // 没有剩余诊断的来源不再重复发布
+        assert!(DiagnosticsManager::clear_diags(&mut diags, 1).is_empty());
+    }
 }
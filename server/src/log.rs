@@ -1,20 +1,48 @@
 use std::{
     collections::HashMap,
+    str::FromStr,
     sync::{
         mpsc::{self, Sender},
-        RwLock,
+        Arc, RwLock,
     },
 };
 
 use tower_lsp::{lsp_types::MessageType, Client};
 use tracing::{
     field,
+    level_filters::LevelFilter,
     span::{self},
     Level, Subscriber,
 };
 
+/// 日志等级句柄，用于在运行时修改日志等级
+#[derive(Clone)]
+pub struct LogLevelHandle(Arc<RwLock<LevelFilter>>);
+
+impl LogLevelHandle {
+    fn new(level: LevelFilter) -> LogLevelHandle {
+        LogLevelHandle(Arc::new(RwLock::new(level)))
+    }
+
+    /// 获取当前日志等级
+    pub fn get_level(&self) -> LevelFilter {
+        *self.0.read().unwrap()
+    }
+
+    /// 修改日志等级，等级为 `off`, `error`, `warn`, `info`, `debug`, `trace` 之一
+    pub fn set_level(&self, level: &str) -> Result<LevelFilter, String> {
+        let level = LevelFilter::from_str(level.trim())
+            .map_err(|_| format!("Invalid log level `{}`", level))?;
+        *self.0.write().unwrap() = level;
+        // 已缓存的 callsite 需要重新判断是否启用
+        tracing::callsite::rebuild_interest_cache();
+        Ok(level)
+    }
+}
+
 pub struct LspSubscriber {
     tx: Sender<(MessageType, String)>,
+    level: LogLevelHandle,
     count: RwLock<u64>,
     span_name_map: RwLock<HashMap<span::Id, String>>,
     prefix: RwLock<Vec<String>>,
@@ -31,10 +59,17 @@ impl LspSubscriber {
         LspSubscriber {
             count: RwLock::new(0),
             tx,
+            level: LogLevelHandle::new(LevelFilter::DEBUG),
             span_name_map: RwLock::new(HashMap::new()),
             prefix: RwLock::new(vec![]),
         }
     }
+
+    /// 获取日志等级句柄
+    pub fn level_handle(&self) -> LogLevelHandle {
+        self.level.clone()
+    }
+
     fn log(&self, typ: MessageType, message: String) {
         let _ = self.tx.send((typ, message));
     }
@@ -42,7 +77,7 @@ impl LspSubscriber {
 
 impl Subscriber for LspSubscriber {
     fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
-        *metadata.level() <= Level::DEBUG
+        *metadata.level() <= self.level.get_level()
     }
 
     fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
@@ -73,8 +108,7 @@ impl Subscriber for LspSubscriber {
 
     fn event(&self, event: &tracing::Event<'_>) {
         let typ = match *event.metadata().level() {
-            Level::TRACE => return,
-            Level::DEBUG => MessageType::LOG,
+            Level::TRACE | Level::DEBUG => MessageType::LOG,
             Level::INFO => MessageType::INFO,
             Level::WARN => MessageType::WARNING,
             Level::ERROR => MessageType::ERROR,
@@ -121,3 +155,20 @@ impl field::Visit for LoggerVisit {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tracing::level_filters::LevelFilter;
+
+    use super::LogLevelHandle;
+
+    #[test]
+    fn set_log_level() {
+        let handle = LogLevelHandle::new(LevelFilter::DEBUG);
+        assert_eq!(handle.set_level("info"), Ok(LevelFilter::INFO));
+        assert_eq!(handle.get_level(), LevelFilter::INFO);
+        assert_eq!(handle.set_level("TRACE"), Ok(LevelFilter::TRACE));
+        assert!(handle.set_level("verbose").is_err());
+        assert_eq!(handle.get_level(), LevelFilter::TRACE);
+    }
+}
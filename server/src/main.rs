@@ -8,8 +8,9 @@ async fn main() {
 
     let (service, socket) = LspService::new(|client| {
         let subscriber = LspSubscriber::new(client.clone());
+        let log_level_handle = subscriber.level_handle();
         tracing::subscriber::set_global_default(subscriber).unwrap();
-        VueLspServer::new(client, None)
+        VueLspServer::new(client, None).with_log_level_handle(log_level_handle)
     });
     Server::new(stdin, stdout, socket)
         .concurrency_level(16)
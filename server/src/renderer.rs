@@ -1,18 +1,31 @@
 mod combined_rendered_results;
+mod convert_options;
+mod decorator_options;
+mod lifecycle_hooks;
 mod mapping;
 pub mod multi_threaded_comment;
 mod parse_document;
 mod parse_import_path;
 mod parse_script;
+mod provide_inject;
 mod render;
 mod render_cache;
+mod reserved_names;
 mod tags_provider;
 mod template_compile;
 
-use html_languageservice::parser::html_document::HTMLDocument;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use html_languageservice::html_data::Description;
+use html_languageservice::parser::html_document::{HTMLDocument, Node};
+use html_languageservice::parser::html_scanner::TokenType;
 pub use mapping::PositionType;
+use render_cache::lib_render_cache::LibComponentProp;
+use render_cache::vue_render_cache::VueRenderCache;
+pub use render_cache::ComponentModel;
 use render_cache::RenderCache;
 use render_cache::RenderCacheGraph;
+pub use render_cache::RenderCacheGraphStats;
+pub use render_cache::RenderCacheProp;
 pub use render_cache::RenderCachePropType;
 use tags_provider::ArcTagsProvider;
 use tokio::fs::File;
@@ -21,27 +34,52 @@ use tokio::sync::OnceCell;
 use tower_lsp::lsp_types::Location;
 
 use std::collections::HashSet;
-use std::{collections::HashMap, io::Error, path::PathBuf};
+use std::{
+    collections::HashMap,
+    io::Error,
+    path::{Path, PathBuf},
+};
 
 use lsp_textdocument::FullTextDocument;
-use tower_lsp::lsp_types::{Position, Range, Uri};
+use tower_lsp::lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, Diagnostic, DiagnosticSeverity, DocumentLink,
+    DocumentSymbol, Hover, HoverContents, MarkupContent, MarkupKind, Position, Range,
+    SelectionRange, SymbolKind, TextEdit, Uri,
+};
 use tracing::error;
 
 pub use combined_rendered_results::get_fill_space_source;
 
-use crate::util;
+/// 默认的解析文件的最大大小，单位为 KB
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024;
+
+use crate::{ast, util, vue_data};
 
 /// # 渲染器
 /// 将项目渲染到同目录下的加上 `.~$` 前缀的目录中
 pub struct Renderer {
     root_uri_target_uri: OnceCell<(Uri, Uri)>,
     alias: HashMap<String, String>,
+    /// tsconfig.json 中 `compilerOptions.baseUrl` 对应的路径
+    base_url: Option<PathBuf>,
     render_cache: RenderCacheGraph,
     provider_map: HashMap<Uri, ArcTagsProvider>,
     /// 组件库列表
     library_list: Vec<Uri>,
     /// 文件被创建时，将会创建的文件，创建完成后清空
     will_create_files: HashSet<Uri>,
+    /// 不进行渲染的文件，相对于项目根目录匹配
+    render_exclude: Option<GlobSet>,
+    /// 目标目录已存在时，是否只重新渲染修改过的文件
+    incremental_render: bool,
+    /// 增量渲染时未修改的文件，首次使用时再创建节点
+    lazy_files: HashSet<Uri>,
+    /// 解析文件的最大大小，单位为 KB，为 0 时不限制
+    max_file_size: u64,
+    /// 超过最大大小而未解析的文件
+    oversized_files: HashSet<Uri>,
+    /// 声明组件的装饰器名称
+    component_decorators: Vec<String>,
 }
 
 impl Renderer {
@@ -49,15 +87,117 @@ impl Renderer {
         Renderer {
             root_uri_target_uri: OnceCell::new(),
             alias: HashMap::new(),
+            base_url: None,
             render_cache: RenderCacheGraph::new(),
             provider_map: HashMap::new(),
             library_list: vec![],
             will_create_files: HashSet::new(),
+            render_exclude: None,
+            incremental_render: false,
+            lazy_files: HashSet::new(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            oversized_files: HashSet::new(),
+            component_decorators: ast::get_default_component_decorators(),
+        }
+    }
+
+    /// 设置是否增量渲染，需要在初始化前设置
+    pub fn set_incremental_render(&mut self, incremental_render: bool) {
+        self.incremental_render = incremental_render;
+    }
+
+    /// 设置解析文件的最大大小，单位为 KB，需要在初始化前设置
+    pub fn set_max_file_size(&mut self, max_file_size: u64) {
+        self.max_file_size = max_file_size;
+    }
+
+    /// 设置声明组件的装饰器名称，为空时使用默认值，需要在初始化前设置
+    pub fn set_component_decorators(&mut self, names: Vec<String>) {
+        if !names.is_empty() {
+            self.component_decorators = names;
+        }
+    }
+
+    /// 设置不进行渲染的文件
+    ///
+    /// `*` 和 `?` 不匹配路径分隔符，`**` 匹配任意层级的目录
+    pub fn set_render_exclude(&mut self, patterns: &[String]) -> Result<(), globset::Error> {
+        if patterns.is_empty() {
+            self.render_exclude = None;
+            return Ok(());
+        }
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+        }
+        self.render_exclude = Some(builder.build()?);
+        Ok(())
+    }
+
+    /// 文件是否被配置为不进行渲染，超过最大大小而未解析的文件同样视为不渲染
+    pub fn is_render_excluded(&self, uri: &Uri) -> bool {
+        if self.oversized_files.contains(uri) {
+            return true;
+        }
+        let Some(render_exclude) = &self.render_exclude else {
+            return false;
+        };
+        let Some((root_uri, _)) = self.root_uri_target_uri.get() else {
+            return false;
+        };
+        Renderer::is_path_excluded(
+            render_exclude,
+            &util::to_file_path(root_uri),
+            &util::to_file_path(uri),
+        )
+    }
+
+    fn is_path_excluded(render_exclude: &GlobSet, root_path: &Path, path: &Path) -> bool {
+        path.strip_prefix(root_path)
+            .is_ok_and(|path| render_exclude.is_match(path))
+    }
+
+    /// 获取渲染缓存图的统计信息
+    pub fn get_render_cache_stats(&self) -> RenderCacheGraphStats {
+        self.render_cache.get_stats()
+    }
+
+    /// 将渲染缓存图输出为 Graphviz DOT 格式，用于调试跨文件的关系
+    pub fn dump_graph(&self) -> String {
+        let Some((root_uri, _)) = self.root_uri_target_uri() else {
+            return String::new();
+        };
+        self.render_cache.to_dot(root_uri)
+    }
+
+    /// 获取渲染文件对应的原文件 uri，不位于目标目录中时返回 None
+    pub fn get_source_uri(&self, uri: &Uri) -> Option<Uri> {
+        let (root_uri, target_uri) = self.root_uri_target_uri()?;
+        if !util::to_file_path(uri).starts_with(util::to_file_path(target_uri)) {
+            return None;
         }
+        let source_path = Renderer::get_source_path(uri, root_uri, target_uri);
+        Some(util::create_uri_from_path(&source_path))
+    }
+
+    /// 组件库数量
+    pub fn get_library_count(&self) -> usize {
+        self.library_list.len()
+    }
+
+    /// 等待创建的文件数量
+    pub fn get_will_create_files_count(&self) -> usize {
+        self.will_create_files.len()
+    }
+
+    /// 尚未创建节点的未修改文件数量
+    pub fn get_lazy_file_count(&self) -> usize {
+        self.lazy_files.len()
     }
 
-    pub fn root_uri_target_uri(&self) -> &(Uri, Uri) {
-        self.root_uri_target_uri.get().unwrap()
+    /// 获取项目目录和渲染目录，初始化完成前返回 None
+    pub fn root_uri_target_uri(&self) -> Option<&(Uri, Uri)> {
+        self.root_uri_target_uri.get()
     }
 
     #[cfg(test)]
@@ -77,7 +217,7 @@ impl Renderer {
     }
 
     pub fn get_html_document(&self, uri: &Uri) -> Option<HTMLDocument> {
-        let cache = &self.render_cache[uri];
+        let cache = self.render_cache.get(uri)?;
         if let RenderCache::VueRenderCache(cache) = cache {
             let mut roots = vec![];
             if let Some(template) = &cache.template {
@@ -102,6 +242,41 @@ impl Renderer {
         }
     }
 
+    /// 获取 vue 组件的渲染内容
+    pub fn get_render_content(&self, uri: &Uri) -> Option<String> {
+        self.render_cache.get(uri)?;
+        self.render_cache.get_node_render_content(uri)
+    }
+
+    /// 获取原文件中模版范围对应的渲染内容
+    ///
+    /// 返回渲染内容以及其相对于 render 插入行的位置
+    pub fn get_node_render_content_range(
+        &self,
+        uri: &Uri,
+        range: &Range,
+    ) -> Option<(String, Position)> {
+        let start = self.get_mapping_position(uri, &range.start)?;
+        let end = self.get_mapping_position(uri, &range.end)?;
+        let cache = self.render_cache.get(uri)?;
+        if let RenderCache::VueRenderCache(cache) = cache {
+            let insert_line = cache
+                .document
+                .position_at(cache.render_insert_offset as u32)
+                .line;
+            let content = cache.template_compile_result.get_content(Some(Range::new(
+                Position::new(0, start.character),
+                Position::new(0, end.character),
+            )));
+            Some((
+                content.to_string(),
+                Position::new(start.line - insert_line, start.character),
+            ))
+        } else {
+            None
+        }
+    }
+
     pub fn get_component_name(&self, uri: &Uri) -> Option<&str> {
         let cache = self.render_cache.get(uri)?;
         if let RenderCache::VueRenderCache(cache) = cache {
@@ -114,8 +289,348 @@ impl Renderer {
         }
     }
 
+    /// 如果位置处于事件绑定的值中，例如 `@click="|"`，那么返回组件及其继承的方法
+    pub fn get_event_handler_methods(
+        &self,
+        uri: &Uri,
+        position: &Position,
+    ) -> Option<Vec<RenderCacheProp>> {
+        let cache = self.render_cache.get(uri)?;
+        let RenderCache::VueRenderCache(cache) = cache else {
+            return None;
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let mut node = cache.template.as_ref()?;
+        while let Some(child) = node
+            .children
+            .iter()
+            .find(|v| v.start <= offset && offset < v.end)
+        {
+            node = child;
+        }
+        let is_in_event_value = node.attributes.iter().any(|(name, attr)| {
+            if !name.starts_with('@') && !name.starts_with("v-on:") {
+                return false;
+            }
+            let Some(value) = &attr.value else {
+                return false;
+            };
+            let value_start = template_compile::get_attr_value_offset(
+                cache.document.get_content(None),
+                attr.offset,
+                name,
+            ) + 1;
+            value.len() >= 2 && value_start <= offset && offset <= value_start + value.len() - 2
+        });
+        if !is_in_event_value {
+            return None;
+        }
+        Some(
+            self.render_cache
+                .get_effective_props(uri)
+                .into_iter()
+                .filter(|v| v.typ == RenderCachePropType::Method)
+                .collect(),
+        )
+    }
+
+    /// 模版中的位置是否提供 HTML 补全
+    ///
+    /// 光标位于标签名或属性名的单词中间时不补全，属性名只在空白字符之后或已输入部分的末尾补全
+    pub fn is_template_completion_allowed(&self, uri: &Uri, position: &Position) -> bool {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return true;
+        };
+        let Some(template) = &cache.template else {
+            return true;
+        };
+        let source = cache.document.get_content(None);
+        let offset = cache.document.offset_at(*position) as usize;
+        let mut node = template;
+        while let Some(child) = node
+            .children
+            .iter()
+            .find(|v| v.start <= offset && offset < v.end)
+        {
+            node = child;
+        }
+        let token_type = Node::find_token_type_in_node(node, offset);
+        if !matches!(
+            token_type,
+            TokenType::StartTag | TokenType::EndTag | TokenType::AttributeName
+        ) {
+            return true;
+        }
+        let is_name_char =
+            |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.' | '@' | '#');
+        let is_before_name = source[..offset].ends_with(is_name_char);
+        let is_after_name = source[offset..].starts_with(is_name_char);
+        !(is_before_name && is_after_name)
+    }
+
+    /// 获取 `ref` 属性值的补全，返回模版中尚未使用的 `@Ref` 成员
+    pub fn get_ref_value_completions(
+        &self,
+        uri: &Uri,
+        position: &Position,
+    ) -> Option<Vec<RenderCacheProp>> {
+        let cache = self.render_cache.get(uri)?;
+        let RenderCache::VueRenderCache(cache) = cache else {
+            return None;
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let template = cache.template.as_ref()?;
+        let mut node = template;
+        while let Some(child) = node
+            .children
+            .iter()
+            .find(|v| v.start <= offset && offset < v.end)
+        {
+            node = child;
+        }
+        let attr = node.attributes.get("ref")?;
+        let value = attr.value.as_ref()?;
+        let source = cache.document.get_content(None);
+        let value_start = template_compile::get_attr_value_offset(source, attr.offset, "ref") + 1;
+        if !(value.len() >= 2 && value_start <= offset && offset <= value_start + value.len() - 2) {
+            return None;
+        }
+        // 排除当前正在编辑的 ref
+        let used_refs = template_compile::get_template_refs(template, source)
+            .into_iter()
+            .filter(|v| v.range.0 != value_start)
+            .map(|v| v.name)
+            .collect::<Vec<_>>();
+        Some(
+            self.render_cache
+                .get_effective_props(uri)
+                .into_iter()
+                .filter(|v| {
+                    v.ref_params
+                        .as_ref()
+                        .is_some_and(|params| !used_refs.contains(&params.name))
+                })
+                .collect(),
+        )
+    }
+
+    /// 位置处于 `<component>` 的 `is` 属性值或 `:is` 的字符串字面量中时，返回已注册的组件名称
+    pub fn get_component_is_completions(
+        &self,
+        uri: &Uri,
+        position: &Position,
+    ) -> Option<Vec<String>> {
+        let RenderCache::VueRenderCache(cache) = self.render_cache.get(uri)? else {
+            return None;
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let mut node = cache.template.as_ref()?;
+        while let Some(child) = node
+            .children
+            .iter()
+            .find(|v| v.start <= offset && offset < v.end)
+        {
+            node = child;
+        }
+        if node.tag.as_deref() != Some("component") {
+            return None;
+        }
+        let (name, attr) = ["is", ":is", "v-bind:is"]
+            .into_iter()
+            .find_map(|name| node.attributes.get(name).map(|attr| (name, attr)))?;
+        let value = attr.value.as_ref()?;
+        let value_start = template_compile::get_attr_value_offset(
+            cache.document.get_content(None),
+            attr.offset,
+            name,
+        ) + 1;
+        if !(value.len() >= 2 && value_start <= offset && offset <= value_start + value.len() - 2) {
+            return None;
+        }
+        // 绑定的值为表达式，只有位于字符串字面量中时才提示组件名称
+        if name != "is" {
+            let before = value[1..offset - value_start + 1].trim_start();
+            let quote = before
+                .chars()
+                .next()
+                .filter(|c| ['\'', '"', '`'].contains(c))?;
+            if before[1..].contains(quote) {
+                return None;
+            }
+        }
+        Some(
+            self.render_cache
+                .get_registers(uri)
+                .into_iter()
+                .map(|(name, ..)| name)
+                .collect(),
+        )
+    }
+
+    /// 位置是否处于 `v-for` 的循环变量中，例如 `v-for="(|, index) in list"`
+    pub fn is_in_v_for_alias(&self, uri: &Uri, position: &Position) -> bool {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return false;
+        };
+        let Some(mut node) = cache.template.as_ref() else {
+            return false;
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        while let Some(child) = node
+            .children
+            .iter()
+            .find(|v| v.start <= offset && offset < v.end)
+        {
+            node = child;
+        }
+        let Some(attr) = node.attributes.get("v-for") else {
+            return false;
+        };
+        let Some(value) = &attr.value else {
+            return false;
+        };
+        if value.len() < 2 {
+            return false;
+        }
+        let value_start = template_compile::get_attr_value_offset(
+            cache.document.get_content(None),
+            attr.offset,
+            "v-for",
+        ) + 1;
+        let Some((left, _)) = template_compile::split_v_for(&value[1..value.len() - 1]) else {
+            return false;
+        };
+        value_start <= offset && offset <= value_start + left.len()
+    }
+
+    /// 获取位置的选择范围
+    ///
+    /// 位于模版中时由外到内依次为模版、元素、属性及不包含引号的属性值，
+    /// `ts_ranges` 是 tsserver 返回并转换回原文件的范围，由内到外排列，
+    /// 位于最内层范围之中的部分作为更精确的表达式范围插入
+    pub fn get_selection_range(
+        &self,
+        uri: &Uri,
+        position: &Position,
+        ts_ranges: &[Range],
+    ) -> SelectionRange {
+        let mut ranges = vec![];
+        if let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) {
+            let document = &cache.document;
+            let source = document.get_content(None);
+            let offset = document.offset_at(*position) as usize;
+            if let Some(mut node) = cache
+                .template
+                .as_ref()
+                .filter(|v| v.start <= offset && offset <= v.end)
+            {
+                let mut offsets = vec![(node.start, node.end)];
+                while let Some(child) = node
+                    .children
+                    .iter()
+                    .find(|v| v.start <= offset && offset < v.end)
+                {
+                    node = child;
+                    offsets.push((node.start, node.end));
+                }
+                let attr = node.attributes.iter().find(|(name, attr)| {
+                    let end = match &attr.value {
+                        Some(value) => {
+                            template_compile::get_attr_value_offset(source, attr.offset, name)
+                                + value.len()
+                        }
+                        None => attr.offset + name.len(),
+                    };
+                    attr.offset <= offset && offset <= end
+                });
+                if let Some((name, attr)) = attr {
+                    let name_end = attr.offset + name.len();
+                    match &attr.value {
+                        Some(value) => {
+                            let value_start =
+                                template_compile::get_attr_value_offset(source, attr.offset, name);
+                            let end = value_start + value.len();
+                            offsets.push((attr.offset, end));
+                            let is_quoted = value.len() > 1
+                                && (value.starts_with('"') || value.starts_with('\''));
+                            if is_quoted && value_start + 1 <= offset && offset < end {
+                                offsets.push((value_start + 1, end - 1));
+                            }
+                        }
+                        None => offsets.push((attr.offset, name_end)),
+                    }
+                }
+                ranges = offsets
+                    .into_iter()
+                    .map(|(start, end)| {
+                        Range::new(
+                            document.position_at(start as u32),
+                            document.position_at(end as u32),
+                        )
+                    })
+                    .collect();
+            }
+        }
+        for range in ts_ranges.iter().rev() {
+            let is_inner = ranges.last().map_or(true, |last: &Range| {
+                last != range && last.start <= range.start && range.end <= last.end
+            });
+            if is_inner {
+                ranges.push(*range);
+            }
+        }
+        if ranges.is_empty() {
+            ranges.push(Range::new(*position, *position));
+        }
+        let mut result: Option<SelectionRange> = None;
+        for range in ranges {
+            result = Some(SelectionRange {
+                range,
+                parent: result.map(Box::new),
+            });
+        }
+        result.unwrap()
+    }
+
+    /// 获取模版中 CSS Modules 类名对应的样式位置
+    ///
+    /// 例如 `$style.foo` 中的 `foo`
+    pub fn get_style_module_location(&self, uri: &Uri, position: &Position) -> Option<Location> {
+        let cache = self.render_cache.get(uri)?;
+        let RenderCache::VueRenderCache(cache) = cache else {
+            return None;
+        };
+        let source = cache.document.get_content(None);
+        let offset = cache.document.offset_at(*position) as usize;
+        let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+        let start = source[..offset]
+            .rfind(|c| !is_ident(c))
+            .map_or(0, |v| v + 1);
+        let end = source[offset..]
+            .find(|c| !is_ident(c))
+            .map_or(source.len(), |v| offset + v);
+        let class = &source[start..end];
+        let object = source[..start].strip_suffix('.')?;
+        let object_start = object.rfind(|c| !is_ident(c)).map_or(0, |v| v + 1);
+        let name = &object[object_start..];
+        let module = cache.style_modules.iter().find(|v| v.name == name)?;
+        let (class, class_offset) = module.classes.iter().find(|v| v.0 == class)?;
+        Some(Location {
+            uri: uri.clone(),
+            range: Range::new(
+                cache.document.position_at(*class_offset as u32),
+                cache
+                    .document
+                    .position_at((class_offset + class.len()) as u32),
+            ),
+        })
+    }
+
     /// 获取标签对应的组件位置
     pub fn get_component_location(&self, uri: &Uri, tag: &str) -> Option<Location> {
+        if vue_data::is_builtin_component(tag) {
+            return None;
+        }
         let (mut registered_uri, register) = self.render_cache.get_register(uri, tag)?;
         let mut export_name = register.export_name.clone();
         let range;
@@ -156,790 +671,5123 @@ impl Renderer {
         })
     }
 
-    pub fn get_component_prop_location(
-        &self,
-        uri: &Uri,
-        tag: &str,
-        attr: &str,
-    ) -> Option<Location> {
-        let attr = if attr.starts_with(":") {
-            &attr[1..]
-        } else {
-            attr
-        };
-        let (mut registered_uri, register) = self.render_cache.get_register(uri, tag)?;
-        let mut export_name = register.export_name.clone();
-        let range;
-        loop {
-            let node = self.render_cache.get(registered_uri)?;
-            match node {
-                RenderCache::VueRenderCache(cache) => {
-                    let prop = cache.props.iter().find(|v| v.name == attr)?;
-                    range = Range {
-                        start: cache.document.position_at(prop.range.0 as u32),
-                        end: cache.document.position_at(prop.range.1 as u32),
-                    };
-                    break;
-                }
-                RenderCache::TsRenderCache(cache) => {
-                    if export_name.is_none() && cache.ts_component.is_some() {
-                        let prop = cache
-                            .ts_component
-                            .as_ref()?
-                            .props
-                            .iter()
-                            .find(|v| v.name == attr)?;
-                        range = Range {
-                            start: cache.document.position_at(prop.range.0 as u32),
-                            end: cache.document.position_at(prop.range.1 as u32),
-                        };
-                        break;
-                    } else {
-                        let (transfer_uri, export) = self
-                            .render_cache
-                            .get_transfer_node(registered_uri, &export_name)?;
-                        registered_uri = transfer_uri;
-                        export_name = export;
-                    }
-                }
-                RenderCache::LibRenderCache(cache) => {
-                    let component = cache
-                        .components
-                        .iter()
-                        .find(|c| export_name.as_ref().is_some_and(|name| name == &c.name))?;
-                    let prop = component.props.iter().find(|v| v.name == attr)?;
-                    return Some(prop.location.clone());
-                }
+    /// 获取动态组件 `<component :is="'X'">` 中字符串字面量指定的标签及其位置
+    ///
+    /// 同时支持静态的 `is="X"`，`:is` 为表达式时返回 None
+    pub fn get_dynamic_component_tag(
+        node: &Node,
+        source: &str,
+    ) -> Option<(String, (usize, usize))> {
+        if node.tag.as_deref() != Some("component") {
+            return None;
+        }
+        for name in [":is", "v-bind:is", "is"] {
+            let Some(attr) = node.attributes.get(name) else {
+                continue;
             };
+            let value = attr.value.as_ref()?;
+            if value.len() < 2 || !(value.starts_with('"') || value.starts_with('\'')) {
+                return None;
+            }
+            let mut expr = &value[1..value.len() - 1];
+            let mut offset = template_compile::get_attr_value_offset(source, attr.offset, name) + 1;
+            if name != "is" {
+                let trimmed = expr.trim_start();
+                offset += expr.len() - trimmed.len();
+                expr = trimmed.trim_end();
+                let quote = expr.chars().next()?;
+                if !['\'', '"', '`'].contains(&quote)
+                    || expr.len() < 2
+                    || !expr.ends_with(quote)
+                    || expr[1..expr.len() - 1].contains(quote)
+                {
+                    return None;
+                }
+                expr = &expr[1..expr.len() - 1];
+                offset += 1;
+            }
+            if expr.is_empty() {
+                return None;
+            }
+            return Some((expr.to_string(), (offset, offset + expr.len())));
         }
-        Some(Location {
-            uri: registered_uri.clone(),
-            range,
-        })
+        None
     }
 
-    pub fn get_component_prop_type(&self, uri: &Uri, prop: &str) -> Option<&str> {
-        let cache = self.render_cache.get(uri)?;
-        if let RenderCache::VueRenderCache(cache) = cache {
-            let prop = cache.props.iter().find(|v| v.name == prop)?;
-            match prop.typ {
-                RenderCachePropType::Property => Some("property"),
-                RenderCachePropType::Method => Some("method"),
-            }
-        } else {
-            None
+    /// 获取位置所在的动态组件字符串字面量指定的标签及其范围
+    fn get_dynamic_component_at(&self, uri: &Uri, position: &Position) -> Option<(String, Range)> {
+        let RenderCache::VueRenderCache(cache) = self.render_cache.get(uri)? else {
+            return None;
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let mut node = cache.template.as_ref()?;
+        while let Some(child) = node
+            .children
+            .iter()
+            .find(|v| v.start <= offset && offset < v.end)
+        {
+            node = child;
+        }
+        let (tag, (start, end)) =
+            Renderer::get_dynamic_component_tag(node, cache.document.get_content(None))?;
+        if offset < start || offset > end {
+            return None;
         }
+        let range = Range::new(
+            cache.document.position_at(start as u32),
+            cache.document.position_at(end as u32),
+        );
+        Some((tag, range))
     }
-}
 
-/// tools
-impl Renderer {
-    pub fn get_line_end(&self, uri: &Uri, line: u32) -> u32 {
-        Renderer::get_line_end_by_document(self.get_document(uri), line)
+    /// 获取位置所在的动态组件字符串字面量对应的组件位置
+    pub fn get_dynamic_component_location(
+        &self,
+        uri: &Uri,
+        position: &Position,
+    ) -> Option<Location> {
+        let (tag, _) = self.get_dynamic_component_at(uri, position)?;
+        self.get_component_location(uri, &tag)
     }
 
-    /// 脚本开始位置
-    pub fn start_position(&self, uri: &Uri) -> Option<Position> {
-        let cache = self.render_cache.get(uri)?;
-        if let RenderCache::VueRenderCache(cache) = cache {
-            Some(
-                cache
-                    .document
-                    .position_at(cache.script.as_ref()?.start_tag_end.unwrap() as u32),
-            )
-        } else {
-            None
-        }
+    /// 获取位置所在的动态组件字符串字面量对应的组件悬停信息
+    pub fn get_dynamic_component_hover(&self, uri: &Uri, position: &Position) -> Option<Hover> {
+        let (tag, range) = self.get_dynamic_component_at(uri, position)?;
+        let component_uri = self.get_registered_component_uri(uri, &tag)?;
+        let description = match self.render_cache.get(component_uri)? {
+            RenderCache::VueRenderCache(cache) => cache.description.clone(),
+            RenderCache::TsRenderCache(cache) => cache.ts_component.as_ref()?.description.clone(),
+            RenderCache::LibRenderCache(_) => return None,
+        };
+        let Some(Description::MarkupContent(markup)) =
+            tags_provider::append_module_path(description, component_uri)
+        else {
+            return None;
+        };
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```html\n<{}>\n```\n{}", tag, markup.value),
+            }),
+            range: Some(range),
+        })
     }
 
-    /// 脚本结束位置
-    pub fn end_position(&self, uri: &Uri) -> Option<Position> {
-        let cache = self.render_cache.get(uri)?;
-        if let RenderCache::VueRenderCache(cache) = cache {
-            Some(
+    /// 获取位置所在的组件标签属性对应的 prop 悬停信息
+    ///
+    /// 与 HTML 全局属性同名（如 `title`）时优先使用组件的 prop，原生元素和组件库的组件返回 None
+    pub fn get_component_prop_hover(&self, uri: &Uri, position: &Position) -> Option<Hover> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return None;
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let mut node = cache.template.as_ref()?;
+        while let Some(child) = node
+            .children
+            .iter()
+            .find(|v| v.start <= offset && offset < v.end)
+        {
+            node = child;
+        }
+        let (attr_name, attr) = node
+            .attributes
+            .iter()
+            .find(|(name, attr)| attr.offset <= offset && offset < attr.offset + name.len())?;
+        let tag = Renderer::get_dynamic_component_tag(node, cache.document.get_content(None))
+            .map(|(tag, _)| tag)
+            .or_else(|| node.tag.clone())?;
+        let component_uri = self.get_registered_component_uri(uri, &tag)?;
+        let name = tags_provider::get_prop_name_of_attribute(attr_name);
+        let prop = self
+            .get_effective_props(component_uri)
+            .into_iter()
+            .find(|v| v.prop_params.is_some() && util::to_kebab_case(&v.name) == name)?;
+        let typ = prop.get_prop_ts_type().unwrap_or("any".to_string());
+        let mut value = format!(
+            "```typescript\n(prop) {}.{}: {}\n```",
+            self.get_component_name(component_uri).unwrap_or("Default"),
+            prop.name,
+            typ
+        );
+        match prop.description {
+            Some(Description::MarkupContent(markup)) => {
+                value += &format!("\n{}", markup.value);
+            }
+            Some(Description::String(description)) => {
+                value += &format!("\n{}", description);
+            }
+            None => {}
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(Range::new(
+                cache.document.position_at(attr.offset as u32),
                 cache
                     .document
-                    .position_at(cache.script.as_ref()?.end_tag_start.unwrap() as u32),
-            )
-        } else {
-            None
-        }
+                    .position_at((attr.offset + attr_name.len()) as u32),
+            )),
+        })
     }
-    pub async fn get_document_from_file(uri: &Uri) -> Result<FullTextDocument, Error> {
-        let mut content = String::new();
 
-        let path = util::to_file_path_string(uri);
-        match File::open(&path).await {
-            Ok(mut file) => {
-                if let Err(err) = file.read_to_string(&mut content).await {
-                    error!("error: {} - {}", path, err);
-                    return Err(err);
+    /// 获取组件在其他组件模版中被使用的位置，按文件分组
+    pub fn get_component_usages(&self, uri: &Uri) -> Vec<Location> {
+        if self.render_cache.get(uri).is_none() {
+            return vec![];
+        }
+        let mut parents = self.render_cache.get_register_parents(uri);
+        parents.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        let mut result = vec![];
+        for (parent_uri, registered_name) in parents {
+            let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(parent_uri) else {
+                continue;
+            };
+            let Some(template) = &cache.template else {
+                continue;
+            };
+            let kebab_name = util::to_kebab_case(registered_name);
+            let mut ranges = vec![];
+            let mut nodes = vec![template];
+            while let Some(node) = nodes.pop() {
+                nodes.extend(node.children.iter());
+                let Some(tag) = &node.tag else {
+                    continue;
+                };
+                if tag == registered_name || tag == &kebab_name {
+                    let start = node.start + 1;
+                    ranges.push(Range::new(
+                        cache.document.position_at(start as u32),
+                        cache.document.position_at((start + tag.len()) as u32),
+                    ));
                 }
             }
-            Err(err) => {
-                error!("error: {} - {}", path, err);
-                return Err(Error::new(std::io::ErrorKind::NotFound, path));
-            }
+            ranges.sort_by(|a, b| a.start.cmp(&b.start));
+            result.extend(ranges.into_iter().map(|range| Location {
+                uri: parent_uri.clone(),
+                range,
+            }));
         }
-        let language_id = path[path.rfind(".").unwrap() + 1..].to_string();
-        Ok(FullTextDocument::new(language_id, 1, content))
+        result
     }
 
-    pub fn is_vue_component(uri: &Uri) -> bool {
-        util::to_file_path(uri)
-            .extension()
-            .is_some_and(|v| v == "vue")
+    /// 获取位置所在的 `@Ref`, `@Emit` 装饰器在模版中对应的位置
+    ///
+    /// `@Ref` 对应自身模版中的 `ref` 属性，`@Emit` 对应其他组件模版中监听该事件的属性，
+    /// 未指定参数时分别为成员名称和方法名称的短横线形式
+    pub fn get_decorator_template_locations(
+        &self,
+        uri: &Uri,
+        position: &Position,
+    ) -> Vec<Location> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let Some(prop) = cache.props.iter().find(|v| {
+            v.decorators_range
+                .is_some_and(|(start, end)| start <= offset && offset <= end)
+        }) else {
+            return vec![];
+        };
+        if let Some(ref_params) = &prop.ref_params {
+            self.get_template_ref_locations(uri, &ref_params.name)
+        } else if let Some(emit_name) = &prop.emit_name {
+            self.get_event_listener_locations(uri, emit_name)
+        } else {
+            vec![]
+        }
     }
 
-    /// uri 是否有效
-    /// * 是文件
-    /// * 存在于文件系统中
-    /// * 不在 node_modules 中
-    /// * 不在 .git 中
-    pub fn is_uri_valid(uri: &Uri) -> bool {
-        let file_path = util::to_file_path(uri);
-        if cfg!(not(test)) {
-            let file_path_str = file_path.to_string_lossy();
-            file_path.is_file()
-                && !file_path_str.contains("/node_modules/")
-                && !file_path_str.contains("/.git/")
-        } else {
-            !file_path.to_string_lossy().contains("/node_modules/")
-        }
+    /// 获取组件模版中静态 `ref` 属性值为指定名称的位置
+    pub fn get_template_ref_locations(&self, uri: &Uri, name: &str) -> Vec<Location> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
+        };
+        let Some(template) = &cache.template else {
+            return vec![];
+        };
+        template_compile::get_template_refs(template, cache.document.get_content(None))
+            .into_iter()
+            .filter(|v| v.name == name)
+            .map(|v| Location {
+                uri: uri.clone(),
+                range: Range::new(
+                    cache.document.position_at(v.range.0 as u32),
+                    cache.document.position_at(v.range.1 as u32),
+                ),
+            })
+            .collect()
     }
 
-    /// uri 是否指向 node_modules 下的库
-    /// * 是目录
-    /// * 存在于文件系统中
-    pub fn is_node_modules(uri: &Uri) -> bool {
-        let file_path = util::to_file_path(uri);
-        if cfg!(not(test)) {
-            file_path.is_dir() && file_path.to_string_lossy().contains("/node_modules/")
-        } else {
-            file_path.to_string_lossy().contains("/node_modules/")
+    /// 获取其他组件模版中监听该组件指定事件的属性位置，按文件分组
+    pub fn get_event_listener_locations(&self, uri: &Uri, event: &str) -> Vec<Location> {
+        let mut parents = self.render_cache.get_register_parents(uri);
+        parents.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        let mut result = vec![];
+        for (parent_uri, registered_name) in parents {
+            let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(parent_uri) else {
+                continue;
+            };
+            let Some(template) = &cache.template else {
+                continue;
+            };
+            let kebab_name = util::to_kebab_case(registered_name);
+            let mut ranges = vec![];
+            let mut nodes = vec![template];
+            while let Some(node) = nodes.pop() {
+                nodes.extend(node.children.iter());
+                if !node
+                    .tag
+                    .as_ref()
+                    .is_some_and(|tag| tag == registered_name || tag == &kebab_name)
+                {
+                    continue;
+                }
+                for (key, attr) in &node.attributes {
+                    let Some(name) = key.strip_prefix('@').or_else(|| key.strip_prefix("v-on:"))
+                    else {
+                        continue;
+                    };
+                    if name.split('.').next() == Some(event) {
+                        ranges.push(Range::new(
+                            cache.document.position_at(attr.offset as u32),
+                            cache.document.position_at((attr.offset + key.len()) as u32),
+                        ));
+                    }
+                }
+            }
+            ranges.sort_by(|a, b| a.start.cmp(&b.start));
+            result.extend(ranges.into_iter().map(|range| Location {
+                uri: parent_uri.clone(),
+                range,
+            }));
         }
+        result
     }
 
-    pub fn is_position_valid_by_document(
-        document: Option<&FullTextDocument>,
+    /// 获取位置所在的 `@Prop` 属性的调用层次项，范围为属性的声明位置
+    pub fn get_prop_call_hierarchy_item(
+        &self,
+        uri: &Uri,
         position: &Position,
-    ) -> bool {
-        if let Some(document) = document {
-            let start = document.offset_at(Position::new(position.line, 0));
-            let end = document.offset_at(Position::new(position.line + 1, 0));
-            position.character < end - start
-        } else {
-            false
+    ) -> Option<CallHierarchyItem> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return None;
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let prop = cache
+            .props
+            .iter()
+            .find(|v| v.has_decorator("Prop") && v.range.0 <= offset && offset <= v.range.1)?;
+        let range = Range::new(
+            cache.document.position_at(prop.range.0 as u32),
+            cache.document.position_at(prop.range.1 as u32),
+        );
+        Some(CallHierarchyItem {
+            name: prop.name.clone(),
+            kind: SymbolKind::PROPERTY,
+            tags: None,
+            detail: self.get_component_name(uri).map(|v| v.to_string()),
+            uri: uri.clone(),
+            range,
+            selection_range: range,
+            data: None,
+        })
+    }
+
+    /// 获取其他组件模版中向该组件 prop 传值的属性，作为调用层次的传入调用，按文件分组
+    ///
+    /// 包括 `:title="expr"`、`v-bind:title` 及静态的 `title="value"`，范围为属性值
+    pub fn get_prop_incoming_calls(&self, uri: &Uri, prop: &str) -> Vec<CallHierarchyIncomingCall> {
+        let prop = util::to_kebab_case(prop);
+        let mut parents = self.render_cache.get_register_parents(uri);
+        parents.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        let mut result = vec![];
+        for (parent_uri, registered_name) in parents {
+            let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(parent_uri) else {
+                continue;
+            };
+            let Some(template) = &cache.template else {
+                continue;
+            };
+            let kebab_name = util::to_kebab_case(registered_name);
+            let mut bindings = vec![];
+            let mut nodes = vec![template];
+            while let Some(node) = nodes.pop() {
+                nodes.extend(node.children.iter());
+                if !node
+                    .tag
+                    .as_ref()
+                    .is_some_and(|tag| tag == registered_name || tag == &kebab_name)
+                {
+                    continue;
+                }
+                for (key, attr) in &node.attributes {
+                    if key.starts_with('@') || key.starts_with("v-on:") {
+                        continue;
+                    }
+                    if tags_provider::get_prop_name_of_attribute(key) != prop {
+                        continue;
+                    }
+                    let value_offset = template_compile::get_attr_value_offset(
+                        cache.document.get_content(None),
+                        attr.offset,
+                        key,
+                    );
+                    let (start, end) = match &attr.value {
+                        Some(value)
+                            if value.len() > 1
+                                && (value.starts_with('"') || value.starts_with('\'')) =>
+                        {
+                            (value_offset + 1, value_offset + value.len() - 1)
+                        }
+                        Some(value) => (value_offset, value_offset + value.len()),
+                        None => (attr.offset, attr.offset + key.len()),
+                    };
+                    bindings.push((key, start, end));
+                }
+            }
+            bindings.sort_by_key(|v| v.1);
+            let detail = self.get_component_name(parent_uri).map(|v| v.to_string());
+            result.extend(bindings.into_iter().map(|(key, start, end)| {
+                let range = Range::new(
+                    cache.document.position_at(start as u32),
+                    cache.document.position_at(end as u32),
+                );
+                CallHierarchyIncomingCall {
+                    from: CallHierarchyItem {
+                        name: key.clone(),
+                        kind: SymbolKind::FIELD,
+                        tags: None,
+                        detail: detail.clone(),
+                        uri: parent_uri.clone(),
+                        range,
+                        selection_range: range,
+                        data: None,
+                    },
+                    from_ranges: vec![range],
+                }
+            }));
         }
+        result
     }
 
-    pub fn get_line_end_by_document(document: Option<&FullTextDocument>, line: u32) -> u32 {
-        if let Some(document) = document {
-            let start = document.offset_at(Position::new(line, 0));
-            let end = document.offset_at(Position::new(line, u32::MAX));
-            let content = document.get_content(Some(Range::new(
-                document.position_at(start),
-                document.position_at(end),
-            )));
-            if content.ends_with("\r\n") {
-                end - start - 2
-            } else if content.ends_with("\n") {
-                end - start - 1
-            } else {
-                end - start
+    /// 获取标签对应的已注册组件的节点，组件库中的组件返回 None
+    ///
+    /// 标签可以是注册名称本身或其短横线形式
+    fn get_registered_component_uri(&self, uri: &Uri, tag: &str) -> Option<&Uri> {
+        if vue_data::is_builtin_component(tag) {
+            return None;
+        }
+        let (_, export_name, prop, mut registered_uri) = self
+            .render_cache
+            .get_registers(uri)
+            .into_iter()
+            .find(|(name, ..)| name == tag || util::to_kebab_case(name) == tag)?;
+        if prop.is_some() {
+            return None;
+        }
+        let mut export_name = export_name;
+        loop {
+            match self.render_cache.get(registered_uri)? {
+                RenderCache::VueRenderCache(_) => return Some(registered_uri),
+                RenderCache::TsRenderCache(cache) => {
+                    if export_name.is_none() && cache.ts_component.is_some() {
+                        return Some(registered_uri);
+                    }
+                    let (transfer_uri, export) = self
+                        .render_cache
+                        .get_transfer_node(registered_uri, &export_name)?;
+                    registered_uri = transfer_uri;
+                    export_name = export;
+                }
+                RenderCache::LibRenderCache(_) => return None,
             }
-        } else {
-            0
         }
     }
 
-    /// 获取目标路径
-    fn get_target_path(uri: &Uri, root_uri: &Uri, target_root_uri: &Uri) -> PathBuf {
-        let src_path = util::to_file_path(uri);
-        let root_path = util::to_file_path(root_uri);
-        let target_root_path = util::to_file_path(target_root_uri);
-        // 计算相对路径
-        let rel_path = src_path.strip_prefix(&root_path).unwrap().to_path_buf();
-        // 转换为目标路径
-        let mut target_path = target_root_path.join(rel_path);
-        if let Some(file_name) = target_path.file_name() {
-            if file_name.to_string_lossy().ends_with(".vue") {
-                let new_file_name = format!("{}.ts", file_name.to_string_lossy());
-                target_path.set_file_name(new_file_name);
+    /// 获取子组件中指定插槽通过 `<slot :x="...">` 绑定的属性及其类型
+    ///
+    /// 类型无法推断时为 `any`
+    pub fn get_slot_props(&self, child_uri: &Uri, slot_name: &str) -> Vec<(String, String)> {
+        self.get_slots(child_uri)
+            .remove(slot_name)
+            .unwrap_or_default()
+    }
+
+    /// 获取组件注册的子组件的作用域插槽属性，用于模版编译
+    pub(super) fn get_component_slot_props(&self, uri: &Uri) -> template_compile::SlotProps {
+        let mut slot_props = template_compile::SlotProps::new();
+        for (name, ..) in self.render_cache.get_registers(uri) {
+            if let Some(child_uri) = self.get_registered_component_uri(uri, &name) {
+                let slots = self.get_slots(child_uri);
+                if !slots.is_empty() {
+                    slot_props.insert(name, slots);
+                }
             }
         }
-        target_path
+        slot_props
     }
 
-    /// 获取原路径
-    pub fn get_source_path(uri: &Uri, root_uri: &Uri, target_root_uri: &Uri) -> PathBuf {
-        let target_path = util::to_file_path(uri);
-        let root_path = util::to_file_path(root_uri);
-        let target_root_path = util::to_file_path(target_root_uri);
-        // 计算相对路径
-        let rel_path = target_path
-            .strip_prefix(target_root_path)
-            .unwrap()
-            .to_path_buf();
-        // 转换为原路径
-        let mut source_path = root_path.join(rel_path);
-        if let Some(file_name) = source_path.file_name() {
-            let file_name = file_name.to_string_lossy().to_string();
-            if file_name.ends_with(".vue.ts") {
-                source_path.set_file_name(&file_name[..file_name.len() - 3]);
+    /// 获取 vue 组件模版中所有插槽绑定的属性及其类型，插槽名称 -> Vec<(属性名称, 类型)>
+    fn get_slots(&self, uri: &Uri) -> HashMap<String, Vec<(String, String)>> {
+        let mut slots: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return slots;
+        };
+        let Some(template) = &cache.template else {
+            return slots;
+        };
+        let props = self.render_cache.get_effective_props(uri);
+        let mut nodes = vec![template];
+        while let Some(node) = nodes.pop() {
+            nodes.extend(node.children.iter().rev());
+            if node.tag.as_deref() != Some("slot") {
+                continue;
+            }
+            let slot_name = node
+                .attributes
+                .get("name")
+                .and_then(|v| v.value.as_ref())
+                .filter(|v| v.len() > 2)
+                .map_or("default".to_string(), |v| v[1..v.len() - 1].to_string());
+            let slot = slots.entry(slot_name).or_default();
+            for key in node.attribute_names_by_order() {
+                let Some(name) = key
+                    .strip_prefix("v-bind:")
+                    .or_else(|| key.strip_prefix(':'))
+                else {
+                    continue;
+                };
+                if name.is_empty() || name == "name" || slot.iter().any(|(v, _)| v == name) {
+                    continue;
+                }
+                let typ = node
+                    .attributes
+                    .get(key)
+                    .and_then(|v| v.value.as_ref())
+                    .filter(|v| v.len() > 1)
+                    .map(|v| {
+                        Renderer::get_slot_prop_type(
+                            &v[1..v.len() - 1],
+                            &props,
+                            &cache.local_type_names,
+                        )
+                    });
+                slot.push((name.to_string(), typ.unwrap_or("any".to_string())));
             }
         }
-        source_path
+        slots
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::{
-        collections::{HashMap, HashSet},
-        str::FromStr,
-    };
+    /// 推断插槽绑定表达式的类型
+    ///
+    /// 支持字面量及带有类型注解的成员，类型注解引用子组件中导入或声明的类型时为 `any`
+    fn get_slot_prop_type(
+        expr: &str,
+        props: &[RenderCacheProp],
+        local_type_names: &[String],
+    ) -> String {
+        let expr = expr.trim();
+        if expr.len() > 1
+            && (expr.starts_with('\'') && expr.ends_with('\'')
+                || expr.starts_with('`') && expr.ends_with('`'))
+        {
+            return "string".to_string();
+        }
+        if expr == "true" || expr == "false" {
+            return "boolean".to_string();
+        }
+        if expr.parse::<f64>().is_ok() {
+            return "number".to_string();
+        }
+        let name = expr.strip_prefix("this.").unwrap_or(expr);
+        let typ = props
+            .iter()
+            .find(|v| v.name == name)
+            .and_then(|v| v.ts_type.as_ref());
+        let is_local_type = |typ: &str| {
+            typ.split(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '$')
+                .any(|ident| local_type_names.iter().any(|v| v == ident))
+        };
+        match typ {
+            Some(typ) if !is_local_type(typ) => typ.clone(),
+            _ => "any".to_string(),
+        }
+    }
 
-    use lsp_textdocument::FullTextDocument;
-    use tokio::sync::OnceCell;
-    use tower_lsp::lsp_types::{
-        DidChangeTextDocumentParams, Position, Range, TextDocumentContentChangeEvent, Uri,
-        VersionedTextDocumentIdentifier,
-    };
+    pub fn get_component_prop_location(
+        &self,
+        uri: &Uri,
+        tag: &str,
+        attr: &str,
+    ) -> Option<Location> {
+        // 同时支持 `:title`、`v-bind:title` 和带修饰符的 `:title.sync` 等形式
+        let attr = &tags_provider::get_prop_name_of_attribute(attr)[..];
+        if vue_data::is_builtin_component(tag) {
+            return None;
+        }
+        let (registered_uri, register) = self.render_cache.get_register(uri, tag)?;
+        // 自身未声明的属性沿继承链查找，基类可能定义在 ts 文件中
+        let mut stack = vec![(registered_uri, register.export_name.clone())];
+        let mut visited = HashSet::new();
+        while let Some((cur_uri, export_name)) = stack.pop() {
+            if !visited.insert((cur_uri, export_name.clone())) {
+                continue;
+            }
+            let (document, props) = match self.render_cache.get(cur_uri)? {
+                RenderCache::VueRenderCache(cache) => (&cache.document, &cache.props),
+                RenderCache::TsRenderCache(cache) => match &cache.ts_component {
+                    Some(ts_component) if export_name.is_none() => {
+                        (&cache.document, &ts_component.props)
+                    }
+                    _ => {
+                        if let Some(transfer) =
+                            self.render_cache.get_transfer_node(cur_uri, &export_name)
+                        {
+                            stack.push(transfer);
+                        }
+                        continue;
+                    }
+                },
+                RenderCache::LibRenderCache(cache) => {
+                    let component = cache
+                        .components
+                        .iter()
+                        .find(|c| export_name.as_ref().is_some_and(|name| name == &c.name))?;
+                    let prop = component
+                        .props
+                        .iter()
+                        .find(|v| util::to_kebab_case(&v.name) == attr)?;
+                    return Some(prop.location.clone());
+                }
+            };
+            let prop = props.iter().find(|v| util::to_kebab_case(&v.name) == attr);
+            if let Some(prop) = prop {
+                return Some(Location {
+                    uri: cur_uri.clone(),
+                    range: Range {
+                        start: document.position_at(prop.range.0 as u32),
+                        end: document.position_at(prop.range.1 as u32),
+                    },
+                });
+            }
+            stack.extend(
+                self.render_cache
+                    .get_extends_nodes(cur_uri)
+                    .into_iter()
+                    .rev(),
+            );
+        }
+        None
+    }
 
-    use crate::renderer::{render_cache::RenderCacheGraph, Renderer};
-    use lazy_static::lazy_static;
+    /// 获取继承当前组件的子组件位置
+    ///
+    /// 位于组件类名时返回子组件类名位置，位于属性时返回子组件中同名属性的位置，
+    /// 没有找到时返回 None
+    pub fn get_implementation_locations(
+        &self,
+        uri: &Uri,
+        position: &Position,
+    ) -> Option<Vec<Location>> {
+        let (document, name_range, props) = self.get_component_declaration(uri)?;
+        let prop_name = if name_range.start <= *position && *position <= name_range.end {
+            None
+        } else {
+            let offset = document.offset_at(*position) as usize;
+            let prop = props
+                .iter()
+                .find(|v| v.range.0 <= offset && offset <= v.range.1)?;
+            Some(&prop.name)
+        };
+        let mut result = vec![];
+        for sub_uri in self.render_cache.get_subtypes(uri) {
+            if let Some((document, name_range, props)) = self.get_component_declaration(sub_uri) {
+                if let Some(prop_name) = prop_name {
+                    if let Some(prop) = props.iter().find(|v| &v.name == prop_name) {
+                        result.push(Location {
+                            uri: sub_uri.clone(),
+                            range: Range {
+                                start: document.position_at(prop.range.0 as u32),
+                                end: document.position_at(prop.range.1 as u32),
+                            },
+                        });
+                    }
+                } else {
+                    result.push(Location {
+                        uri: sub_uri.clone(),
+                        range: name_range,
+                    });
+                }
+            }
+        }
+        (!result.is_empty()).then_some(result)
+    }
 
-    use super::PositionType;
+    /// 获取文件中定义的组件的文档、类名范围和属性
+    fn get_component_declaration(
+        &self,
+        uri: &Uri,
+    ) -> Option<(&FullTextDocument, Range, &Vec<RenderCacheProp>)> {
+        match self.render_cache.get(uri)? {
+            RenderCache::VueRenderCache(cache) => Some((
+                &cache.document,
+                Range {
+                    start: cache.document.position_at(cache.name_range.0 as u32),
+                    end: cache.document.position_at(cache.name_range.1 as u32),
+                },
+                &cache.props,
+            )),
+            RenderCache::TsRenderCache(cache) => {
+                let component = cache.ts_component.as_ref()?;
+                Some((&cache.document, component.name_range, &component.props))
+            }
+            RenderCache::LibRenderCache(_) => None,
+        }
+    }
 
-    lazy_static! {
-        static ref TEST1_INDEX: Uri =
-            Uri::from_str("file:///path/project/src/test1/index.vue").unwrap();
-        static ref TEST1_COMPONENT1: Uri =
-            Uri::from_str("file:///path/project/src/test1/components/MyComponent1.vue").unwrap();
-        static ref TEST1_COMPONENT2: Uri =
-            Uri::from_str("file:///path/project/src/test1/components/MyComponent2.vue").unwrap();
-        static ref TEST1_COMPONENT3: Uri =
-            Uri::from_str("file:///path/project/src/test1/components/MyComponent3.vue").unwrap();
-        static ref TEST2_INDEX: Uri =
-            Uri::from_str("file:///path/project/src/test2/index.vue").unwrap();
-        static ref TEST2_TS: Uri =
-            Uri::from_str("file:///path/project/src/test2/ts/transfer.ts").unwrap();
-        static ref TEST2_COMPONENT4: Uri =
-            Uri::from_str("file:///path/project/src/test2/components/MyComponent4.vue").unwrap();
-        static ref TEST2_COMPONENT5: Uri =
-            Uri::from_str("file:///path/project/src/test2/components/MyComponent5.vue").unwrap();
+    /// 获取模版表达式中可以补全的全局属性，位于成员访问 `a.|` 中时返回空
+    pub fn get_global_property_completions(
+        &self,
+        uri: &Uri,
+        position: &Position,
+    ) -> Vec<LibComponentProp> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let before = cache.document.get_content(None)[..offset]
+            .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_' || c == '$');
+        if before.ends_with('.') {
+            return vec![];
+        }
+        self.render_cache
+            .get_global_properties()
+            .into_iter()
+            .cloned()
+            .collect()
     }
 
-    fn create_renderer() -> Renderer {
-        let cache_graph = RenderCacheGraph::new();
-        let mut renderer = Renderer {
-            root_uri_target_uri: OnceCell::from((
-                Uri::from_str("file:///path/project").unwrap(),
-                Uri::from_str("file:///path/.~$project").unwrap(),
+    /// 获取组件沿继承链展开后的所有属性
+    ///
+    /// 结果缓存在节点中，节点或祖先节点变化时缓存失效
+    pub fn get_effective_props(&self, uri: &Uri) -> Vec<RenderCacheProp> {
+        self.render_cache.get_effective_props(uri)
+    }
+
+    /// 获取组件的 model，包括继承的属性
+    ///
+    /// 优先使用 `@Model`, `@ModelSync`, `@VModel` 声明的 model，其次为 `@Component` 的 `model` 选项，
+    /// 最后为 `value` 属性，都不存在时返回 None
+    pub fn get_component_model(&self, uri: &Uri) -> Option<ComponentModel> {
+        let props = self.render_cache.get_effective_props(uri);
+        if let Some(params) = props.iter().find_map(|v| v.model_params.as_ref()) {
+            return Some(ComponentModel {
+                prop: params.prop.clone(),
+                typ: params.typ.clone(),
+                event: params.event.clone(),
+                writable: params.writable,
+            });
+        }
+        if let Some((prop, event)) = match self.render_cache.get(uri) {
+            Some(RenderCache::VueRenderCache(cache)) => cache.options_model.as_ref(),
+            _ => None,
+        } {
+            return Some(ComponentModel {
+                prop: prop.clone(),
+                typ: props
+                    .iter()
+                    .find(|v| &v.name == prop && v.prop_params.is_some())
+                    .and_then(|v| v.get_prop_ts_type()),
+                event: event.clone(),
+                writable: false,
+            });
+        }
+        let prop = props
+            .into_iter()
+            .find(|v| v.name == "value" && v.prop_params.is_some())?;
+        Some(ComponentModel {
+            prop: prop.name,
+            typ: prop.get_prop_ts_type(),
+            event: "input".to_string(),
+            writable: false,
+        })
+    }
+
+    /// 获取 `@Prop`, `@Model` 等装饰器选项键的悬停信息
+    pub fn get_decorator_option_hover(&self, uri: &Uri, position: &Position) -> Option<Hover> {
+        let RenderCache::VueRenderCache(cache) = self.render_cache.get(uri)? else {
+            return None;
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let (key, range) = cache
+            .decorator_option_keys
+            .iter()
+            .find(|(_, range)| range.0 <= offset && offset <= range.1)?;
+        let description = decorator_options::get_decorator_option_description(key)?;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```ts\n(option) {}\n```\n{}", key, description),
+            }),
+            range: Some(Range::new(
+                cache.document.position_at(range.0 as u32),
+                cache.document.position_at(range.1 as u32),
             )),
-            alias: HashMap::new(),
-            render_cache: cache_graph,
-            provider_map: HashMap::new(),
-            library_list: vec![],
-            will_create_files: HashSet::new(),
+        })
+    }
+
+    /// 位置处于组件类体中成员名称处时，返回尚未定义的生命周期钩子及其说明
+    ///
+    /// 当前行光标前只能是修饰符和正在输入的标识符
+    pub fn get_lifecycle_hook_completions(
+        &self,
+        uri: &Uri,
+        position: &Position,
+    ) -> Vec<(&'static str, &'static str)> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
         };
-        // test1/index.vue
-        renderer.create_node_from_document(
-            &TEST1_INDEX,
-            FullTextDocument::new(
-                "vue".to_string(),
-                0,
-                [
-                    "<template>",
-                    "  <MyComponent1 title=\"Title\" />",
-                    "</template>",
-                    "<script lang=\"ts\">",
-                    "import Vue from 'vue';",
-                    "import { Component } from 'vue-property-decorator';",
-                    "import MyComponent1 from './components/MyComponent1.vue';",
-                    "@Component({",
-                    "  components: {",
-                    "    MyComponent1,",
-                    "  },",
-                    "})",
-                    "export default class Index extends Vue {",
-                    "}",
-                    "</script>",
-                ]
-                .join("\n")
-                .to_string(),
-            ),
-        );
-        // test1/components/MyComponent1.vue
+        let source = cache.document.get_content(None);
+        let offset = cache.document.offset_at(*position) as usize;
+        if !Renderer::is_in_class_body(cache, offset) {
+            return vec![];
+        }
+        let line_start = source[..offset].rfind('\n').map_or(0, |v| v + 1);
+        let mut prefix = source[line_start..offset].trim_start();
+        for modifier in ["public ", "protected ", "private ", "async "] {
+            if let Some(rest) = prefix.strip_prefix(modifier) {
+                prefix = rest.trim_start();
+            }
+        }
+        if !prefix.chars().all(Renderer::is_ident_char) {
+            return vec![];
+        }
+        lifecycle_hooks::LIFECYCLE_HOOKS
+            .iter()
+            .filter(|(name, _)| cache.props.iter().all(|v| v.name != *name))
+            .copied()
+            .collect()
+    }
+
+    /// 获取组件类体中生命周期钩子方法名称的悬停信息
+    pub fn get_lifecycle_hook_hover(&self, uri: &Uri, position: &Position) -> Option<Hover> {
+        let RenderCache::VueRenderCache(cache) = self.render_cache.get(uri)? else {
+            return None;
+        };
+        let source = cache.document.get_content(None);
+        let offset = cache.document.offset_at(*position) as usize;
+        let start = source[..offset]
+            .rfind(|c| !Renderer::is_ident_char(c))
+            .map_or(0, |v| v + 1);
+        let end = source[offset..]
+            .find(|c| !Renderer::is_ident_char(c))
+            .map_or(source.len(), |v| offset + v);
+        let name = &source[start..end];
+        let description = lifecycle_hooks::get_lifecycle_hook_description(name)?;
+        // 只处理方法声明，不处理调用和同名属性
+        if !source[end..].trim_start().starts_with('(') || !Renderer::is_in_class_body(cache, start)
+        {
+            return None;
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!(
+                    "```ts\n(lifecycle hook) {}(): void\n```\n{}",
+                    name, description
+                ),
+            }),
+            range: Some(Range::new(
+                cache.document.position_at(start as u32),
+                cache.document.position_at(end as u32),
+            )),
+        })
+    }
+
+    /// 获取将选项式组件转换为类组件的修改，范围需要与 `export default` 语句相交
+    ///
+    /// 仅支持 TypeScript 脚本，未指定 `name` 选项时使用文件名作为类名
+    pub fn get_convert_to_class_edits(&self, uri: &Uri, range: &Range) -> Option<Vec<TextEdit>> {
+        let RenderCache::VueRenderCache(cache) = self.render_cache.get(uri)? else {
+            return None;
+        };
+        if cache.is_js() {
+            return None;
+        }
+        let script = cache.script.as_ref()?;
+        let path = util::to_file_path(uri);
+        let name = path.file_stem()?.to_string_lossy();
+        let result = convert_options::convert_options_to_class(
+            cache.document.get_content(None),
+            script.start_tag_end?,
+            script.end_tag_start?,
+            &name,
+        )?;
+        let start = cache.document.offset_at(range.start) as usize;
+        let end = cache.document.offset_at(range.end) as usize;
+        if end < result.export_range.0 || result.export_range.1 < start {
+            return None;
+        }
+        Some(
+            result
+                .edits
+                .into_iter()
+                .map(|((start, end), new_text)| TextEdit {
+                    range: Range::new(
+                        cache.document.position_at(start as u32),
+                        cache.document.position_at(end as u32),
+                    ),
+                    new_text,
+                })
+                .collect(),
+        )
+    }
+
+    /// 偏移是否直接位于组件类体中，不包括方法体和装饰器参数等嵌套结构
+    ///
+    /// 使用最近一次成功解析脚本得到的范围，脚本存在语法错误时范围随变更移动
+    fn is_in_class_body(cache: &VueRenderCache, offset: usize) -> bool {
+        cache.class_body_start < offset
+            && offset <= cache.render_insert_offset
+            && !cache
+                .class_nested_ranges
+                .iter()
+                .any(|(start, end)| *start < offset && offset < *end)
+    }
+
+    fn is_ident_char(c: char) -> bool {
+        c.is_ascii_alphanumeric() || c == '_' || c == '$'
+    }
+
+    pub fn get_component_prop_type(&self, uri: &Uri, prop: &str) -> Option<&str> {
+        let cache = self.render_cache.get(uri)?;
+        if let RenderCache::VueRenderCache(cache) = cache {
+            let prop = cache.props.iter().find(|v| v.name == prop)?;
+            match prop.typ {
+                RenderCachePropType::Property => Some("property"),
+                RenderCachePropType::Method => Some("method"),
+                RenderCachePropType::Computed { .. } => Some("property"),
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// symbols
+impl Renderer {
+    /// 获取组件公开接口的符号，按 Props, Events, Watchers 分组
+    pub fn get_component_symbols(&self, uri: &Uri) -> Vec<DocumentSymbol> {
+        let cache = self.render_cache.get(uri);
+        let Some(RenderCache::VueRenderCache(cache)) = cache else {
+            return vec![];
+        };
+        let groups: [(&str, &[&str], SymbolKind); 3] = [
+            (
+                "Props",
+                &["Prop", "PropSync", "Model"],
+                SymbolKind::PROPERTY,
+            ),
+            ("Events", &["Emit"], SymbolKind::EVENT),
+            ("Watchers", &["Watch"], SymbolKind::METHOD),
+        ];
+        let mut symbols = vec![];
+        for (name, decorators, kind) in groups {
+            let children = cache
+                .props
+                .iter()
+                .filter(|prop| decorators.iter().any(|v| prop.has_decorator(v)))
+                .map(|prop| {
+                    let range = Range::new(
+                        cache.document.position_at(prop.range.0 as u32),
+                        cache.document.position_at(prop.range.1 as u32),
+                    );
+                    // 事件使用触发的事件名称，而不是方法名称
+                    let name = match &prop.emit_name {
+                        Some(emit_name) if kind == SymbolKind::EVENT => emit_name.clone(),
+                        _ => prop.name.clone(),
+                    };
+                    #[allow(deprecated)]
+                    DocumentSymbol {
+                        name,
+                        detail: None,
+                        kind,
+                        tags: None,
+                        deprecated: None,
+                        range,
+                        selection_range: range,
+                        children: None,
+                    }
+                })
+                .collect::<Vec<_>>();
+            if children.is_empty() {
+                continue;
+            }
+            let range = Range::new(
+                children.first().unwrap().range.start,
+                children.last().unwrap().range.end,
+            );
+            #[allow(deprecated)]
+            symbols.push(DocumentSymbol {
+                name: name.to_string(),
+                detail: None,
+                kind: SymbolKind::NAMESPACE,
+                tags: None,
+                deprecated: None,
+                range,
+                selection_range: range,
+                children: Some(children),
+            });
+        }
+        symbols
+    }
+
+    /// 获取组件类名的范围以及属性和事件的数量，用于 code lens
+    pub fn get_component_counts(&self, uri: &Uri) -> Option<(Range, usize, usize)> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return None;
+        };
+        if cache.name_range.0 == cache.name_range.1 {
+            return None;
+        }
+        let count = |decorators: &[&str]| {
+            cache
+                .props
+                .iter()
+                .filter(|prop| decorators.iter().any(|v| prop.has_decorator(v)))
+                .count()
+        };
+        let range = Range::new(
+            cache.document.position_at(cache.name_range.0 as u32),
+            cache.document.position_at(cache.name_range.1 as u32),
+        );
+        Some((
+            range,
+            count(&["Prop", "PropSync", "Model"]),
+            count(&["Emit"]),
+        ))
+    }
+}
+
+/// diagnostics
+impl Renderer {
+    /// 获取模版中的诊断信息
+    ///
+    /// 目前检查多余的根元素、静态属性传递给非字符串类型的 prop 以及 `v-model` 绑定到只读计算属性的情况
+    pub fn get_template_diagnostics(&self, uri: &Uri) -> Vec<Diagnostic> {
+        let cache = self.render_cache.get(uri);
+        let Some(RenderCache::VueRenderCache(cache)) = cache else {
+            return vec![];
+        };
+        let Some(template) = &cache.template else {
+            return vec![];
+        };
+        let props = self.render_cache.get_effective_props(uri);
+        let mut diagnostics = vec![];
+        // 多余的根元素不参与模版编译
+        for root in template_compile::get_extra_roots(template) {
+            let end = root.start_tag_end.unwrap_or(root.end);
+            diagnostics.push(Diagnostic {
+                range: Range::new(
+                    cache.document.position_at(root.start as u32),
+                    cache.document.position_at(end as u32),
+                ),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("vue".to_string()),
+                message: "Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead.".to_string(),
+                ..Default::default()
+            });
+        }
+        // 位于 v-for 中的 ref 为数组，`@Ref` 成员的类型需要与之一致
+        for template_ref in
+            template_compile::get_template_refs(template, cache.document.get_content(None))
+        {
+            let ref_params = props.iter().find_map(|v| {
+                v.ref_params
+                    .as_ref()
+                    .filter(|params| params.name == template_ref.name)
+            });
+            let Some(ref_params) = ref_params else {
+                continue;
+            };
+            if ref_params.is_array == template_ref.in_v_for {
+                continue;
+            }
+            let message = if template_ref.in_v_for {
+                format!(
+                    "Ref '{}' is used inside v-for and will be an array, but the @Ref member is not typed as an array.",
+                    template_ref.name
+                )
+            } else {
+                format!(
+                    "Ref '{}' is not used inside v-for and will not be an array, but the @Ref member is typed as an array.",
+                    template_ref.name
+                )
+            };
+            diagnostics.push(Diagnostic {
+                range: Range::new(
+                    cache.document.position_at(template_ref.range.0 as u32),
+                    cache.document.position_at(template_ref.range.1 as u32),
+                ),
+                severity: Some(DiagnosticSeverity::WARNING),
+                source: Some("vue".to_string()),
+                message,
+                ..Default::default()
+            });
+        }
+        let mut nodes = vec![template];
+        while let Some(node) = nodes.pop() {
+            nodes.extend(node.children.iter());
+            // 展开绑定的属性无法静态确定，不检查该标签的属性
+            if let Some(tag) = node
+                .tag
+                .as_ref()
+                .filter(|_| !template_compile::has_spread_bind(node))
+            {
+                diagnostics.append(&mut self.get_static_prop_diagnostics(
+                    uri,
+                    tag,
+                    node,
+                    &cache.document,
+                ));
+            }
+            let Some(attr) = node.attributes.get("v-model") else {
+                continue;
+            };
+            // 组件未定义 model 时不能使用 v-model，内置组件不检查
+            if let Some(tag) = &node.tag {
+                if !["input", "select", "textarea"].contains(&tag.as_str())
+                    && !vue_data::is_builtin_component(tag)
+                {
+                    let component_uri = self.get_registered_component_uri(uri, tag);
+                    if let Some(component_uri) = component_uri {
+                        if self.get_component_model(component_uri).is_none() {
+                            let value_len = attr.value.as_ref().map_or(0, |v| v.len() + 1);
+                            diagnostics.push(Diagnostic {
+                                range: Range::new(
+                                    cache.document.position_at(attr.offset as u32),
+                                    cache.document.position_at(
+                                        (attr.offset + "v-model".len() + value_len) as u32,
+                                    ),
+                                ),
+                                severity: Some(DiagnosticSeverity::ERROR),
+                                source: Some("vue".to_string()),
+                                message: format!(
+                                    "Component '{}' does not define a model, v-model cannot be used on it.",
+                                    tag
+                                ),
+                                ..Default::default()
+                            });
+                        }
+                    }
+                }
+            }
+            let Some(value) = &attr.value else {
+                continue;
+            };
+            if value.len() < 2 || !(value.starts_with('"') || value.starts_with('\'')) {
+                continue;
+            }
+            let value_offset = template_compile::get_attr_value_offset(
+                cache.document.get_content(None),
+                attr.offset,
+                "v-model",
+            ) + 1;
+            let expr = &value[1..value.len() - 1];
+            let name = expr.trim().trim_start_matches("this.");
+            let prop = props.iter().find(|v| v.name == name);
+            if let Some(prop) = prop {
+                if prop.typ == (RenderCachePropType::Computed { writable: false }) {
+                    diagnostics.push(Diagnostic {
+                        range: Range::new(
+                            cache.document.position_at(value_offset as u32),
+                            cache.document.position_at((value_offset + expr.len()) as u32),
+                        ),
+                        severity: Some(DiagnosticSeverity::ERROR),
+                        source: Some("vue".to_string()),
+                        message: format!(
+                            "Cannot bind v-model to '{}' because it is a computed property without a setter.",
+                            name
+                        ),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        diagnostics.sort_by(|a, b| a.range.start.cmp(&b.range.start));
+        diagnostics
+    }
+
+    /// 获取组件标签上的静态属性传递给非字符串类型的 prop 的诊断信息
+    ///
+    /// 与 HTML 全局属性同名（如 `title`）时优先按组件的 prop 检查，原生元素保持 HTML 属性的行为
+    fn get_static_prop_diagnostics(
+        &self,
+        uri: &Uri,
+        tag: &str,
+        node: &Node,
+        document: &FullTextDocument,
+    ) -> Vec<Diagnostic> {
+        let Some(component_uri) = self.get_registered_component_uri(uri, tag) else {
+            return vec![];
+        };
+        let props = self.get_effective_props(component_uri);
+        let mut diagnostics = vec![];
+        for name in node.attribute_names_by_order() {
+            if name.starts_with([':', '@', '#']) || name.starts_with("v-") {
+                continue;
+            }
+            let attr = &node.attributes[name];
+            if attr.value.is_none() {
+                continue;
+            }
+            let prop_name = tags_provider::get_prop_name_of_attribute(name);
+            let Some((prop, typ)) = props.iter().find_map(|v| {
+                v.prop_params.as_ref()?;
+                (util::to_kebab_case(&v.name) == prop_name).then_some((v, v.get_prop_ts_type()?))
+            }) else {
+                continue;
+            };
+            if !["number", "Record<string, any>", "Function"].contains(&typ.as_str())
+                && !typ.ends_with("[]")
+            {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                range: Range::new(
+                    document.position_at(attr.offset as u32),
+                    document.position_at((attr.offset + name.len()) as u32),
+                ),
+                severity: Some(DiagnosticSeverity::WARNING),
+                source: Some("vue".to_string()),
+                message: format!(
+                    "Prop '{}' of component '{}' expects {}, but a static attribute always passes a string. Use ':{}' to bind an expression.",
+                    prop.name, tag, typ, name
+                ),
+                ..Default::default()
+            });
+        }
+        diagnostics
+    }
+
+    /// 获取使用 Vue 保留名称的属性和数据的诊断信息
+    pub fn get_reserved_name_diagnostics(&self, uri: &Uri) -> Vec<Diagnostic> {
+        let cache = self.render_cache.get(uri);
+        let Some(RenderCache::VueRenderCache(cache)) = cache else {
+            return vec![];
+        };
+        let source = cache.document.get_content(None);
+        cache
+            .reserved_members
+            .iter()
+            .filter_map(|&(start, end)| {
+                let message = reserved_names::get_reserved_name_message(&source[start..end])?;
+                Some(Diagnostic {
+                    range: Range::new(
+                        cache.document.position_at(start as u32),
+                        cache.document.position_at(end as u32),
+                    ),
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    source: Some("vue".to_string()),
+                    message,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    /// 获取脚本解析失败的诊断信息
+    ///
+    /// 语法错误本身由 tsserver 报告，这里只在 `<script>` 标签上提示组件信息不再更新
+    pub fn get_script_parse_diagnostics(&self, uri: &Uri) -> Vec<Diagnostic> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
+        };
+        if !cache.script_syntax_error {
+            return vec![];
+        }
+        let Some(script) = &cache.script else {
+            return vec![];
+        };
+        let end = script.start_tag_end.unwrap_or(script.end);
+        vec![Diagnostic {
+            range: Range::new(
+                cache.document.position_at(script.start as u32),
+                cache.document.position_at(end as u32),
+            ),
+            severity: Some(DiagnosticSeverity::WARNING),
+            source: Some("vue".to_string()),
+            message: "Failed to parse script, component information will not be updated until the syntax errors are fixed.".to_string(),
+            ..Default::default()
+        }]
+    }
+
+    /// 获取模版语言不受支持的诊断信息
+    ///
+    /// 非 HTML 语言的模版（如 pug）不进行编译，模版相关的功能不可用
+    pub fn get_template_lang_diagnostics(&self, uri: &Uri) -> Vec<Diagnostic> {
+        let cache = self.render_cache.get(uri);
+        let Some(RenderCache::VueRenderCache(cache)) = cache else {
+            return vec![];
+        };
+        cache
+            .custom_blocks
+            .iter()
+            .filter(|block| block.tag.as_ref().is_some_and(|tag| tag == "template"))
+            .filter_map(|block| {
+                let lang = parse_document::get_template_lang(block)?;
+                let attr = block.attributes.get("lang")?;
+                let start = template_compile::get_attr_value_offset(
+                    cache.document.get_content(None),
+                    attr.offset,
+                    "lang",
+                ) + 1;
+                Some(Diagnostic {
+                    range: Range::new(
+                        cache.document.position_at(start as u32),
+                        cache.document.position_at((start + lang.len()) as u32),
+                    ),
+                    severity: Some(DiagnosticSeverity::INFORMATION),
+                    source: Some("vue".to_string()),
+                    message: format!(
+                        "Template language '{}' is not supported. Template features are disabled.",
+                        lang
+                    ),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    /// 获取继承的类不是组件的诊断信息
+    ///
+    /// 继承的类不是组件时属性不会沿继承链传递，需要在关系图刷新后调用
+    pub fn get_extends_diagnostics(&self, uri: &Uri) -> Vec<Diagnostic> {
+        let cache = self.render_cache.get(uri);
+        let Some(RenderCache::VueRenderCache(cache)) = cache else {
+            return vec![];
+        };
+        let Some((component, (start, end))) = &cache.extends_clause else {
+            return vec![];
+        };
+        let Some(extends_uri) = self.get_uri_from_path(uri, &component.path) else {
+            return vec![];
+        };
+        if self
+            .render_cache
+            .is_component_export(&extends_uri, &component.export_name)
+        {
+            return vec![];
+        }
+        vec![Diagnostic {
+            range: Range::new(
+                cache.document.position_at(*start as u32),
+                cache.document.position_at(*end as u32),
+            ),
+            severity: Some(DiagnosticSeverity::HINT),
+            source: Some("vue".to_string()),
+            message: format!(
+                "'{}' is not a Vue component. Its props are not inherited.",
+                &cache.document.get_content(None)[*start..*end]
+            ),
+            ..Default::default()
+        }]
+    }
+
+    /// 获取组件导入路径、对应的文件路径及其范围，用于检查导入的文件是否存在
+    ///
+    /// 不包含从 node_modules 解析的模块
+    pub fn get_component_import_files(&self, uri: &Uri) -> Vec<(String, PathBuf, Range)> {
+        let cache = self.render_cache.get(uri);
+        let Some(RenderCache::VueRenderCache(cache)) = cache else {
+            return vec![];
+        };
+        let Some((root_uri, _)) = self.root_uri_target_uri.get() else {
+            return vec![];
+        };
+        cache
+            .component_imports
+            .iter()
+            .filter(|(path, _)| self.is_local_import_path(path))
+            .map(|(path, (start, end))| {
+                let file_path = parse_import_path::parse_import_path(
+                    uri,
+                    path,
+                    &self.alias,
+                    self.base_url.as_deref(),
+                    root_uri,
+                );
+                let range = Range::new(
+                    cache.document.position_at(*start as u32),
+                    cache.document.position_at(*end as u32),
+                );
+                (path.clone(), file_path, range)
+            })
+            .collect()
+    }
+
+    /// 获取无法解析的组件导入路径的诊断信息
+    ///
+    /// 需要访问文件系统，不应在持有渲染器的锁时调用
+    pub fn get_unresolved_import_diagnostics(
+        import_files: Vec<(String, PathBuf, Range)>,
+    ) -> Vec<Diagnostic> {
+        import_files
+            .into_iter()
+            .filter(|(_, file_path, _)| parse_import_path::resolve_file_path(file_path).is_none())
+            .map(|(path, _, range)| Diagnostic {
+                range,
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("vue".to_string()),
+                message: format!("Cannot find module '{}'.", path),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// 获取脚本中导入路径的文档链接，指向解析得到的源文件
+    ///
+    /// 跳过从 node_modules 解析的模块和无法解析的路径
+    pub fn get_import_links(&self, uri: &Uri) -> Vec<DocumentLink> {
+        let cache = self.render_cache.get(uri);
+        let Some(RenderCache::VueRenderCache(cache)) = cache else {
+            return vec![];
+        };
+        cache
+            .import_paths
+            .iter()
+            .filter(|(path, _)| self.is_local_import_path(path))
+            .filter_map(|(path, (start, end))| {
+                let target = self.get_uri_from_path(uri, path)?;
+                // 不包含路径两侧的引号
+                Some(DocumentLink {
+                    range: Range::new(
+                        cache.document.position_at(*start as u32 + 1),
+                        cache.document.position_at(*end as u32 - 1),
+                    ),
+                    target: Some(target),
+                    tooltip: None,
+                    data: None,
+                })
+            })
+            .collect()
+    }
+
+    /// 是否为相对路径、绝对路径或别名路径
+    fn is_local_import_path(&self, path: &str) -> bool {
+        path.starts_with('.')
+            || path.starts_with('/')
+            || self.alias.keys().any(|key| path.starts_with(key))
+    }
+
+    /// 获取重命名文件时需要更新的导入路径，编辑位于重命名前的文件中
+    ///
+    /// * 通过继承、注册或转换关系导入该文件的组件和 ts 文件
+    /// * 被重命名的文件自身的相对导入路径
+    pub fn get_rename_import_edits(
+        &self,
+        old_uri: &Uri,
+        new_uri: &Uri,
+    ) -> HashMap<Uri, Vec<TextEdit>> {
+        let mut changes = HashMap::new();
+        if self.render_cache.get(old_uri).is_none() {
+            return changes;
+        }
+        let old_path = util::to_file_path(old_uri);
+        let new_path = util::to_file_path(new_uri);
+        for importer in self.render_cache.get_importers(old_uri) {
+            let Some((document, import_paths)) = self.get_import_paths(importer) else {
+                continue;
+            };
+            let importer_path = util::to_file_path(importer);
+            let edits = import_paths
+                .into_iter()
+                .filter(|(path, _)| self.is_local_import_path(path))
+                .filter(|(path, _)| {
+                    self.get_uri_from_path(importer, path).as_ref() == Some(old_uri)
+                })
+                .filter_map(|(path, range)| {
+                    let new_text = parse_import_path::get_moved_import_path(
+                        &path,
+                        &old_path,
+                        &importer_path,
+                        &new_path,
+                        &self.alias,
+                    )?;
+                    Some(Renderer::create_import_path_edit(document, range, new_text))
+                })
+                .collect::<Vec<_>>();
+            if !edits.is_empty() {
+                changes.insert(importer.clone(), edits);
+            }
+        }
+        if let Some((document, import_paths)) = self.get_import_paths(old_uri) {
+            let edits = import_paths
+                .into_iter()
+                .filter(|(path, _)| path.starts_with('.'))
+                .filter_map(|(path, range)| {
+                    let target_path = util::to_file_path(&self.get_uri_from_path(old_uri, &path)?);
+                    let new_text = parse_import_path::get_moved_import_path(
+                        &path,
+                        &target_path,
+                        &new_path,
+                        &target_path,
+                        &self.alias,
+                    )?;
+                    if new_text == path {
+                        return None;
+                    }
+                    Some(Renderer::create_import_path_edit(document, range, new_text))
+                })
+                .collect::<Vec<_>>();
+            if !edits.is_empty() {
+                changes.entry(old_uri.clone()).or_default().extend(edits);
+            }
+        }
+        changes
+    }
+
+    /// 获取文件中的所有导入路径及路径字符串的范围
+    fn get_import_paths(
+        &self,
+        uri: &Uri,
+    ) -> Option<(&FullTextDocument, Vec<(String, (usize, usize))>)> {
+        match self.render_cache.get(uri)? {
+            RenderCache::VueRenderCache(cache) => {
+                Some((&cache.document, cache.import_paths.clone()))
+            }
+            RenderCache::TsRenderCache(cache) => {
+                let source = cache.document.get_content(None);
+                let (module, _) = ast::parse_source(source, 0, source.len());
+                Some((&cache.document, ast::get_import_paths(&module.ok()?)))
+            }
+            RenderCache::LibRenderCache(_) => None,
+        }
+    }
+
+    /// 创建替换导入路径的编辑，不包含路径两侧的引号
+    fn create_import_path_edit(
+        document: &FullTextDocument,
+        (start, end): (usize, usize),
+        new_text: String,
+    ) -> TextEdit {
+        TextEdit {
+            range: Range::new(
+                document.position_at(start as u32 + 1),
+                document.position_at(end as u32 - 1),
+            ),
+            new_text,
+        }
+    }
+}
+
+/// format
+impl Renderer {
+    /// 获取整理模版的编辑，不修改模版以外的部分
+    ///
+    /// * 同一行中标签名和属性之间的多个空白合并为一个空格，移除 `>` 前的空白
+    /// * 没有任何内容的元素改为自闭合，只包含空白的元素保持不变
+    pub fn get_template_format_edits(&self, uri: &Uri) -> Vec<TextEdit> {
+        let cache = self.render_cache.get(uri);
+        let Some(RenderCache::VueRenderCache(cache)) = cache else {
+            return vec![];
+        };
+        let Some(template) = &cache.template else {
+            return vec![];
+        };
+        let document = &cache.document;
+        let source = document.get_content(None);
+        let mut edits = vec![];
+        let mut nodes = template.children.iter().collect::<Vec<_>>();
+        while let Some(node) = nodes.pop() {
+            nodes.extend(node.children.iter());
+            let (Some(tag), Some(start_tag_end)) = (&node.tag, node.start_tag_end) else {
+                continue;
+            };
+            // 属性之间的空白
+            let mut prev_end = node.start + 1 + tag.len();
+            let mut is_aligned = true;
+            for key in node.attribute_names_by_order() {
+                let attr = &node.attributes[key];
+                if attr.offset <= prev_end {
+                    is_aligned = false;
+                    break;
+                }
+                edits.extend(Renderer::get_whitespace_edit(
+                    document,
+                    prev_end,
+                    attr.offset,
+                    " ",
+                ));
+                let mut end = attr.offset + key.len();
+                if let Some(value) = &attr.value {
+                    if source.get(end..end + 1) != Some("=")
+                        || source.get(end + 1..end + 1 + value.len()) != Some(value.as_str())
+                    {
+                        is_aligned = false;
+                        break;
+                    }
+                    end += 1 + value.len();
+                }
+                prev_end = end;
+            }
+            let is_self_closing = source[..start_tag_end].ends_with("/>");
+            if is_aligned {
+                let edit = if is_self_closing {
+                    Renderer::get_whitespace_edit(document, prev_end, start_tag_end - 2, " ")
+                } else {
+                    Renderer::get_whitespace_edit(document, prev_end, start_tag_end - 1, "")
+                };
+                edits.extend(edit);
+            }
+            // 没有任何内容的元素改为自闭合，空白可能影响渲染结果
+            if let Some(end_tag_start) = node.end_tag_start {
+                if start_tag_end == end_tag_start && !["pre", "textarea"].contains(&tag.as_str()) {
+                    edits.push(TextEdit {
+                        range: Range::new(
+                            document.position_at(start_tag_end as u32 - 1),
+                            document.position_at(node.end as u32),
+                        ),
+                        new_text: " />".to_string(),
+                    });
+                }
+            }
+        }
+        edits.sort_by(|a, b| a.range.start.cmp(&b.range.start));
+        edits
+    }
+
+    /// 将同一行中的空白替换为 `new_text`，范围内存在非空白字符或换行时返回 None
+    fn get_whitespace_edit(
+        document: &FullTextDocument,
+        start: usize,
+        end: usize,
+        new_text: &str,
+    ) -> Option<TextEdit> {
+        let text = document.get_content(None).get(start..end)?;
+        if text == new_text
+            || !text.chars().all(|c| c == ' ' || c == '\t')
+            || (text.is_empty() && !new_text.is_empty())
+        {
+            return None;
+        }
+        Some(TextEdit {
+            range: Range::new(
+                document.position_at(start as u32),
+                document.position_at(end as u32),
+            ),
+            new_text: new_text.to_string(),
+        })
+    }
+}
+
+/// tools
+impl Renderer {
+    pub fn get_line_end(&self, uri: &Uri, line: u32) -> u32 {
+        Renderer::get_line_end_by_document(self.get_document(uri), line)
+    }
+
+    /// 脚本开始位置
+    pub fn start_position(&self, uri: &Uri) -> Option<Position> {
+        let cache = self.render_cache.get(uri)?;
+        if let RenderCache::VueRenderCache(cache) = cache {
+            Some(
+                cache
+                    .document
+                    .position_at(cache.script.as_ref()?.start_tag_end.unwrap() as u32),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// 脚本结束位置
+    pub fn end_position(&self, uri: &Uri) -> Option<Position> {
+        let cache = self.render_cache.get(uri)?;
+        if let RenderCache::VueRenderCache(cache) = cache {
+            Some(
+                cache
+                    .document
+                    .position_at(cache.script.as_ref()?.end_tag_start.unwrap() as u32),
+            )
+        } else {
+            None
+        }
+    }
+    pub async fn get_document_from_file(uri: &Uri) -> Result<FullTextDocument, Error> {
+        let mut content = String::new();
+
+        let path = util::to_file_path_string(uri);
+        match File::open(&path).await {
+            Ok(mut file) => {
+                if let Err(err) = file.read_to_string(&mut content).await {
+                    error!("error: {} - {}", path, err);
+                    return Err(err);
+                }
+            }
+            Err(err) => {
+                error!("error: {} - {}", path, err);
+                return Err(Error::new(std::io::ErrorKind::NotFound, path));
+            }
+        }
+        let language_id = path[path.rfind(".").unwrap() + 1..].to_string();
+        Ok(FullTextDocument::new(language_id, 1, content))
+    }
+
+    /// 是否为脚本为 JavaScript 的 vue 组件，此时渲染为 `.vue.js`
+    pub fn is_js_component(&self, uri: &Uri) -> bool {
+        self.render_cache.get(uri).is_some_and(|v| v.is_js())
+    }
+
+    pub fn is_vue_component(uri: &Uri) -> bool {
+        util::to_file_path(uri)
+            .extension()
+            .is_some_and(|v| v == "vue")
+    }
+
+    /// uri 是否有效
+    /// * 是文件
+    /// * 存在于文件系统中
+    /// * 不在 node_modules 中
+    /// * 不在 .git 中
+    pub fn is_uri_valid(uri: &Uri) -> bool {
+        let file_path = util::to_file_path(uri);
+        if cfg!(not(test)) {
+            let file_path_str = file_path.to_string_lossy();
+            file_path.is_file()
+                && !file_path_str.contains("/node_modules/")
+                && !file_path_str.contains("/.git/")
+        } else {
+            !file_path.to_string_lossy().contains("/node_modules/")
+        }
+    }
+
+    /// uri 是否指向 node_modules 下的库
+    /// * 是目录
+    /// * 存在于文件系统中
+    pub fn is_node_modules(uri: &Uri) -> bool {
+        let file_path = util::to_file_path(uri);
+        if cfg!(not(test)) {
+            file_path.is_dir() && file_path.to_string_lossy().contains("/node_modules/")
+        } else {
+            file_path.to_string_lossy().contains("/node_modules/")
+        }
+    }
+
+    pub fn is_position_valid_by_document(
+        document: Option<&FullTextDocument>,
+        position: &Position,
+    ) -> bool {
+        if let Some(document) = document {
+            let start = document.offset_at(Position::new(position.line, 0));
+            let end = document.offset_at(Position::new(position.line + 1, 0));
+            position.character < end - start
+        } else {
+            false
+        }
+    }
+
+    pub fn get_line_end_by_document(document: Option<&FullTextDocument>, line: u32) -> u32 {
+        if let Some(document) = document {
+            let start = document.offset_at(Position::new(line, 0));
+            let end = document.offset_at(Position::new(line, u32::MAX));
+            let content = document.get_content(Some(Range::new(
+                document.position_at(start),
+                document.position_at(end),
+            )));
+            if content.ends_with("\r\n") {
+                end - start - 2
+            } else if content.ends_with("\n") {
+                end - start - 1
+            } else {
+                end - start
+            }
+        } else {
+            0
+        }
+    }
+
+    /// 获取目标路径，vue 文件的脚本为 JavaScript 时目标文件后缀为 `.vue.js`
+    fn get_target_path(uri: &Uri, root_uri: &Uri, target_root_uri: &Uri, is_js: bool) -> PathBuf {
+        let src_path = util::to_file_path(uri);
+        let root_path = util::to_file_path(root_uri);
+        let target_root_path = util::to_file_path(target_root_uri);
+        // 计算相对路径
+        let rel_path = src_path.strip_prefix(&root_path).unwrap().to_path_buf();
+        // 转换为目标路径
+        let mut target_path = target_root_path.join(rel_path);
+        if let Some(file_name) = target_path.file_name() {
+            if file_name.to_string_lossy().ends_with(".vue") {
+                let suffix = if is_js { "js" } else { "ts" };
+                let new_file_name = format!("{}.{}", file_name.to_string_lossy(), suffix);
+                target_path.set_file_name(new_file_name);
+            }
+        }
+        target_path
+    }
+
+    /// 获取原路径
+    pub fn get_source_path(uri: &Uri, root_uri: &Uri, target_root_uri: &Uri) -> PathBuf {
+        let target_path = util::to_file_path(uri);
+        let root_path = util::to_file_path(root_uri);
+        let target_root_path = util::to_file_path(target_root_uri);
+        // 计算相对路径
+        let rel_path = target_path
+            .strip_prefix(target_root_path)
+            .unwrap()
+            .to_path_buf();
+        // 转换为原路径
+        let mut source_path = root_path.join(rel_path);
+        if let Some(file_name) = source_path.file_name() {
+            let file_name = file_name.to_string_lossy().to_string();
+            if file_name.ends_with(".vue.ts") || file_name.ends_with(".vue.js") {
+                source_path.set_file_name(&file_name[..file_name.len() - 3]);
+            }
+        }
+        source_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        path::PathBuf,
+        str::FromStr,
+    };
+
+    use lsp_textdocument::FullTextDocument;
+    use tokio::sync::OnceCell;
+    use tower_lsp::lsp_types::{
+        DiagnosticSeverity, DidChangeTextDocumentParams, HoverContents, Location, Position, Range,
+        SymbolKind, TextDocumentContentChangeEvent, TextEdit, Uri, VersionedTextDocumentIdentifier,
+    };
+
+    use crate::{
+        ast,
+        convert::{ConvertBack, ConvertOptions, ConvertTo},
+        renderer::{
+            render_cache::{
+                lib_render_cache::{LibComponentProp, LibRenderCache},
+                RenderCache, RenderCacheGraph, RenderCacheGraphStats,
+            },
+            ComponentModel, Renderer,
+        },
+        util,
+    };
+    use lazy_static::lazy_static;
+
+    use html_languageservice::html_data::Description;
+    use html_languageservice::language_facts::data_provider::IHTMLDataProvider;
+
+    use super::PositionType;
+
+    lazy_static! {
+        static ref TEST1_INDEX: Uri =
+            Uri::from_str("file:///path/project/src/test1/index.vue").unwrap();
+        static ref TEST1_COMPONENT1: Uri =
+            Uri::from_str("file:///path/project/src/test1/components/MyComponent1.vue").unwrap();
+        static ref TEST1_COMPONENT2: Uri =
+            Uri::from_str("file:///path/project/src/test1/components/MyComponent2.vue").unwrap();
+        static ref TEST1_COMPONENT3: Uri =
+            Uri::from_str("file:///path/project/src/test1/components/MyComponent3.vue").unwrap();
+        static ref TEST2_INDEX: Uri =
+            Uri::from_str("file:///path/project/src/test2/index.vue").unwrap();
+        static ref TEST2_TS: Uri =
+            Uri::from_str("file:///path/project/src/test2/ts/transfer.ts").unwrap();
+        static ref TEST2_COMPONENT4: Uri =
+            Uri::from_str("file:///path/project/src/test2/components/MyComponent4.vue").unwrap();
+        static ref TEST2_COMPONENT5: Uri =
+            Uri::from_str("file:///path/project/src/test2/components/MyComponent5.vue").unwrap();
+    }
+
+    /// 以多行源码创建 vue 文件的节点，注册等关系在 `flush` 后加入关系图
+    pub(super) fn add_vue_document(renderer: &mut Renderer, uri: &Uri, lines: &[&str]) {
         renderer.create_node_from_document(
+            uri,
+            FullTextDocument::new("vue".to_string(), 0, lines.join("\n")),
+        );
+    }
+
+    fn create_renderer() -> Renderer {
+        let cache_graph = RenderCacheGraph::new();
+        let mut renderer = Renderer {
+            root_uri_target_uri: OnceCell::from((
+                Uri::from_str("file:///path/project").unwrap(),
+                Uri::from_str("file:///path/.~$project").unwrap(),
+            )),
+            alias: HashMap::new(),
+            base_url: None,
+            render_cache: cache_graph,
+            provider_map: HashMap::new(),
+            library_list: vec![],
+            will_create_files: HashSet::new(),
+            render_exclude: None,
+            incremental_render: false,
+            lazy_files: HashSet::new(),
+            max_file_size: 0,
+            oversized_files: HashSet::new(),
+            component_decorators: ast::get_default_component_decorators(),
+        };
+        // test1/index.vue
+        add_vue_document(
+            &mut renderer,
+            &TEST1_INDEX,
+            &[
+                "<template>",
+                "  <MyComponent1 title=\"Title\" />",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "import MyComponent1 from './components/MyComponent1.vue';",
+                "@Component({",
+                "  components: {",
+                "    MyComponent1,",
+                "  },",
+                "})",
+                "export default class Index extends Vue {",
+                "}",
+                "</script>",
+            ],
+        );
+        // test1/components/MyComponent1.vue
+        add_vue_document(
+            &mut renderer,
             &TEST1_COMPONENT1,
+            &[
+                "<template>",
+                "  <div :title=\"title\" :tabIndex=\"\">{{ text }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component } from 'vue-property-decorator';",
+                "import MyComponent2 from './MyComponent2.vue';",
+                "@Component",
+                "export default class MyComponent1 extends MyComponent2 {",
+                "  @Prop({ type: String, required: true })",
+                "  private title!: string;",
+                "  private text = 'Hello World';",
+                "}",
+                "</script>",
+            ],
+        );
+        // test1/components/MyComponent2.vue
+        add_vue_document(
+            &mut renderer,
+            &TEST1_COMPONENT2,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "@Component",
+                "export default class MyComponent2 extends Vue {",
+                "  @Prop({ type: Boolean, default: false })",
+                "  private readonly!: boolean;",
+                "  private state = 'init';",
+                "}",
+                "</script>",
+            ],
+        );
+        // test1/components/MyComponent3.vue
+        add_vue_document(
+            &mut renderer,
+            &TEST1_COMPONENT3,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "@Component",
+                "export default class MyComponent3 extends Vue {",
+                "  @Prop({ type: Boolean, default: false })",
+                "  private disabled!: boolean;",
+                "  private show = false;",
+                "}",
+                "</script>",
+            ],
+        );
+        // test2/index.vue
+        add_vue_document(
+            &mut renderer,
+            &TEST2_INDEX,
+            &[
+                "<template>",
+                "  <MyComponent4 title=\"Title\" />",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "import MyComponent4 from './ts/transfer.ts';",
+                "@Component({",
+                "  components: {",
+                "    MyComponent4,",
+                "  },",
+                "})",
+                "export default class Index extends Vue {",
+                "}",
+                "</script>",
+            ],
+        );
+        // test2/ts/transfer.ts
+        renderer.create_node_from_document(
+            &TEST2_TS,
             FullTextDocument::new(
-                "vue".to_string(),
+                "typescript".to_string(),
                 0,
+                [
+                    "import MyComponent4 from '../components/MyComponent4.vue';",
+                    "export default MyComponent4;",
+                ]
+                .join("\n")
+                .to_string(),
+            ),
+        );
+        // test2/components/MyComponent4.vue
+        add_vue_document(
+            &mut renderer,
+            &TEST2_COMPONENT4,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "@Component",
+                "export default class MyComponent4 extends Vue {",
+                "  @Prop({ type: Boolean, default: false })",
+                "  private disabled!: boolean;",
+                "  private show = false;",
+                "}",
+                "</script>",
+            ],
+        );
+        // test2/components/MyComponent5.vue
+        add_vue_document(
+            &mut renderer,
+            &TEST2_COMPONENT5,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "@Component",
+                "export default class MyComponent5 extends Vue {",
+                "  @Prop({ type: Boolean, default: false })",
+                "  private disabled!: boolean;",
+                "  private show = false;",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+
+        renderer
+    }
+
+    fn create_empty_document() -> FullTextDocument {
+        FullTextDocument::new("vue".to_string(), 0, "".to_string())
+    }
+
+    fn create_changes(
+        changes: &[(u32, u32, u32, u32, Option<u32>, &str)],
+    ) -> Vec<TextDocumentContentChangeEvent> {
+        changes
+            .iter()
+            .map(|v| TextDocumentContentChangeEvent {
+                range: Some(Range {
+                    start: Position {
+                        line: v.0,
+                        character: v.1,
+                    },
+                    end: Position {
+                        line: v.2,
+                        character: v.3,
+                    },
+                }),
+                range_length: v.4,
+                text: v.5.to_string(),
+            })
+            .collect()
+    }
+
+    fn create_params(
+        uri: &Uri,
+        changes: &[(u32, u32, u32, u32, Option<u32>, &str)],
+    ) -> DidChangeTextDocumentParams {
+        DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: uri.clone(),
+                version: 1,
+            },
+            content_changes: create_changes(changes),
+        }
+    }
+
+    fn assert_mapping(pos: (u32, u32), expected: Option<(u32, u32)>) {
+        let renderer = create_renderer();
+        let result = renderer.get_mapping_position(
+            &TEST1_COMPONENT1,
+            &Position {
+                line: pos.0,
+                character: pos.1,
+            },
+        );
+        let expected = expected.map(|v| Position {
+            line: v.0,
+            character: v.1,
+        });
+        assert_eq!(result, expected);
+    }
+
+    fn assert_original(pos: (u32, u32), expected: Option<(u32, u32)>) {
+        let renderer = create_renderer();
+        let result = renderer.get_original_position(
+            &TEST1_COMPONENT1,
+            &Position {
+                line: pos.0,
+                character: pos.1,
+            },
+        );
+        let expected = expected.map(|v| Position {
+            line: v.0,
+            character: v.1,
+        });
+        assert_eq!(result, expected);
+    }
+
+    fn assert_original_range(range: (u32, u32, u32, u32), expected: Option<(u32, u32, u32, u32)>) {
+        let renderer = create_renderer();
+        let result = renderer.get_original_range(
+            &TEST1_COMPONENT1,
+            &Range {
+                start: Position {
+                    line: range.0,
+                    character: range.1,
+                },
+                end: Position {
+                    line: range.2,
+                    character: range.3,
+                },
+            },
+        );
+        let expected = expected.map(|v| Range {
+            start: Position {
+                line: v.0,
+                character: v.1,
+            },
+            end: Position {
+                line: v.2,
+                character: v.3,
+            },
+        });
+        assert_eq!(result, expected);
+    }
+
+    fn assert_position_type(pos: (u32, u32), expected: Option<PositionType>) {
+        let renderer = create_renderer();
+        let result = renderer.get_position_type(
+            &TEST1_COMPONENT1,
+            &Position {
+                line: pos.0,
+                character: pos.1,
+            },
+        );
+        assert_eq!(result, expected);
+    }
+
+    #[tokio::test]
+    async fn update_vue_script_props() {
+        let mut renderer = create_renderer();
+        let params = create_params(&TEST1_COMPONENT1, &[(9, 15, 9, 15, Some(0), "1")]);
+        let document = FullTextDocument::new(
+            "vue".to_string(),
+            1,
+            [
+                "<template>",
+                "  <div :title=\"title\" :tabIndex=\"\">{{ text }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component } from 'vue-property-decorator';",
+                "import MyComponent2 from './MyComponent2.vue';",
+                "@Component",
+                "export default class MyComponent1 extends MyComponent2 {",
+                "  @Prop({ type: String, required: true })",
+                "  private title1!: string;",
+                "  private text = 'Hello World';",
+                "}",
+                "</script>",
+            ]
+            .join("\n"),
+        );
+        // 属性变更后进行全量渲染
+        let result = renderer.update(&TEST1_COMPONENT1, params, &document).await;
+        assert_eq!(result.content_changes.len(), 1);
+        assert_eq!(result.content_changes[0].range, None);
+        assert!(result.content_changes[0].text.contains("title1"));
+        // 上游节点应该更新
+        assert_eq!(
+            renderer
+                .render_cache
+                .get(&TEST1_INDEX)
+                .unwrap()
+                .get_version(),
+            Some(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn update_vue_extends_relation() {
+        let mut renderer = create_renderer();
+        let extends_uri = renderer.render_cache.get_extends_uri(&TEST1_COMPONENT1);
+        let expected_uri: Option<&Uri> = Some(&TEST1_COMPONENT2);
+        assert_eq!(extends_uri, expected_uri);
+        // 删除导入 MyComponent2
+        let params = create_params(&TEST1_COMPONENT1, &[(5, 0, 5, 45, Some(45), "")]);
+        let expected = create_changes(&[(5, 0, 5, 45, Some(45), "")]);
+        let result = renderer
+            .update(&TEST1_COMPONENT1, params, &create_empty_document())
+            .await;
+        assert_eq!(result.content_changes, expected);
+        let extends_uri = renderer.render_cache.get_extends_uri(&TEST1_COMPONENT1);
+        assert_eq!(extends_uri, None);
+        // 添加导入 MyComponent3
+        let text = "import MyComponent3 from './MyComponent3.vue';";
+        let params = create_params(&TEST1_COMPONENT1, &[(5, 0, 5, 0, Some(0), text)]);
+        let expected = create_changes(&[(5, 0, 5, 0, Some(0), text)]);
+        let result = renderer
+            .update(&TEST1_COMPONENT1, params, &create_empty_document())
+            .await;
+        assert_eq!(result.content_changes, expected);
+        let extends_uri = renderer.render_cache.get_extends_uri(&TEST1_COMPONENT1);
+        assert_eq!(extends_uri, None);
+        // extends MyComponent2 改为 MyComponent3
+        let params = create_params(&TEST1_COMPONENT1, &[(7, 53, 7, 54, Some(1), "3")]);
+        let expected = create_changes(&[(7, 53, 7, 54, Some(1), "3")]);
+        let result = renderer
+            .update(&TEST1_COMPONENT1, params, &create_empty_document())
+            .await;
+        assert_eq!(result.content_changes, expected);
+        let extends_uri = renderer.render_cache.get_extends_uri(&TEST1_COMPONENT1);
+        let expected_uri: Option<&Uri> = Some(&TEST1_COMPONENT3);
+        assert_eq!(extends_uri, expected_uri);
+    }
+
+    #[tokio::test]
+    async fn update_vue_registers_relation() {
+        let mut renderer = create_renderer();
+        // 将 ./components/MyComponent1.vue 替换为 ./components/MyComponent2.vue
+        let params = create_params(&TEST1_INDEX, &[(6, 50, 6, 51, Some(1), "2")]);
+        let expected = create_changes(&[(6, 50, 6, 51, Some(1), "2")]);
+        let result = renderer
+            .update(&TEST1_INDEX, params, &create_empty_document())
+            .await;
+        assert_eq!(result.content_changes, expected);
+        let registers = renderer.render_cache.get_registers(&TEST1_INDEX);
+        let expected_uri: &Uri = &TEST1_COMPONENT2;
+        assert_eq!(
+            registers,
+            vec![("MyComponent1".to_string(), None, None, expected_uri,)]
+        );
+        // 将 import MyComponent1 改为 import MyComponent2
+        let params = create_params(&TEST1_INDEX, &[(6, 18, 6, 19, Some(1), "2")]);
+        let expected = create_changes(&[(6, 18, 6, 19, Some(1), "2")]);
+        let result = renderer
+            .update(&TEST1_INDEX, params, &create_empty_document())
+            .await;
+        assert_eq!(result.content_changes, expected);
+        let registers = renderer.render_cache.get_registers(&TEST1_INDEX);
+        assert_eq!(registers, vec![]);
+        // 将 components 内的 MyComponent1 改为 MyComponent2
+        let params = create_params(&TEST1_INDEX, &[(9, 15, 9, 16, Some(1), "2")]);
+        let expected = create_changes(&[(9, 15, 9, 16, Some(1), "2")]);
+        let result = renderer
+            .update(&TEST1_INDEX, params, &create_empty_document())
+            .await;
+        assert_eq!(result.content_changes, expected);
+        let registers = renderer.render_cache.get_registers(&TEST1_INDEX);
+        let expected_uri: &Uri = &TEST1_COMPONENT2;
+        assert_eq!(
+            registers,
+            vec![("MyComponent2".to_string(), None, None, expected_uri,)]
+        );
+    }
+
+    #[tokio::test]
+    async fn update_ts_transfers() {
+        let mut renderer = create_renderer();
+        let transfer_result = renderer.render_cache.get_transfer_node(&TEST2_TS, &None);
+        let expected: Option<(&Uri, Option<_>)> = Some((&TEST2_COMPONENT4, None));
+        assert_eq!(transfer_result, expected);
+        // ../components/MyComponent4.vue 改为 ../components/MyComponent5.vue
+        let params = create_params(&TEST2_TS, &[(0, 51, 0, 52, Some(1), "5")]);
+        let expected = create_changes(&[(0, 51, 0, 52, Some(1), "5")]);
+        let result = renderer
+            .update(&TEST2_TS, params, &create_empty_document())
+            .await;
+        assert_eq!(result.content_changes, expected);
+        let transfer_result = renderer.render_cache.get_transfer_node(&TEST2_TS, &None);
+        let expected: Option<(&Uri, Option<_>)> = Some((&TEST2_COMPONENT5, None));
+        assert_eq!(transfer_result, expected);
+    }
+
+    #[tokio::test]
+    async fn barrel_component_location() {
+        // 导入路径通过文件系统解析，组件文件需要实际存在
+        let root_dir = tempfile::tempdir().unwrap();
+        let components_dir = root_dir.path().join("src/components");
+        std::fs::create_dir_all(&components_dir).unwrap();
+        let mut renderer = create_renderer();
+        renderer.root_uri_target_uri = OnceCell::from((
+            util::create_uri_from_path(root_dir.path()),
+            Uri::from_str("file:///path/.~$project").unwrap(),
+        ));
+        let mut create_file = |path: PathBuf, language_id: &str, content: String| {
+            std::fs::write(&path, &content).unwrap();
+            let uri = util::create_uri_from_path(&path);
+            renderer.create_node_from_document(
+                &uri,
+                FullTextDocument::new(language_id.to_string(), 0, content),
+            );
+            uri
+        };
+        create_file(
+            components_dir.join("index.ts"),
+            "typescript",
+            [
+                "import Bar from './Bar.vue';",
+                "export { default as Foo } from './Foo.vue';",
+                "export { Bar };",
+            ]
+            .join("\n"),
+        );
+        let [foo_uri, bar_uri] = ["Foo", "Bar"].map(|name| {
+            create_file(
+                components_dir.join(format!("{}.vue", name)),
+                "vue",
                 [
                     "<template>",
-                    "  <div :title=\"title\" :tabIndex=\"\">{{ text }}</div>",
+                    "  <div></div>",
                     "</template>",
                     "<script lang=\"ts\">",
+                    "import Vue from 'vue';",
                     "import { Component } from 'vue-property-decorator';",
-                    "import MyComponent2 from './MyComponent2.vue';",
                     "@Component",
-                    "export default class MyComponent1 extends MyComponent2 {",
-                    "  @Prop({ type: String, required: true })",
-                    "  private title!: string;",
-                    "  private text = 'Hello World';",
+                    &format!("export default class {} extends Vue {{", name),
+                    "}",
+                    "</script>",
+                ]
+                .join("\n"),
+            )
+        });
+        // 显式指定 index 文件以及省略 index 文件的目录导入
+        for (file, path) in [
+            ("index.vue", "./components/index.ts"),
+            ("extensionless.vue", "./components"),
+        ] {
+            let index_uri = util::create_uri_from_path(&root_dir.path().join("src").join(file));
+            add_vue_document(
+                &mut renderer,
+                &index_uri,
+                &[
+                    "<template>",
+                    "  <div><Foo /><Bar /></div>",
+                    "</template>",
+                    "<script lang=\"ts\">",
+                    "import Vue from 'vue';",
+                    "import { Component } from 'vue-property-decorator';",
+                    &format!("import {{ Foo, Bar }} from '{}';", path),
+                    "@Component({",
+                    "  components: {",
+                    "    Foo,",
+                    "    Bar,",
+                    "  },",
+                    "})",
+                    "export default class Index extends Vue {",
                     "}",
                     "</script>",
+                ],
+            );
+            renderer.render_cache.flush();
+            for (uri, tag) in [(&foo_uri, "Foo"), (&bar_uri, "Bar")] {
+                let location = renderer.get_component_location(&index_uri, tag).unwrap();
+                assert_eq!(&location.uri, uri);
+                assert_eq!(
+                    location.range,
+                    Range::new(Position::new(7, 21), Position::new(7, 24))
+                );
+            }
+            let provider = renderer.get_tags_provider(&index_uri).await;
+            let mut tags = provider
+                .provide_tags()
+                .iter()
+                .map(|v| v.name.clone())
+                .collect::<Vec<_>>();
+            tags.sort();
+            assert_eq!(tags, vec!["Bar", "Foo"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn tag_module_path() {
+        let renderer = create_renderer();
+        let provider = renderer.get_tags_provider(&TEST1_INDEX).await;
+        let tag = provider
+            .provide_tags()
+            .iter()
+            .find(|v| v.name == "MyComponent1")
+            .unwrap();
+        // 组件描述后追加源文件路径，而不是渲染目录中的路径
+        let Some(Description::MarkupContent(markup)) = &tag.description else {
+            panic!("expected markup description");
+        };
+        assert_eq!(
+            markup.value,
+            [
+                "```typescript",
+                "class MyComponent1",
+                "```",
+                "",
+                "```typescript",
+                "module \"/path/project/src/test1/components/MyComponent1.vue\"",
+                "```",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn barrel_default_re_export() {
+        let mut renderer = create_renderer();
+        let parent_uri = Uri::from_str("file:///path/project/src/test5/Parent.vue").unwrap();
+        let barrel_uri = Uri::from_str("file:///path/project/src/test5/index.ts").unwrap();
+        let foo_uri = Uri::from_str("file:///path/project/src/test5/Foo.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &parent_uri,
+            &[
+                "<template>",
+                "  <div><Foo /><DefaultFoo /></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "import DefaultFoo, { Foo } from './index.ts';",
+                "@Component({",
+                "  components: {",
+                "    Foo,",
+                "    DefaultFoo,",
+                "  },",
+                "})",
+                "export default class Parent extends Vue {",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.create_node_from_document(
+            &barrel_uri,
+            FullTextDocument::new(
+                "typescript".to_string(),
+                0,
+                [
+                    "export { default as Foo } from './Foo.vue';",
+                    "export { default } from './Foo.vue';",
+                ]
+                .join("\n")
+                .to_string(),
+            ),
+        );
+        add_vue_document(
+            &mut renderer,
+            &foo_uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Foo extends Vue {",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        // 具名导出和默认导出都转换到 Foo.vue 的默认导出
+        for export_name in [Some("Foo".to_string()), None] {
+            let transfer = renderer
+                .render_cache
+                .get_transfer_node(&barrel_uri, &export_name);
+            assert_eq!(transfer, Some((&foo_uri, None)));
+        }
+        for tag in ["Foo", "DefaultFoo"] {
+            let location = renderer.get_component_location(&parent_uri, tag).unwrap();
+            assert_eq!(location.uri, foo_uri);
+            assert_eq!(
+                location.range,
+                Range::new(Position::new(7, 21), Position::new(7, 24))
+            );
+        }
+    }
+
+    #[test]
+    fn implementation_locations() {
+        let renderer = create_renderer();
+        let locations =
+            renderer.get_implementation_locations(&TEST1_COMPONENT2, &Position::new(7, 25));
+        assert_eq!(
+            locations,
+            Some(vec![Location {
+                uri: TEST1_COMPONENT1.clone(),
+                range: Range::new(Position::new(7, 21), Position::new(7, 33)),
+            }])
+        );
+        // 子组件中不存在同名属性
+        let locations =
+            renderer.get_implementation_locations(&TEST1_COMPONENT2, &Position::new(9, 12));
+        assert_eq!(locations, None);
+        // 不位于类名或属性上
+        let locations =
+            renderer.get_implementation_locations(&TEST1_COMPONENT2, &Position::new(1, 2));
+        assert_eq!(locations, None);
+    }
+
+    #[tokio::test]
+    async fn delete_registered_component() {
+        let mut renderer = create_renderer();
+        let RenderCache::VueRenderCache(cache) = &renderer.render_cache[&TEST1_COMPONENT1] else {
+            panic!("MyComponent1.vue should be a vue node");
+        };
+        let content = cache.document.get_content(None).to_string();
+        assert_eq!(
+            renderer.get_registered_component_uri(&TEST1_INDEX, "MyComponent1"),
+            Some(&*TEST1_COMPONENT1)
+        );
+        renderer.did_delete_files(vec![TEST1_COMPONENT1.clone()]);
+        assert_eq!(
+            renderer.get_registered_component_uri(&TEST1_INDEX, "MyComponent1"),
+            None
+        );
+        // 其他节点的索引不受影响
+        let stats = renderer.get_render_cache_stats();
+        assert_eq!(stats.vue_nodes, 6);
+        assert_eq!(stats.unknown_nodes, 0);
+        assert_eq!(stats.register_edges, 1);
+        assert!(renderer
+            .get_registered_component_uri(&TEST2_INDEX, "MyComponent4")
+            .is_some());
+        // 重新创建后恢复注册关系
+        renderer.create_node_from_document(
+            &TEST1_COMPONENT1,
+            FullTextDocument::new("vue".to_string(), 0, content),
+        );
+        renderer.render_cache.flush();
+        assert_eq!(
+            renderer.get_registered_component_uri(&TEST1_INDEX, "MyComponent1"),
+            Some(&*TEST1_COMPONENT1)
+        );
+        assert_eq!(renderer.get_render_cache_stats().register_edges, 2);
+    }
+
+    #[test]
+    fn builtin_components() {
+        let renderer = create_renderer();
+        assert!(renderer
+            .get_component_location(&TEST1_INDEX, "keep-alive")
+            .is_none());
+        assert!(renderer
+            .get_component_location(&TEST1_INDEX, "KeepAlive")
+            .is_none());
+        assert_eq!(
+            renderer
+                .get_component_location(&TEST1_INDEX, "MyComponent1")
+                .map(|v| v.uri),
+            Some(TEST1_COMPONENT1.clone())
+        );
+        assert!(renderer
+            .get_component_prop_location(&TEST1_INDEX, "transition", "name")
+            .is_none());
+    }
+
+    #[test]
+    fn ts_base_props() {
+        let mut renderer = create_renderer();
+        let base_uri = Uri::from_str("file:///path/project/src/test4/base.ts").unwrap();
+        let child_uri = Uri::from_str("file:///path/project/src/test4/Child.vue").unwrap();
+        let parent_uri = Uri::from_str("file:///path/project/src/test4/Parent.vue").unwrap();
+        renderer.create_node_from_document(
+            &base_uri,
+            FullTextDocument::new(
+                "typescript".to_string(),
+                0,
+                [
+                    "import Vue from 'vue';",
+                    "import { Component, Prop } from 'vue-property-decorator';",
+                    "@Component",
+                    "export default class Base extends Vue {",
+                    "  @Prop({ type: String })",
+                    "  readonly baseTitle!: string;",
+                    "}",
                 ]
                 .join("\n")
                 .to_string(),
             ),
         );
-        // test1/components/MyComponent2.vue
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template>",
+                "  <div>{{ baseTitle }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop } from 'vue-property-decorator';",
+                "import Base from './base.ts';",
+                "@Component",
+                "export default class Child extends Base {",
+                "  @Prop({ type: Boolean })",
+                "  readonly disabled!: boolean;",
+                "}",
+                "</script>",
+            ],
+        );
+        add_vue_document(
+            &mut renderer,
+            &parent_uri,
+            &[
+                "<template>",
+                "  <Child :base-title=\"title\" />",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "import Child from './Child.vue';",
+                "@Component({",
+                "  components: {",
+                "    Child,",
+                "  },",
+                "})",
+                "export default class Parent extends Vue {",
+                "  private title = '';",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let props = renderer.get_effective_props(&child_uri);
+        let names = props.iter().map(|v| &v.name[..]).collect::<Vec<_>>();
+        assert_eq!(names, vec!["disabled", "baseTitle"]);
+        assert!(props[1].prop_params.is_some());
+        // 父组件绑定基类中声明的属性
+        let location = renderer
+            .get_component_prop_location(&parent_uri, "Child", ":base-title")
+            .unwrap();
+        assert_eq!(location.uri, base_uri);
+        assert_eq!(
+            location.range,
+            Range::new(Position::new(5, 11), Position::new(5, 20))
+        );
+        // 自身声明的属性
+        let location = renderer
+            .get_component_prop_location(&parent_uri, "Child", "disabled")
+            .unwrap();
+        assert_eq!(location.uri, child_uri);
+    }
+
+    #[test]
+    fn import_links() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/links.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "import MyComponent1 from './components/MyComponent1.vue';",
+                "@Component({ components: { MyComponent1 } })",
+                "export default class Links extends Vue {}",
+                "</script>",
+            ],
+        );
+        // 跳过 node_modules 中的模块
+        let links = renderer.get_import_links(&uri);
+        assert_eq!(links.len(), 1);
+        assert_eq!(
+            links[0].range,
+            Range::new(Position::new(6, 26), Position::new(6, 55))
+        );
+        assert_eq!(links[0].target.as_ref(), Some(&*TEST1_COMPONENT1));
+    }
+
+    #[test]
+    fn component_usages() {
+        let renderer = create_renderer();
+        let locations = renderer.get_component_usages(&TEST1_COMPONENT1);
+        assert_eq!(
+            locations,
+            vec![Location {
+                uri: TEST1_INDEX.clone(),
+                range: Range::new(Position::new(1, 3), Position::new(1, 15)),
+            }]
+        );
+        // 通过 ts 文件中转注册
+        let locations = renderer.get_component_usages(&TEST2_COMPONENT4);
+        assert_eq!(
+            locations,
+            vec![Location {
+                uri: TEST2_INDEX.clone(),
+                range: Range::new(Position::new(1, 3), Position::new(1, 15)),
+            }]
+        );
+        assert_eq!(renderer.get_component_usages(&TEST1_COMPONENT3), vec![]);
+    }
+
+    #[test]
+    fn prop_call_hierarchy() {
+        let mut renderer = create_renderer();
+        let item = renderer
+            .get_prop_call_hierarchy_item(&TEST1_COMPONENT1, &Position::new(9, 12))
+            .unwrap();
+        assert_eq!(item.name, "title");
+        assert_eq!(item.detail.as_deref(), Some("MyComponent1"));
+        assert_eq!(
+            item.range,
+            Range::new(Position::new(9, 10), Position::new(9, 15))
+        );
+        // 不是 prop 的属性
+        assert!(renderer
+            .get_prop_call_hierarchy_item(&TEST1_COMPONENT1, &Position::new(10, 12))
+            .is_none());
+        let calls = renderer.get_prop_incoming_calls(&TEST1_COMPONENT1, &item.name);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].from.uri, *TEST1_INDEX);
+        assert_eq!(calls[0].from.name, "title");
+        assert_eq!(
+            calls[0].from_ranges,
+            vec![Range::new(Position::new(1, 23), Position::new(1, 28))]
+        );
+
+        let parent_uri = Uri::from_str("file:///path/project/src/test1/Bindings.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &parent_uri,
+            &[
+                "<template>",
+                "  <div>",
+                "    <MyComponent1 :title=\"name\" @title=\"a\" />",
+                "    <my-component1 v-bind:title.sync=\"text\" :text=\"text\" />",
+                "  </div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import MyComponent1 from './components/MyComponent1.vue';",
+                "@Component({ components: { MyComponent1 } })",
+                "export default class Bindings extends Vue {}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let calls = renderer.get_prop_incoming_calls(&TEST1_COMPONENT1, "title");
+        assert_eq!(
+            calls
+                .iter()
+                .filter(|v| v.from.uri == parent_uri)
+                .map(|v| (&v.from.name[..], v.from.detail.as_deref(), v.from.range))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    ":title",
+                    Some("Bindings"),
+                    Range::new(Position::new(2, 26), Position::new(2, 30))
+                ),
+                (
+                    "v-bind:title.sync",
+                    Some("Bindings"),
+                    Range::new(Position::new(3, 38), Position::new(3, 42))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn decorator_template_locations() {
+        let mut renderer = create_renderer();
+        let child_uri = Uri::from_str("file:///path/project/src/test1/Emitter.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template>",
+                "  <div><p ref=\"box\" /><p ref=\"box\" /><p ref=\"inner\" /></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component, Emit, Ref } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Emitter extends Vue {",
+                "  @Ref('box') readonly box!: HTMLElement;",
+                "  @Emit('change')",
+                "  onChange() {}",
+                "  @Ref() readonly inner!: HTMLElement;",
+                "  @Emit()",
+                "  resetValue() {}",
+                "  @Emit(",
+                "    'close'",
+                "  )",
+                "  onClose() {}",
+                "}",
+                "</script>",
+            ],
+        );
+        let parent_uri = Uri::from_str("file:///path/project/src/test1/EmitParent.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &parent_uri,
+            &[
+                "<template>",
+                "  <Emitter @change=\"a\" v-on:change.native=\"b\" />",
+                "  <Emitter @reset-value=\"c\" @close=\"d\" />",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "import Emitter from './Emitter.vue';",
+                "@Component({ components: { Emitter } })",
+                "export default class EmitParent extends Vue {}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let locations = renderer.get_decorator_template_locations(&child_uri, &Position::new(8, 9));
+        assert_eq!(
+            locations.iter().map(|v| v.range).collect::<Vec<_>>(),
+            vec![
+                Range::new(Position::new(1, 15), Position::new(1, 18)),
+                Range::new(Position::new(1, 30), Position::new(1, 33)),
+            ]
+        );
+        let locations =
+            renderer.get_decorator_template_locations(&child_uri, &Position::new(9, 11));
+        assert_eq!(
+            locations,
+            vec![
+                Location {
+                    uri: parent_uri.clone(),
+                    range: Range::new(Position::new(1, 11), Position::new(1, 18)),
+                },
+                Location {
+                    uri: parent_uri.clone(),
+                    range: Range::new(Position::new(1, 23), Position::new(1, 41)),
+                },
+            ]
+        );
+        // 不位于装饰器上
+        assert_eq!(
+            renderer.get_decorator_template_locations(&child_uri, &Position::new(8, 24)),
+            vec![]
+        );
+        // 未指定参数时使用成员名称和方法名称的短横线形式
+        let locations =
+            renderer.get_decorator_template_locations(&child_uri, &Position::new(11, 4));
+        assert_eq!(
+            locations.iter().map(|v| v.range).collect::<Vec<_>>(),
+            vec![Range::new(Position::new(1, 45), Position::new(1, 50))]
+        );
+        let locations =
+            renderer.get_decorator_template_locations(&child_uri, &Position::new(12, 4));
+        assert_eq!(
+            locations.iter().map(|v| v.range).collect::<Vec<_>>(),
+            vec![Range::new(Position::new(2, 11), Position::new(2, 23))]
+        );
+        // 参数位于装饰器的下一行
+        let locations =
+            renderer.get_decorator_template_locations(&child_uri, &Position::new(15, 6));
+        assert_eq!(
+            locations.iter().map(|v| v.range).collect::<Vec<_>>(),
+            vec![Range::new(Position::new(2, 28), Position::new(2, 34))]
+        );
+    }
+
+    #[test]
+    fn inject_diagnostics() {
+        let mut renderer = create_renderer();
+        let child_uri = Uri::from_str("file:///path/project/src/test1/Injected.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component, Inject } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Injected extends Vue {",
+                "  @Inject() readonly theme!: string;",
+                "  @Inject('size') readonly currentSize!: number;",
+                "  @Inject({ from: 'color', default: 'red' }) readonly color!: string;",
+                "  @Inject(THEME_KEY) readonly symbolTheme!: string;",
+                "  @Inject('$validator') readonly validator!: unknown;",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        // 未被其他组件注册时不检查
+        assert_eq!(renderer.get_inject_diagnostics(&child_uri), vec![]);
+        let parent_uri = Uri::from_str("file:///path/project/src/test1/Provider.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &parent_uri,
+            &[
+                "<template>",
+                "  <Injected />",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component, Provide } from 'vue-property-decorator';",
+                "import Injected from './Injected.vue';",
+                "@Component({ components: { Injected } })",
+                "export default class Provider extends Vue {",
+                "  @Provide() theme = 'dark';",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        // 没有任何组件提供 size 时可能由根实例或插件全局提供
+        assert_eq!(renderer.get_inject_diagnostics(&child_uri), vec![]);
+        let other_uri = Uri::from_str("file:///path/project/src/test1/Other.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &other_uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component, Provide } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Other extends Vue {",
+                "  @Provide('size') size = 1;",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let diagnostics = renderer.get_inject_diagnostics(&child_uri);
+        // 已提供的 theme、存在默认值的 color 以及无法确定提供者的 symbolTheme 和 validator 不报告
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|v| (&v.message[..], v.range, v.severity))
+                .collect::<Vec<_>>(),
+            vec![(
+                "Injection 'size' is not provided by any parent component.",
+                Range::new(Position::new(9, 27), Position::new(9, 38)),
+                Some(DiagnosticSeverity::HINT),
+            )]
+        );
+    }
+
+    #[test]
+    fn provide_inject_locations() {
+        let mut renderer = create_renderer();
+        let child_uri = Uri::from_str("file:///path/project/src/test1/Injected.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component, Inject } from 'vue-property-decorator';",
+                "@Component({ inject: ['size'] })",
+                "export default class Injected extends Vue {",
+                "  @Inject() readonly theme!: string;",
+                "}",
+                "</script>",
+            ],
+        );
+        let parent_uri = Uri::from_str("file:///path/project/src/test1/Provider.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &parent_uri,
+            &[
+                "<template>",
+                "  <Injected />",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component, Provide } from 'vue-property-decorator';",
+                "import Injected from './Injected.vue';",
+                "@Component({ components: { Injected }, provide: { size: 1 } })",
+                "export default class Provider extends Vue {",
+                "  @Provide() theme = 'dark';",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let location = |uri: &Uri, line, start, end| Location {
+            uri: uri.clone(),
+            range: Range::new(Position::new(line, start), Position::new(line, end)),
+        };
+        // 装饰器和选项中的注入都可以跳转到提供的位置
+        assert_eq!(
+            renderer.get_inject_provider_locations(&child_uri, &Position::new(8, 23)),
+            vec![location(&parent_uri, 9, 13, 18)]
+        );
+        assert_eq!(
+            renderer.get_inject_provider_locations(&child_uri, &Position::new(6, 24)),
+            vec![location(&parent_uri, 7, 50, 54)]
+        );
+        assert_eq!(
+            renderer.get_provide_injector_locations(&parent_uri, &Position::new(7, 51)),
+            vec![location(&child_uri, 6, 23, 27)]
+        );
+        assert_eq!(
+            renderer.get_provide_injector_locations(&parent_uri, &Position::new(9, 14)),
+            vec![location(&child_uri, 8, 21, 26)]
+        );
+        assert_eq!(renderer.get_inject_diagnostics(&child_uri), vec![]);
+    }
+
+    #[test]
+    fn render_cache_stats() {
+        let renderer = create_renderer();
+        assert_eq!(
+            renderer.get_render_cache_stats(),
+            RenderCacheGraphStats {
+                vue_nodes: 7,
+                ts_nodes: 1,
+                lib_nodes: 0,
+                unknown_nodes: 0,
+                extends_edges: 1,
+                register_edges: 2,
+                transfer_edges: 1,
+                virtual_edges: 0,
+            }
+        );
+        assert_eq!(renderer.get_library_count(), 0);
+        assert_eq!(renderer.get_will_create_files_count(), 0);
+    }
+
+    #[test]
+    fn decorator_option_hover() {
+        let renderer = create_renderer();
+        // `@Prop({ type: String, required: true })` 中的 `required`
+        let hover = renderer
+            .get_decorator_option_hover(&TEST1_COMPONENT1, &Position::new(8, 26))
+            .unwrap();
+        assert_eq!(
+            hover.range,
+            Some(Range::new(Position::new(8, 24), Position::new(8, 32)))
+        );
+        // 不位于装饰器选项上
+        assert!(renderer
+            .get_decorator_option_hover(&TEST1_COMPONENT1, &Position::new(9, 12))
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn update_full() {
+        let mut renderer = create_renderer();
+        let params = create_params(&TEST1_INDEX, &[(3, 7, 3, 7, Some(0), "1")]);
+        let expected = DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: TEST1_INDEX.clone(),
+                version: 1,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: "".to_string(),
+            }],
+        };
+        let result = renderer
+            .update(
+                &TEST1_INDEX,
+                params,
+                &FullTextDocument::new(
+                    "vue".to_string(),
+                    1,
+                    [
+                        "<template>",
+                        "  <MyComponent1 title=\"Title\" />",
+                        "</template>",
+                        "<script1 lang=\"ts\">",
+                        "import Vue from 'vue';",
+                        "import { Component } from 'vue-property-decorator';",
+                        "import MyComponent1 from './components/MyComponent1.vue';",
+                        "@Component({",
+                        "  components: {",
+                        "    MyComponent1,",
+                        "  },",
+                        "})",
+                        "export default class Index extends Vue {",
+                        "}",
+                        "</script>",
+                    ]
+                    .join("\n"),
+                ),
+            )
+            .await;
+        assert_eq!(result, expected);
+
+        let params = create_params(&TEST1_INDEX, &[(3, 7, 3, 8, Some(1), "")]);
+        let result = renderer
+            .update(
+                &TEST1_INDEX,
+                params,
+                &FullTextDocument::new(
+                    "vue".to_string(),
+                    1,
+                    [
+                        "<template>",
+                        "  <MyComponent1 title=\"Title\" />",
+                        "</template>",
+                        "<script lang=\"ts\">",
+                        "import Vue from 'vue';",
+                        "import { Component } from 'vue-property-decorator';",
+                        "import MyComponent1 from './components/MyComponent1.vue';",
+                        "@Component({",
+                        "  components: {",
+                        "    MyComponent1,",
+                        "  },",
+                        "})",
+                        "export default class Index extends Vue {",
+                        "}",
+                        "</script>",
+                    ]
+                    .join("\n"),
+                ),
+            )
+            .await;
+        let expected = DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: TEST1_INDEX.clone(),
+                version: 1,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: renderer
+                    .render_cache
+                    .get_node_render_content(&TEST1_INDEX)
+                    .unwrap(),
+            }],
+        };
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn unresolved_import_diagnostics() {
+        let renderer = create_renderer();
+        let import_files = renderer.get_component_import_files(&TEST1_INDEX);
+        assert_eq!(
+            import_files
+                .iter()
+                .map(|(path, file_path, _)| (&path[..], file_path.clone()))
+                .collect::<Vec<_>>(),
+            vec![(
+                "./components/MyComponent1.vue",
+                util::to_file_path(&TEST1_COMPONENT1)
+            )]
+        );
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Exists.vue"), "").unwrap();
+        let diagnostics = Renderer::get_unresolved_import_diagnostics(vec![
+            (
+                "./Exists.vue".to_string(),
+                dir.path().join("Exists.vue"),
+                Range::default(),
+            ),
+            (
+                "./Missing.vue".to_string(),
+                dir.path().join("Missing.vue"),
+                Range::default(),
+            ),
+        ]);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|v| &v.message[..])
+                .collect::<Vec<_>>(),
+            vec!["Cannot find module './Missing.vue'."]
+        );
+    }
+
+    #[tokio::test]
+    async fn effective_props() {
+        let mut renderer = create_renderer();
+        let props = renderer.get_effective_props(&TEST1_COMPONENT1);
+        let names = props.iter().map(|v| &v.name[..]).collect::<Vec<_>>();
+        assert_eq!(names, vec!["title", "text", "readonly", "state"]);
+        // 祖先节点更新后，缓存应该失效
+        let params = create_params(&TEST1_COMPONENT2, &[(9, 18, 9, 18, Some(0), "1")]);
+        renderer
+            .update(&TEST1_COMPONENT2, params, &create_empty_document())
+            .await;
+        let props = renderer.get_effective_props(&TEST1_COMPONENT1);
+        let names = props.iter().map(|v| &v.name[..]).collect::<Vec<_>>();
+        assert_eq!(names, vec!["title", "text", "readonly1", "state"]);
+        // 版本沿继承关系传播到注册了子组件的节点
+        assert_eq!(
+            renderer
+                .render_cache
+                .get(&TEST1_INDEX)
+                .unwrap()
+                .get_version(),
+            Some(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn effective_props_cache() {
+        let mut renderer = create_renderer();
+        let is_cached = |renderer: &Renderer| match renderer.render_cache.get(&TEST1_COMPONENT1) {
+            Some(RenderCache::VueRenderCache(cache)) => cache.effective_props.get().is_some(),
+            _ => false,
+        };
+        assert!(!is_cached(&renderer));
+        renderer.get_effective_props(&TEST1_COMPONENT1);
+        assert!(is_cached(&renderer));
+        // 祖先节点的属性变化后，子组件的缓存被清空
+        let params = create_params(&TEST1_COMPONENT2, &[(9, 18, 9, 18, Some(0), "1")]);
+        renderer
+            .update(&TEST1_COMPONENT2, params, &create_empty_document())
+            .await;
+        assert!(!is_cached(&renderer));
+        let props = renderer.get_effective_props(&TEST1_COMPONENT1);
+        let names = props.iter().map(|v| &v.name[..]).collect::<Vec<_>>();
+        assert_eq!(names, vec!["title", "text", "readonly1", "state"]);
+        // 祖先节点的模版变更只移动属性的位置，子组件的缓存同样被清空
+        let params = create_params(&TEST1_COMPONENT2, &[(1, 7, 1, 7, Some(0), "a")]);
+        renderer
+            .update(&TEST1_COMPONENT2, params, &create_empty_document())
+            .await;
+        assert!(!is_cached(&renderer));
+        let range = |uri: &Uri| {
+            renderer
+                .get_effective_props(uri)
+                .into_iter()
+                .find(|v| v.name == "readonly1")
+                .map(|v| v.range)
+        };
+        assert_eq!(range(&TEST1_COMPONENT1), range(&TEST1_COMPONENT2));
+    }
+
+    #[tokio::test]
+    async fn mixins() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/mixins.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div>{{ text }}{{ show }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Mixins } from 'vue-property-decorator';",
+                "import MyComponent1 from './components/MyComponent1.vue';",
+                "import MyComponent3 from './components/MyComponent3.vue';",
+                "@Component",
+                "export default class Mixin extends Mixins(MyComponent1, MyComponent3) {",
+                "  private own = '';",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let props = renderer.get_effective_props(&uri);
+        let names = props.iter().map(|v| &v.name[..]).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["own", "title", "text", "readonly", "state", "disabled", "show"]
+        );
+        let content = renderer.render_cache.get_node_render_content(&uri).unwrap();
+        assert!(content
+            .contains("let {own,text,state,show} = this;const {title,readonly,disabled} = this;"));
+        // 移除混入 MyComponent3
+        let params = create_params(&uri, &[(8, 54, 8, 68, Some(14), "")]);
+        renderer
+            .update(&uri, params, &create_empty_document())
+            .await;
+        let props = renderer.render_cache.get_effective_props(&uri);
+        let names = props.iter().map(|v| &v.name[..]).collect::<Vec<_>>();
+        assert_eq!(names, vec!["own", "title", "text", "readonly", "state"]);
+    }
+
+    #[tokio::test]
+    async fn options_mixins() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/OptionsMixin.vue").unwrap();
+        let lines = |mixins: &str| {
+            [
+                "<template>",
+                "  <div>{{ text }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import MyComponent1 from './components/MyComponent1.vue';",
+                "import MyComponent3 from './components/MyComponent3.vue';",
+                &format!("@Component({{ mixins: [{}] }})", mixins),
+                "export default class OptionsMixin extends Vue {",
+                "  private own = '';",
+                "}",
+                "</script>",
+            ]
+            .join("\n")
+        };
         renderer.create_node_from_document(
-            &TEST1_COMPONENT2,
+            &uri,
+            FullTextDocument::new("vue".to_string(), 0, lines("MyComponent1")),
+        );
+        renderer.render_cache.flush();
+        let content = renderer.render_cache.get_node_render_content(&uri).unwrap();
+        assert!(content.contains("let {own,text,state} = this;const {title,readonly} = this;"));
+        // 混入组件的成员通过接口合并补充到类的类型中
+        assert!(content.ends_with(
+            "\ntype __VueMixin0 = InstanceType<typeof MyComponent1>;export default interface OptionsMixin extends __VueMixin0 {}"
+        ));
+        // 混入组件变更后全量渲染
+        let params = create_params(&uri, &[(7, 34, 7, 34, Some(0), ", MyComponent3")]);
+        let document =
+            FullTextDocument::new("vue".to_string(), 1, lines("MyComponent1, MyComponent3"));
+        let result = renderer.update(&uri, params, &document).await;
+        assert_eq!(result.content_changes.len(), 1);
+        assert_eq!(result.content_changes[0].range, None);
+        assert!(result.content_changes[0].text.ends_with(
+            "type __VueMixin1 = InstanceType<typeof MyComponent3>;export default interface OptionsMixin extends __VueMixin0,__VueMixin1 {}"
+        ));
+    }
+
+    #[test]
+    fn global_properties() {
+        let mut renderer = create_renderer();
+        let lib_uri = Uri::from_str("file:///path/project/node_modules/ui-lib").unwrap();
+        let create_prop = |name: &str| LibComponentProp {
+            name: name.to_string(),
+            description: None,
+            location: Location {
+                uri: lib_uri.clone(),
+                range: Range::default(),
+            },
+        };
+        renderer.render_cache.add_node(
+            &lib_uri,
+            RenderCache::LibRenderCache(LibRenderCache {
+                name: "ui-lib".to_string(),
+                components: vec![],
+                global_properties: vec![
+                    create_prop("$message"),
+                    create_prop("t"),
+                    create_prop("format"),
+                ],
+            }),
+        );
+        let uri = Uri::from_str("file:///path/project/src/test1/Global.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div>{{ t('a') }}{{ foo.t }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Global extends Vue {",
+                "  private foo = { t: '' };",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        // `$` 开头的全局属性通过 `this.` 前缀访问，模版中未引用的全局属性，均不需要解构
+        let content = renderer.render_cache.get_node_render_content(&uri).unwrap();
+        assert!(content.contains("let {foo} = this;const {t} = this;"));
+        let names = |position: Position| {
+            renderer
+                .get_global_property_completions(&uri, &position)
+                .into_iter()
+                .map(|v| v.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(Position::new(1, 11)), vec!["$message", "t", "format"]);
+        // 成员访问中不提示
+        assert!(names(Position::new(1, 27)).is_empty());
+    }
+
+    #[test]
+    fn component_symbols() {
+        let renderer = create_renderer();
+        let symbols = renderer.get_component_symbols(&TEST1_COMPONENT1);
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "Props");
+        let children = symbols[0].children.as_ref().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "title");
+        assert_eq!(
+            children[0].range,
+            Range::new(Position::new(9, 10), Position::new(9, 15))
+        );
+    }
+
+    #[test]
+    fn component_event_symbols() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/Events.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Emit, Prop, Vue, Watch } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Events extends Vue {",
+                "  @Prop(String) title!: string;",
+                "  @Emit('update:title')",
+                "  setTitle() {}",
+                "  @Emit()",
+                "  resetCount() {}",
+                "  @Watch('title')",
+                "  onTitleChange() {}",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let symbols = renderer.get_component_symbols(&uri);
+        assert_eq!(
+            symbols.iter().map(|v| &v.name[..]).collect::<Vec<_>>(),
+            vec!["Props", "Events", "Watchers"]
+        );
+        let events = symbols[1].children.as_ref().unwrap();
+        // 使用触发的事件名称，未指定时为方法名称的短横线形式
+        assert_eq!(
+            events.iter().map(|v| &v.name[..]).collect::<Vec<_>>(),
+            vec!["update:title", "reset-count"]
+        );
+        assert_eq!(events[0].kind, SymbolKind::EVENT);
+        assert_eq!(
+            events[0].range,
+            Range::new(Position::new(9, 2), Position::new(9, 10))
+        );
+        let watchers = symbols[2].children.as_ref().unwrap();
+        assert_eq!(watchers[0].name, "onTitleChange");
+    }
+
+    #[test]
+    fn component_counts() {
+        let renderer = create_renderer();
+        assert_eq!(
+            renderer.get_component_counts(&TEST1_COMPONENT1),
+            Some((Range::new(Position::new(7, 21), Position::new(7, 33)), 1, 0))
+        );
+        assert_eq!(renderer.get_component_usages(&TEST1_COMPONENT1).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn readonly_props() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/readonly.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div @click=\"title = 'x'\">{{ count }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Readonly extends Vue {",
+                "  @Prop(String) title!: string;",
+                "  count = 0;",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let content = renderer.render_cache.get_node_render_content(&uri).unwrap();
+        assert!(content.contains("let {count} = this;const {title} = this;"));
+        // 对 prop 赋值的诊断映射回模版
+        let range = Range::new(Position::new(1, 15), Position::new(1, 20));
+        let (text, _) = renderer
+            .get_node_render_content_range(&uri, &range)
+            .unwrap();
+        assert_eq!(text, "title");
+        let diagnostic_range = Range::new(
+            renderer.get_mapping_position(&uri, &range.start).unwrap(),
+            renderer.get_mapping_position(&uri, &range.end).unwrap(),
+        );
+        assert_eq!(
+            renderer.get_original_range(&uri, &diagnostic_range),
+            Some(range)
+        );
+        // 增加 prop 后重新渲染，新的 prop 同样使用 `const` 声明
+        let document = FullTextDocument::new(
+            "vue".to_string(),
+            1,
+            [
+                "<template>",
+                "  <div @click=\"title = 'x'\">{{ count }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Readonly extends Vue {",
+                "  @Prop(String) title!: string;",
+                "  @Prop(Number) size!: number;",
+                "  count = 0;",
+                "}",
+                "</script>",
+            ]
+            .join("\n"),
+        );
+        let params = create_params(
+            &uri,
+            &[(8, 0, 8, 0, Some(0), "  @Prop(Number) size!: number;\n")],
+        );
+        let result = renderer.update(&uri, params, &document).await;
+        assert_eq!(result.content_changes.len(), 1);
+        assert_eq!(result.content_changes[0].range, None);
+        assert!(result.content_changes[0]
+            .text
+            .contains("let {count} = this;const {title,size} = this;"));
+    }
+
+    #[test]
+    fn source_uri() {
+        let renderer = create_renderer();
+        let source_uri = |uri: &str| {
+            renderer
+                .get_source_uri(&Uri::from_str(uri).unwrap())
+                .map(|v| v.to_string())
+        };
+        assert_eq!(
+            source_uri("file:///path/.~$project/src/test1/index.vue.ts"),
+            Some("file:///path/project/src/test1/index.vue".to_string())
+        );
+        assert_eq!(
+            source_uri("file:///path/.~$project/src/ts/transfer.ts"),
+            Some("file:///path/project/src/ts/transfer.ts".to_string())
+        );
+        assert_eq!(source_uri("file:///path/project/src/test1/index.vue"), None);
+    }
+
+    #[test]
+    fn dump_graph() {
+        let renderer = create_renderer();
+        let dot = renderer.dump_graph();
+        assert!(dot.starts_with("digraph RenderCacheGraph {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("[label=\"src/test1/index.vue\", shape=box];"));
+        assert!(dot.contains("[label=\"register: MyComponent1\"];"));
+        assert!(dot.contains("[label=\"extends\"];"));
+    }
+
+    #[test]
+    fn component_prop_hover() {
+        let renderer = create_renderer();
+        // 组件的 prop `title` 与 HTML 全局属性同名
+        let hover = renderer
+            .get_component_prop_hover(&TEST1_INDEX, &Position::new(1, 17))
+            .unwrap();
+        assert_eq!(
+            hover.range,
+            Some(Range::new(Position::new(1, 16), Position::new(1, 21)))
+        );
+        let HoverContents::Markup(markup) = hover.contents else {
+            panic!("expected markup");
+        };
+        assert!(markup
+            .value
+            .starts_with("```typescript\n(prop) MyComponent1.title: string\n```"));
+        let location = renderer
+            .get_component_prop_location(&TEST1_INDEX, "MyComponent1", ":title.sync")
+            .unwrap();
+        assert_eq!(location.uri, *TEST1_COMPONENT1);
+        // 原生元素保持 HTML 属性的行为
+        assert_eq!(
+            renderer.get_component_prop_hover(&TEST1_COMPONENT1, &Position::new(1, 9)),
+            None
+        );
+    }
+
+    #[test]
+    fn pug_template() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/Pug.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template lang=\"pug\">",
+                "div(:title=\"title\") {{ count }}",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Pug extends Vue {",
+                "  @Prop(String) title!: string;",
+                "  count = 0;",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        // 模版不进行编译，不产生映射
+        let Some(RenderCache::VueRenderCache(cache)) = renderer.render_cache.get(&uri) else {
+            panic!();
+        };
+        assert!(cache.template.is_none());
+        assert!(cache.mapping.is_empty());
+        assert_eq!(cache.template_compile_result.get_content(None), "");
+        assert_eq!(
+            renderer.get_position_type(&uri, &Position::new(1, 12)),
+            None
+        );
+        assert_eq!(
+            renderer.get_mapping_position(&uri, &Position::new(1, 12)),
+            None
+        );
+        // 脚本仍然正常解析
+        assert_eq!(
+            renderer.get_position_type(&uri, &Position::new(7, 18)),
+            Some(PositionType::Script)
+        );
+        assert_eq!(
+            renderer
+                .get_effective_props(&uri)
+                .iter()
+                .map(|v| &v.name[..])
+                .collect::<Vec<_>>(),
+            vec!["title", "count"]
+        );
+        let diagnostics = renderer.get_template_lang_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(0, 16), Position::new(0, 19))
+        );
+        assert_eq!(
+            diagnostics[0].severity,
+            Some(DiagnosticSeverity::INFORMATION)
+        );
+        assert!(renderer
+            .get_template_lang_diagnostics(&TEST1_INDEX)
+            .is_empty());
+    }
+
+    #[test]
+    fn extends_non_component() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test6/Child.vue").unwrap();
+        let base_uri = Uri::from_str("file:///path/project/src/test6/Base.ts").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component } from 'vue-property-decorator';",
+                "import Base from './Base.ts';",
+                "@Component",
+                "export default class Child extends Base {",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.create_node_from_document(
+            &base_uri,
             FullTextDocument::new(
-                "vue".to_string(),
+                "typescript".to_string(),
                 0,
-                [
-                    "<template>",
-                    "  <div></div>",
-                    "</template>",
-                    "<script lang=\"ts\">",
-                    "import Vue from 'vue';",
-                    "import { Component } from 'vue-property-decorator';",
-                    "@Component",
-                    "export default class MyComponent2 extends Vue {",
-                    "  @Prop({ type: Boolean, default: false })",
-                    "  private readonly!: boolean;",
-                    "  private state = 'init';",
-                    "}",
-                    "</script>",
-                ]
-                .join("\n")
-                .to_string(),
+                "export default class Base {\n}".to_string(),
             ),
         );
-        // test1/components/MyComponent3.vue
+        renderer.render_cache.flush();
+        let diagnostics = renderer.get_extends_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(7, 35), Position::new(7, 39))
+        );
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::HINT));
+        // 继承组件及继承 Vue 时没有诊断
+        assert!(renderer
+            .get_extends_diagnostics(&TEST1_COMPONENT1)
+            .is_empty());
+        assert!(renderer.get_extends_diagnostics(&TEST1_INDEX).is_empty());
+    }
+
+    #[test]
+    fn original_script_range() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/Format.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Format extends Vue {",
+                "  private a = 1;",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let Some(RenderCache::VueRenderCache(cache)) = renderer.render_cache.get(&uri) else {
+            panic!();
+        };
+        let len = cache.template_compile_result.get_content(None).len() as u32;
+        let range = |start: (u32, u32), end: (u32, u32)| {
+            Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+        };
+        // render 区域之前的范围不变
+        assert_eq!(
+            renderer.get_original_script_range(&uri, &range((7, 2), (7, 9))),
+            Some(range((7, 2), (7, 9)))
+        );
+        // 模版编译结果之后的类结束括号
+        assert_eq!(
+            renderer.get_original_script_range(&uri, &range((9, len), (9, len + 1))),
+            Some(range((8, 0), (8, 1)))
+        );
+        // 跨越 render 区域或位于脚本之外
+        assert_eq!(
+            renderer.get_original_script_range(&uri, &range((7, 2), (9, len + 1))),
+            None
+        );
+        assert_eq!(
+            renderer.get_original_script_range(&uri, &range((10, 0), (10, 9))),
+            None
+        );
+    }
+
+    #[test]
+    fn expression_selection_range() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/selection.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div :title=\"a.b.c\"></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Selection extends Vue {",
+                "  private a = { b: { c: '' } };",
+                "}",
+                "</script>",
+            ],
+        );
+        let range =
+            |start: u32, end: u32| Range::new(Position::new(1, start), Position::new(1, end));
+        // tsserver 返回的渲染文件中的范围，由内到外依次为 `a`, `a.b`, `a.b.c`
+        let render_position = |character: u32| {
+            renderer
+                .get_mapping_position(&uri, &Position::new(1, character))
+                .unwrap()
+        };
+        let ts_ranges = [(15, 16), (15, 18), (15, 20)]
+            .into_iter()
+            .map(|(start, end)| {
+                let render_range = Range::new(render_position(start), render_position(end));
+                renderer.get_original_range(&uri, &render_range).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let selection_range = renderer.get_selection_range(&uri, &Position::new(1, 15), &ts_ranges);
+        let mut ranges = vec![];
+        let mut current = Some(&selection_range);
+        while let Some(item) = current {
+            ranges.push(item.range);
+            current = item.parent.as_deref();
+        }
+        assert_eq!(
+            ranges,
+            vec![
+                range(15, 16),
+                range(15, 18),
+                // 不包含引号的属性值与 `a.b.c` 相同
+                range(15, 20),
+                range(7, 21),
+                range(2, 28),
+                Range::new(Position::new(0, 0), Position::new(2, 11)),
+            ]
+        );
+
+        // 非 vue 文件直接使用 tsserver 返回的范围
+        let uri = Uri::from_str("file:///path/project/src/util.ts").unwrap();
+        let ts_ranges = vec![range(15, 16), range(15, 18), range(0, 30)];
+        let selection_range = renderer.get_selection_range(&uri, &Position::new(1, 15), &ts_ranges);
+        let mut ranges = vec![];
+        let mut current = Some(&selection_range);
+        while let Some(item) = current {
+            ranges.push(item.range);
+            current = item.parent.as_deref();
+        }
+        assert_eq!(ranges, ts_ranges);
+    }
+
+    #[tokio::test]
+    async fn uninitialized() {
+        // 初始化完成前到达的请求不应导致崩溃
+        let mut renderer = Renderer::new();
+        let uri = Uri::from_str("file:///path/project/src/App.vue").unwrap();
+        let position = Position::new(1, 4);
+        renderer.did_open(&uri).await;
+        assert_eq!(renderer.get_position_type(&uri, &position), None);
+        assert!(renderer.get_html_document(&uri).is_none());
+        assert_eq!(renderer.get_source_uri(&uri), None);
+        assert_eq!(renderer.get_uri_from_path(&uri, "./Other.vue"), None);
+        assert_eq!(renderer.dump_graph(), "");
+        let options = ConvertOptions {
+            uri: Some(&uri),
+            renderer: Some(&renderer),
+        };
+        assert_eq!(uri.clone().convert_to(&options).await, uri);
+        assert_eq!(uri.clone().convert_back(&options).await, uri);
+    }
+
+    #[test]
+    fn static_prop_diagnostics() {
+        let mut renderer = create_renderer();
+        let child_uri = Uri::from_str("file:///path/project/src/test1/Tooltip.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template><div></div></template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Tooltip extends Vue {",
+                "  @Prop({ type: Number }) title!: number;",
+                "  @Prop({ type: String }) label!: string;",
+                "  @Prop({ type: Object }) config;",
+                "}",
+                "</script>",
+            ],
+        );
+        let uri = Uri::from_str("file:///path/project/src/test1/page.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div title=\"a\"><Tooltip title=\"1\" label=\"b\" :title=\"1\" config=\"c\" /></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import Tooltip from './Tooltip.vue';",
+                "@Component({ components: { Tooltip } })",
+                "export default class Page extends Vue {}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        // 组件上的 `title` 按 prop 检查，原生元素上的 `title` 保持 HTML 属性的行为
+        let diagnostics = renderer.get_template_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(1, 26), Position::new(1, 31))
+        );
+        assert!(diagnostics[0].message.starts_with("Prop 'title'"));
+        // 没有类型注解时使用 `type` 转换后的类型
+        assert_eq!(
+            diagnostics[1].range,
+            Range::new(Position::new(1, 57), Position::new(1, 63))
+        );
+        assert!(diagnostics[1]
+            .message
+            .contains("expects Record<string, any>"));
+    }
+
+    #[test]
+    fn synthetic_region() {
+        let renderer = create_renderer();
+        // render 方法插入在类的结束括号处，props 列表位于 `protected render(){` 之后
+        let props_position = Position::new(11, "protected render(){let {".len() as u32);
+        assert!(renderer.is_in_synthetic_region(&TEST1_COMPONENT1, &props_position));
+        assert!(renderer.is_in_synthetic_region(&TEST1_COMPONENT1, &Position::new(11, 0)));
+        assert!(!renderer.is_in_synthetic_region(&TEST1_COMPONENT1, &Position::new(10, 10)));
+
+        let mut renderer = Renderer::new();
+        let uri = Uri::from_str("file:///path/project/src/Empty.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div :title=\"\">{{ title }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Empty extends Vue {",
+                "  private title = '';",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        // 与请求相同，经由位置类型得到编译结果中的位置
+        let get_template_expr_position =
+            |position: Position| match renderer.get_position_type(&uri, &position) {
+                Some(PositionType::TemplateExpr(pos)) => pos,
+                position_type => panic!("unexpected position type {:?}", position_type),
+            };
+        // 空的绑定值映射到编译结果 `();( title );` 中的 `)`
+        let empty_value_position = get_template_expr_position(Position::new(1, 15));
+        assert_eq!(empty_value_position.character, 1);
+        assert!(renderer.is_in_synthetic_region(&uri, &empty_value_position));
+        // 插值中的表达式以及映射的边界不是合成的内容
+        let title_position = get_template_expr_position(Position::new(1, 21));
+        assert!(!renderer.is_in_synthetic_region(&uri, &title_position));
+        let title_end_position = get_template_expr_position(Position::new(1, 26));
+        assert!(!renderer.is_in_synthetic_region(&uri, &title_end_position));
+    }
+
+    #[test]
+    fn render_content_range() {
+        let renderer = create_renderer();
+        let result = renderer.get_node_render_content_range(
+            &TEST1_COMPONENT1,
+            &Range::new(Position::new(1, 15), Position::new(1, 20)),
+        );
+        let (content, position) = result.unwrap();
+        assert_eq!(content, "title");
+        assert_eq!(position.line, 1);
+    }
+
+    #[test]
+    fn render_exclude() {
+        let mut renderer = create_renderer();
+        renderer
+            .set_render_exclude(&[
+                "dist/**".to_string(),
+                "**/*.generated.vue".to_string(),
+                "src/legacy/*.{ts,vue}".to_string(),
+            ])
+            .unwrap();
+        let is_excluded = |path: &str| renderer.is_render_excluded(&Uri::from_str(path).unwrap());
+        assert!(is_excluded("file:///path/project/dist/index.vue"));
+        assert!(is_excluded("file:///path/project/dist/a/b.ts"));
+        assert!(!is_excluded("file:///path/project/src/dist/index.vue"));
+        assert!(is_excluded("file:///path/project/a.generated.vue"));
+        assert!(is_excluded("file:///path/project/src/a.generated.vue"));
+        assert!(!is_excluded("file:///path/project/src/index.vue"));
+        assert!(is_excluded("file:///path/project/src/legacy/a.vue"));
+        assert!(!is_excluded("file:///path/project/src/legacy/a/b.vue"));
+        assert!(renderer
+            .set_render_exclude(&["src/{a".to_string()])
+            .is_err());
+        renderer.set_render_exclude(&[]).unwrap();
+        assert!(!renderer.is_render_excluded(&TEST1_INDEX));
+    }
+
+    #[test]
+    fn js_decorator_component() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/decorator.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template><div>{{ title }}</div></template>",
+                "<script>",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Decorator extends Vue {",
+                "  @Prop({ type: String }) title;",
+                "}",
+                "</script>",
+            ],
+        );
+        assert!(renderer.is_js_component(&uri));
+        let Some(RenderCache::VueRenderCache(cache)) = renderer.render_cache.get(&uri) else {
+            panic!();
+        };
+        assert_eq!(cache.props[0].name, "title");
+        let content = renderer.get_render_content(&uri).unwrap();
+        // JavaScript 中不能使用访问修饰符和类型注解
+        assert!(content.contains("          render(){"));
+        assert!(content.contains("let $event;"));
+        assert!(!content.contains("protected"));
+        assert!(!content.contains(":any"));
+    }
+
+    #[test]
+    fn js_component_path() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/plain.vue").unwrap();
         renderer.create_node_from_document(
-            &TEST1_COMPONENT3,
+            &uri,
             FullTextDocument::new(
                 "vue".to_string(),
                 0,
-                [
-                    "<template>",
-                    "  <div></div>",
-                    "</template>",
-                    "<script lang=\"ts\">",
-                    "import Vue from 'vue';",
-                    "import { Component } from 'vue-property-decorator';",
-                    "@Component",
-                    "export default class MyComponent3 extends Vue {",
-                    "  @Prop({ type: Boolean, default: false })",
-                    "  private disabled!: boolean;",
-                    "  private show = false;",
-                    "}",
-                    "</script>",
-                ]
-                .join("\n")
-                .to_string(),
+                "<template><div></div></template>\n<script>\nexport default {};\n</script>"
+                    .to_string(),
             ),
         );
-        // test2/index.vue
-        renderer.create_node_from_document(
-            &TEST2_INDEX,
-            FullTextDocument::new(
-                "vue".to_string(),
-                0,
-                [
-                    "<template>",
-                    "  <MyComponent4 title=\"Title\" />",
-                    "</template>",
-                    "<script lang=\"ts\">",
-                    "import Vue from 'vue';",
-                    "import { Component } from 'vue-property-decorator';",
-                    "import MyComponent4 from './ts/transfer.ts';",
-                    "@Component({",
-                    "  components: {",
-                    "    MyComponent4,",
-                    "  },",
-                    "})",
-                    "export default class Index extends Vue {",
-                    "}",
-                    "</script>",
-                ]
-                .join("\n")
-                .to_string(),
-            ),
+        assert!(renderer.is_js_component(&uri));
+        assert!(!renderer.is_js_component(&TEST1_INDEX));
+        let (root_uri, target_root_uri) = renderer.root_uri_target_uri().unwrap().clone();
+        let target_path = Renderer::get_target_path(&uri, &root_uri, &target_root_uri, true);
+        assert!(target_path
+            .to_string_lossy()
+            .ends_with("/src/test1/plain.vue.js"));
+        let target_uri = util::create_uri_from_path(&target_path);
+        assert_eq!(
+            Renderer::get_source_path(&target_uri, &root_uri, &target_root_uri),
+            util::to_file_path(&uri)
         );
-        // test2/ts/transfer.ts
-        renderer.create_node_from_document(
-            &TEST2_TS,
-            FullTextDocument::new(
-                "typescript".to_string(),
-                0,
-                [
-                    "import MyComponent4 from '../components/MyComponent4.vue';",
-                    "export default MyComponent4;",
-                ]
-                .join("\n")
-                .to_string(),
-            ),
+    }
+
+    #[test]
+    fn event_handler_methods() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/event.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div><button @click=\"handle\" :title=\"title\"></button></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component } from 'vue-property-decorator';",
+                "import MyComponent2 from './components/MyComponent2.vue';",
+                "@Component",
+                "export default class Event extends MyComponent2 {",
+                "  private title = '';",
+                "  private handleClick() {}",
+                "}",
+                "</script>",
+            ],
         );
-        // test2/components/MyComponent4.vue
-        renderer.create_node_from_document(
-            &TEST2_COMPONENT4,
-            FullTextDocument::new(
-                "vue".to_string(),
-                0,
-                [
-                    "<template>",
-                    "  <div></div>",
-                    "</template>",
-                    "<script lang=\"ts\">",
-                    "import Vue from 'vue';",
-                    "import { Component } from 'vue-property-decorator';",
-                    "@Component",
-                    "export default class MyComponent4 extends Vue {",
-                    "  @Prop({ type: Boolean, default: false })",
-                    "  private disabled!: boolean;",
-                    "  private show = false;",
-                    "}",
-                    "</script>",
-                ]
-                .join("\n")
-                .to_string(),
-            ),
+        renderer.render_cache.flush();
+        let methods = renderer
+            .get_event_handler_methods(&uri, &Position::new(1, 29))
+            .unwrap();
+        assert_eq!(
+            methods.iter().map(|v| &v.name[..]).collect::<Vec<_>>(),
+            vec!["handleClick"]
         );
-        // test2/components/MyComponent5.vue
-        renderer.create_node_from_document(
-            &TEST2_COMPONENT5,
-            FullTextDocument::new(
-                "vue".to_string(),
-                0,
-                [
-                    "<template>",
-                    "  <div></div>",
-                    "</template>",
-                    "<script lang=\"ts\">",
-                    "import Vue from 'vue';",
-                    "import { Component } from 'vue-property-decorator';",
-                    "@Component",
-                    "export default class MyComponent5 extends Vue {",
-                    "  @Prop({ type: Boolean, default: false })",
-                    "  private disabled!: boolean;",
-                    "  private show = false;",
-                    "}",
-                    "</script>",
-                ]
-                .join("\n")
-                .to_string(),
-            ),
+        assert!(renderer
+            .get_event_handler_methods(&uri, &Position::new(1, 40))
+            .is_none());
+
+        // 混入的方法
+        let mixin_uri = Uri::from_str("file:///path/project/src/test1/EventMixin.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &mixin_uri,
+            &[
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class EventMixin extends Vue {",
+                "  handleReset() {}",
+                "}",
+                "</script>",
+            ],
+        );
+        let uri = Uri::from_str("file:///path/project/src/test1/mixinEvent.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div><button @click=\"handle\"></button></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Mixins } from 'vue-property-decorator';",
+                "import EventMixin from './EventMixin.vue';",
+                "@Component",
+                "export default class MixinEvent extends Mixins(EventMixin) {",
+                "  private handleClick() {}",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let methods = renderer
+            .get_event_handler_methods(&uri, &Position::new(1, 29))
+            .unwrap();
+        assert_eq!(
+            methods.iter().map(|v| &v.name[..]).collect::<Vec<_>>(),
+            vec!["handleClick", "handleReset"]
+        );
+    }
+
+    #[test]
+    fn ref_value_completions() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/refs.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div><input ref=\"\" /><span ref=\"label\"></span></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Ref } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Refs extends Vue {",
+                "  @Ref() readonly input!: HTMLInputElement;",
+                "  @Ref() readonly label!: HTMLSpanElement;",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let refs = renderer
+            .get_ref_value_completions(&uri, &Position::new(1, 19))
+            .unwrap();
+        assert_eq!(
+            refs.into_iter()
+                .filter_map(|v| v.ref_params)
+                .map(|v| (v.name, v.typ))
+                .collect::<Vec<_>>(),
+            vec![("input".to_string(), Some("HTMLInputElement".to_string()))]
+        );
+        assert!(renderer
+            .get_ref_value_completions(&uri, &Position::new(1, 10))
+            .is_none());
+    }
+
+    #[test]
+    fn template_completion_allowed() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/allowed.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div title=\"abc\"></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Allowed extends Vue {}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let is_allowed =
+            |character| renderer.is_template_completion_allowed(&uri, &Position::new(1, character));
+        // 标签名中间
+        assert!(!is_allowed(5));
+        // 属性名中间
+        assert!(!is_allowed(9));
+        // 属性名之前的空白字符之后以及属性名末尾
+        assert!(is_allowed(7));
+        assert!(is_allowed(12));
+        // 属性值中
+        assert!(is_allowed(15));
+    }
+
+    #[test]
+    fn component_is_completions() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/is.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div><component is=\"My\" /><component :is=\"'My' + comp\" /></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "import MyComponent1 from './components/MyComponent1.vue';",
+                "@Component({ components: { MyComponent1 } })",
+                "export default class Is extends Vue {",
+                "  private comp = '1';",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        assert_eq!(
+            renderer.get_component_is_completions(&uri, &Position::new(1, 24)),
+            Some(vec!["MyComponent1".to_string()])
+        );
+        assert_eq!(
+            renderer.get_component_is_completions(&uri, &Position::new(1, 47)),
+            Some(vec!["MyComponent1".to_string()])
+        );
+        // 位于字符串字面量之外
+        assert_eq!(
+            renderer.get_component_is_completions(&uri, &Position::new(1, 53)),
+            None
+        );
+    }
+
+    #[test]
+    fn slot_props() {
+        let mut renderer = create_renderer();
+        let child_uri = Uri::from_str("file:///path/project/src/test1/Child.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template>",
+                "  <div>",
+                "    <slot :row=\"item\" :label=\"'x'\" :user=\"user\" :value=\"item[0]\" :current=\"current\" />",
+                "    <slot name=\"footer\" :count=\"1\" :total=\"this.total\" />",
+                "  </div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import { User } from './types';",
+                "interface Item { id: number }",
+                "@Component",
+                "export default class Child extends Vue {",
+                "  private item: string[] = [];",
+                "  private user: User | null = null;",
+                "  private current: Item | null = null;",
+                "  get total(): number {",
+                "    return this.item.length;",
+                "  }",
+                "}",
+                "</script>",
+            ],
+        );
+        let uri = Uri::from_str("file:///path/project/src/test1/parent.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <Child v-slot=\"{ row }\">{{ row }}</Child>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import Child from './Child.vue';",
+                "@Component({ components: { Child } })",
+                "export default class Parent extends Vue {}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let to_vec = |props: &[(&str, &str)]| {
+            props
+                .iter()
+                .map(|(name, typ)| (name.to_string(), typ.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            renderer.get_slot_props(&child_uri, "default"),
+            to_vec(&[
+                ("row", "string[]"),
+                ("label", "string"),
+                ("user", "any"),
+                ("value", "any"),
+                ("current", "any"),
+            ])
+        );
+        assert_eq!(
+            renderer.get_slot_props(&child_uri, "footer"),
+            to_vec(&[("count", "number"), ("total", "number")])
+        );
+        assert_eq!(renderer.get_slot_props(&child_uri, "header"), vec![]);
+
+        renderer.update_slot_props(&uri);
+        let Some(RenderCache::VueRenderCache(cache)) = renderer.render_cache.get(&uri) else {
+            panic!();
+        };
+        assert_eq!(
+            cache.template_compile_result.get_content(None),
+            "{const { row } = {} as { [key: string]: any; row: string[]; label: string; user: any; value: any; };( row );}"
+        );
+    }
+
+    #[tokio::test]
+    async fn update_child_slot_props() {
+        let mut renderer = create_renderer();
+        let child_uri = Uri::from_str("file:///path/project/src/test1/SlotChild.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template><div><slot :row=\"'a'\" /></div></template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class SlotChild extends Vue {}",
+                "</script>",
+            ],
+        );
+        let uri = Uri::from_str("file:///path/project/src/test1/SlotParent.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <SlotChild v-slot=\"{ row }\">{{ row }}</SlotChild>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import SlotChild from './SlotChild.vue';",
+                "@Component({ components: { SlotChild } })",
+                "export default class SlotParent extends Vue {}",
+                "</script>",
+            ],
         );
         renderer.render_cache.flush();
+        renderer.update_slot_props(&uri);
+        let get_compile_result = |renderer: &Renderer| {
+            let Some(RenderCache::VueRenderCache(cache)) = renderer.render_cache.get(&uri) else {
+                panic!();
+            };
+            cache.template_compile_result.get_content(None).to_string()
+        };
+        assert!(get_compile_result(&renderer).contains("row: string;"));
 
+        // 子组件的插槽绑定变化后重新编译父组件
+        let params = create_params(&child_uri, &[(0, 27, 0, 30, Some(3), "1")]);
         renderer
+            .update(&child_uri, params, &create_empty_document())
+            .await;
+        assert!(get_compile_result(&renderer).contains("row: number;"));
+        assert_eq!(
+            renderer.render_cache.get(&uri).unwrap().get_version(),
+            Some(1)
+        );
     }
 
-    fn create_empty_document() -> FullTextDocument {
-        FullTextDocument::new("vue".to_string(), 0, "".to_string())
+    #[test]
+    fn adjacent_mapping_positions() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/last.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div>{{ title }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Last extends Vue {",
+                "  private title = '';",
+                "}",
+                "</script>",
+            ],
+        );
+        // 插值是模版的最后一个标记，编译结果为 `( title );`
+        let pos = renderer
+            .get_mapping_position(&uri, &Position::new(1, 15))
+            .unwrap();
+        assert_eq!(pos.character, 7);
+        // 位于映射内部时不重试
+        assert_eq!(renderer.get_adjacent_mapping_positions(&uri, &pos), vec![]);
+        // 位于映射的结尾，只向映射内移动
+        let pos = renderer
+            .get_mapping_position(&uri, &Position::new(1, 16))
+            .unwrap();
+        assert_eq!(pos.character, 8);
+        assert_eq!(
+            renderer.get_adjacent_mapping_positions(&uri, &pos),
+            vec![Position::new(pos.line, 7)]
+        );
+        // 位于映射的开头
+        assert_eq!(
+            renderer.get_adjacent_mapping_positions(&uri, &Position::new(pos.line, 1)),
+            vec![Position::new(pos.line, 2)]
+        );
+        // 编译结果的末尾
+        assert_eq!(
+            renderer.get_adjacent_mapping_positions(&uri, &Position::new(pos.line, 10)),
+            vec![Position::new(pos.line, 9)]
+        );
     }
 
-    fn create_changes(
-        changes: &[(u32, u32, u32, u32, Option<u32>, &str)],
-    ) -> Vec<TextDocumentContentChangeEvent> {
-        changes
+    #[test]
+    fn v_for_alias() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/for.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div v-for=\"(item, index) of list\">{{ item }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class For extends Vue {",
+                "  private list: string[] = [];",
+                "}",
+                "</script>",
+            ],
+        );
+        assert!(renderer.is_in_v_for_alias(&uri, &Position::new(1, 16)));
+        assert!(renderer.is_in_v_for_alias(&uri, &Position::new(1, 27)));
+        assert!(!renderer.is_in_v_for_alias(&uri, &Position::new(1, 32)));
+        assert!(!renderer.is_in_v_for_alias(&uri, &Position::new(1, 40)));
+    }
+
+    #[tokio::test]
+    async fn component_prop_attributes() {
+        let mut renderer = create_renderer();
+        let index_uri = Uri::from_str("file:///path/project/src/test1/props.vue").unwrap();
+        let child_uri =
+            Uri::from_str("file:///path/project/src/test1/components/PropsChild.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &index_uri,
+            &[
+                "<template>",
+                "  <PropsChild :title-text.sync=\"text\" cou />",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import PropsChild from './components/PropsChild.vue';",
+                "@Component({",
+                "  components: {",
+                "    PropsChild,",
+                "  },",
+                "})",
+                "export default class Props extends Vue {",
+                "}",
+                "</script>",
+            ],
+        );
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class PropsChild extends Vue {",
+                "  @Prop({ type: String })",
+                "  private titleText!: string;",
+                "  @Prop({ type: Number, required: true })",
+                "  private count!: number;",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let provider = renderer.get_tags_provider(&index_uri).await;
+        let tag = provider
+            .provide_tags()
             .iter()
-            .map(|v| TextDocumentContentChangeEvent {
-                range: Some(Range {
-                    start: Position {
-                        line: v.0,
-                        character: v.1,
-                    },
-                    end: Position {
-                        line: v.2,
-                        character: v.3,
-                    },
-                }),
-                range_length: v.4,
-                text: v.5.to_string(),
-            })
-            .collect()
+            .find(|v| v.name == "PropsChild")
+            .unwrap();
+        assert_eq!(
+            tag.attributes
+                .iter()
+                .map(|v| &v.name[..])
+                .collect::<Vec<_>>(),
+            vec![
+                "count",
+                ":count",
+                "titleText",
+                ":titleText",
+                "title-text",
+                ":title-text"
+            ]
+        );
+        let detail = provider
+            .get_attribute_detail("PropsChild", ":count")
+            .unwrap();
+        assert_eq!(detail.typ, Some("Number".to_string()));
+        assert!(detail.required);
+        let detail = provider
+            .get_attribute_detail("PropsChild", "title-text")
+            .unwrap();
+        assert_eq!(detail.typ, Some("String".to_string()));
+        assert!(!detail.required);
+        // 已传递的属性不再提供，光标所在的属性除外
+        let html_document = renderer.get_html_document(&index_uri).unwrap();
+        let offset = "<template>\n  <PropsChild :title-text.sync=\"text\" cou".len();
+        let node = html_document.find_node_at(offset, &mut vec![]).unwrap();
+        assert_eq!(
+            provider
+                .get_absent_attributes("PropsChild", &node, offset)
+                .iter()
+                .map(|v| &v.name[..])
+                .collect::<Vec<_>>(),
+            vec!["count", ":count"]
+        );
     }
 
-    fn create_params(
-        uri: &Uri,
-        changes: &[(u32, u32, u32, u32, Option<u32>, &str)],
-    ) -> DidChangeTextDocumentParams {
-        DidChangeTextDocumentParams {
-            text_document: VersionedTextDocumentIdentifier {
-                uri: uri.clone(),
-                version: 1,
-            },
-            content_changes: create_changes(changes),
-        }
+    #[test]
+    fn style_module_location() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/style.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div :class=\"$style.root\"></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Style extends Vue {}",
+                "</script>",
+                "<style module>",
+                ".root { display: flex; }",
+                "</style>",
+            ],
+        );
+        let location = renderer.get_style_module_location(&uri, &Position::new(1, 24));
+        assert_eq!(
+            location.unwrap().range,
+            Range::new(Position::new(10, 1), Position::new(10, 5))
+        );
+        let location = renderer.get_style_module_location(&uri, &Position::new(1, 18));
+        assert_eq!(location, None);
     }
 
-    fn assert_mapping(pos: (u32, u32), expected: Option<(u32, u32)>) {
+    #[test]
+    fn multiple_template_roots() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/roots.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div>{{ text }}</div>",
+                "  <span>{{ text }}</span>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Roots extends Vue {",
+                "  private text = '';",
+                "}",
+                "</script>",
+            ],
+        );
+        assert!(renderer
+            .get_mapping_position(&uri, &Position::new(1, 10))
+            .is_some());
+        assert_eq!(
+            renderer.get_mapping_position(&uri, &Position::new(2, 11)),
+            None
+        );
+        let diagnostics = renderer.get_template_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(2, 2), Position::new(2, 8))
+        );
+    }
+
+    #[test]
+    fn rename_import_edits() {
         let renderer = create_renderer();
-        let result = renderer.get_mapping_position(
-            &TEST1_COMPONENT1,
-            &Position {
-                line: pos.0,
-                character: pos.1,
-            },
+        let new_uri = Uri::from_str("file:///path/project/src/test1/views/Child.vue").unwrap();
+        let changes = renderer.get_rename_import_edits(&TEST1_COMPONENT1, &new_uri);
+        assert_eq!(changes.len(), 2);
+        // 父组件中的导入路径
+        assert_eq!(
+            changes[&*TEST1_INDEX],
+            vec![TextEdit {
+                range: Range::new(Position::new(6, 26), Position::new(6, 55)),
+                new_text: "./views/Child.vue".to_string(),
+            }]
         );
-        let expected = expected.map(|v| Position {
-            line: v.0,
-            character: v.1,
-        });
-        assert_eq!(result, expected);
+        // 被重命名的组件自身的相对导入路径
+        assert_eq!(
+            changes[&*TEST1_COMPONENT1],
+            vec![TextEdit {
+                range: Range::new(Position::new(5, 25), Position::new(5, 43)),
+                new_text: "../components/MyComponent2.vue".to_string(),
+            }]
+        );
+        // 同一目录下重命名时自身的导入路径不变
+        let new_uri = Uri::from_str("file:///path/project/src/test1/components/Child.vue").unwrap();
+        let changes = renderer.get_rename_import_edits(&TEST1_COMPONENT1, &new_uri);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[&*TEST1_INDEX][0].new_text, "./components/Child.vue");
     }
 
-    fn assert_original(pos: (u32, u32), expected: Option<(u32, u32)>) {
-        let renderer = create_renderer();
-        let result = renderer.get_original_position(
-            &TEST1_COMPONENT1,
-            &Position {
-                line: pos.0,
-                character: pos.1,
-            },
+    #[test]
+    fn lifecycle_hooks() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/hooks.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Hooks extends Vue {",
+                "  mounted() {",
+                "    console.log('}');",
+                "  }",
+                "  private cre",
+                "  @Prop({",
+                "    default: 1,",
+                "  })",
+                "  readonly count!: number;",
+                "}",
+                "</script>",
+            ],
         );
-        let expected = expected.map(|v| Position {
-            line: v.0,
-            character: v.1,
-        });
-        assert_eq!(result, expected);
+        let names = renderer
+            .get_lifecycle_hook_completions(&uri, &Position::new(10, 13))
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert!(names.contains(&"created"));
+        // 已经定义的钩子不再提示
+        assert!(!names.contains(&"mounted"));
+        // 位于方法体中
+        assert!(renderer
+            .get_lifecycle_hook_completions(&uri, &Position::new(8, 8))
+            .is_empty());
+        // 位于装饰器参数中
+        assert!(renderer
+            .get_lifecycle_hook_completions(&uri, &Position::new(12, 4))
+            .is_empty());
+        let hover = renderer
+            .get_lifecycle_hook_hover(&uri, &Position::new(7, 4))
+            .unwrap();
+        assert_eq!(
+            hover.range,
+            Some(Range::new(Position::new(7, 2), Position::new(7, 9)))
+        );
+        assert!(renderer
+            .get_lifecycle_hook_hover(&uri, &Position::new(8, 12))
+            .is_none());
     }
 
-    fn assert_original_range(range: (u32, u32, u32, u32), expected: Option<(u32, u32, u32, u32)>) {
-        let renderer = create_renderer();
-        let result = renderer.get_original_range(
-            &TEST1_COMPONENT1,
-            &Range {
-                start: Position {
-                    line: range.0,
-                    character: range.1,
-                },
-                end: Position {
-                    line: range.2,
-                    character: range.3,
-                },
-            },
+    #[test]
+    fn component_model() {
+        let mut renderer = create_renderer();
+        let model_uri = Uri::from_str("file:///path/project/src/test1/model.vue").unwrap();
+        let value_uri = Uri::from_str("file:///path/project/src/test1/value.vue").unwrap();
+        let v_model_uri = Uri::from_str("file:///path/project/src/test1/v-model.vue").unwrap();
+        let option_uri = Uri::from_str("file:///path/project/src/test1/option.vue").unwrap();
+        for (uri, options, member) in [
+            (
+                &model_uri,
+                "",
+                "  @ModelSync('checked', 'change', { type: Boolean }) readonly checkedValue!: boolean;",
+            ),
+            (&value_uri, "", "  @Prop({ type: String }) readonly value!: string;"),
+            (
+                &v_model_uri,
+                "",
+                "  @VModel({ name: 'text', type: String }) textValue!: string;",
+            ),
+            (
+                &option_uri,
+                "({ model: { prop: 'checked', event: 'change' } })",
+                "  @Prop({ type: Boolean }) readonly checked!: boolean;",
+            ),
+        ] {
+            add_vue_document(
+                &mut renderer,
+                uri,
+                &[
+                    "<template>",
+                    "  <div></div>",
+                    "</template>",
+                    "<script lang=\"ts\">",
+                    "import { Component, ModelSync, Prop, VModel, Vue } from 'vue-property-decorator';",
+                    &format!("@Component{}", options),
+                    "export default class Model extends Vue {",
+                    member,
+                    "}",
+                    "</script>",
+                ],
+            );
+        }
+        renderer.render_cache.flush();
+        assert_eq!(
+            renderer.get_component_model(&model_uri),
+            Some(ComponentModel {
+                prop: "checked".to_string(),
+                typ: Some("boolean".to_string()),
+                event: "change".to_string(),
+                writable: true,
+            })
         );
-        let expected = expected.map(|v| Range {
-            start: Position {
-                line: v.0,
-                character: v.1,
-            },
-            end: Position {
-                line: v.2,
-                character: v.3,
-            },
-        });
-        assert_eq!(result, expected);
+        assert_eq!(
+            renderer.get_component_model(&value_uri),
+            Some(ComponentModel {
+                prop: "value".to_string(),
+                typ: Some("string".to_string()),
+                event: "input".to_string(),
+                writable: false,
+            })
+        );
+        assert_eq!(
+            renderer.get_component_model(&v_model_uri),
+            Some(ComponentModel {
+                prop: "text".to_string(),
+                typ: Some("string".to_string()),
+                event: "input".to_string(),
+                writable: true,
+            })
+        );
+        assert_eq!(
+            renderer.get_component_model(&option_uri),
+            Some(ComponentModel {
+                prop: "checked".to_string(),
+                typ: Some("boolean".to_string()),
+                event: "change".to_string(),
+                writable: false,
+            })
+        );
+        assert_eq!(renderer.get_component_model(&TEST1_COMPONENT1), None);
     }
 
-    fn assert_position_type(pos: (u32, u32), expected: Option<PositionType>) {
-        let renderer = create_renderer();
-        let result = renderer.get_position_type(
-            &TEST1_COMPONENT1,
-            &Position {
-                line: pos.0,
-                character: pos.1,
-            },
+    #[test]
+    fn template_diagnostics() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/computed.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div><input v-model=\"value1\" /><input v-model=\"value2\" /></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Computed extends Vue {",
+                "  get value1() { return ''; }",
+                "  get value2() { return ''; }",
+                "  set value2(value: string) {}",
+                "}",
+                "</script>",
+            ],
+        );
+        let diagnostics = renderer.get_template_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(1, 23), Position::new(1, 29))
         );
-        assert_eq!(result, expected);
     }
 
     #[test]
-    fn update_vue_script_props() {
+    fn v_model_without_model_diagnostics() {
         let mut renderer = create_renderer();
-        let params = create_params(&TEST1_COMPONENT1, &[(9, 15, 9, 15, Some(0), "1")]);
-        let expected = create_changes(&[
-            (9, 15, 9, 15, Some(0), "1"),
-            (11, 24, 11, 34, Some(10), "title1,text"),
-        ]);
-        let result = renderer.update(&TEST1_COMPONENT1, params, &create_empty_document());
-        assert_eq!(result.content_changes, expected);
-        // 上游节点应该更新
+        let model_uri =
+            Uri::from_str("file:///path/project/src/test1/components/Model.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &model_uri,
+            &[
+                "<template>",
+                "  <input />",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component, Model } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Model extends Vue {",
+                "  @Model('change', { type: String }) readonly checked!: string;",
+                "}",
+                "</script>",
+            ],
+        );
+        // 通过 `@Component` 的 `model` 选项声明 model
+        let option_model_uri =
+            Uri::from_str("file:///path/project/src/test1/components/OptionModel.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &option_model_uri,
+            &[
+                "<template>",
+                "  <input />",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component({ model: { prop: 'checked', event: 'change' } })",
+                "export default class OptionModel extends Vue {",
+                "  @Prop() readonly checked!: boolean;",
+                "}",
+                "</script>",
+            ],
+        );
+        let uri = Uri::from_str("file:///path/project/src/test1/model.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div><my-component1 v-model=\"text\" /><Model v-model=\"text\" /><input v-model=\"text\" /><OptionModel v-model=\"text\" /></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "import MyComponent1 from './components/MyComponent1.vue';",
+                "import Model from './components/Model.vue';",
+                "import OptionModel from './components/OptionModel.vue';",
+                "@Component({",
+                "  components: {",
+                "    MyComponent1,",
+                "    Model,",
+                "    OptionModel,",
+                "  },",
+                "})",
+                "export default class Index extends Vue {",
+                "  private text = '';",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let diagnostics = renderer.get_template_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
         assert_eq!(
-            renderer
-                .render_cache
-                .get(&TEST1_INDEX)
-                .unwrap()
-                .get_version(),
-            Some(1)
+            diagnostics[0].range,
+            Range::new(Position::new(1, 22), Position::new(1, 36))
         );
     }
 
     #[test]
-    fn update_vue_extends_relation() {
+    fn spread_bind_diagnostics() {
         let mut renderer = create_renderer();
-        let extends_uri = renderer.render_cache.get_extends_uri(&TEST1_COMPONENT1);
-        let expected_uri: Option<&Uri> = Some(&TEST1_COMPONENT2);
-        assert_eq!(extends_uri, expected_uri);
-        // 删除导入 MyComponent2
-        let params = create_params(&TEST1_COMPONENT1, &[(5, 0, 5, 45, Some(45), "")]);
-        let expected = create_changes(&[(5, 0, 5, 45, Some(45), "")]);
-        let result = renderer.update(&TEST1_COMPONENT1, params, &create_empty_document());
-        assert_eq!(result.content_changes, expected);
-        let extends_uri = renderer.render_cache.get_extends_uri(&TEST1_COMPONENT1);
-        assert_eq!(extends_uri, None);
-        // 添加导入 MyComponent3
-        let text = "import MyComponent3 from './MyComponent3.vue';";
-        let params = create_params(&TEST1_COMPONENT1, &[(5, 0, 5, 0, Some(0), text)]);
-        let expected = create_changes(&[(5, 0, 5, 0, Some(0), text)]);
-        let result = renderer.update(&TEST1_COMPONENT1, params, &create_empty_document());
-        assert_eq!(result.content_changes, expected);
-        let extends_uri = renderer.render_cache.get_extends_uri(&TEST1_COMPONENT1);
-        assert_eq!(extends_uri, None);
-        // extends MyComponent2 改为 MyComponent3
-        let params = create_params(&TEST1_COMPONENT1, &[(7, 53, 7, 54, Some(1), "3")]);
-        let expected = create_changes(&[(7, 53, 7, 54, Some(1), "3")]);
-        let result = renderer.update(&TEST1_COMPONENT1, params, &create_empty_document());
-        assert_eq!(result.content_changes, expected);
-        let extends_uri = renderer.render_cache.get_extends_uri(&TEST1_COMPONENT1);
-        let expected_uri: Option<&Uri> = Some(&TEST1_COMPONENT3);
-        assert_eq!(extends_uri, expected_uri);
+        let child_uri = Uri::from_str("file:///path/project/src/test1/Counter.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template><div></div></template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Counter extends Vue {",
+                "  @Prop({ type: Number }) count!: number;",
+                "}",
+                "</script>",
+            ],
+        );
+        let uri = Uri::from_str("file:///path/project/src/test1/spread.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div>",
+                "    <Counter count=\"1\" />",
+                "    <Counter v-bind=\"attrs\" count=\"1\" />",
+                "    <Counter :=\"attrs\" count=\"1\" />",
+                "    <Counter v-bind.sync=\"attrs\" count=\"1\" />",
+                "  </div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import Counter from './Counter.vue';",
+                "@Component({ components: { Counter } })",
+                "export default class Spread extends Vue {",
+                "  private attrs = { count: 1 };",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        // 没有展开绑定时报告静态属性，展开绑定的标签不检查属性
+        let diagnostics = renderer.get_template_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(2, 13), Position::new(2, 18))
+        );
     }
 
     #[test]
-    fn update_vue_registers_relation() {
+    fn dynamic_component() {
         let mut renderer = create_renderer();
-        // 将 ./components/MyComponent1.vue 替换为 ./components/MyComponent2.vue
-        let params = create_params(&TEST1_INDEX, &[(6, 50, 6, 51, Some(1), "2")]);
-        let expected = create_changes(&[(6, 50, 6, 51, Some(1), "2")]);
-        let result = renderer.update(&TEST1_INDEX, params, &create_empty_document());
-        assert_eq!(result.content_changes, expected);
-        let registers = renderer.render_cache.get_registers(&TEST1_INDEX);
-        let expected_uri: &Uri = &TEST1_COMPONENT2;
+        let uri = Uri::from_str("file:///path/project/src/test1/dynamic.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div><component :is=\"'MyComponent1'\" title=\"t\" /><component :is=\"comp\" /></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "import MyComponent1 from './components/MyComponent1.vue';",
+                "@Component({",
+                "  components: {",
+                "    MyComponent1,",
+                "  },",
+                "})",
+                "export default class Dynamic extends Vue {",
+                "  private comp = 'MyComponent1';",
+                "}",
+                "</script>",
+            ],
+        );
+        renderer.render_cache.flush();
+        let location = renderer.get_dynamic_component_location(&uri, &Position::new(1, 26));
+        assert_eq!(location.unwrap().uri, *TEST1_COMPONENT1);
+        let hover = renderer.get_dynamic_component_hover(&uri, &Position::new(1, 26));
         assert_eq!(
-            registers,
-            vec![("MyComponent1".to_string(), None, None, expected_uri,)]
+            hover.unwrap().range,
+            Some(Range::new(Position::new(1, 24), Position::new(1, 36)))
         );
-        // 将 import MyComponent1 改为 import MyComponent2
-        let params = create_params(&TEST1_INDEX, &[(6, 18, 6, 19, Some(1), "2")]);
-        let expected = create_changes(&[(6, 18, 6, 19, Some(1), "2")]);
-        let result = renderer.update(&TEST1_INDEX, params, &create_empty_document());
-        assert_eq!(result.content_changes, expected);
-        let registers = renderer.render_cache.get_registers(&TEST1_INDEX);
-        assert_eq!(registers, vec![]);
-        // 将 components 内的 MyComponent1 改为 MyComponent2
-        let params = create_params(&TEST1_INDEX, &[(9, 15, 9, 16, Some(1), "2")]);
-        let expected = create_changes(&[(9, 15, 9, 16, Some(1), "2")]);
-        let result = renderer.update(&TEST1_INDEX, params, &create_empty_document());
-        assert_eq!(result.content_changes, expected);
-        let registers = renderer.render_cache.get_registers(&TEST1_INDEX);
-        let expected_uri: &Uri = &TEST1_COMPONENT2;
         assert_eq!(
-            registers,
-            vec![("MyComponent2".to_string(), None, None, expected_uri,)]
+            renderer.get_dynamic_component_location(&uri, &Position::new(1, 68)),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn script_parse_diagnostics() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/broken.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Broken extends Vue {",
+                "  private text = ;",
+                "}",
+                "</script>",
+            ],
+        );
+        let diagnostics = renderer.get_script_parse_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(3, 0), Position::new(3, 18))
+        );
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            renderer.get_script_parse_diagnostics(&TEST1_COMPONENT1),
+            vec![]
+        );
+        // 修复语法错误后不再提示
+        let document = FullTextDocument::new(
+            "vue".to_string(),
+            1,
+            [
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Broken extends Vue {",
+                "  private text = 1;",
+                "}",
+                "</script>",
+            ]
+            .join("\n"),
         );
+        let params = create_params(&uri, &[(7, 17, 7, 17, Some(0), "1")]);
+        renderer.update(&uri, params, &document).await;
+        assert_eq!(renderer.get_script_parse_diagnostics(&uri), vec![]);
     }
 
     #[test]
-    fn update_ts_transfers() {
+    fn v_for_ref_diagnostics() {
         let mut renderer = create_renderer();
-        let transfer_result = renderer.render_cache.get_transfer_node(&TEST2_TS, &None);
-        let expected: Option<(&Uri, Option<_>)> = Some((&TEST2_COMPONENT4, None));
-        assert_eq!(transfer_result, expected);
-        // ../components/MyComponent4.vue 改为 ../components/MyComponent5.vue
-        let params = create_params(&TEST2_TS, &[(0, 51, 0, 52, Some(1), "5")]);
-        let expected = create_changes(&[(0, 51, 0, 52, Some(1), "5")]);
-        let result = renderer.update(&TEST2_TS, params, &create_empty_document());
-        assert_eq!(result.content_changes, expected);
-        let transfer_result = renderer.render_cache.get_transfer_node(&TEST2_TS, &None);
-        let expected: Option<(&Uri, Option<_>)> = Some((&TEST2_COMPONENT5, None));
-        assert_eq!(transfer_result, expected);
+        let uri = Uri::from_str("file:///path/project/src/test1/refs.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div ref=\"root\"><p v-for=\"item in list\" ref=\"items\" /><p v-for=\"row in list\" ref=\"row\" /></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component, Ref } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Refs extends Vue {",
+                "  private list = [1, 2, 3];",
+                "  @Ref('items') readonly items!: Vue[];",
+                "  @Ref() readonly row!: HTMLElement;",
+                "}",
+                "</script>",
+            ],
+        );
+        let diagnostics = renderer.get_template_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(1, 84), Position::new(1, 87))
+        );
+        // 渲染结果中 `v-for` 中的 ref 类型为数组
+        let content = renderer.render_cache.get_node_render_content(&uri).unwrap();
+        assert!(content.ends_with(concat!(
+            "\nexport default interface Refs {$refs:{",
+            "\"root\":import('vue').default|Element;",
+            "\"items\":(import('vue').default|Element)[];",
+            "\"row\":(import('vue').default|Element)[]",
+            "}&import('vue').default['$refs'];}"
+        )));
     }
 
     #[test]
-    fn update_full() {
+    fn declared_refs_declaration() {
         let mut renderer = create_renderer();
-        let params = create_params(&TEST1_INDEX, &[(3, 7, 3, 7, Some(0), "1")]);
-        let expected = DidChangeTextDocumentParams {
-            text_document: VersionedTextDocumentIdentifier {
-                uri: TEST1_INDEX.clone(),
-                version: 1,
-            },
-            content_changes: vec![TextDocumentContentChangeEvent {
-                range: None,
-                range_length: None,
-                text: "".to_string(),
-            }],
-        };
-        let result = renderer.update(
-            &TEST1_INDEX,
-            params,
-            &FullTextDocument::new(
-                "vue".to_string(),
-                1,
-                [
-                    "<template>",
-                    "  <MyComponent1 title=\"Title\" />",
-                    "</template>",
-                    "<script1 lang=\"ts\">",
-                    "import Vue from 'vue';",
-                    "import { Component } from 'vue-property-decorator';",
-                    "import MyComponent1 from './components/MyComponent1.vue';",
-                    "@Component({",
-                    "  components: {",
-                    "    MyComponent1,",
-                    "  },",
-                    "})",
-                    "export default class Index extends Vue {",
-                    "}",
-                    "</script>",
-                ]
-                .join("\n"),
-            ),
+        let uri = Uri::from_str("file:///path/project/src/test1/declared-refs.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div><input ref=\"input\" /></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class DeclaredRefs extends Vue {",
+                "  $refs!: { input: HTMLInputElement };",
+                "}",
+                "</script>",
+            ],
         );
-        assert_eq!(result, expected);
+        // 类中声明了 `$refs` 时不再合并声明，避免重复声明不同的类型
+        let content = renderer.render_cache.get_node_render_content(&uri).unwrap();
+        assert!(!content.contains("export default interface DeclaredRefs"));
+    }
 
-        let params = create_params(&TEST1_INDEX, &[(3, 7, 3, 8, Some(1), "")]);
-        let result = renderer.update(
-            &TEST1_INDEX,
-            params,
-            &FullTextDocument::new(
-                "vue".to_string(),
-                1,
-                [
-                    "<template>",
-                    "  <MyComponent1 title=\"Title\" />",
-                    "</template>",
-                    "<script lang=\"ts\">",
-                    "import Vue from 'vue';",
-                    "import { Component } from 'vue-property-decorator';",
-                    "import MyComponent1 from './components/MyComponent1.vue';",
-                    "@Component({",
-                    "  components: {",
-                    "    MyComponent1,",
-                    "  },",
-                    "})",
-                    "export default class Index extends Vue {",
-                    "}",
-                    "</script>",
-                ]
-                .join("\n"),
-            ),
+    #[test]
+    fn template_format_edits() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/format.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div   class=\"a\"  :title=\"t\"  ><Child></Child><span> </span><br></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "export default class Format  extends Vue {}",
+                "</script>",
+            ],
+        );
+        let edits = renderer
+            .get_template_format_edits(&uri)
+            .into_iter()
+            .map(|v| {
+                (
+                    v.range.start.line,
+                    v.range.start.character,
+                    v.range.end.character,
+                    v.new_text,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            edits,
+            vec![
+                (1, 6, 9, " ".to_string()),
+                (1, 18, 20, " ".to_string()),
+                (1, 30, 32, "".to_string()),
+                (1, 39, 48, " />".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reserved_name_diagnostics() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/reserved.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div></div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import Vue from 'vue';",
+                "import { Component } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Reserved extends Vue {",
+                "  private $el = null;",
+                "}",
+                "</script>",
+            ],
+        );
+        // 在属性前插入内容后，诊断范围应该随之移动
+        let params = create_params(&uri, &[(8, 10, 8, 10, Some(0), "readonly ")]);
+        renderer
+            .update(&uri, params, &create_empty_document())
+            .await;
+        let diagnostics = renderer.get_reserved_name_diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(8, 19), Position::new(8, 22))
         );
-        let expected = DidChangeTextDocumentParams {
-            text_document: VersionedTextDocumentIdentifier {
-                uri: TEST1_INDEX.clone(),
-                version: 1,
-            },
-            content_changes: vec![TextDocumentContentChangeEvent {
-                range: None,
-                range_length: None,
-                text: renderer
-                    .render_cache
-                    .get_node_render_content(&TEST1_INDEX)
-                    .unwrap(),
-            }],
-        };
-        assert_eq!(result, expected);
     }
 
     #[test]
@@ -958,6 +5806,44 @@ mod tests {
         assert_mapping((1, 44), None);
     }
 
+    #[test]
+    fn mapping_astral_plane_char() {
+        let mut renderer = create_renderer();
+        let uri = Uri::from_str("file:///path/project/src/test1/emoji.vue").unwrap();
+        add_vue_document(
+            &mut renderer,
+            &uri,
+            &[
+                "<template>",
+                "  <div>{{ '😀' + text }}</div>",
+                "</template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Emoji extends Vue {",
+                "  private text = '';",
+                "}",
+                "</script>",
+            ],
+        );
+        // 位置使用 UTF-16 编码，😀 占用两个字符
+        let position = Position::new(1, 17);
+        let mapping = renderer.get_mapping_position(&uri, &position).unwrap();
+        let RenderCache::VueRenderCache(cache) = renderer.render_cache.get(&uri).unwrap() else {
+            panic!();
+        };
+        let compile_result = cache.template_compile_result.get_content(None);
+        let text_offset = compile_result.rfind("text").unwrap();
+        assert_eq!(
+            mapping.character,
+            compile_result[..text_offset].encode_utf16().count() as u32
+        );
+        assert_eq!(
+            renderer.get_original_position(&uri, &mapping),
+            Some(position)
+        );
+    }
+
     #[test]
     fn mapping_ts() {
         let renderer = create_renderer();
@@ -1,20 +1,118 @@
+use super::template_compile::TemplateRef;
+
 /// 组合渲染结果
 /// * props 中包含原文件的属性和继承的属性并且继承的属性位于原文件的属性之后
+/// * readonly_props 是组件接收的 prop，使用 `const` 声明，模版中对其赋值时报错
+/// * style_modules 是 CSS Modules 的声明，位于 render 方法体的开头
+/// * 脚本为 JavaScript 时不能使用访问修饰符和类型注解，访问修饰符使用空格代替以保持偏移不变
+/// * class_declaration 是类的类型声明，追加在末尾以保持偏移不变
+#[allow(clippy::too_many_arguments)]
 pub fn combined_rendered_results(
     script_start_pos: usize,
     script_end_pos: usize,
     template_compile_result: &str,
     props: &Vec<&str>,
+    readonly_props: &Vec<&str>,
+    style_modules: &str,
     render_insert_offset: usize,
     source: &str,
+    is_js: bool,
+    class_declaration: &str,
 ) -> String {
     let source = get_fill_space_source(source, script_start_pos, script_end_pos);
+    let event = if is_js {
+        "let $event;"
+    } else {
+        "const $event:any;"
+    };
+    let readonly_props = if readonly_props.is_empty() {
+        String::new()
+    } else {
+        format!("const {{{}}} = this;", readonly_props.join(","))
+    };
     format!(
-        "{}protected render(){{let {{{}}} = this;const $event:any;\n{}{}",
+        "{}{}{}let {{{}}} = this;{}{}\n{}{}{}",
         &source[..render_insert_offset],
+        get_render_method_prefix(is_js),
+        style_modules,
         props.join(","),
+        readonly_props,
+        event,
         template_compile_result,
-        &source[render_insert_offset..]
+        &source[render_insert_offset..],
+        class_declaration
+    )
+}
+
+/// 获取插入的 render 方法声明到方法体开头的部分，方法体以 CSS Modules 的声明开始
+pub fn get_render_method_prefix(is_js: bool) -> &'static str {
+    if is_js {
+        "          render(){"
+    } else {
+        "protected render(){"
+    }
+}
+
+/// 获取追加在渲染结果末尾的类的类型声明
+///
+/// 通过与默认导出的类同名的接口合并补充类的类型，脚本为 JavaScript 或类没有名称时返回空字符串
+/// * `@Component({ mixins: [A, B] })` 中混入组件的成员
+/// * 模版中 ref 对应的 `$refs` 的类型，位于 `v-for` 中的 ref 为数组，类中声明了 `$refs` 时以声明为准
+pub fn get_class_declaration(
+    class_name: &str,
+    options_mixins: &[String],
+    refs: &[TemplateRef],
+    declares_refs: bool,
+    is_js: bool,
+) -> String {
+    if is_js || class_name.is_empty() {
+        return String::new();
+    }
+    // 同名的 ref 只要有一个位于 `v-for` 中即为数组
+    let mut ref_types: Vec<(&str, bool)> = vec![];
+    for item in refs.iter().filter(|v| !declares_refs && !v.name.is_empty()) {
+        match ref_types.iter_mut().find(|(name, _)| *name == item.name) {
+            Some((_, in_v_for)) => *in_v_for |= item.in_v_for,
+            None => ref_types.push((item.name.as_str(), item.in_v_for)),
+        }
+    }
+    if options_mixins.is_empty() && ref_types.is_empty() {
+        return String::new();
+    }
+    let aliases = (0..options_mixins.len())
+        .map(|i| format!("__VueMixin{}", i))
+        .collect::<Vec<_>>();
+    let extends = if aliases.is_empty() {
+        String::new()
+    } else {
+        format!(" extends {}", aliases.join(","))
+    };
+    let body = if ref_types.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "$refs:{{{}}}&import('vue').default['$refs'];",
+            ref_types
+                .iter()
+                .map(|(name, in_v_for)| if *in_v_for {
+                    format!("{:?}:(import('vue').default|Element)[]", name)
+                } else {
+                    format!("{:?}:import('vue').default|Element", name)
+                })
+                .collect::<Vec<_>>()
+                .join(";")
+        )
+    };
+    format!(
+        "\n{}export default interface {}{} {{{}}}",
+        options_mixins
+            .iter()
+            .zip(&aliases)
+            .map(|(mixin, alias)| format!("type {} = InstanceType<typeof {}>;", alias, mixin))
+            .collect::<String>(),
+        class_name,
+        extends,
+        body
     )
 }
 
@@ -0,0 +1,683 @@
+use swc_common::{source_map::SmallPos, Span, Spanned};
+use swc_ecma_ast::{
+    BlockStmt, BlockStmtOrExpr, Callee, Expr, Function, ImportSpecifier, Lit, MemberProp,
+    MethodProp, Module, ModuleDecl, ModuleItem, ObjectLit, Prop, PropOrSpread, Stmt, TsEntityName,
+    TsType,
+};
+
+use crate::{ast, util};
+
+/// 转换后类成员及装饰器选项的缩进
+const INDENT: &str = "    ";
+
+/// 选项式组件转换为类组件的结果
+pub struct OptionsConversion {
+    /// `export default` 语句的范围
+    pub export_range: (usize, usize),
+    /// 需要修改的范围及新的文本
+    pub edits: Vec<((usize, usize), String)>,
+}
+
+/// 将 `export default Vue.extend({...})` 或 `export default defineComponent({...})` 形式的组件
+/// 转换为 vue-property-decorator 的类组件
+///
+/// `props`, `data`, `computed` 和 `methods` 转换为类成员，其他选项保留在 `@Component` 中，
+/// 无法逐个转换为成员的选项也会整体保留。未指定 `name` 选项时使用 `default_name` 作为类名
+///
+/// 转换后不再使用的 `defineComponent` 导入会被移除
+pub fn convert_options_to_class(
+    source: &str,
+    start_pos: usize,
+    end_pos: usize,
+    default_name: &str,
+) -> Option<OptionsConversion> {
+    let (module, _) = ast::parse_source(source, start_pos, end_pos);
+    let module = module.ok()?;
+    let (export_span, object, is_define_component) = get_options_object(&module)?;
+
+    let mut options = vec![];
+    let mut props = vec![];
+    let mut data = vec![];
+    let mut computed = vec![];
+    let mut methods = vec![];
+    let mut name = default_name.to_string();
+    for prop_or_spread in &object.props {
+        let converted = match prop_or_spread {
+            PropOrSpread::Prop(prop) => {
+                let key = get_prop_key(prop);
+                let value = if let Prop::KeyValue(prop) = prop.as_ref() {
+                    Some(prop.value.as_ref())
+                } else {
+                    None
+                };
+                match (key.as_deref(), value) {
+                    (Some("props"), Some(value)) => convert_props(value, source).map(|v| props = v),
+                    (Some("data"), _) => convert_data(prop, source).map(|v| data = v),
+                    (Some("computed"), Some(value)) => {
+                        convert_computed(value, source).map(|v| computed = v)
+                    }
+                    (Some("methods"), Some(value)) => {
+                        convert_methods(value, source).map(|v| methods = v)
+                    }
+                    (Some("name"), Some(Expr::Lit(Lit::Str(value)))) => {
+                        name = value.value.to_string();
+                        None
+                    }
+                    _ => None,
+                }
+            }
+            PropOrSpread::Spread(_) => None,
+        };
+        if converted.is_none() {
+            options.push(get_prop_or_spread_source(prop_or_spread, source));
+        }
+    }
+
+    let mut text = String::new();
+    if options.is_empty() {
+        text += "@Component\n";
+    } else {
+        text += "@Component({\n";
+        for option in options {
+            text += &format!("{}{},\n", INDENT, option);
+        }
+        text += "})\n";
+    }
+    text += &format!(
+        "export default class {} extends Vue {{\n",
+        to_class_name(&name)
+    );
+    let groups = [&props, &data, &computed, &methods]
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .collect::<Vec<_>>();
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            text += "\n";
+        }
+        for member in group.iter() {
+            text += &format!("{}{}\n", INDENT, member);
+        }
+    }
+    text += "}";
+
+    // 补充导入装饰器和基类，已在当前模块中导入的名称不再导入
+    let mut imports = vec!["Component"];
+    if !props.is_empty() {
+        imports.push("Prop");
+    }
+    imports.push("Vue");
+    let mut last_import_end = None;
+    for item in &module.body {
+        if let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item {
+            imports.retain(|name| {
+                import
+                    .specifiers
+                    .iter()
+                    .all(|v| ast::get_local_from_import_specifier(v) != *name)
+            });
+            last_import_end = Some(import.span.hi.to_usize());
+        }
+    }
+    let export_range = (export_span.lo.to_usize(), export_span.hi.to_usize());
+    let mut edits = vec![];
+    let mut define_component_import = if is_define_component {
+        get_define_component_import_range(&module, source, start_pos, end_pos)
+    } else {
+        None
+    };
+    if !imports.is_empty() {
+        let import = format!(
+            "import {{ {} }} from 'vue-property-decorator'",
+            imports.join(", ")
+        );
+        if let Some((range, true)) = define_component_import {
+            // 整个导入语句被移除时在原位置导入
+            edits.push((range, format!("{}\n", import)));
+            define_component_import = None;
+        } else if let Some(end) = last_import_end {
+            edits.push(((end, end), format!("\n{}", import)));
+        } else {
+            text = format!("{}\n\n{}", import, text);
+        }
+    }
+    if let Some((range, _)) = define_component_import {
+        edits.push((range, String::new()));
+    }
+    edits.push((export_range, text));
+    edits.sort_by_key(|(range, _)| range.0);
+    Some(OptionsConversion {
+        export_range,
+        edits,
+    })
+}
+
+/// 获取 `export default` 的选项对象，以及是否使用 `defineComponent` 定义
+fn get_options_object(module: &Module) -> Option<(Span, &ObjectLit, bool)> {
+    module.body.iter().find_map(|item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) = item else {
+            return None;
+        };
+        let Expr::Call(call) = export.expr.as_ref() else {
+            return None;
+        };
+        let Callee::Expr(callee) = &call.callee else {
+            return None;
+        };
+        let is_define_component =
+            matches!(callee.as_ref(), Expr::Ident(ident) if ident.sym.as_str() == "defineComponent");
+        let is_options_api = is_define_component
+            || match callee.as_ref() {
+                Expr::Member(member) => match (member.obj.as_ref(), &member.prop) {
+                    (Expr::Ident(obj), MemberProp::Ident(prop)) => {
+                        obj.sym.as_str() == "Vue" && prop.sym.as_str() == "extend"
+                    }
+                    _ => false,
+                },
+                _ => false,
+            };
+        if !is_options_api || call.args.len() != 1 {
+            return None;
+        }
+        if let Expr::Object(object) = call.args[0].expr.as_ref() {
+            Some((export.span, object, is_define_component))
+        } else {
+            None
+        }
+    })
+}
+
+/// 获取需要移除的 `defineComponent` 导入的范围
+///
+/// 返回: (范围, 是否为整个导入语句)，模块中除导入和 `export default` 之外仍然使用时返回 None
+fn get_define_component_import_range(
+    module: &Module,
+    source: &str,
+    start_pos: usize,
+    end_pos: usize,
+) -> Option<((usize, usize), bool)> {
+    if source[start_pos..end_pos]
+        .matches("defineComponent")
+        .count()
+        > 2
+    {
+        return None;
+    }
+    module.body.iter().find_map(|item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            return None;
+        };
+        let specifiers = &import.specifiers;
+        let index = specifiers
+            .iter()
+            .position(|v| ast::get_local_from_import_specifier(v) == "defineComponent")?;
+        if specifiers.len() == 1 {
+            let start = import.span.lo.to_usize();
+            let mut end = import.span.hi.to_usize();
+            if source[end..].starts_with('\n') {
+                end += 1;
+            }
+            return Some(((start, end), true));
+        }
+        let span = specifiers[index].span();
+        let named_count = specifiers
+            .iter()
+            .filter(|v| matches!(v, ImportSpecifier::Named(_)))
+            .count();
+        let range = if named_count == 1 {
+            // 唯一的命名导入连同大括号一起移除，如 `import Vue, { defineComponent } from 'vue'`
+            let end = span.hi.to_usize();
+            let end = end + source[end..].find('}')? + 1;
+            (specifiers[index - 1].span().hi.to_usize(), end)
+        } else if let Some(next) = specifiers.get(index + 1) {
+            (span.lo.to_usize(), next.span().lo.to_usize())
+        } else {
+            (
+                specifiers[index - 1].span().hi.to_usize(),
+                span.hi.to_usize(),
+            )
+        };
+        Some((range, false))
+    })
+}
+
+fn get_prop_key(prop: &Prop) -> Option<String> {
+    match prop {
+        Prop::KeyValue(prop) => Some(ast::get_name_form_prop_name(&prop.key)),
+        Prop::Method(prop) => Some(ast::get_name_form_prop_name(&prop.key)),
+        _ => None,
+    }
+}
+
+/// 转换 `props` 选项，支持数组和对象两种形式
+///
+/// 未指定 `required: true` 且没有默认值的属性可能为 undefined，声明为可选的成员
+fn convert_props(expr: &Expr, source: &str) -> Option<Vec<String>> {
+    match expr {
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .map(|elem| {
+                if let Expr::Lit(Lit::Str(name)) = elem.as_ref()?.expr.as_ref() {
+                    Some(format!("@Prop() readonly {}?: any", name.value))
+                } else {
+                    None
+                }
+            })
+            .collect(),
+        Expr::Object(object) => object
+            .props
+            .iter()
+            .map(|prop| {
+                let PropOrSpread::Prop(prop) = prop else {
+                    return None;
+                };
+                let Prop::KeyValue(prop) = prop.as_ref() else {
+                    return None;
+                };
+                let name = ast::get_name_form_prop_name(&prop.key);
+                let (typ, is_optional) = match prop.value.as_ref() {
+                    Expr::Object(options) => {
+                        let typ = options
+                            .props
+                            .iter()
+                            .find_map(|v| ast::get_value_of_specified_prop(v, "type"))
+                            .map_or("any".to_string(), |v| get_prop_ts_type(v, source));
+                        let is_required = options.props.iter().any(|v| {
+                            matches!(
+                                ast::get_value_of_specified_prop(v, "required"),
+                                Some(Expr::Lit(Lit::Bool(value))) if value.value
+                            )
+                        });
+                        let has_default = options.props.iter().any(|v| {
+                            matches!(v, PropOrSpread::Prop(prop) if get_prop_key(prop).as_deref() == Some("default"))
+                        });
+                        (typ, !is_required && !has_default)
+                    }
+                    value => (get_prop_ts_type(value, source), true),
+                };
+                let span = prop.value.span();
+                Some(format!(
+                    "@Prop({}) readonly {}{}: {}",
+                    get_source_text(source, span.lo.to_usize(), span.hi.to_usize()),
+                    name,
+                    if is_optional { "?" } else { "!" },
+                    typ
+                ))
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// 根据属性的 `type` 获取 TS 类型，`Array as PropType<string[]>` 使用 `PropType` 的类型参数
+fn get_prop_ts_type(expr: &Expr, source: &str) -> String {
+    match expr {
+        Expr::Ident(ident) => util::get_constructor_ts_type(&ident.sym),
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .map(|elem| {
+                elem.as_ref()
+                    .map_or("any".to_string(), |v| get_prop_ts_type(&v.expr, source))
+            })
+            .collect::<Vec<_>>()
+            .join(" | "),
+        Expr::TsAs(expr) => {
+            if let TsType::TsTypeRef(type_ref) = expr.type_ann.as_ref() {
+                if let TsEntityName::Ident(ident) = &type_ref.type_name {
+                    if ident.sym.as_str() == "PropType" {
+                        if let Some(param) =
+                            type_ref.type_params.as_ref().and_then(|v| v.params.first())
+                        {
+                            let span = param.span();
+                            return source[span.lo.to_usize()..span.hi.to_usize()].to_string();
+                        }
+                    }
+                }
+            }
+            get_prop_ts_type(&expr.expr, source)
+        }
+        _ => "any".to_string(),
+    }
+}
+
+/// 转换 `data` 选项，只支持直接返回对象字面量的函数
+fn convert_data(prop: &Prop, source: &str) -> Option<Vec<String>> {
+    let mut ret = match prop {
+        Prop::Method(method) => get_function_return(&method.function)?,
+        Prop::KeyValue(prop) => match prop.value.as_ref() {
+            Expr::Arrow(arrow) => match arrow.body.as_ref() {
+                BlockStmtOrExpr::Expr(expr) => expr.as_ref(),
+                BlockStmtOrExpr::BlockStmt(block) => get_block_return(block)?,
+            },
+            Expr::Fn(expr) => get_function_return(&expr.function)?,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    while let Expr::Paren(expr) = ret {
+        ret = &expr.expr;
+    }
+    let Expr::Object(object) = ret else {
+        return None;
+    };
+    object
+        .props
+        .iter()
+        .map(|prop| {
+            let PropOrSpread::Prop(prop) = prop else {
+                return None;
+            };
+            match prop.as_ref() {
+                Prop::KeyValue(prop) => {
+                    let key = ast::get_name_span_from_prop_name(&prop.key);
+                    let value = prop.value.span();
+                    Some(format!(
+                        "{} = {}",
+                        &source[key.lo.to_usize()..key.hi.to_usize()],
+                        get_source_text(source, value.lo.to_usize(), value.hi.to_usize())
+                    ))
+                }
+                Prop::Shorthand(ident) => Some(format!("{0} = {0}", ident.sym)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// 转换 `computed` 选项，方法转换为 getter，包含 `get` 和 `set` 的对象转换为 getter 和 setter
+fn convert_computed(expr: &Expr, source: &str) -> Option<Vec<String>> {
+    let Expr::Object(object) = expr else {
+        return None;
+    };
+    let mut members = vec![];
+    for prop in &object.props {
+        let PropOrSpread::Prop(prop) = prop else {
+            return None;
+        };
+        match prop.as_ref() {
+            Prop::Method(method) if !method.function.is_async && !method.function.is_generator => {
+                members.push(format!("get {}", get_method_source(method, source)?));
+            }
+            Prop::KeyValue(prop) => {
+                let Expr::Object(accessors) = prop.value.as_ref() else {
+                    return None;
+                };
+                let key = ast::get_name_span_from_prop_name(&prop.key);
+                let key = &source[key.lo.to_usize()..key.hi.to_usize()];
+                for accessor in &accessors.props {
+                    let PropOrSpread::Prop(accessor) = accessor else {
+                        return None;
+                    };
+                    let Prop::Method(accessor) = accessor.as_ref() else {
+                        return None;
+                    };
+                    let kind = ast::get_name_form_prop_name(&accessor.key);
+                    if kind != "get" && kind != "set" {
+                        return None;
+                    }
+                    let start = ast::get_name_span_from_prop_name(&accessor.key).hi;
+                    let end = accessor.function.body.as_ref()?.span.hi;
+                    members.push(format!(
+                        "{} {}{}",
+                        kind,
+                        key,
+                        get_source_text(source, start.to_usize(), end.to_usize())
+                    ));
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(members)
+}
+
+/// 转换 `methods` 选项，只支持方法形式
+fn convert_methods(expr: &Expr, source: &str) -> Option<Vec<String>> {
+    let Expr::Object(object) = expr else {
+        return None;
+    };
+    object
+        .props
+        .iter()
+        .map(|prop| {
+            let PropOrSpread::Prop(prop) = prop else {
+                return None;
+            };
+            if let Prop::Method(method) = prop.as_ref() {
+                get_method_source(method, source)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn get_block_return(block: &BlockStmt) -> Option<&Expr> {
+    if let [Stmt::Return(ret)] = block.stmts.as_slice() {
+        ret.arg.as_deref()
+    } else {
+        None
+    }
+}
+
+fn get_function_return(function: &Function) -> Option<&Expr> {
+    get_block_return(function.body.as_ref()?)
+}
+
+/// 获取方法的源码，包含 `async` 和 `*` 前缀
+fn get_method_source(method: &MethodProp, source: &str) -> Option<String> {
+    let start = ast::get_name_span_from_prop_name(&method.key).lo.to_usize();
+    let end = method.function.body.as_ref()?.span.hi.to_usize();
+    let mut result = String::new();
+    if method.function.is_async {
+        result += "async ";
+    }
+    if method.function.is_generator {
+        result += "*";
+    }
+    result += &get_source_text(source, start, end);
+    Some(result)
+}
+
+fn get_prop_or_spread_source(prop: &PropOrSpread, source: &str) -> String {
+    if let PropOrSpread::Prop(prop) = prop {
+        if let Prop::Method(method) = prop.as_ref() {
+            if let Some(result) = get_method_source(method, source) {
+                return result;
+            }
+        }
+    }
+    let span = prop.span();
+    get_source_text(source, span.lo.to_usize(), span.hi.to_usize())
+}
+
+/// 获取源码，并将除第一行以外的缩进调整为相对于 `INDENT` 的缩进
+fn get_source_text(source: &str, start: usize, end: usize) -> String {
+    let line_start = source[..start].rfind('\n').map_or(0, |v| v + 1);
+    let line = &source[line_start..start];
+    let indent = &line[..line.len() - line.trim_start().len()];
+    source[start..end]
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| match line.strip_prefix(indent) {
+            Some(rest) if i > 0 => format!("{}{}", INDENT, rest),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 将组件名称转换为类名，如 `my-component` 转换为 `MyComponent`
+fn to_class_name(name: &str) -> String {
+    let result = name
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '$')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) => c.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<String>();
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        "Default".to_string()
+    } else {
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn convert(source: &str) -> Option<String> {
+        let result = super::convert_options_to_class(source, 0, source.len(), "my-component")?;
+        let mut source = source.to_string();
+        for ((start, end), text) in result.edits.into_iter().rev() {
+            source.replace_range(start..end, &text);
+        }
+        Some(source)
+    }
+
+    #[test]
+    fn convert_sections() {
+        let source = [
+            "import Vue, { PropType } from 'vue'",
+            "import Foo from './Foo.vue'",
+            "export default Vue.extend({",
+            "    components: { Foo },",
+            "    props: {",
+            "        title: String,",
+            "        items: { type: Array as PropType<string[]>, default: () => [] },",
+            "    },",
+            "    data() {",
+            "        return { count: 0 }",
+            "    },",
+            "    computed: {",
+            "        double(): number {",
+            "            return this.count * 2",
+            "        },",
+            "        value: {",
+            "            get() { return this.count },",
+            "            set(v: number) { this.count = v },",
+            "        },",
+            "    },",
+            "    methods: {",
+            "        async load() {",
+            "            await this.$nextTick()",
+            "        },",
+            "    },",
+            "    created() {},",
+            "})",
+        ]
+        .join("\n");
+        assert_eq!(
+            convert(&source).unwrap(),
+            [
+                "import Vue, { PropType } from 'vue'",
+                "import Foo from './Foo.vue'",
+                "import { Component, Prop } from 'vue-property-decorator'",
+                "@Component({",
+                "    components: { Foo },",
+                "    created() {},",
+                "})",
+                "export default class MyComponent extends Vue {",
+                "    @Prop(String) readonly title?: string",
+                "    @Prop({ type: Array as PropType<string[]>, default: () => [] }) readonly items!: string[]",
+                "",
+                "    count = 0",
+                "",
+                "    get double(): number {",
+                "        return this.count * 2",
+                "    }",
+                "    get value() { return this.count }",
+                "    set value(v: number) { this.count = v }",
+                "",
+                "    async load() {",
+                "        await this.$nextTick()",
+                "    }",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn keep_unsupported_sections() {
+        let source = [
+            "import { defineComponent } from 'vue'",
+            "export default defineComponent({",
+            "    name: 'user-card',",
+            "    props: ['id'],",
+            "    data() {",
+            "        const count = 0",
+            "        return { count }",
+            "    },",
+            "})",
+        ]
+        .join("\n");
+        assert_eq!(
+            convert(&source).unwrap(),
+            [
+                "import { Component, Prop, Vue } from 'vue-property-decorator'",
+                "@Component({",
+                "    name: 'user-card',",
+                "    data() {",
+                "        const count = 0",
+                "        return { count }",
+                "    },",
+                "})",
+                "export default class UserCard extends Vue {",
+                "    @Prop() readonly id?: any",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn define_component_import() {
+        let source = [
+            "import Vue, { defineComponent } from 'vue'",
+            "export default defineComponent({",
+            "    props: { size: { type: Number, required: true } },",
+            "})",
+        ]
+        .join("\n");
+        assert_eq!(
+            convert(&source).unwrap(),
+            [
+                "import Vue from 'vue'",
+                "import { Component, Prop } from 'vue-property-decorator'",
+                "@Component",
+                "export default class MyComponent extends Vue {",
+                "    @Prop({ type: Number, required: true }) readonly size!: number",
+                "}",
+            ]
+            .join("\n")
+        );
+
+        let source = [
+            "import { defineComponent, PropType } from 'vue'",
+            "export default defineComponent({})",
+        ]
+        .join("\n");
+        assert_eq!(
+            convert(&source).unwrap(),
+            [
+                "import { PropType } from 'vue'",
+                "import { Component, Vue } from 'vue-property-decorator'",
+                "@Component",
+                "export default class MyComponent extends Vue {",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn not_options_api() {
+        assert!(convert("export default { name: 'a' }").is_none());
+        assert!(convert("@Component\nexport default class A extends Vue {}").is_none());
+    }
+}
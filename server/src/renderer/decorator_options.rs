@@ -0,0 +1,28 @@
+/// 获取 `@Prop`, `@Model` 等装饰器选项的说明，不是已知的选项时返回 None
+pub fn get_decorator_option_description(key: &str) -> Option<&'static str> {
+    match key {
+        "type" => Some(
+            "Runtime type check of the prop. Can be a native constructor (`String`, `Number`, `Boolean`, `Array`, `Object`, `Date`, `Function`, `Symbol`), a custom constructor, or an array of these.\n\n```ts\n@Prop({ type: [String, Number] })\n```",
+        ),
+        "required" => Some(
+            "Whether the prop is required. A warning is emitted in development if a required prop is not provided.\n\n```ts\n@Prop({ required: true })\n```",
+        ),
+        "default" => Some(
+            "Default value used when the prop is not provided. Objects and arrays must be returned from a factory function.\n\n```ts\n@Prop({ default: () => [] })\n```",
+        ),
+        "validator" => Some(
+            "Custom validator function that receives the prop value and returns whether it is valid.\n\n```ts\n@Prop({ validator: (value: string) => ['small', 'large'].includes(value) })\n```",
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn decorator_option_description() {
+        assert!(super::get_decorator_option_description("type").is_some());
+        assert!(super::get_decorator_option_description("default").is_some());
+        assert_eq!(super::get_decorator_option_description("immediate"), None);
+    }
+}
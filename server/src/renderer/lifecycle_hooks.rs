@@ -0,0 +1,69 @@
+/// Vue 2 的生命周期钩子及其说明，按调用顺序排列
+pub static LIFECYCLE_HOOKS: &[(&str, &str)] = &[
+    (
+        "beforeCreate",
+        "Called synchronously immediately after the instance has been initialized, before data observation and event/watcher setup.",
+    ),
+    (
+        "created",
+        "Called synchronously after the instance is created. At this stage, the instance has finished processing the options which means the following have been set up: data observation, computed properties, methods, watch/event callbacks. However, the mounting phase has not been started, and the `$el` property will not be available yet.",
+    ),
+    (
+        "beforeMount",
+        "Called right before the mounting begins: the `render` function is about to be called for the first time.\n\n**This hook is not called during server-side rendering.**",
+    ),
+    (
+        "mounted",
+        "Called after the instance has been mounted, where `el` is replaced by the newly created `vm.$el`. Note that `mounted` does **not** guarantee that all child components have also been mounted. If you want to wait until the entire view has been rendered, you can use `vm.$nextTick` inside of `mounted`.\n\n**This hook is not called during server-side rendering.**",
+    ),
+    (
+        "beforeUpdate",
+        "Called when data changes, before the DOM is patched. This is a good place to access the existing DOM before an update, e.g. to remove manually added event listeners.\n\n**This hook is not called during server-side rendering, because only the initial render is performed server-side.**",
+    ),
+    (
+        "updated",
+        "Called after a data change causes the virtual DOM to be re-rendered and patched. The component's DOM will have been updated when this hook is called. However, in most cases you should avoid changing state inside the hook.\n\n**This hook is not called during server-side rendering.**",
+    ),
+    (
+        "activated",
+        "Called when a kept-alive component is activated.\n\n**This hook is not called during server-side rendering.**",
+    ),
+    (
+        "deactivated",
+        "Called when a kept-alive component is deactivated.\n\n**This hook is not called during server-side rendering.**",
+    ),
+    (
+        "beforeDestroy",
+        "Called right before a Vue instance is destroyed. At this stage the instance is still fully functional.\n\n**This hook is not called during server-side rendering.**",
+    ),
+    (
+        "destroyed",
+        "Called after a Vue instance has been destroyed. When this hook is called, all directives of the Vue instance have been unbound, all event listeners have been removed, and all child Vue instances have also been destroyed.\n\n**This hook is not called during server-side rendering.**",
+    ),
+    (
+        "errorCaptured",
+        "Called when an error from any descendent component is captured. The hook receives three arguments: the error, the component instance that triggered the error, and a string containing information on where the error was captured. The hook can return `false` to stop the error from propagating further.",
+    ),
+    (
+        "serverPrefetch",
+        "Async function to be resolved before the component is rendered on the server. It should return a Promise.\n\n**This hook is only called during server-side rendering.**",
+    ),
+];
+
+/// 获取生命周期钩子的说明，不是生命周期钩子时返回 None
+pub fn get_lifecycle_hook_description(name: &str) -> Option<&'static str> {
+    LIFECYCLE_HOOKS
+        .iter()
+        .find(|(hook, _)| *hook == name)
+        .map(|(_, description)| *description)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn lifecycle_hook_description() {
+        assert!(super::get_lifecycle_hook_description("mounted").is_some());
+        assert!(super::get_lifecycle_hook_description("beforeDestroy").is_some());
+        assert_eq!(super::get_lifecycle_hook_description("render"), None);
+    }
+}
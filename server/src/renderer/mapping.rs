@@ -3,6 +3,8 @@ use tower_lsp::lsp_types::{Position, Range, Uri};
 use super::{render_cache::RenderCache, Renderer};
 
 /// mapping
+///
+/// 位置的字符偏移均按 UTF-16 编码计算，与协商的 `position_encoding` 一致
 impl Renderer {
     pub fn is_position_valid(&self, uri: &Uri, position: &Position) -> bool {
         Renderer::is_position_valid_by_document(self.get_document(uri), position)
@@ -12,10 +14,7 @@ impl Renderer {
         let cache = self.render_cache.get(uri)?;
         if let RenderCache::VueRenderCache(cache) = cache {
             let document = &cache.document;
-            let line = document
-                .position_at(cache.render_insert_offset as u32 + 1)
-                .line
-                + 1;
+            let line = document.position_at(cache.render_insert_offset as u32).line + 1;
             if line == position.line {
                 let offset = cache.template_compile_result.offset_at(Position {
                     line: 0,
@@ -37,16 +36,102 @@ impl Renderer {
         Some(Range { start, end })
     }
 
+    /// 将渲染文件中的范围转换为原文件中用于定位的范围
+    ///
+    /// 位于插入的 render 区域中的范围映射到对应的模版表达式，无法映射时定位到组件类名
+    pub fn get_original_location_range(&self, uri: &Uri, range: &Range) -> Option<Range> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return None;
+        };
+        if let Some(range) = self.get_original_range(uri, range) {
+            return Some(range);
+        }
+        if let (Some(start), Some(end)) = (
+            self.get_original_render_position(uri, &range.start),
+            self.get_original_render_position(uri, &range.end),
+        ) {
+            return Some(Range { start, end });
+        }
+        if let Some(start) = self.get_original_position(uri, &range.start) {
+            return Some(Range { start, end: start });
+        }
+        Some(Range::new(
+            cache.document.position_at(cache.name_range.0 as u32),
+            cache.document.position_at(cache.name_range.1 as u32),
+        ))
+    }
+
+    /// 渲染文件中的位置是否位于合成的内容中
+    ///
+    /// 插入的 render 方法声明中的 props 列表等内容，以及模版编译结果中不属于任何非空映射的部分，
+    /// 在原文件中没有对应的位置。映射的两端视为映射内，例如空的绑定值映射到其后的 `)`，属于合成的内容
+    pub fn is_in_synthetic_region(&self, uri: &Uri, position: &Position) -> bool {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return false;
+        };
+        let insert_position = cache
+            .document
+            .position_at(cache.render_insert_offset as u32);
+        if position.line == insert_position.line {
+            position.character >= insert_position.character
+        } else if position.line == insert_position.line + 1 {
+            let result = &cache.template_compile_result;
+            let offset = result.offset_at(Position {
+                line: 0,
+                character: position.character,
+            }) as usize;
+            offset < result.get_content(None).len()
+                && !cache
+                    .mapping
+                    .iter()
+                    .any(|&(target, _, len)| len > 0 && target <= offset && offset <= target + len)
+        } else {
+            false
+        }
+    }
+
+    /// 渲染文件中的位置是否位于插入的 render 区域
+    pub fn is_in_render_region(&self, uri: &Uri, position: &Position) -> bool {
+        matches!(
+            self.render_cache.get(uri),
+            Some(RenderCache::VueRenderCache(_))
+        ) && self.get_original_render_position(uri, position).is_none()
+    }
+
+    /// 将渲染文件中脚本部分的范围转换为原文件的范围
+    ///
+    /// 如果范围与插入的 render 区域相交，或者不在脚本内，返回 None
+    pub fn get_original_script_range(&self, uri: &Uri, range: &Range) -> Option<Range> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return None;
+        };
+        let script = cache.script.as_ref()?;
+        let document = &cache.document;
+        let insert_position = document.position_at(cache.render_insert_offset as u32);
+        // 起止位置分别位于 render 区域两侧
+        if range.start <= insert_position && range.end > insert_position {
+            return None;
+        }
+        let range = Range::new(
+            self.get_original_render_position(uri, &range.start)?,
+            self.get_original_render_position(uri, &range.end)?,
+        );
+        let start = document.offset_at(range.start) as usize;
+        let end = document.offset_at(range.end) as usize;
+        if script.start_tag_end? <= start && end <= script.end_tag_start? {
+            Some(range)
+        } else {
+            None
+        }
+    }
+
     pub fn get_mapping_position(&self, uri: &Uri, position: &Position) -> Option<Position> {
         let cache = self.render_cache.get(uri)?;
         if let RenderCache::VueRenderCache(cache) = cache {
             let document = &cache.document;
             let offset =
                 self.get_mapping_offset(uri, document.offset_at(*position) as usize)? as u32;
-            let line = document
-                .position_at(cache.render_insert_offset as u32 + 1)
-                .line
-                + 1;
+            let line = document.position_at(cache.render_insert_offset as u32).line + 1;
             Some(Position {
                 line,
                 character: cache.template_compile_result.position_at(offset).character,
@@ -56,8 +141,43 @@ impl Renderer {
         }
     }
 
+    /// 获取编译后位置在映射范围内相邻的位置，位于映射边界的请求没有结果时使用相邻位置重试
+    ///
+    /// 编译结果的两端也视为边界，位于结尾时向左移动，位于开头时向右移动，不在边界时返回空
+    pub fn get_adjacent_mapping_positions(&self, uri: &Uri, position: &Position) -> Vec<Position> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
+        };
+        let result = &cache.template_compile_result;
+        let offset = result.offset_at(Position {
+            line: 0,
+            character: position.character,
+        }) as usize;
+        let end = result.get_content(None).len();
+        let mapping = cache.mapping.iter().filter(|(_, _, length)| *length > 0);
+        if mapping
+            .clone()
+            .any(|(start, _, length)| *start < offset && offset < start + length)
+        {
+            return vec![];
+        }
+        let mut positions = vec![];
+        if position.character > 0
+            && (offset == end
+                || mapping
+                    .clone()
+                    .any(|(start, _, length)| start + length == offset))
+        {
+            positions.push(Position::new(position.line, position.character - 1));
+        }
+        if offset < end && (offset == 0 || mapping.clone().any(|(start, ..)| *start == offset)) {
+            positions.push(Position::new(position.line, position.character + 1));
+        }
+        positions
+    }
+
     pub fn get_position_type(&self, uri: &Uri, position: &Position) -> Option<PositionType> {
-        let cache = &self.render_cache[uri];
+        let cache = self.render_cache.get(uri)?;
         if let RenderCache::VueRenderCache(cache) = cache {
             let offset = cache.document.offset_at(*position) as usize;
             if let Some(template) = &cache.template {
@@ -89,6 +209,41 @@ impl Renderer {
 }
 
 impl Renderer {
+    /// 获取渲染文件中 render 区域之外的位置在原文件中的位置，位于 render 区域时返回 None
+    ///
+    /// 与 `VueRenderCache::get_render_position` 互逆
+    fn get_original_render_position(&self, uri: &Uri, position: &Position) -> Option<Position> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return None;
+        };
+        let insert_position = cache
+            .document
+            .position_at(cache.render_insert_offset as u32);
+        if position.line < insert_position.line
+            || (position.line == insert_position.line
+                && position.character <= insert_position.character)
+        {
+            Some(*position)
+        } else if position.line == insert_position.line + 1 {
+            let result = &cache.template_compile_result;
+            let len = result
+                .position_at(result.get_content(None).len() as u32)
+                .character;
+            if position.character >= len {
+                Some(Position::new(
+                    insert_position.line,
+                    position.character - len + insert_position.character,
+                ))
+            } else {
+                None
+            }
+        } else if position.line > insert_position.line + 1 {
+            Some(Position::new(position.line - 1, position.character))
+        } else {
+            None
+        }
+    }
+
     /// 获取编译前的偏移量，如果不在 template 范围内，返回 None
     fn get_original_offset(&self, uri: &Uri, offset: usize) -> Option<usize> {
         let cache = self.render_cache.get(uri)?;
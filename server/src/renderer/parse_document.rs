@@ -5,11 +5,20 @@ use html_languageservice::{
 use lsp_textdocument::FullTextDocument;
 use tower_lsp::lsp_types::Range;
 
-/// 解析文档，输出 template 节点和 script 节点，并确保 script 节点存在 start_tag_end 和 end_tag_start
-pub fn parse_document(document: &FullTextDocument) -> (Option<Node>, Option<Node>, Vec<Node>) {
+/// 解析文档，输出 template 节点、script 节点、style 节点和自定义块节点，
+/// 并确保 script 节点存在 start_tag_end 和 end_tag_start
+///
+/// 自定义块（如 `<i18n>`, `<docs>`）的内容不会被解析，作为不含子节点的节点输出
+///
+/// 非 HTML 语言的模版（如 `<template lang="pug">`）无法编译，同样作为自定义块输出
+pub fn parse_document(
+    document: &FullTextDocument,
+) -> (Option<Node>, Option<Node>, Vec<Node>, Vec<Node>) {
     let empty_data_manager = HTMLDataManager::default();
+    let source = document.get_content(None);
+    let masked_source = mask_custom_blocks(source);
     let html_document = html_parse::parse_html_document(
-        document.get_content(None),
+        masked_source.as_deref().unwrap_or(source),
         document.language_id(),
         &empty_data_manager,
         true,
@@ -17,18 +26,146 @@ pub fn parse_document(document: &FullTextDocument) -> (Option<Node>, Option<Node
     let mut script = None;
     let mut template = None;
     let mut style = vec![];
+    let mut custom_blocks = vec![];
     for root in html_document.roots {
         if root.tag.as_ref().is_some_and(|v| v == "script") {
             if root.start_tag_end.is_some() && root.end_tag_start.is_some() {
                 script = Some(root);
             }
-        } else if root.tag.as_ref().is_some_and(|v| v == "template") {
+        } else if root.tag.as_ref().is_some_and(|v| v == "template") && is_html_template(&root) {
             template = Some(root);
         } else if root.tag.as_ref().is_some_and(|v| v == "style") {
             style.push(root);
+        } else if root.tag.is_some() {
+            custom_blocks.push(root);
         }
     }
-    (template, script, style)
+    (template, script, style, custom_blocks)
+}
+
+/// 将顶层自定义块的内容填充空白，避免内容被解析为标签后导致之后的块解析错误
+///
+/// 填充前后长度不变，没有自定义块时返回 None
+fn mask_custom_blocks(source: &str) -> Option<String> {
+    // 标签名称不区分大小写，转换为小写后长度不变
+    let lower = source.to_ascii_lowercase();
+    let mut ranges = vec![];
+    let mut pos = 0;
+    while let Some(start) = lower[pos..].find('<').map(|v| pos + v) {
+        if lower[start..].starts_with("<!--") {
+            pos = lower[start..]
+                .find("-->")
+                .map_or(lower.len(), |v| start + v + 3);
+            continue;
+        }
+        let name_start = start + 1;
+        let name_end = lower[name_start..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_')
+            .map_or(lower.len(), |v| name_start + v);
+        if name_start == name_end {
+            pos = name_start;
+            continue;
+        }
+        let name = &lower[name_start..name_end];
+        let Some(start_tag_end) = find_start_tag_end(&lower, name_end) else {
+            break;
+        };
+        if lower[..start_tag_end].ends_with("/>") {
+            pos = start_tag_end;
+            continue;
+        }
+        let Some(end_tag_start) = find_end_tag_start(&lower, name, start_tag_end) else {
+            break;
+        };
+        if !matches!(name, "template" | "script" | "style")
+            || (name == "template" && is_non_html_lang(&lower[name_end..start_tag_end]))
+        {
+            ranges.push((start_tag_end, end_tag_start));
+        }
+        pos = end_tag_start + name.len() + 2;
+    }
+    if ranges.is_empty() {
+        return None;
+    }
+    let mut bytes = source.as_bytes().to_vec();
+    for (start, end) in ranges {
+        for byte in &mut bytes[start..end] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// 开始标签中的 lang 属性是否为非 HTML 语言，如 ` lang="pug">`
+///
+/// 解析为节点后与 `is_html_template` 一样读取属性，`lang = "pug"` 等写法同样支持
+fn is_non_html_lang(start_tag: &str) -> bool {
+    let empty_data_manager = HTMLDataManager::default();
+    let html_document = html_parse::parse_html_document(
+        &format!("<template{}</template>", start_tag),
+        "html",
+        &empty_data_manager,
+        true,
+    );
+    html_document
+        .roots
+        .first()
+        .is_some_and(|template| !is_html_template(template))
+}
+
+/// 获取开始标签结束的位置，忽略引号中的 `>`
+fn find_start_tag_end(source: &str, from: usize) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in source[from..].char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return Some(from + i + 1),
+            None => {}
+        }
+    }
+    None
+}
+
+/// 获取结束标签开始的位置，只有 template 可以嵌套
+fn find_end_tag_start(source: &str, name: &str, from: usize) -> Option<usize> {
+    let open = format!("<{}", name);
+    let close = format!("</{}", name);
+    let mut depth = 0;
+    let mut pos = from;
+    loop {
+        let end_tag_start = pos + source[pos..].find(&close)?;
+        if name == "template" {
+            depth += source[pos..end_tag_start].matches(&open).count();
+        }
+        if depth == 0 {
+            return Some(end_tag_start);
+        }
+        depth -= 1;
+        pos = end_tag_start + close.len();
+    }
+}
+
+/// script 节点是否为 JavaScript，未指定 lang 或者 lang 为 js/jsx 时为 JavaScript
+pub fn is_js_script(script: &Node) -> bool {
+    match script.attributes.get("lang").and_then(|v| v.value.as_ref()) {
+        Some(lang) => matches!(lang.trim_matches(|c| c == '"' || c == '\''), "js" | "jsx"),
+        None => true,
+    }
+}
+
+/// 获取模版的语言，未指定 lang 时为 None
+pub fn get_template_lang(template: &Node) -> Option<&str> {
+    let lang = template.attributes.get("lang")?.value.as_ref()?;
+    Some(lang.trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// 模版是否为 HTML，未指定 lang 或者 lang 为 html 时为 HTML
+pub fn is_html_template(template: &Node) -> bool {
+    get_template_lang(template).map_or(true, |lang| lang.eq_ignore_ascii_case("html"))
 }
 
 /// 将文档指定范围解析为节点
@@ -46,3 +183,99 @@ pub fn parse_as_node(document: &FullTextDocument, range: Option<Range>) -> Optio
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use lsp_textdocument::FullTextDocument;
+
+    #[test]
+    fn custom_blocks() {
+        let source = [
+            "<template><div></div></template>",
+            "<script lang=\"ts\">",
+            "export default class App extends Vue {}",
+            "</script>",
+            "<i18n lang=\"json\">",
+            "{ \"en\": { \"tip\": \"<script> <!-- <div>\" } }",
+            "</i18n>",
+            "<style scoped>",
+            ".root {}",
+            "</style>",
+        ]
+        .join("\n");
+        let document = FullTextDocument::new("vue".to_string(), 0, source.clone());
+        let (template, script, style, custom_blocks) = super::parse_document(&document);
+        assert!(template.is_some());
+        assert!(script.is_some());
+        assert_eq!(custom_blocks.len(), 1);
+        let block = &custom_blocks[0];
+        assert_eq!(block.tag.as_deref(), Some("i18n"));
+        assert!(block.children.is_empty());
+        assert_eq!(block.start, source.find("<i18n").unwrap());
+        assert_eq!(block.end, source.find("<style").unwrap() - 1);
+        assert_eq!(style.len(), 1);
+        assert_eq!(style[0].start, source.find("<style").unwrap());
+        assert_eq!(style[0].end, source.len());
+    }
+
+    #[test]
+    fn pug_template() {
+        let source = [
+            "<template lang=\"pug\">",
+            "div(:title=\"title\")",
+            "  span <b>{{ text }}</b>",
+            "</template>",
+            "<script lang=\"ts\">",
+            "export default class App extends Vue {}",
+            "</script>",
+        ]
+        .join("\n");
+        let document = FullTextDocument::new("vue".to_string(), 0, source.clone());
+        let (template, script, _, custom_blocks) = super::parse_document(&document);
+        assert!(template.is_none());
+        assert!(script.is_some());
+        assert_eq!(custom_blocks.len(), 1);
+        assert_eq!(custom_blocks[0].tag.as_deref(), Some("template"));
+        assert!(custom_blocks[0].children.is_empty());
+        assert_eq!(super::get_template_lang(&custom_blocks[0]), Some("pug"));
+        assert!(super::is_non_html_lang(" lang='pug'>"));
+        assert!(!super::is_non_html_lang(" lang=\"html\">"));
+        assert!(!super::is_non_html_lang(" functional>"));
+        assert!(super::is_non_html_lang(" lang = \"pug\">"));
+
+        // 等号两侧可以有空白
+        let document = FullTextDocument::new(
+            "vue".to_string(),
+            0,
+            "<template lang = \"pug\">\ndiv <b>{{ text }}</b>\n</template>".to_string(),
+        );
+        let (template, _, _, custom_blocks) = super::parse_document(&document);
+        assert!(template.is_none());
+        assert_eq!(custom_blocks.len(), 1);
+        assert!(custom_blocks[0].children.is_empty());
+        assert_eq!(super::get_template_lang(&custom_blocks[0]), Some("pug"));
+
+        // lang 不区分大小写
+        let document = FullTextDocument::new(
+            "vue".to_string(),
+            0,
+            "<template lang=\"HTML\"><div></div></template>".to_string(),
+        );
+        let (template, _, _, custom_blocks) = super::parse_document(&document);
+        assert!(template.is_some());
+        assert!(custom_blocks.is_empty());
+        assert!(!super::is_non_html_lang(" lang=\"HTML\">"));
+    }
+
+    #[test]
+    fn mask_custom_blocks() {
+        assert_eq!(
+            super::mask_custom_blocks("<template><docs></docs></template>"),
+            None
+        );
+        assert_eq!(
+            super::mask_custom_blocks("<docs>\n<a>\n</docs><style></style>").as_deref(),
+            Some("<docs>\n   \n</docs><style></style>")
+        );
+    }
+}
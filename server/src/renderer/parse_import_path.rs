@@ -1,15 +1,37 @@
 #[cfg(target_os = "windows")]
 use std::str::FromStr;
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
 
 use tower_lsp::lsp_types::Uri;
 
 use crate::util;
 
+/// # 解析 baseUrl
+/// 从 tsconfig.json 文件内容获取 `compilerOptions.baseUrl` 对应的绝对路径
+pub fn parse_base_url(tsconfig: &str, root_uri: &Uri) -> Option<PathBuf> {
+    let tsconfig = serde_json::from_str::<serde_json::Value>(tsconfig).ok()?;
+    let base_url = tsconfig.get("compilerOptions")?.get("baseUrl")?.as_str()?;
+    let mut result = util::to_file_path(root_uri);
+    for component in Path::new(base_url).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            Component::Normal(part) => result.push(part),
+            _ => {}
+        }
+    }
+    Some(result)
+}
+
 /// # 解析别名
-/// 从 tsconfig.json 文件内容获取别名信息
+/// 从 tsconfig.json 文件内容获取别名信息，存在 baseUrl 时别名相对于 baseUrl
 pub fn parse_alias(tsconfig: &str, root_uri: &Uri) -> HashMap<String, String> {
-    let root_path = util::to_file_path(root_uri);
+    let root_path = parse_base_url(tsconfig, root_uri).unwrap_or(util::to_file_path(root_uri));
     let mut alias = HashMap::new();
     let tsconfig = serde_json::from_str::<serde_json::Value>(&tsconfig);
     if let Ok(tsconfig) = tsconfig {
@@ -48,14 +70,16 @@ pub fn parse_alias(tsconfig: &str, root_uri: &Uri) -> HashMap<String, String> {
 ///
 /// * 处理别名
 /// * 处理相对路径
+/// * 处理相对于 baseUrl 的路径
 ///
 /// ## 注意
-/// 不判断对应文件是否存在
+/// 除 baseUrl 外不判断对应文件是否存在，baseUrl 下不存在时回退到 node_modules
 /// 不添加后缀
 pub fn parse_import_path(
     base_uri: &Uri,
     path: &str,
     alias: &HashMap<String, String>,
+    base_url: Option<&Path>,
     root_uri: &Uri,
 ) -> PathBuf {
     if path.starts_with(".") {
@@ -102,6 +126,15 @@ pub fn parse_import_path(
             return PathBuf::from(file_path);
         }
     }
+    // 可能相对于 baseUrl
+    if let Some(base_url) = base_url {
+        if !path.starts_with('/') {
+            let file_path = base_url.join(&file_path);
+            if file_path.is_dir() || resolve_file_path(&file_path).is_some() {
+                return file_path;
+            }
+        }
+    }
     // 可能位于 node_modules 中
     let path = util::to_file_path(root_uri).join("node_modules").join(path);
     #[cfg(target_os = "windows")]
@@ -114,13 +147,110 @@ pub fn parse_import_path(
     }
 }
 
+/// 解析导入路径对应的文件，如果文件不存在，依次尝试添加后缀和目录下的 index 文件
+pub fn resolve_file_path(file_path: &Path) -> Option<PathBuf> {
+    if file_path.is_file() {
+        return Some(file_path.to_path_buf());
+    }
+    if let Some(file_name) = file_path.file_name() {
+        let suffix_list = [".d.ts", ".ts"];
+        for suffix in suffix_list {
+            let new_file_name = format!("{}{}", file_name.to_str().unwrap(), suffix);
+            let new_file_path = file_path.with_file_name(new_file_name);
+            if new_file_path.is_file() {
+                return Some(new_file_path);
+            }
+        }
+    }
+    let index_list = ["index.ts", "index.d.ts", "index.vue"];
+    for index in index_list {
+        let new_file_path = file_path.join(index);
+        if new_file_path.is_file() {
+            return Some(new_file_path);
+        }
+    }
+    None
+}
+
+/// # 获取从 base_path 所在目录导入 file_path 的相对路径
+/// 结果总是以 `./` 或 `../` 开头
+pub fn get_relative_import_path(base_path: &Path, file_path: &Path) -> String {
+    let base_dir = base_path
+        .parent()
+        .map(|v| v.components().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let target = file_path.components().collect::<Vec<_>>();
+    let common = base_dir
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut parts = vec![];
+    for _ in common..base_dir.len() {
+        parts.push("..".to_string());
+    }
+    for component in &target[common..] {
+        parts.push(component.as_os_str().to_string_lossy().to_string());
+    }
+    let path = parts.join("/");
+    if path.starts_with("..") {
+        path
+    } else {
+        format!("./{}", path)
+    }
+}
+
+/// # 获取文件移动后的导入路径
+///
+/// * `path` 是原导入路径，`old_path` 是原导入路径对应的文件
+/// * `base_path` 是导入语句所在文件移动后的路径，`file_path` 是导入的文件移动后的路径
+///
+/// 保持原路径的写法，包括别名、相对路径以及是否省略后缀，
+/// 无法保持时返回 None，例如导入目录下的 index 文件
+pub fn get_moved_import_path(
+    path: &str,
+    old_path: &Path,
+    base_path: &Path,
+    file_path: &Path,
+    alias: &HashMap<String, String>,
+) -> Option<String> {
+    let file_name = old_path.file_name()?.to_string_lossy();
+    let file_stem = old_path.file_stem()?.to_string_lossy();
+    let mut file_path = file_path.to_path_buf();
+    if !path.ends_with(&*file_name) {
+        if !path.ends_with(&*file_stem) {
+            return None;
+        }
+        file_path.set_extension("");
+    }
+    if path.starts_with('/') {
+        return Some(file_path.to_string_lossy().to_string());
+    }
+    if !path.starts_with('.') {
+        let (key, value) = alias.iter().find(|(key, _)| path.starts_with(*key))?;
+        if let Ok(rest) = file_path.strip_prefix(value) {
+            let rest = rest
+                .components()
+                .map(|v| v.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+            return Some(format!("{}{}", key, rest.join("/")));
+        }
+    }
+    Some(get_relative_import_path(base_path, &file_path))
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashMap, str::FromStr};
 
     use tower_lsp::lsp_types::Uri;
 
-    use super::{parse_alias, parse_import_path};
+    use std::path::Path;
+
+    use super::{
+        get_moved_import_path, get_relative_import_path, parse_alias, parse_base_url,
+        parse_import_path, resolve_file_path,
+    };
 
     fn assert_alias(tsconfig: &str, expected: &[(&str, &str)]) {
         let root_uri = Uri::from_str("file:///tmp/project").unwrap();
@@ -137,6 +267,7 @@ mod tests {
             &base_uri,
             path,
             &HashMap::from_iter(alias.iter().map(|(k, v)| (k.to_string(), v.to_string()))),
+            None,
             &root_uri,
         );
         assert_eq!(result.to_string_lossy(), expected);
@@ -192,4 +323,151 @@ mod tests {
     fn node_modules() {
         assert_parse("vue", "/tmp/project/node_modules/vue", &[]);
     }
+
+    #[test]
+    fn alias_directory_index() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let root_path = root_dir.path();
+        std::fs::create_dir_all(root_path.join("src/components/MyButton")).unwrap();
+        std::fs::create_dir_all(root_path.join("src/components/MyInput")).unwrap();
+        std::fs::write(root_path.join("src/components/MyButton/index.vue"), "").unwrap();
+        std::fs::write(root_path.join("src/components/MyInput/index.vue"), "").unwrap();
+        std::fs::write(root_path.join("src/components/MyInput/index.d.ts"), "").unwrap();
+
+        let root = root_path.to_string_lossy();
+        let base_uri = Uri::from_str(&format!("file://{}/src/index.vue", root)).unwrap();
+        let root_uri = Uri::from_str(&format!("file://{}", root)).unwrap();
+        let alias = HashMap::from([("@/".to_string(), format!("{}/src/", root))]);
+        let resolve = |path: &str| {
+            let file_path = parse_import_path(&base_uri, path, &alias, None, &root_uri);
+            resolve_file_path(&file_path)
+        };
+        assert_eq!(
+            resolve("@/components/MyButton"),
+            Some(root_path.join("src/components/MyButton/index.vue"))
+        );
+        // index.d.ts 优先于 index.vue
+        assert_eq!(
+            resolve("@/components/MyInput"),
+            Some(root_path.join("src/components/MyInput/index.d.ts"))
+        );
+        assert_eq!(resolve("@/components/MySelect"), None);
+    }
+
+    #[test]
+    fn base_url() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let root_path = root_dir.path();
+        std::fs::create_dir_all(root_path.join("src/components")).unwrap();
+        std::fs::write(root_path.join("src/components/MyButton.vue"), "").unwrap();
+
+        let root = root_path.to_string_lossy();
+        let base_uri = Uri::from_str(&format!("file://{}/src/views/index.vue", root)).unwrap();
+        let root_uri = Uri::from_str(&format!("file://{}", root)).unwrap();
+        let tsconfig = r#"{
+			"compilerOptions": {
+				"baseUrl": "./src/",
+				"paths": {
+					"@/*": ["*"]
+				}
+			}
+		}"#;
+        let base_url = parse_base_url(tsconfig, &root_uri);
+        assert_eq!(base_url, Some(root_path.join("src")));
+        assert_eq!(
+            parse_alias(tsconfig, &root_uri),
+            HashMap::from([("@/".to_string(), format!("{}/src/", root))])
+        );
+        let parse = |path: &str| {
+            parse_import_path(
+                &base_uri,
+                path,
+                &HashMap::new(),
+                base_url.as_deref(),
+                &root_uri,
+            )
+        };
+        assert_eq!(
+            parse("components/MyButton.vue"),
+            root_path.join("src/components/MyButton.vue")
+        );
+        // baseUrl 下不存在时回退到 node_modules
+        assert_eq!(parse("vue"), root_path.join("node_modules/vue"));
+    }
+
+    #[test]
+    fn relative_import_path() {
+        let relative =
+            |base: &str, file: &str| get_relative_import_path(Path::new(base), Path::new(file));
+        assert_eq!(
+            relative("/tmp/project/a.vue", "/tmp/project/b.vue"),
+            "./b.vue"
+        );
+        assert_eq!(
+            relative("/tmp/project/views/a.vue", "/tmp/project/components/b.vue"),
+            "../components/b.vue"
+        );
+        assert_eq!(
+            relative("/tmp/project/a.vue", "/tmp/project/components/b.vue"),
+            "./components/b.vue"
+        );
+    }
+
+    #[test]
+    fn moved_import_path() {
+        let alias = HashMap::from([("@/".to_string(), "/tmp/project/src/".to_string())]);
+        let moved = |path: &str, old: &str, new: &str| {
+            get_moved_import_path(
+                path,
+                Path::new(old),
+                Path::new("/tmp/project/src/index.vue"),
+                Path::new(new),
+                &alias,
+            )
+        };
+        assert_eq!(
+            moved(
+                "./Old.vue",
+                "/tmp/project/src/Old.vue",
+                "/tmp/project/src/views/New.vue"
+            ),
+            Some("./views/New.vue".to_string())
+        );
+        // 省略后缀
+        assert_eq!(
+            moved(
+                "./util",
+                "/tmp/project/src/util.ts",
+                "/tmp/project/src/utils/index.ts"
+            ),
+            Some("./utils/index".to_string())
+        );
+        // 别名
+        assert_eq!(
+            moved(
+                "@/Old.vue",
+                "/tmp/project/src/Old.vue",
+                "/tmp/project/src/views/New.vue"
+            ),
+            Some("@/views/New.vue".to_string())
+        );
+        // 导入目录下的 index 文件
+        assert_eq!(
+            moved(
+                "./old",
+                "/tmp/project/src/old/index.vue",
+                "/tmp/project/src/new/index.vue"
+            ),
+            None
+        );
+        // node_modules 中的模块
+        assert_eq!(
+            moved(
+                "lib/Old.vue",
+                "/tmp/project/node_modules/lib/Old.vue",
+                "/tmp/project/New.vue"
+            ),
+            None
+        );
+    }
 }
@@ -1,28 +1,54 @@
 use html_languageservice::html_data::Description;
 use swc_common::{source_map::SmallPos, Span, Spanned};
 use swc_ecma_ast::{ClassMember, Module};
+use swc_ecma_parser::error::Error;
 
 use crate::ast;
 
 use super::{
     multi_threaded_comment::MultiThreadedComments,
-    render_cache::{RenderCacheProp, RenderCachePropParam},
+    render_cache::{
+        RenderCacheInjectParam, RenderCacheModelParam, RenderCacheProp, RenderCachePropParam,
+        RenderCachePropType, RenderCacheRefParam,
+    },
+    reserved_names,
 };
 
-/// 解析脚本，输出 props, render_insert_offset, extends_component, registers
-pub fn parse_script(source: &str, start_pos: usize, end_pos: usize) -> Option<ParseScriptResult> {
+/// 解析脚本，输出 props, render_insert_offset, extends_component, mixins, registers
+///
+/// `component_decorators` 为声明组件的装饰器名称
+pub fn parse_script(
+    source: &str,
+    start_pos: usize,
+    end_pos: usize,
+    component_decorators: &[String],
+) -> Option<ParseScriptResult> {
+    try_parse_script(source, start_pos, end_pos, component_decorators)
+        .ok()
+        .flatten()
+}
+
+/// 解析脚本，脚本存在语法错误时返回 Err
+pub fn try_parse_script(
+    source: &str,
+    start_pos: usize,
+    end_pos: usize,
+    component_decorators: &[String],
+) -> Result<Option<ParseScriptResult>, Error> {
     let (module, comments) = ast::parse_source(source, start_pos, end_pos);
-    if let Ok(module) = &module {
-        parse_module(module, &comments, source)
-    } else {
-        None
-    }
+    Ok(parse_module(
+        &module?,
+        &comments,
+        source,
+        component_decorators,
+    ))
 }
 
 pub fn parse_module(
     module: &Module,
     comments: &MultiThreadedComments,
     source: &str,
+    component_decorators: &[String],
 ) -> Option<ParseScriptResult> {
     let mut extends_component = None;
     if let Some(class) = ast::get_default_class_expr_from_module(module) {
@@ -33,6 +59,9 @@ pub fn parse_module(
             .map(|ident| ident.sym.to_string())
             .unwrap_or("Default".to_string());
         let mut props = vec![];
+        let mut reserved_members = vec![];
+        let mut decorator_option_keys = vec![];
+        let mut provide_keys = vec![];
         for member in class
             .class
             .body
@@ -51,14 +80,86 @@ pub fn parse_module(
                     typ: v.0,
                     default: v.1,
                     required: v.2,
+                    validator: v.3,
                 });
-            props.push(RenderCacheProp {
-                name,
-                range: (start, end),
-                description,
-                typ,
-                prop_params,
+            // 没有类型注解时，从 `@Prop` 的默认值推断
+            let ts_type = ast::get_class_member_type_ann(member, source)
+                .or_else(|| ast::get_class_member_prop_default_type(member, source));
+            let inject_params = ast::get_class_member_inject_params(member, source).map(|v| {
+                RenderCacheInjectParam {
+                    from: v.0,
+                    default: v.1,
+                    is_literal_key: v.2,
+                }
+            });
+            let ref_params =
+                ast::get_class_member_ref_params(member, source).map(|(name, is_array, typ)| {
+                    RenderCacheRefParam {
+                        name,
+                        is_array,
+                        typ,
+                    }
+                });
+            let emit_name = ast::get_class_member_emit_name(member);
+            let model_params = ast::get_class_member_model_params(member, source).map(
+                |(prop, event, typ, writable)| RenderCacheModelParam {
+                    prop,
+                    event,
+                    typ,
+                    writable,
+                },
+            );
+            let decorators = ast::get_class_member_decorators(member);
+            let decorators_range = ast::get_class_member_decorators_range(member);
+            decorator_option_keys.append(&mut ast::get_class_member_decorator_option_keys(member));
+            if let Some(key) = ast::get_class_member_provide_key(member) {
+                provide_keys.push(key);
+            }
+            // 使用保留名称的属性和数据
+            if typ == RenderCachePropType::Property
+                && reserved_names::get_reserved_name_message(&name).is_some()
+            {
+                let is_prop = decorators.iter().any(|v| {
+                    ["Prop", "PropSync", "Model", "ModelSync", "VModel"].contains(&&v[..])
+                });
+                // 未知的装饰器（如自定义的日志装饰器）不影响数据的判断
+                let is_data = ast::has_class_member_value(member)
+                    && !decorators
+                        .iter()
+                        .any(|v| ["Inject", "InjectReactive", "Ref"].contains(&&v[..]));
+                if is_prop || is_data {
+                    reserved_members.push((start, end));
+                }
+            }
+            // getter 和 setter 合并为同一个计算属性
+            let computed = props.iter_mut().find(|v| {
+                v.name == name
+                    && matches!(v.typ, RenderCachePropType::Computed { .. })
+                    && matches!(typ, RenderCachePropType::Computed { .. })
             });
+            if let Some(computed) = computed {
+                if typ == (RenderCachePropType::Computed { writable: true }) {
+                    computed.typ = typ;
+                }
+                if computed.ts_type.is_none() {
+                    computed.ts_type = ts_type;
+                }
+            } else {
+                props.push(RenderCacheProp {
+                    name,
+                    range: (start, end),
+                    description,
+                    typ,
+                    ts_type,
+                    prop_params,
+                    inject_params,
+                    ref_params,
+                    model_params,
+                    emit_name,
+                    decorators,
+                    decorators_range,
+                });
+            }
             // 获取安全更新范围
             match member {
                 ClassMember::Method(method) => {
@@ -103,9 +204,33 @@ pub fn parse_module(
                 }
             }
         }
+        let extends_range = extends_component
+            .as_ref()
+            .and(class.class.super_class.as_ref())
+            .map(|v| (v.span_lo().to_usize(), v.span_hi().to_usize()));
+        let mut mixins = vec![];
+        // `extends Mixins(A, B)` 与 `@Component({ mixins: [A, B] })` 均作为混入处理
+        let options_mixins = ast::get_options_mixins(module, class, component_decorators);
+        let mut mixin_idents = ast::get_mixins_components(class).unwrap_or(vec![]);
+        mixin_idents.extend(options_mixins.iter().cloned());
+        for mixin_ident in mixin_idents {
+            if let Some((orig_name, path)) = ast::get_import_from_module(module, &mixin_ident) {
+                mixins.push(ExtendsComponent {
+                    export_name: orig_name,
+                    path,
+                });
+            }
+        }
         let render_insert_offset = class.class.span.hi.to_usize() - 1;
+        let class_nested_ranges = class
+            .class
+            .body
+            .iter()
+            .flat_map(ast::get_class_member_nested_ranges)
+            .collect();
         let mut registers = vec![];
-        let registered_components = ast::get_registered_components(module, class).unwrap_or(vec![]);
+        let registered_components =
+            ast::get_registered_components(module, class, component_decorators).unwrap_or(vec![]);
         for (name, export, prop, path) in registered_components {
             registers.push(RegisterComponent {
                 name,
@@ -114,13 +239,43 @@ pub fn parse_module(
                 path,
             });
         }
+        let provide_keys = [
+            ast::get_options_provide_keys(module, class, component_decorators),
+            provide_keys,
+        ]
+        .concat();
+        let import_paths = ast::get_import_paths(module);
+        // 作为组件使用的导入路径
+        let component_imports = import_paths
+            .iter()
+            .cloned()
+            .filter(|(path, _)| {
+                extends_component.as_ref().is_some_and(|v| &v.path == path)
+                    || mixins.iter().any(|v| &v.path == path)
+                    || registers.iter().any(|v| &v.path == path)
+            })
+            .collect();
         Some(ParseScriptResult {
             name_span: class.ident.span(),
+            functional: ast::is_functional_component(module, class, component_decorators),
             description: ast::get_class_expr_description(class, comments),
             props,
             render_insert_offset,
+            class_body_start: ast::get_class_body_start(class, source),
+            class_nested_ranges,
             extends_component,
+            extends_range,
+            mixins,
+            options_mixins,
             registers,
+            reserved_members,
+            import_paths,
+            component_imports,
+            decorator_option_keys,
+            provide_keys,
+            inject_keys: ast::get_options_inject_keys(module, class, component_decorators),
+            options_model: ast::get_options_model(module, class, component_decorators),
+            local_type_names: ast::get_local_type_names(module),
             safe_update_range,
         })
     } else {
@@ -129,7 +284,7 @@ pub fn parse_module(
 }
 
 /// 继承的组件
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ExtendsComponent {
     /// 导出的组件名，如果是默认导出，则为 None，如果被重命名，那么则为重命名前的名称
     pub export_name: Option<String>,
@@ -155,20 +310,64 @@ pub struct RegisterComponent {
 #[derive(Default, Debug)]
 pub struct ParseScriptResult {
     pub name_span: Span,
+    /// 是否为函数式组件
+    pub functional: bool,
     pub description: Option<Description>,
     pub props: Vec<RenderCacheProp>,
     pub render_insert_offset: usize,
+    /// 类体左花括号的位置
+    pub class_body_start: usize,
+    /// 类体中成员的装饰器、参数、方法体和初始值等嵌套结构的范围
+    pub class_nested_ranges: Vec<(usize, usize)>,
     pub extends_component: Option<ExtendsComponent>,
+    /// `extends` 子句中父类表达式的范围，仅在存在继承的组件时有值
+    pub extends_range: Option<(usize, usize)>,
+    /// `extends Mixins(A, B)` 或 `@Component({ mixins: [A, B] })` 混入的组件
+    pub mixins: Vec<ExtendsComponent>,
+    /// `@Component({ mixins: [A, B] })` 中混入组件的标识符，类的类型中不包含其成员
+    pub options_mixins: Vec<String>,
     pub registers: Vec<RegisterComponent>,
+    /// 使用 Vue 保留名称的属性和数据的名称范围
+    pub reserved_members: Vec<(usize, usize)>,
+    /// 所有导入路径及路径字符串的范围
+    pub import_paths: Vec<(String, (usize, usize))>,
+    /// 作为组件使用的导入路径及路径字符串的范围
+    pub component_imports: Vec<(String, (usize, usize))>,
+    /// `@Prop`, `@Model` 等装饰器选项对象中的键及其范围
+    pub decorator_option_keys: Vec<(String, (usize, usize))>,
+    /// `@Component` 选项对象中 `provide` 提供的 key 及其范围
+    pub provide_keys: Vec<(String, (usize, usize))>,
+    /// `@Component` 选项对象中 `inject` 注入的 key 及其范围
+    pub inject_keys: Vec<(String, (usize, usize))>,
+    /// `@Component({ model: { prop, event } })` 中的 model 属性名称和事件名称
+    pub options_model: Option<(String, String)>,
+    /// 导入的标识符及声明的类型名称，只在当前文件中可用
+    pub local_type_names: Vec<String>,
     pub safe_update_range: Vec<(usize, usize)>,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ExtendsComponent, RegisterComponent};
+    use crate::{
+        ast,
+        renderer::render_cache::{
+            RenderCacheInjectParam, RenderCachePropParam, RenderCachePropType,
+        },
+    };
+
+    use super::{ExtendsComponent, ParseScriptResult, RegisterComponent};
+
+    fn parse_script(source: &str) -> Option<ParseScriptResult> {
+        super::parse_script(
+            source,
+            0,
+            source.len(),
+            &ast::get_default_component_decorators(),
+        )
+    }
 
     fn assert_props(source: &str, expected: &[&str]) {
-        let props = super::parse_script(source, 0, source.len()).unwrap().props;
+        let props = parse_script(source).unwrap().props;
         assert_eq!(
             props.iter().map(|v| v.name.clone()).collect::<Vec<_>>(),
             expected.iter().map(|v| v.to_string()).collect::<Vec<_>>()
@@ -176,16 +375,12 @@ mod tests {
     }
 
     fn assert_render_insert_offset(source: &str, expected: usize) {
-        let render_insert_offset = super::parse_script(source, 0, source.len())
-            .unwrap()
-            .render_insert_offset;
+        let render_insert_offset = parse_script(source).unwrap().render_insert_offset;
         assert_eq!(render_insert_offset, expected);
     }
 
     fn assert_extends_component(source: &str, expected: Option<(Option<&str>, &str)>) {
-        let extends_component = super::parse_script(source, 0, source.len())
-            .unwrap()
-            .extends_component;
+        let extends_component = parse_script(source).unwrap().extends_component;
         assert_eq!(
             extends_component,
             expected.map(|v| ExtendsComponent {
@@ -195,16 +390,50 @@ mod tests {
         )
     }
 
+    fn assert_inject_params(source: &str, expected: &[(&str, Option<(&str, bool, bool)>)]) {
+        let props = parse_script(source).unwrap().props;
+        assert_eq!(
+            props
+                .into_iter()
+                .map(|v| (v.name, v.inject_params))
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|v| (
+                    v.0.to_string(),
+                    v.1.map(|(from, default, is_literal_key)| RenderCacheInjectParam {
+                        from: from.to_string(),
+                        default,
+                        is_literal_key,
+                    })
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    fn assert_prop_types(source: &str, expected: &[(&str, RenderCachePropType)]) {
+        let props = parse_script(source).unwrap().props;
+        assert_eq!(
+            props
+                .into_iter()
+                .map(|v| (v.name, v.typ))
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|v| (v.0.to_string(), v.1.clone()))
+                .collect::<Vec<_>>()
+        );
+    }
+
     fn assert_registers(source: &str, expected: &[RegisterComponent]) {
-        let registers = super::parse_script(source, 0, source.len())
-            .unwrap()
-            .registers;
+        let registers = parse_script(source).unwrap().registers;
         assert_eq!(registers, expected.to_vec())
     }
 
     #[test]
     fn normal() {
         let source = &[
+            "import { Component } from 'vue-property-decorator'",
             "import MyComponent1 from './components/MyComponent1.vue'",
             "import MyComponent2 from './components/MyComponent2.vue'",
             "@Component({",
@@ -251,6 +480,7 @@ mod tests {
     #[test]
     fn extends_component() {
         let source = &[
+            "import { Component } from 'vue-property-decorator'",
             "import MyComponent1 from './components/MyComponent1.vue'",
             "import MyComponent2 from './components/MyComponent2.vue'",
             "@Component({",
@@ -274,9 +504,645 @@ mod tests {
         assert_extends_component(source, Some((None, "./components/MyComponent1.vue")));
     }
 
+    #[test]
+    fn options_decorator() {
+        let source = &[
+            "import { Options, Vue } from 'vue-class-component'",
+            "import MyComponent1 from './components/MyComponent1.vue'",
+            "import MyMixin1 from './mixins/MyMixin1.vue'",
+            "@Options({",
+            "    components: { MyComponent1 },",
+            "    mixins: [MyMixin1],",
+            "})",
+            "export default class Test extends Vue {",
+            "   private prop1 = ''",
+            "}",
+        ]
+        .join("\n");
+        assert_props(source, &["prop1"]);
+        assert_registers(
+            source,
+            &[RegisterComponent {
+                name: "MyComponent1".to_string(),
+                export: None,
+                prop: None,
+                path: "./components/MyComponent1.vue".to_string(),
+            }],
+        );
+        let result = parse_script(source).unwrap();
+        assert_eq!(
+            result.mixins,
+            vec![ExtendsComponent {
+                export_name: None,
+                path: "./mixins/MyMixin1.vue".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_component_decorator() {
+        let source = &[
+            "import { Component } from './decorators'",
+            "import MyComponent1 from './components/MyComponent1.vue'",
+            "@Component({",
+            "    components: { MyComponent1 },",
+            "})",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        assert_registers(source, &[]);
+        // 只识别配置的装饰器名称
+        let source = &[
+            "import { Options } from 'vue-class-component'",
+            "import MyComponent1 from './components/MyComponent1.vue'",
+            "@Options({",
+            "    components: { MyComponent1 },",
+            "})",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        let result =
+            super::parse_script(source, 0, source.len(), &["Component".to_string()]).unwrap();
+        assert_eq!(result.registers, vec![]);
+    }
+
+    #[test]
+    fn generic_extends_component() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator';",
+            "import MyComponent1 from './components/MyComponent1.vue';",
+            "@Component",
+            "export default class Test extends MyComponent1<Props> {}",
+        ]
+        .join("\n");
+        assert_extends_component(source, Some((None, "./components/MyComponent1.vue")));
+        let source = &[
+            "import { Component } from 'vue-property-decorator';",
+            "import MyComponent1 from './components/MyComponent1.vue';",
+            "@Component",
+            "export default class Test extends (MyComponent1<Props> as VueClass<MyComponent1>) {}",
+        ]
+        .join("\n");
+        assert_extends_component(source, Some((None, "./components/MyComponent1.vue")));
+    }
+
+    #[test]
+    fn mixins() {
+        let source = &[
+            "import { Component, Mixins } from 'vue-property-decorator'",
+            "import MyMixin1 from './mixins/MyMixin1.vue'",
+            "import { MyMixin2 } from './mixins'",
+            "@Component",
+            "export default class Test extends Mixins(MyMixin1, MyMixin2) {",
+            "   private prop1 = ''",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(result.extends_component, None);
+        assert_eq!(
+            result.mixins,
+            vec![
+                ExtendsComponent {
+                    export_name: None,
+                    path: "./mixins/MyMixin1.vue".to_string(),
+                },
+                ExtendsComponent {
+                    export_name: Some("MyMixin2".to_string()),
+                    path: "./mixins".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn options_mixins() {
+        let source = &[
+            "import { Component, Vue } from 'vue-property-decorator'",
+            "import MyMixin1 from './mixins/MyMixin1.vue'",
+            "import { MyMixin2 } from './mixins'",
+            "@Component({",
+            "    mixins: [MyMixin1, MyMixin2],",
+            "})",
+            "export default class Test extends Vue {",
+            "   private prop1 = ''",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(
+            result.mixins,
+            vec![
+                ExtendsComponent {
+                    export_name: None,
+                    path: "./mixins/MyMixin1.vue".to_string(),
+                },
+                ExtendsComponent {
+                    export_name: Some("MyMixin2".to_string()),
+                    path: "./mixins".to_string(),
+                },
+            ]
+        );
+        assert_eq!(result.options_mixins, vec!["MyMixin1", "MyMixin2"]);
+    }
+
+    #[test]
+    fn aliased_decorators() {
+        let source = &[
+            "import { Component as Comp, Prop as P } from 'vue-property-decorator'",
+            "import MyComponent1 from './components/MyComponent1.vue'",
+            "@Comp({",
+            "    components: {",
+            "        MyComponent1,",
+            "    },",
+            "})",
+            "export default class Test extends Vue {",
+            "   @P({ type: String, required: true }) title!: string",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(result.registers.len(), 1);
+        assert_eq!(result.props[0].decorators, vec!["Prop".to_string()]);
+        assert_eq!(
+            result.props[0].prop_params,
+            Some(RenderCachePropParam {
+                typ: Some("String".to_string()),
+                default: false,
+                required: true,
+                validator: false,
+            })
+        );
+    }
+
+    #[test]
+    fn prop_validator() {
+        let source = &[
+            "import { Component, Prop, Vue } from 'vue-property-decorator'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Prop({ type: String, validator: (v: string) => ['a', 'b'].includes(v) }) size!: string",
+            "   @Prop({ default: 1, validator(v: number) { return v > 0 } }) count",
+            "   @Prop({ type: Boolean }) flag",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(
+            result
+                .props
+                .into_iter()
+                .map(|v| v.prop_params.unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                RenderCachePropParam {
+                    typ: Some("String".to_string()),
+                    default: false,
+                    required: false,
+                    validator: true,
+                },
+                RenderCachePropParam {
+                    typ: None,
+                    default: true,
+                    required: false,
+                    validator: true,
+                },
+                RenderCachePropParam {
+                    typ: Some("Boolean".to_string()),
+                    default: false,
+                    required: false,
+                    validator: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn prop_type_cast() {
+        let source = &[
+            "import { Component, Prop, Vue } from 'vue-property-decorator'",
+            "import { PropType } from 'vue'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Prop({ type: Object as PropType<User>, required: true }) user",
+            "   @Prop({ type: Array as PropType<User[]> }) users!: Item[]",
+            "   @Prop({ type: Object as Vue.PropType<{ id: number }> }) item",
+            "   @Prop({ type: Object as Record<string, any> }) options",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(
+            result
+                .props
+                .into_iter()
+                .map(|v| v.prop_params.unwrap().typ)
+                .collect::<Vec<_>>(),
+            vec![
+                Some("User".to_string()),
+                Some("User[]".to_string()),
+                Some("{ id: number }".to_string()),
+                Some("Object as Record<string, any>".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn prop_type_thunk() {
+        let source = &[
+            "import { Component, Prop, Vue } from 'vue-property-decorator'",
+            "import { Foo, Models } from './models'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Prop({ type: () => Foo }) foo",
+            "   @Prop({ type: () => Models.Bar, required: true }) bar",
+            "   @Prop({ type: () => (Foo) }) baz",
+            "   @Prop({ type: () => Foo.create() }) qux",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(
+            result
+                .props
+                .into_iter()
+                .map(|v| v.prop_params.unwrap().typ)
+                .collect::<Vec<_>>(),
+            vec![
+                Some("Foo".to_string()),
+                Some("Models.Bar".to_string()),
+                Some("Foo".to_string()),
+                // 不是构造函数时保持原样
+                Some("() => Foo.create()".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn prop_type() {
+        let source = &[
+            "import { Component, Prop, Vue } from 'vue-property-decorator'",
+            "import { MySize } from './enums'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Prop({ default: MySize.Large }) size!: MySize",
+            "   @Prop({ default: MySize.Large }) size2",
+            "   @Prop({ type: String, default: 'a' }) text",
+            "   @Prop({ default: 1 }) count",
+            "   @Prop({ default: () => [] }) list",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(
+            result
+                .props
+                .iter()
+                .map(|v| (
+                    v.prop_params.as_ref().and_then(|v| v.typ.as_deref()),
+                    v.ts_type.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (None, Some("MySize")),
+                (None, Some("MySize")),
+                (Some("String"), None),
+                (None, Some("number")),
+                (None, Some("any[]"))
+            ]
+        );
+    }
+
+    #[test]
+    fn prop_factory_default() {
+        let source = &[
+            "import { Component, Prop, Vue } from 'vue-property-decorator'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Prop({ type: Array, default: () => [] }) list",
+            "   @Prop({ type: Object, default: () => ({}) }) obj",
+            "   @Prop({ default: () => ({ a: 1 }) }) options",
+            "   @Prop({ default: () => { return [1, 2] } }) values",
+            "   @Prop({ type: Array, default: () => [] }) items!: string[]",
+            "   @Prop({ default: () => new Date() }) date",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(
+            result
+                .props
+                .iter()
+                .map(|v| v.prop_params.as_ref().map(|params| (
+                    params.typ.as_deref(),
+                    v.ts_type.as_deref(),
+                    params.default
+                )))
+                .collect::<Vec<_>>(),
+            vec![
+                Some((Some("Array"), None, true)),
+                Some((Some("Object"), None, true)),
+                Some((None, Some("Record<string, any>"), true)),
+                Some((None, Some("any[]"), true)),
+                Some((Some("Array"), Some("string[]"), true)),
+                Some((None, None, true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn namespaced_decorators() {
+        let source = &[
+            "import * as VPD from 'vue-property-decorator'",
+            "import MyComponent1 from './components/MyComponent1.vue'",
+            "@VPD.Component({",
+            "    components: {",
+            "        MyComponent1,",
+            "    },",
+            "})",
+            "export default class Test extends Vue {",
+            "   @VPD.Prop({ type: String }) title!: string",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(result.registers.len(), 1);
+        assert_eq!(result.props[0].decorators, vec!["Prop".to_string()]);
+        assert!(result.props[0].prop_params.is_some());
+    }
+
+    #[test]
+    fn default_imported_decorators() {
+        let source = &[
+            "import Comp from 'vue-class-component'",
+            "import { Prop, Vue } from 'vue-property-decorator'",
+            "import MyComponent1 from './components/MyComponent1.vue'",
+            "@Comp({",
+            "    components: {",
+            "        MyComponent1,",
+            "    },",
+            "})",
+            "export default class Test extends Vue {",
+            "   @Prop({ type: String }) title!: string",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(result.registers.len(), 1);
+        assert!(result.props[0].prop_params.is_some());
+    }
+
+    #[test]
+    fn foreign_aliased_decorators() {
+        // 其他模块导入的同名装饰器不进行还原
+        let source = &[
+            "import { Component, Vue } from 'vue-property-decorator'",
+            "import { Prop as Track } from './decorators'",
+            "import * as Other from './other'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Track() title!: string",
+            "   @Other.Prop() text!: string",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(result.props.len(), 2);
+        assert!(result.props.iter().all(|v| v.prop_params.is_none()));
+    }
+
+    #[test]
+    fn spread_options() {
+        let source = &[
+            "import { Component, Vue } from 'vue-property-decorator'",
+            "import Foo from './components/Foo.vue'",
+            "import Bar from './components/Bar.vue'",
+            "const mixinOpts = { components: { Bar } }",
+            "@Component({ ...mixinOpts, components: { Foo } })",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        let names = result
+            .registers
+            .iter()
+            .map(|v| &v.name[..])
+            .collect::<Vec<_>>();
+        // 后出现的 `components` 覆盖展开的选项
+        assert_eq!(names, vec!["Foo"]);
+
+        let source = &[
+            "import { Component, Vue } from 'vue-property-decorator'",
+            "import Foo from './components/Foo.vue'",
+            "const baseOptions = { components: { Foo } }",
+            "@Component(baseOptions)",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(result.registers.len(), 1);
+        assert_eq!(result.registers[0].name, "Foo");
+
+        // 内联的组件覆盖展开对象中的同名组件
+        let source = &[
+            "import { Component, Vue } from 'vue-property-decorator'",
+            "import Foo from './components/Foo.vue'",
+            "import Bar from './components/Bar.vue'",
+            "const base = { Foo, Bar }",
+            "@Component({ components: { ...base, Foo: Bar } })",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        let registers = result
+            .registers
+            .iter()
+            .map(|v| (&v.name[..], &v.path[..]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            registers,
+            vec![
+                ("Bar", "./components/Bar.vue"),
+                ("Foo", "./components/Bar.vue")
+            ]
+        );
+    }
+
+    #[test]
+    fn rename_registers() {
+        let source = &[
+            "import { Component, Vue } from 'vue-property-decorator'",
+            "import RealComp from './components/RealComp.vue'",
+            "import { Select } from 'element-ui'",
+            "@Component({",
+            "    components: {",
+            "        Alias: RealComp,",
+            "        'my-option': Select.Option,",
+            "        Unknown: NotImported,",
+            "    },",
+            "})",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(
+            result.registers,
+            vec![
+                RegisterComponent {
+                    name: "Alias".to_string(),
+                    export: None,
+                    prop: None,
+                    path: "./components/RealComp.vue".to_string(),
+                },
+                RegisterComponent {
+                    name: "my-option".to_string(),
+                    export: Some("Select".to_string()),
+                    prop: Some("Option".to_string()),
+                    path: "element-ui".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn component_imports() {
+        let source = &[
+            "import { Component, Vue } from 'vue-property-decorator'",
+            "import MyComponent1 from './components/MyComponent1.vue'",
+            "import util from './util'",
+            "@Component({",
+            "    components: {",
+            "        MyComponent1,",
+            "    },",
+            "})",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        let imports = result
+            .component_imports
+            .iter()
+            .map(|(path, range)| (&path[..], &source[range.0..range.1]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            imports,
+            vec![(
+                "./components/MyComponent1.vue",
+                "'./components/MyComponent1.vue'"
+            )]
+        );
+    }
+
+    #[test]
+    fn decorator_option_keys() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Prop({ type: String, required: true, default() { return '' } }) title!: string",
+            "   @Model('change', { type: Boolean }) checked!: boolean",
+            "   @Watch('title', { immediate: true }) onTitleChange() {}",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        let keys = result
+            .decorator_option_keys
+            .iter()
+            .map(|(key, range)| {
+                assert_eq!(&source[range.0..range.1], key);
+                &key[..]
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["type", "required", "default", "type"]);
+    }
+
+    #[test]
+    fn reserved_members() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Prop() $title!: string",
+            "   private _uid = 0",
+            "   private $refs!: { input: HTMLInputElement }",
+            "   private _method() {}",
+            "   private text = ''",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        let names = result
+            .reserved_members
+            .iter()
+            .map(|v| &source[v.0..v.1])
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["$title", "_uid"]);
+    }
+
+    #[test]
+    fn stacked_decorators() {
+        let source = &[
+            "import { Component, Prop, Vue } from 'vue-property-decorator'",
+            "import MyComponent1 from './components/MyComponent1.vue'",
+            "@Log",
+            "@Component({",
+            "    components: {",
+            "        MyComponent1,",
+            "    },",
+            "})",
+            "@Track('test')",
+            "export default class Test extends Vue {",
+            "   @Log @Prop({ type: String, required: true }) title!: string",
+            "   @Prop(Number) @Log() count!: number",
+            "   @Log private _uid = 0",
+            "   @Debounce(300) onInput() {}",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert_eq!(result.registers.len(), 1);
+        assert_eq!(result.registers[0].name, "MyComponent1");
+        assert_eq!(
+            result
+                .props
+                .iter()
+                .map(|v| (&v.name[..], v.decorators.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("title", vec!["Log".to_string(), "Prop".to_string()]),
+                ("count", vec!["Prop".to_string(), "Log".to_string()]),
+                ("_uid", vec!["Log".to_string()]),
+                ("onInput", vec!["Debounce".to_string()]),
+            ]
+        );
+        assert_eq!(
+            result
+                .props
+                .iter()
+                .map(|v| v.prop_params.as_ref().map(|v| (v.typ.clone(), v.required)))
+                .collect::<Vec<_>>(),
+            vec![
+                Some((Some("string".to_string()), true)),
+                Some((Some("number".to_string()), false)),
+                None,
+                None,
+            ]
+        );
+        assert_eq!(result.props[3].typ, RenderCachePropType::Method);
+        // 带有未知装饰器的数据仍然是数据
+        let names = result
+            .reserved_members
+            .iter()
+            .map(|v| &source[v.0..v.1])
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["_uid"]);
+    }
+
     #[test]
     fn with_lib_component() {
         let source = &[
+            "import { Component } from 'vue-property-decorator'",
             "import { Button, Select } from 'component-library'",
             "import MyComponent1 from './components/MyComponent1.vue'",
             "import MyComponent2 from './components/MyComponent2.vue'",
@@ -335,6 +1201,7 @@ mod tests {
     #[test]
     fn with_mixins() {
         let source = &[
+            "import { Component } from 'vue-property-decorator'",
             "import MyComponent1 from './components/MyComponent1.vue'",
             "import MyComponent2 from '@components/MyComponent2.vue'",
             "@Component({",
@@ -366,4 +1233,247 @@ mod tests {
             }],
         );
     }
+
+    #[test]
+    fn inject() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Inject() readonly foo!: string",
+            "   @Inject('bar') readonly bar1!: string",
+            "   @Inject({ from: 'baz', default: () => [] }) readonly items!: unknown[]",
+            "   @Inject({ from: 'qux' }) readonly qux1!: string",
+            "   @Inject(THEME_KEY) readonly theme!: string",
+            "   @Inject({ from: SIZE_KEY }) readonly size!: number",
+            "   private prop1 = ''",
+            "}",
+        ]
+        .join("\n");
+        assert_inject_params(
+            source,
+            &[
+                ("foo", Some(("foo", false, true))),
+                ("bar1", Some(("bar", false, true))),
+                ("items", Some(("baz", true, true))),
+                ("qux1", Some(("qux", false, true))),
+                ("theme", Some(("THEME_KEY", false, false))),
+                ("size", Some(("SIZE_KEY", false, false))),
+                ("prop1", None),
+            ],
+        );
+    }
+
+    #[test]
+    fn options_provide_inject() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component({",
+            "    inject: ['foo', 'bar'],",
+            "    provide() {",
+            "        return { theme: this.theme, size };",
+            "    },",
+            "})",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        let range = |key: &str, skip: usize| {
+            let start = source.match_indices(key).nth(skip).unwrap().0;
+            (key.to_string(), (start, start + key.len()))
+        };
+        assert_eq!(result.inject_keys, vec![range("foo", 0), range("bar", 0)]);
+        assert_eq!(
+            result.provide_keys,
+            vec![range("theme", 0), range("size", 0)]
+        );
+
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component({",
+            "    inject: { qux: 'foo', baz: { from: 'bar', default: 1 }, size: {} },",
+            "    provide: { color: 'red' },",
+            "})",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        let range = |key: &str| {
+            let start = source.find(key).unwrap();
+            (start, start + key.len())
+        };
+        assert_eq!(
+            result.inject_keys,
+            vec![
+                ("foo".to_string(), range("qux")),
+                ("bar".to_string(), range("baz")),
+                ("size".to_string(), range("size")),
+            ]
+        );
+        assert_eq!(
+            result.provide_keys,
+            vec![("color".to_string(), range("color"))]
+        );
+    }
+
+    #[test]
+    fn decorator_provide_keys() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "    @Provide() theme = 'dark';",
+            "    @ProvideReactive('size') currentSize = 1;",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        let range = |key: &str| {
+            let start = source.find(key).unwrap();
+            (key.to_string(), (start, start + key.len()))
+        };
+        assert_eq!(result.provide_keys, vec![range("theme"), range("size")]);
+    }
+
+    #[test]
+    fn model_params() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Model('change', { type: Boolean }) readonly checked!: boolean",
+            "   @ModelSync('value', 'update', { type: String }) readonly text!: string",
+            "   @VModel() name!: string",
+            "   @Prop() readonly value!: string",
+            "}",
+        ]
+        .join("\n");
+        let props = parse_script(source).unwrap().props;
+        assert_eq!(
+            props
+                .into_iter()
+                .filter_map(|v| v.model_params)
+                .map(|v| (v.prop, v.event, v.typ, v.writable))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "checked".to_string(),
+                    "change".to_string(),
+                    Some("boolean".to_string()),
+                    false
+                ),
+                (
+                    "value".to_string(),
+                    "update".to_string(),
+                    Some("string".to_string()),
+                    true
+                ),
+                (
+                    "value".to_string(),
+                    "input".to_string(),
+                    Some("string".to_string()),
+                    true
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn ref_params() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   @Ref() readonly input!: HTMLInputElement",
+            "   @Ref('items') readonly itemList!: Vue[]",
+            "   @Ref('rows') readonly rows!: Array<HTMLElement> | undefined",
+            "   private prop1 = ''",
+            "}",
+        ]
+        .join("\n");
+        let props = parse_script(source).unwrap().props;
+        assert_eq!(
+            props
+                .into_iter()
+                .map(|v| (v.name, v.ref_params.map(|v| (v.name, v.is_array, v.typ))))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "input".to_string(),
+                    Some((
+                        "input".to_string(),
+                        false,
+                        Some("HTMLInputElement".to_string())
+                    ))
+                ),
+                (
+                    "itemList".to_string(),
+                    Some(("items".to_string(), true, Some("Vue[]".to_string())))
+                ),
+                (
+                    "rows".to_string(),
+                    Some((
+                        "rows".to_string(),
+                        true,
+                        Some("Array<HTMLElement> | undefined".to_string())
+                    ))
+                ),
+                ("prop1".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn computed() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component",
+            "export default class Test extends Vue {",
+            "   private prop1 = ''",
+            "   get prop2() {",
+            "       return this.prop1",
+            "   }",
+            "   get prop3() {",
+            "       return this.prop1",
+            "   }",
+            "   set prop3(value: string) {",
+            "       this.prop1 = value",
+            "   }",
+            "   private method1() {}",
+            "}",
+        ]
+        .join("\n");
+        assert_prop_types(
+            source,
+            &[
+                ("prop1", RenderCachePropType::Property),
+                ("prop2", RenderCachePropType::Computed { writable: false }),
+                ("prop3", RenderCachePropType::Computed { writable: true }),
+                ("method1", RenderCachePropType::Method),
+            ],
+        );
+    }
+
+    #[test]
+    fn functional() {
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component({ functional: true })",
+            "export default class Test extends Vue {",
+            "   @Prop({ type: String })",
+            "   private title!: string",
+            "}",
+        ]
+        .join("\n");
+        let result = parse_script(source).unwrap();
+        assert!(result.functional);
+        assert_props(source, &["title"]);
+        let source = &[
+            "import { Component } from 'vue-property-decorator'",
+            "@Component({ functional: false })",
+            "export default class Test extends Vue {}",
+        ]
+        .join("\n");
+        assert!(!parse_script(source).unwrap().functional);
+    }
 }
@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Location, Position, Range, Uri};
+
+use super::{render_cache::RenderCache, Renderer};
+
+/// provide / inject
+impl Renderer {
+    /// 获取直接或间接注册了当前组件的所有祖先组件
+    fn get_ancestor_uris(&self, uri: &Uri) -> Vec<&Uri> {
+        let mut result = vec![];
+        let mut visited = HashSet::from([uri]);
+        let mut stack = vec![uri];
+        while let Some(cur) = stack.pop() {
+            if self.render_cache.get(cur).is_none() {
+                continue;
+            }
+            for (parent, _) in self.render_cache.get_register_parents(cur) {
+                if visited.insert(parent) {
+                    result.push(parent);
+                    stack.push(parent);
+                }
+            }
+        }
+        result
+    }
+
+    /// 获取直接或间接注册在当前组件中的所有后代组件
+    fn get_descendant_uris(&self, uri: &Uri) -> Vec<&Uri> {
+        let mut result = vec![];
+        let mut visited = HashSet::from([uri]);
+        let mut stack = vec![uri];
+        while let Some(cur) = stack.pop() {
+            if self.render_cache.get(cur).is_none() {
+                continue;
+            }
+            for (name, ..) in self.render_cache.get_registers(cur) {
+                let Some(child) = self.get_registered_component_uri(cur, &name) else {
+                    continue;
+                };
+                if visited.insert(child) {
+                    result.push(child);
+                    stack.push(child);
+                }
+            }
+        }
+        result
+    }
+
+    /// 获取组件或其继承的组件中提供指定 key 的位置
+    fn get_provided_key_locations(&self, uri: &Uri, key: &str) -> Vec<Location> {
+        let mut result = vec![];
+        let mut visited = HashSet::from([uri]);
+        let mut stack = vec![uri];
+        while let Some(cur) = stack.pop() {
+            let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(cur) else {
+                continue;
+            };
+            for (_, (start, end)) in cache.provide_keys.iter().filter(|(name, _)| name == key) {
+                result.push(Location {
+                    uri: cur.clone(),
+                    range: Range::new(
+                        cache.document.position_at(*start as u32),
+                        cache.document.position_at(*end as u32),
+                    ),
+                });
+            }
+            for (extends_uri, _) in self.render_cache.get_extends_nodes(cur) {
+                if visited.insert(extends_uri) {
+                    stack.push(extends_uri);
+                }
+            }
+        }
+        result
+    }
+
+    /// 获取组件中注入指定 key 的位置，包括 `@Inject` 装饰器和选项中的 `inject`
+    fn get_injected_key_locations(&self, uri: &Uri, key: &str) -> Vec<Location> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
+        };
+        cache
+            .props
+            .iter()
+            .filter(|prop| prop.inject_params.as_ref().is_some_and(|v| v.from == key))
+            .map(|prop| prop.range)
+            .chain(
+                cache
+                    .inject_keys
+                    .iter()
+                    .filter(|(name, _)| name == key)
+                    .map(|(_, range)| *range),
+            )
+            .map(|(start, end)| Location {
+                uri: uri.clone(),
+                range: Range::new(
+                    cache.document.position_at(start as u32),
+                    cache.document.position_at(end as u32),
+                ),
+            })
+            .collect()
+    }
+
+    /// 获取位置上注入的 key 在祖先组件中提供的位置
+    pub fn get_inject_provider_locations(&self, uri: &Uri, position: &Position) -> Vec<Location> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let contains = |(start, end): &(usize, usize)| *start <= offset && offset <= *end;
+        let key = cache
+            .props
+            .iter()
+            .filter(|prop| contains(&prop.range))
+            .find_map(|prop| Some(&prop.inject_params.as_ref()?.from))
+            .or_else(|| {
+                cache
+                    .inject_keys
+                    .iter()
+                    .find(|(_, range)| contains(range))
+                    .map(|(name, _)| name)
+            });
+        let Some(key) = key else {
+            return vec![];
+        };
+        self.get_ancestor_uris(uri)
+            .into_iter()
+            .flat_map(|ancestor| self.get_provided_key_locations(ancestor, key))
+            .collect()
+    }
+
+    /// 获取位置上提供的 key 在后代组件中注入的位置
+    pub fn get_provide_injector_locations(&self, uri: &Uri, position: &Position) -> Vec<Location> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
+        };
+        let offset = cache.document.offset_at(*position) as usize;
+        let Some((key, _)) = cache
+            .provide_keys
+            .iter()
+            .find(|(_, (start, end))| *start <= offset && offset <= *end)
+        else {
+            return vec![];
+        };
+        self.get_descendant_uris(uri)
+            .into_iter()
+            .flat_map(|descendant| self.get_injected_key_locations(descendant, key))
+            .collect()
+    }
+
+    /// 项目中是否有组件提供了指定的 key
+    fn is_key_provided_by_any_component(&self, key: &str) -> bool {
+        self.render_cache.get_vue_uris().iter().any(|uri| {
+            matches!(
+                self.render_cache.get(uri),
+                Some(RenderCache::VueRenderCache(cache))
+                    if cache.provide_keys.iter().any(|(name, _)| name == key)
+            )
+        })
+    }
+
+    /// 获取 `@Inject` 注入的 key 在所有祖先组件中都没有提供的诊断信息
+    ///
+    /// 存在默认值或组件未被其他组件注册时不检查，
+    /// key 为变量或 Symbol，或者没有任何组件提供该 key 时，可能由根实例或插件全局提供，同样不检查
+    pub fn get_inject_diagnostics(&self, uri: &Uri) -> Vec<Diagnostic> {
+        let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get(uri) else {
+            return vec![];
+        };
+        let injects = cache
+            .props
+            .iter()
+            .filter_map(|prop| Some((prop, prop.inject_params.as_ref()?)))
+            .filter(|(_, params)| !params.default && params.is_literal_key)
+            .collect::<Vec<_>>();
+        if injects.is_empty() {
+            return vec![];
+        }
+        let ancestors = self.get_ancestor_uris(uri);
+        if ancestors.is_empty() {
+            return vec![];
+        }
+        injects
+            .into_iter()
+            .filter_map(|(prop, params)| {
+                let key = &params.from;
+                if !self.is_key_provided_by_any_component(key)
+                    || ancestors
+                        .iter()
+                        .any(|ancestor| !self.get_provided_key_locations(ancestor, key).is_empty())
+                {
+                    return None;
+                }
+                Some(Diagnostic {
+                    range: Range::new(
+                        cache.document.position_at(prop.range.0 as u32),
+                        cache.document.position_at(prop.range.1 as u32),
+                    ),
+                    severity: Some(DiagnosticSeverity::HINT),
+                    source: Some("vue".to_string()),
+                    message: format!(
+                        "Injection '{}' is not provided by any parent component.",
+                        key
+                    ),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
@@ -1,5 +1,6 @@
-#[cfg(target_os = "windows")]
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use lsp_textdocument::FullTextDocument;
 use tokio::{
@@ -15,7 +16,8 @@ use tower_lsp::{
 };
 #[cfg(target_os = "windows")]
 use tower_lsp::{NotCancellable, OngoingProgress, Unbounded};
-use tracing::{error, warn};
+use tracing::info;
+use tracing::{debug, error, warn};
 use walkdir::WalkDir;
 
 use crate::util;
@@ -32,6 +34,9 @@ use super::{
     Renderer,
 };
 
+/// 超过最大大小的 vue 文件的渲染结果
+const OVERSIZED_PLACEHOLDER: &str = "import Vue from 'vue';\nexport default Vue;\n";
+
 impl Renderer {
     /// 创建渲染目录，并进行渲染
     pub async fn init(&mut self, root_uri: &Uri, client: &Client, work_done_token: ProgressToken) {
@@ -45,11 +50,13 @@ impl Renderer {
         target_root_path.pop();
         let project_name = src_path.file_name().unwrap().to_str().unwrap();
         target_root_path.push(format!(".~${}", project_name));
+        // 增量渲染时保留已存在的目标目录，只重新渲染修改过的文件
+        let incremental = self.incremental_render && target_root_path.exists();
         // windows 下，如果目标目录已经存在，那么跳过删除和重新复制 node_modules
         #[cfg(target_os = "windows")]
         let skip = target_root_path.exists();
         #[cfg(not(target_os = "windows"))]
-        if target_root_path.exists() {
+        if target_root_path.exists() && !incremental {
             fs::remove_dir_all(&target_root_path).await.unwrap();
         }
         #[cfg(target_os = "windows")]
@@ -69,14 +76,19 @@ impl Renderer {
             .set((root_uri.clone(), target_root_uri.clone()))
             .unwrap();
         progress.report("Initializing...").await;
-        self.render(root_uri, &target_root_uri).await;
+        self.render(root_uri, &target_root_uri, incremental).await;
 
         // 创建 node_modules 的链接
         if node_modules_src_path.exists() {
             #[cfg(not(target_os = "windows"))]
-            fs::symlink(&node_modules_src_path, &node_modules_target_path)
+            if fs::symlink_metadata(&node_modules_target_path)
                 .await
-                .unwrap();
+                .is_err()
+            {
+                fs::symlink(&node_modules_src_path, &node_modules_target_path)
+                    .await
+                    .unwrap();
+            }
             #[cfg(target_os = "windows")]
             if !skip {
                 async fn copy_dir(
@@ -123,9 +135,42 @@ impl Renderer {
                     }
                     Ok(())
                 }
-                copy_dir(&node_modules_src_path, &node_modules_target_path, &progress)
-                    .await
-                    .unwrap();
+                /// 创建目录联接，不需要管理员权限
+                async fn create_junction(src: &PathBuf, dst: &PathBuf) -> std::io::Result<()> {
+                    let output = tokio::process::Command::new("cmd")
+                        .arg("/C")
+                        .arg("mklink")
+                        .arg("/J")
+                        .arg(dst)
+                        .arg(src)
+                        .output()
+                        .await?;
+                    if output.status.success() {
+                        Ok(())
+                    } else {
+                        Err(std::io::Error::new(
+                            std::io::ErrorKind::Other,
+                            String::from_utf8_lossy(&output.stderr).to_string(),
+                        ))
+                    }
+                }
+                let start_time = std::time::Instant::now();
+                // 优先创建目录联接，失败时回退到复制
+                match create_junction(&node_modules_src_path, &node_modules_target_path).await {
+                    Ok(_) => {
+                        info!(
+                            "node_modules junction created in {:?}, copy skipped",
+                            start_time.elapsed()
+                        );
+                    }
+                    Err(e) => {
+                        warn!("Failed to create node_modules junction: {}", e);
+                        copy_dir(&node_modules_src_path, &node_modules_target_path, &progress)
+                            .await
+                            .unwrap();
+                        info!("node_modules copied in {:?}", start_time.elapsed());
+                    }
+                }
             }
         }
         progress.finish().await;
@@ -136,27 +181,37 @@ impl Renderer {
     /// * 更新继承关系
     /// * 更新注册关系
     /// * 更新继承自当前文件的文件
-    pub fn update(
+    pub async fn update(
         &mut self,
         uri: &Uri,
         params: DidChangeTextDocumentParams,
         document: &FullTextDocument,
     ) -> DidChangeTextDocumentParams {
         let mut content_changes = vec![];
+        let old_slots = self.get_slots(uri);
+        let mut is_slot_props_change = false;
         for change in &params.content_changes {
             let cache = self.render_cache.get_mut(uri).unwrap();
-            let result = cache.update(change.clone());
+            let result = cache.update(change.clone(), &self.component_decorators);
+            // 属性的位置可能发生变化，继承自当前文件的组件同样需要重新展开
+            self.render_cache.clear_node_effective_props(uri);
             if let Some(mut result) = result {
                 // 更新影响的组件的版本
                 if result.is_change {
                     self.render_cache.update_incoming_node_version(uri);
                 }
-                // 更新继承关系
+                // 更新继承关系，混入同样以继承边表示
+                if result.extends_component.is_some() || result.mixins.is_some() {
+                    self.render_cache.remove_extends_edges(uri);
+                }
                 if let Some(extends_component) = result.extends_component {
-                    self.render_cache.remove_extends_edge(uri);
                     self.create_extends_relation(uri, extends_component);
                 }
+                if let Some(mixins) = result.mixins {
+                    self.create_mixins_relation(uri, mixins);
+                }
                 // 更新注册关系
+                let is_registers_change = result.registers.is_some();
                 if let Some(registers) = result.registers {
                     self.render_cache.remove_registers_edges(uri);
                     self.create_registers_relation(uri, registers);
@@ -167,9 +222,19 @@ impl Renderer {
                     self.create_transfers_relation(uri, transfers);
                 }
                 content_changes.append(&mut result.changes);
-                self.render_cache.flush();
+                self.flush().await;
+                // 注册的子组件变化时重新编译模版
+                if is_registers_change && self.update_slot_props(uri) {
+                    is_slot_props_change = true;
+                }
             } else {
                 // 重新解析节点
+                let old_component = match self.render_cache.get(uri) {
+                    Some(RenderCache::VueRenderCache(cache)) => {
+                        Some((cache.props.clone(), cache.description.clone()))
+                    }
+                    _ => None,
+                };
                 self.render_cache.remove_outgoing_edge(uri);
                 self.create_node_from_document(
                     uri,
@@ -183,7 +248,23 @@ impl Renderer {
                         document.get_content(None).to_string(),
                     ),
                 );
-                self.render_cache.flush();
+                self.flush().await;
+                self.update_slot_props(uri);
+                self.update_register_parents_slot_props(uri);
+                // 属性或描述变更时更新影响的组件的版本
+                if let (Some((props, description)), Some(RenderCache::VueRenderCache(cache))) =
+                    (old_component, self.render_cache.get(uri))
+                {
+                    if props.len() != cache.props.len()
+                        || props
+                            .iter()
+                            .zip(&cache.props)
+                            .any(|(old, new)| !old.is_equal_exclude_range(new))
+                        || description != cache.description
+                    {
+                        self.render_cache.update_incoming_node_version(uri);
+                    }
+                }
                 if let Some(content) = self.render_cache.get_node_render_content(uri) {
                     return DidChangeTextDocumentParams {
                         text_document: params.text_document,
@@ -198,6 +279,22 @@ impl Renderer {
                 }
             }
         }
+        if self.get_slots(uri) != old_slots {
+            self.update_register_parents_slot_props(uri);
+        }
+        // 模版重新编译后增量修改不再适用，全量更新
+        if is_slot_props_change {
+            if let Some(content) = self.render_cache.get_node_render_content(uri) {
+                return DidChangeTextDocumentParams {
+                    text_document: params.text_document,
+                    content_changes: vec![TextDocumentContentChangeEvent {
+                        range: None,
+                        range_length: None,
+                        text: content,
+                    }],
+                };
+            }
+        }
         DidChangeTextDocumentParams {
             text_document: params.text_document,
             content_changes,
@@ -205,12 +302,50 @@ impl Renderer {
     }
 
     /// 保存 vue 节点，重新全量渲染，返回变更内容
+    ///
+    /// 超过最大大小而未解析的文件重新检查大小，恢复到限制以内时重新创建节点
     pub async fn save(&mut self, uri: &Uri) -> Option<DidChangeTextDocumentParams> {
+        if self.oversized_files.contains(uri) {
+            self.recreate_oversized_node(uri).await;
+            return None;
+        }
         // 保存前再次全量解析 vue 节点为 update 出错提供修复机会
         let version = self.render_cache.get(uri)?.get_version()?;
         self.render_cache.remove_outgoing_edge(uri);
         self.create_node(uri).await;
-        self.render_cache.flush();
+        self.full_render(uri, version).await
+    }
+
+    /// 使用编辑器中的文档重新全量解析并渲染 vue 节点，返回变更内容
+    ///
+    /// 用于增量更新出现不同步时手动恢复
+    pub async fn rerender(
+        &mut self,
+        uri: &Uri,
+        document: &FullTextDocument,
+    ) -> Option<DidChangeTextDocumentParams> {
+        let version = self.render_cache.get(uri)?.get_version()?;
+        self.render_cache.remove_outgoing_edge(uri);
+        self.create_node_from_document(
+            uri,
+            FullTextDocument::new(
+                document.language_id().to_string(),
+                version,
+                document.get_content(None).to_string(),
+            ),
+        );
+        self.full_render(uri, version).await
+    }
+
+    /// 全量解析节点后，更新版本并渲染，返回变更内容
+    async fn full_render(
+        &mut self,
+        uri: &Uri,
+        version: i32,
+    ) -> Option<DidChangeTextDocumentParams> {
+        self.flush().await;
+        self.update_slot_props(uri);
+        self.update_register_parents_slot_props(uri);
         self.render_cache
             .get_mut(uri)
             .unwrap()
@@ -233,10 +368,11 @@ impl Renderer {
             None
         };
 
-        if !cfg!(test) {
-            let (root_uri, target_root_uri) = self.root_uri_target_uri.get().unwrap();
-            self.render_cache
-                .render_node(uri, root_uri, target_root_uri);
+        if let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get() {
+            if !cfg!(test) {
+                self.render_cache
+                    .render_node(uri, root_uri, target_root_uri);
+            }
         }
 
         change
@@ -248,11 +384,18 @@ impl Renderer {
     }
 
     /// 文件打开时检查节点是否存在，如果节点不存在，那么先创建节点
+    ///
+    /// 增量渲染时尚未在后台创建节点的文件在此时创建节点并重新渲染
     pub async fn did_open(&mut self, uri: &Uri) {
-        if self.render_cache.get(uri).is_none() {
-            let (root_uri, target_root_uri) = self.root_uri_target_uri.get().unwrap().clone();
+        // 初始化完成前打开的文件在初始化时创建节点
+        let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get().cloned() else {
+            return;
+        };
+        if self.render_cache.get(uri).is_none() && !self.is_render_excluded(uri) {
+            self.lazy_files.remove(uri);
             self.create_node(uri).await;
-            self.render_cache.flush();
+            self.flush().await;
+            self.update_slot_props(uri);
             self.render_cache
                 .render_node(uri, &root_uri, &target_root_uri);
         }
@@ -260,32 +403,46 @@ impl Renderer {
 
     pub fn will_create_files(&mut self, uris: Vec<Uri>) {
         for uri in uris {
-            if Renderer::is_uri_valid(&uri) {
+            if Renderer::is_uri_valid(&uri) && !self.is_render_excluded(&uri) {
                 self.will_create_files.insert(uri);
             }
         }
     }
 
     pub async fn did_create_files(&mut self, uris: Vec<Uri>) {
-        let (root_uri, target_root_uri) = self.root_uri_target_uri.get().unwrap().clone();
+        let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get().cloned() else {
+            return;
+        };
         for uri in uris {
-            if Renderer::is_uri_valid(&uri) {
+            if Renderer::is_uri_valid(&uri) && !self.is_render_excluded(&uri) {
                 self.create_node(&uri).await;
                 self.render_cache
                     .render_node(&uri, &root_uri, &target_root_uri);
                 self.will_create_files.remove(&uri);
             }
         }
-        self.render_cache.flush();
+        self.flush().await;
     }
 
     pub fn did_delete_files(&mut self, uris: Vec<Uri>) {
-        let (root_uri, target_root_uri) = self.root_uri_target_uri.get().unwrap().clone();
+        let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get().cloned() else {
+            return;
+        };
         for uri in uris {
             if self.render_cache.get(&uri).is_some() {
                 self.render_cache.update_incoming_node_version(&uri);
                 self.render_cache
                     .remove_node(&uri, &root_uri, &target_root_uri);
+            } else if self.lazy_files.remove(&uri) {
+                // 未创建节点的文件只需要删除渲染结果，此时不知道脚本语言，两者都删除
+                let target_path =
+                    Renderer::get_target_path(&uri, &root_uri, &target_root_uri, false);
+                let js_target_path =
+                    Renderer::get_target_path(&uri, &root_uri, &target_root_uri, true);
+                tokio::spawn(async {
+                    let _ = fs::remove_file(target_path).await;
+                    let _ = fs::remove_file(js_target_path).await;
+                });
             }
         }
     }
@@ -295,7 +452,9 @@ impl Renderer {
         client: &Client,
         work_done_token: ProgressToken,
     ) {
-        let (root_uri, target_root_uri) = self.root_uri_target_uri.get().unwrap().clone();
+        let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get().cloned() else {
+            return;
+        };
         let target_root_path = util::to_file_path(&target_root_uri);
         fs::remove_dir_all(&target_root_path).await.unwrap();
         self.init(&root_uri, client, work_done_token).await;
@@ -313,6 +472,7 @@ impl Renderer {
                     let mut content = String::new();
                     file.read_to_string(&mut content).await.unwrap();
                     self.alias = parse_import_path::parse_alias(&content, root_uri);
+                    self.base_url = parse_import_path::parse_base_url(&content, root_uri);
                 }
                 Err(err) => {
                     error!("Read tsconfig.json error: {}", err);
@@ -324,8 +484,12 @@ impl Renderer {
 
     /// 读取目录下的文件，并渲染到目标目录
     /// 同时构建组件间关系图
-    async fn render(&mut self, root_uri: &Uri, target_root_uri: &Uri) {
+    ///
+    /// 增量渲染时，渲染结果比源文件新的文件不会被读取，在首次使用时再创建节点
+    async fn render(&mut self, root_uri: &Uri, target_root_uri: &Uri, incremental: bool) {
         let root_path = util::to_file_path(root_uri);
+        let render_exclude = self.render_exclude.clone();
+        self.lazy_files.clear();
         // 遍历目录
         for entry in WalkDir::new(root_path.clone())
             .follow_links(true)
@@ -335,12 +499,15 @@ impl Renderer {
                     .to_str()
                     .map(|s| s.starts_with(".git") || s == "node_modules")
                     .unwrap_or(false)
+                    && !render_exclude.as_ref().is_some_and(|render_exclude| {
+                        Renderer::is_path_excluded(render_exclude, &root_path, e.path())
+                    })
             })
         {
             if let Ok(entry) = entry {
                 let src_path = entry.path();
                 let uri = util::create_uri_from_path(src_path);
-                let target_path = Renderer::get_target_path(&uri, root_uri, target_root_uri);
+                let target_path = Renderer::get_target_path(&uri, root_uri, target_root_uri, false);
 
                 // 如果父目录不存在，先创建父目录
                 if let Some(parent) = target_path.parent() {
@@ -349,6 +516,29 @@ impl Renderer {
                     }
                 }
                 if src_path.is_file() {
+                    // vue 文件的渲染结果可能为 `.vue.ts` 或 `.vue.js`
+                    if incremental
+                        && (Renderer::is_target_up_to_date(src_path, &target_path).await
+                            || (Renderer::is_vue_component(&uri)
+                                && Renderer::is_target_up_to_date(
+                                    src_path,
+                                    &Renderer::get_target_path(
+                                        &uri,
+                                        root_uri,
+                                        target_root_uri,
+                                        true,
+                                    ),
+                                )
+                                .await))
+                    {
+                        if src_path
+                            .extension()
+                            .is_some_and(|v| v == "vue" || v == "ts")
+                        {
+                            self.lazy_files.insert(uri);
+                        }
+                        continue;
+                    }
                     if src_path.extension().is_some_and(|v| v == "vue") {
                         // 创建 vue 节点
                         self.create_node(&uri).await;
@@ -369,21 +559,290 @@ impl Renderer {
                 warn!("walk error: {:?}", entry.unwrap_err());
             }
         }
+        if incremental {
+            let target_root_path = util::to_file_path(target_root_uri);
+            let _ = tokio::task::spawn_blocking(move || {
+                Renderer::remove_stale_targets(&root_path, &target_root_path)
+            })
+            .await;
+            info!(
+                "incremental render: {} files unchanged",
+                self.lazy_files.len()
+            );
+        }
         // 创建组件库节点
         let library_list = self.library_list.clone();
         for lib_node in &library_list {
             self.create_lib_node(lib_node);
         }
-        self.render_cache.flush();
+        self.flush().await;
+        for uri in self.render_cache.get_vue_uris() {
+            self.update_slot_props(&uri);
+        }
         self.render_cache.render(root_uri, target_root_uri);
     }
 
+    /// 目标文件是否不早于源文件，硬链接与源文件的修改时间相同
+    async fn is_target_up_to_date(src_path: &Path, target_path: &Path) -> bool {
+        let src = fs::metadata(src_path).await.and_then(|v| v.modified()).ok();
+        let target = fs::metadata(target_path)
+            .await
+            .and_then(|v| v.modified())
+            .ok();
+        match (src, target) {
+            (Some(src), Some(target)) => target >= src,
+            _ => false,
+        }
+    }
+
+    /// 删除源文件已不存在的目标文件
+    fn remove_stale_targets(root_path: &Path, target_root_path: &Path) {
+        for entry in WalkDir::new(target_root_path)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != "node_modules")
+            .flatten()
+        {
+            let target_path = entry.path();
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(rel_path) = target_path.strip_prefix(target_root_path) else {
+                continue;
+            };
+            let src_path = root_path.join(rel_path);
+            // vue 文件的渲染结果增加了 `.ts` 或 `.js` 后缀
+            let vue_src_path = src_path
+                .to_str()
+                .and_then(|v| v.strip_suffix(".vue.ts").or(v.strip_suffix(".vue.js")))
+                .map(|v| PathBuf::from(format!("{}.vue", v)));
+            if !src_path.exists() && !vue_src_path.is_some_and(|v| v.exists()) {
+                if let Err(err) = std::fs::remove_file(target_path) {
+                    warn!("remove stale target error: {}", err);
+                }
+            }
+        }
+    }
+
+    /// 将虚拟边加入关系图，边指向尚未创建节点的文件时先创建节点
+    ///
+    /// 新加载的组件库扩展了全局属性时，重新渲染所有 vue 节点
+    async fn flush(&mut self) {
+        let mut lazy_uris = vec![];
+        loop {
+            let uris = self
+                .render_cache
+                .get_missing_edge_targets()
+                .into_iter()
+                .filter(|uri| self.lazy_files.remove(uri))
+                .collect::<Vec<_>>();
+            if uris.is_empty() {
+                break;
+            }
+            for uri in uris {
+                self.create_lazy_node(&uri).await;
+                lazy_uris.push(uri);
+            }
+        }
+        let has_global_properties = self.create_missing_lib_nodes();
+        self.render_cache.flush();
+        self.render_lazy_nodes(&lazy_uris).await;
+        if has_global_properties {
+            if let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get() {
+                self.render_cache.render(root_uri, target_root_uri);
+            }
+        }
+    }
+
+    /// 创建注册后尚未创建节点的组件库，返回新的组件库是否扩展了全局属性
+    fn create_missing_lib_nodes(&mut self) -> bool {
+        let mut has_global_properties = false;
+        for uri in self.library_list.clone() {
+            if self.render_cache.get(&uri).is_some() {
+                continue;
+            }
+            self.create_lib_node(&uri);
+            has_global_properties |= matches!(
+                self.render_cache.get(&uri),
+                Some(RenderCache::LibRenderCache(cache)) if !cache.global_properties.is_empty()
+            );
+        }
+        has_global_properties
+    }
+
+    /// 根据注册的子组件更新 vue 节点模版编译使用的作用域插槽属性，返回是否重新编译了模版
+    ///
+    /// 需要在关系图刷新后调用
+    pub(super) fn update_slot_props(&mut self, uri: &Uri) -> bool {
+        let slot_props = self.get_component_slot_props(uri);
+        if let Some(RenderCache::VueRenderCache(cache)) = self.render_cache.get_mut(uri) {
+            cache.set_slot_props(slot_props)
+        } else {
+            false
+        }
+    }
+
+    /// 子组件的插槽变化后，更新注册了它的父组件的作用域插槽属性，重新编译的父组件更新版本并重新渲染
+    fn update_register_parents_slot_props(&mut self, uri: &Uri) {
+        let parents = self
+            .render_cache
+            .get_register_parents(uri)
+            .into_iter()
+            .map(|(uri, _)| uri.clone())
+            .collect::<HashSet<_>>();
+        for parent in parents {
+            if !self.update_slot_props(&parent) {
+                continue;
+            }
+            let cache = self.render_cache.get_mut(&parent).unwrap();
+            if let Some(version) = cache.get_version() {
+                cache.update_version(version + 1);
+            }
+            if let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get() {
+                self.render_cache
+                    .render_node(&parent, root_uri, target_root_uri);
+            }
+        }
+    }
+
+    /// 文件是否超过最大大小，单位为 KB，为 0 时不限制
+    async fn is_file_oversized(path: &Path, max_file_size: u64) -> bool {
+        max_file_size > 0
+            && fs::metadata(path)
+                .await
+                .is_ok_and(|v| v.len() > max_file_size * 1024)
+    }
+
+    /// 为超过最大大小的文件创建不解析内容的节点
+    ///
+    /// 节点视为不包含组件的 ts 文件，使指向它的边可以加入关系图，
+    /// vue 文件在目标目录中渲染为默认导出 Vue 的占位文件，使导入仍然可以解析
+    async fn create_oversized_node(&mut self, uri: &Uri) {
+        info!("skip parsing oversized file: {}", uri.path());
+        self.oversized_files.insert(uri.clone());
+        if Renderer::is_vue_component(uri) {
+            if let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get() {
+                let target_path = Renderer::get_target_path(uri, root_uri, target_root_uri, false);
+                if let Err(err) = fs::write(&target_path, OVERSIZED_PLACEHOLDER).await {
+                    error!("error: {} - {}", target_path.to_string_lossy(), err);
+                }
+            }
+        }
+        self.create_ts_node(
+            uri,
+            FullTextDocument::new("typescript".to_string(), 1, String::new()),
+        );
+    }
+
+    /// 超过最大大小的文件恢复到限制以内时，重新创建节点并渲染
+    async fn recreate_oversized_node(&mut self, uri: &Uri) {
+        if Renderer::is_file_oversized(&util::to_file_path(uri), self.max_file_size).await {
+            return;
+        }
+        info!("recreate file within size limit: {}", uri.path());
+        self.render_cache.remove_outgoing_edge(uri);
+        self.create_node(uri).await;
+        self.flush().await;
+        self.update_slot_props(uri);
+        self.render_cache.update_incoming_node_version(uri);
+        if let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get() {
+            self.render_cache
+                .render_node(uri, root_uri, target_root_uri);
+        }
+    }
+
+    /// 为增量渲染时未修改的文件分批创建节点，返回是否仍有未创建节点的文件
+    ///
+    /// 未创建节点的文件中的注册、继承等关系不在关系图中，
+    /// 查找注册了当前组件的父组件、继承当前组件的子组件等反向关系时会遗漏，因此初始化后在后台全部创建
+    pub async fn materialize_lazy_files(&mut self, count: usize) -> bool {
+        let uris = self
+            .lazy_files
+            .iter()
+            .take(count)
+            .cloned()
+            .collect::<Vec<_>>();
+        for uri in &uris {
+            self.lazy_files.remove(uri);
+            self.create_lazy_node(uri).await;
+        }
+        self.flush().await;
+        self.render_lazy_nodes(&uris).await;
+        !self.lazy_files.is_empty()
+    }
+
+    /// 为增量渲染时未修改的文件创建节点
+    async fn create_lazy_node(&mut self, uri: &Uri) {
+        let path = util::to_file_path(uri);
+        if Renderer::is_file_oversized(&path, self.max_file_size).await {
+            self.create_oversized_node(uri).await;
+            return;
+        }
+        match fs::read_to_string(&path).await {
+            Ok(content) => {
+                let language_id = path
+                    .extension()
+                    .map(|v| v.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                self.create_node_from_document(uri, FullTextDocument::new(language_id, 1, content));
+            }
+            Err(err) => {
+                error!("error: {} - {}", path.to_string_lossy(), err);
+            }
+        }
+    }
+
+    /// 重新渲染增量渲染时创建的 vue 节点
+    ///
+    /// 渲染结果还依赖继承的组件、全局属性等，它们修改后未修改文件的渲染结果已过期，内容变化时重新写入
+    async fn render_lazy_nodes(&mut self, uris: &[Uri]) {
+        for uri in uris {
+            self.update_slot_props(uri);
+        }
+        let Some((root_uri, target_root_uri)) = self.root_uri_target_uri.get() else {
+            return;
+        };
+        for uri in uris {
+            let Some(cache @ RenderCache::VueRenderCache(_)) = self.render_cache.get(uri) else {
+                continue;
+            };
+            let target_path =
+                Renderer::get_target_path(uri, root_uri, target_root_uri, cache.is_js());
+            let Some(content) = self.render_cache.get_node_render_content(uri) else {
+                continue;
+            };
+            if fs::read_to_string(&target_path)
+                .await
+                .is_ok_and(|v| v == content)
+            {
+                continue;
+            }
+            debug!("render lazy node: {}", target_path.to_string_lossy());
+            if let Err(err) = fs::write(&target_path, content).await {
+                error!("error: {} - {}", target_path.to_string_lossy(), err);
+            }
+        }
+    }
+
     /// 创建节点及相关的边
     /// * 如果是 vue 文件，那么创建 vue 节点
     /// * 如果是 ts 文件，那么创建 ts 节点
     /// * 如果都不是或者创建失败，那么创建 Unknown 节点
     async fn create_node(&mut self, uri: &Uri) {
-        let document = Renderer::get_document_from_file(uri).await.unwrap();
+        if Renderer::is_file_oversized(&util::to_file_path(uri), self.max_file_size).await {
+            self.create_oversized_node(uri).await;
+            return;
+        }
+        self.oversized_files.remove(uri);
+        let document = match Renderer::get_document_from_file(uri).await {
+            Ok(document) => document,
+            // 读取失败时创建空的节点，保证后续渲染时节点存在
+            Err(_) => {
+                let language_id = util::to_file_path(uri)
+                    .extension()
+                    .map_or(String::new(), |v| v.to_string_lossy().to_string());
+                FullTextDocument::new(language_id, 1, String::new())
+            }
+        };
         if Renderer::is_vue_component(uri) {
             self.create_vue_node(uri, document);
         } else {
@@ -404,7 +863,7 @@ impl Renderer {
     /// * 如果存在继承关系，那么创建继承边
     /// * 如果存在注册关系，那么创建注册边
     fn create_vue_node(&mut self, uri: &Uri, document: FullTextDocument) {
-        let result = vue_render_cache::parse_vue_file(&document);
+        let result = vue_render_cache::parse_vue_file(&document, &self.component_decorators);
         self.render_cache.add_node(
             uri,
             RenderCache::VueRenderCache(VueRenderCache {
@@ -412,20 +871,40 @@ impl Renderer {
                 template: result.template,
                 script: result.script,
                 style: result.style,
+                custom_blocks: result.custom_blocks,
+                style_modules: result.style_modules,
+                functional: result.functional,
                 name_range: result.name_range,
                 description: result.description,
                 props: result.props,
                 render_insert_offset: result.render_insert_offset,
+                class_body_start: result.class_body_start,
+                class_nested_ranges: result.class_nested_ranges,
+                extends_clause: result.extends_component.clone().zip(result.extends_range),
+                options_mixins: result.options_mixins,
+                class_declaration: result.class_declaration,
+                script_syntax_error: result.script_syntax_error,
+                local_type_names: result.local_type_names,
+                reserved_members: result.reserved_members,
+                import_paths: result.import_paths,
+                component_imports: result.component_imports,
+                decorator_option_keys: result.decorator_option_keys,
+                provide_keys: result.provide_keys,
+                inject_keys: result.inject_keys,
+                options_model: result.options_model,
                 template_compile_result: FullTextDocument::new(
                     "typescript".to_string(),
                     0,
                     result.template_compile_result,
                 ),
                 mapping: result.mapping,
+                slot_props: SlotProps::new(),
                 safe_update_range: result.safe_update_range,
+                effective_props: OnceLock::new(),
             }),
         );
         self.create_extends_relation(uri, result.extends_component);
+        self.create_mixins_relation(uri, result.mixins);
         self.create_registers_relation(uri, result.registers);
     }
 
@@ -434,9 +913,9 @@ impl Renderer {
     /// * 如果存在组件并且存在注册关系，那么创建注册边
     /// * 创建节点间中转关系
     fn create_ts_node(&mut self, uri: &Uri, document: FullTextDocument) {
-        let result = ts_render_cache::parse_ts_file(&document);
+        let result = ts_render_cache::parse_ts_file(&document, &self.component_decorators);
         let mut ts_component = None;
-        if let Some((name_range, description, props, extends_component, registers)) =
+        if let Some((name_range, description, props, extends_component, mixins, registers)) =
             result.ts_component
         {
             ts_component = Some(TsComponent {
@@ -445,6 +924,7 @@ impl Renderer {
                 props,
             });
             self.create_extends_relation(uri, extends_component);
+            self.create_mixins_relation(uri, mixins);
             self.create_registers_relation(uri, registers);
         };
         self.render_cache.add_node(
@@ -453,6 +933,7 @@ impl Renderer {
                 document,
                 ts_component,
                 local_exports: result.local_exports,
+                effective_props: OnceLock::new(),
             }),
         );
         self.create_transfers_relation(uri, result.transfers);
@@ -485,6 +966,13 @@ impl Renderer {
         }
     }
 
+    /// 创建混入关系，每个混入组件都创建一条继承边
+    fn create_mixins_relation(&mut self, uri: &Uri, mixins: Vec<ExtendsComponent>) {
+        for mixin in mixins {
+            self.create_extends_relation(uri, Some(mixin));
+        }
+    }
+
     /// 创建注册关系
     fn create_registers_relation(&mut self, uri: &Uri, registers: Vec<RegisterComponent>) {
         for register in registers {
@@ -537,50 +1025,23 @@ impl Renderer {
     }
 
     /// 从导入路径获取 uri，如果对应的文件不存在，返回 None
-    #[cfg(not(test))]
-    fn get_uri_from_path(&self, base_uri: &Uri, path: &str) -> Option<Uri> {
+    pub(super) fn get_uri_from_path(&self, base_uri: &Uri, path: &str) -> Option<Uri> {
         let file_path = parse_import_path::parse_import_path(
             base_uri,
             path,
             &self.alias,
-            &self.root_uri_target_uri.get().unwrap().0,
+            self.base_url.as_deref(),
+            &self.root_uri_target_uri.get()?.0,
         );
         if file_path.is_dir() && file_path.to_string_lossy().contains("/node_modules/") {
             return Some(util::create_uri_from_path(&file_path));
         }
 
-        // 如果文件不存在，那么尝试添加后缀
-        if !file_path.is_file() {
-            if let Some(file_name) = file_path.file_name() {
-                let suffix_list = [".d.ts", ".ts"];
-                for suffix in suffix_list {
-                    let new_file_name = format!("{}{}", file_name.to_str().unwrap(), suffix);
-                    let new_file_path = file_path.with_file_name(new_file_name);
-                    if new_file_path.is_file() {
-                        return Some(util::create_uri_from_path(&new_file_path));
-                    }
-                }
-            }
-            let new_file_path = file_path.join("index.ts");
-            if new_file_path.is_file() {
-                return Some(util::create_uri_from_path(&new_file_path));
-            }
-            None
-        } else {
-            Some(util::create_uri_from_path(&file_path))
-        }
-    }
-
-    #[cfg(test)]
-    /// 从导入路径获取 uri，如果对应的文件不存在，返回 None
-    fn get_uri_from_path(&self, base_uri: &Uri, path: &str) -> Option<Uri> {
-        let file_path = parse_import_path::parse_import_path(
-            base_uri,
-            path,
-            &self.alias,
-            &self.root_uri_target_uri.get().unwrap().0,
-        );
-        Some(util::create_uri_from_path(&file_path))
+        let resolved = parse_import_path::resolve_file_path(&file_path);
+        // 测试中的节点大多不存在于文件系统，直接使用导入路径
+        #[cfg(test)]
+        let resolved = resolved.or(Some(file_path));
+        resolved.map(|file_path| util::create_uri_from_path(&file_path))
     }
 }
 
@@ -595,8 +1056,10 @@ mod tests {
     use tower_lsp::lsp_types::{Location, Position, Range, Uri};
 
     use crate::{
+        ast,
         renderer::{
             render_cache::{RenderCache, RenderCacheGraph},
+            tests::add_vue_document,
             Renderer,
         },
         util,
@@ -623,10 +1086,17 @@ mod tests {
                 Uri::from_str("file:///path/.~$project").unwrap(),
             )),
             alias: HashMap::new(),
+            base_url: None,
             render_cache: cache_graph,
             provider_map: HashMap::new(),
             library_list: vec![],
             will_create_files: HashSet::new(),
+            render_exclude: None,
+            incremental_render: false,
+            lazy_files: HashSet::new(),
+            max_file_size: 0,
+            oversized_files: HashSet::new(),
+            component_decorators: ast::get_default_component_decorators(),
         };
         renderer.create_lib_node(&lib_uri);
         let lib_cache = renderer.render_cache.get(&lib_uri).unwrap();
@@ -656,4 +1126,225 @@ mod tests {
             )
         }
     }
+
+    #[tokio::test]
+    async fn lazy_node() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let root_path = root_dir.path();
+        std::fs::write(
+            root_path.join("Child.vue"),
+            [
+                "<template><div></div></template>",
+                "<script lang=\"ts\">",
+                "import { Component, Prop, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Child extends Vue {",
+                "  @Prop() title!: string;",
+                "}",
+                "</script>",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        // 渲染结果未过期，但依赖的组件已修改
+        let target_root_path = root_path.join(".~target");
+        std::fs::create_dir_all(&target_root_path).unwrap();
+        std::fs::write(target_root_path.join("Child.vue.ts"), "stale").unwrap();
+        let root_uri = util::create_uri_from_path(root_path);
+        let child_uri = util::create_uri_from_path(&root_path.join("Child.vue"));
+        let index_uri = util::create_uri_from_path(&root_path.join("index.vue"));
+        let mut renderer = Renderer {
+            root_uri_target_uri: OnceCell::from((
+                root_uri,
+                util::create_uri_from_path(&target_root_path),
+            )),
+            alias: HashMap::new(),
+            base_url: None,
+            render_cache: RenderCacheGraph::new(),
+            provider_map: HashMap::new(),
+            library_list: vec![],
+            will_create_files: HashSet::new(),
+            render_exclude: None,
+            incremental_render: true,
+            lazy_files: HashSet::from([child_uri.clone()]),
+            max_file_size: 0,
+            oversized_files: HashSet::new(),
+            component_decorators: ast::get_default_component_decorators(),
+        };
+        add_vue_document(
+            &mut renderer,
+            &index_uri,
+            &[
+                "<template><Child title=\"a\" /></template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import Child from './Child.vue';",
+                "@Component({ components: { Child } })",
+                "export default class Index extends Vue {}",
+                "</script>",
+            ],
+        );
+        // 注册的组件尚未创建节点，加入关系图前先创建
+        renderer.flush().await;
+        assert_eq!(renderer.get_lazy_file_count(), 0);
+        let child = renderer.render_cache.get(&child_uri).unwrap();
+        if let RenderCache::VueRenderCache(child) = child {
+            assert_eq!(child.props[0].name, "title");
+        } else {
+            panic!("Child.vue should be a vue node");
+        }
+        assert_eq!(renderer.render_cache.get_stats().register_edges, 1);
+        // 创建节点后重新渲染
+        assert_eq!(
+            std::fs::read_to_string(target_root_path.join("Child.vue.ts")).ok(),
+            renderer.render_cache.get_node_render_content(&child_uri)
+        );
+    }
+
+    #[tokio::test]
+    async fn materialize_lazy_files() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let root_path = root_dir.path();
+        std::fs::write(
+            root_path.join("index.vue"),
+            [
+                "<template><Child /></template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import Child from './Child.vue';",
+                "@Component({ components: { Child } })",
+                "export default class Index extends Vue {}",
+                "</script>",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        let root_uri = util::create_uri_from_path(root_path);
+        let child_uri = util::create_uri_from_path(&root_path.join("Child.vue"));
+        let index_uri = util::create_uri_from_path(&root_path.join("index.vue"));
+        let mut renderer = Renderer {
+            root_uri_target_uri: OnceCell::from((
+                root_uri,
+                Uri::from_str("file:///path/.~$project").unwrap(),
+            )),
+            alias: HashMap::new(),
+            base_url: None,
+            render_cache: RenderCacheGraph::new(),
+            provider_map: HashMap::new(),
+            library_list: vec![],
+            will_create_files: HashSet::new(),
+            render_exclude: None,
+            incremental_render: true,
+            lazy_files: HashSet::from([index_uri.clone()]),
+            max_file_size: 0,
+            oversized_files: HashSet::new(),
+            component_decorators: ast::get_default_component_decorators(),
+        };
+        add_vue_document(
+            &mut renderer,
+            &child_uri,
+            &[
+                "<template><div></div></template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Child extends Vue {}",
+                "</script>",
+            ],
+        );
+        renderer.flush().await;
+        // 未创建节点的文件中的注册关系不在关系图中
+        assert!(renderer
+            .render_cache
+            .get_register_parents(&child_uri)
+            .is_empty());
+        assert!(!renderer.materialize_lazy_files(10).await);
+        assert_eq!(renderer.get_lazy_file_count(), 0);
+        let parents = renderer
+            .render_cache
+            .get_register_parents(&child_uri)
+            .into_iter()
+            .map(|(uri, _)| uri.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(parents, vec![index_uri]);
+    }
+
+    #[tokio::test]
+    async fn oversized_node() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let target_root_dir = tempfile::tempdir().unwrap();
+        let root_path = root_dir.path();
+        let big_path = root_path.join("Big.vue");
+        std::fs::write(&big_path, "<template><div></div></template>".repeat(64)).unwrap();
+        assert!(Renderer::is_file_oversized(&big_path, 1).await);
+        assert!(!Renderer::is_file_oversized(&big_path, 0).await);
+        assert!(!Renderer::is_file_oversized(&big_path, 1024).await);
+
+        let root_uri = util::create_uri_from_path(root_path);
+        let big_uri = util::create_uri_from_path(&big_path);
+        let index_uri = util::create_uri_from_path(&root_path.join("index.vue"));
+        let mut renderer = Renderer::new();
+        renderer
+            .root_uri_target_uri
+            .set((
+                root_uri.clone(),
+                util::create_uri_from_path(target_root_dir.path()),
+            ))
+            .unwrap();
+        renderer.set_max_file_size(1);
+        renderer.lazy_files.insert(big_uri.clone());
+        add_vue_document(
+            &mut renderer,
+            &index_uri,
+            &[
+                "<template><Big /></template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "import Big from './Big.vue';",
+                "@Component({ components: { Big } })",
+                "export default class Index extends Vue {}",
+                "</script>",
+            ],
+        );
+        // 超过最大大小的文件创建为不解析内容的节点
+        renderer.flush().await;
+        assert!(matches!(
+            renderer.render_cache.get(&big_uri),
+            Some(RenderCache::TsRenderCache(cache)) if cache.ts_component.is_none()
+        ));
+        assert!(renderer.is_render_excluded(&big_uri));
+        assert!(!renderer.is_render_excluded(&index_uri));
+        // vue 文件在目标目录中写入占位文件
+        let target_path = Renderer::get_target_path(
+            &big_uri,
+            &root_uri,
+            &util::create_uri_from_path(target_root_dir.path()),
+            false,
+        );
+        assert_eq!(
+            std::fs::read_to_string(&target_path).unwrap(),
+            super::OVERSIZED_PLACEHOLDER
+        );
+
+        // 恢复到限制以内后保存时重新创建节点
+        std::fs::write(
+            &big_path,
+            [
+                "<template><div></div></template>",
+                "<script lang=\"ts\">",
+                "import { Component, Vue } from 'vue-property-decorator';",
+                "@Component",
+                "export default class Big extends Vue {}",
+                "</script>",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        renderer.save(&big_uri).await;
+        assert!(matches!(
+            renderer.render_cache.get(&big_uri),
+            Some(RenderCache::VueRenderCache(_))
+        ));
+        assert!(!renderer.is_render_excluded(&big_uri));
+    }
 }
@@ -2,10 +2,14 @@ pub mod lib_render_cache;
 pub mod ts_render_cache;
 pub mod vue_render_cache;
 
-use std::{collections::HashMap, ops::Index};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Index,
+    sync::OnceLock,
+};
 
 use html_languageservice::html_data::Description;
-use lib_render_cache::LibRenderCache;
+use lib_render_cache::{LibComponentProp, LibRenderCache};
 use petgraph::{graph::NodeIndex, visit::EdgeRef, Direction, Graph};
 use swc_common::util::take::Take;
 use tokio::fs;
@@ -19,7 +23,7 @@ use crate::util;
 use super::{
     combined_rendered_results,
     parse_script::{ExtendsComponent, RegisterComponent},
-    Renderer,
+    template_compile, Renderer,
 };
 
 type RRGraph = Graph<RenderCache, Relationship>;
@@ -31,6 +35,8 @@ pub struct RenderCacheGraph {
     url_map: HashMap<NodeIndex, Uri>,
     /// 未加入的边
     virtual_edges: Vec<(Uri, Uri, Relationship)>,
+    /// 指向已移除节点的边，节点重新创建时恢复
+    dangling_edges: Vec<(Uri, Uri, Relationship)>,
 }
 
 impl RenderCacheGraph {
@@ -40,6 +46,7 @@ impl RenderCacheGraph {
             idx_map: HashMap::new(),
             url_map: HashMap::new(),
             virtual_edges: vec![],
+            dangling_edges: vec![],
         }
     }
 
@@ -58,12 +65,26 @@ impl RenderCacheGraph {
         // 检查对应节点是否存在
         let idx = self.idx_map.get(uri);
         if let Some(idx) = idx {
-            let node = self.graph.node_weight_mut(*idx).unwrap();
+            let idx = *idx;
+            let node = self.graph.node_weight_mut(idx).unwrap();
             *node = cache;
+            self.clear_effective_props(idx);
         } else {
             let idx = self.graph.add_node(cache);
             self.idx_map.insert(uri.clone(), idx);
             self.url_map.insert(idx, uri.clone());
+            // 恢复节点被移除前其他节点指向它的边
+            let (edges, dangling_edges) = self
+                .dangling_edges
+                .take()
+                .into_iter()
+                .partition::<Vec<_>, _>(|(_, to, _)| to == uri);
+            self.dangling_edges = dangling_edges;
+            for (from, to, relation) in edges {
+                if self.idx_map.contains_key(&from) {
+                    self.add_edge(&from, &to, relation);
+                }
+            }
         }
     }
 
@@ -89,6 +110,9 @@ impl RenderCacheGraph {
         // 检查相同的边是否存在
         let mut edges = self.graph.edges_connecting(a, b);
         if edges.find(|edge| *edge.weight() == relation).is_none() {
+            if relation.is_extends() || relation.is_transfer() {
+                self.clear_effective_props(a);
+            }
             self.graph.add_edge(a, b, relation);
         }
     }
@@ -103,6 +127,8 @@ impl RenderCacheGraph {
     /// 移除节点下游边
     pub fn remove_outgoing_edge(&mut self, uri: &Uri) {
         let idx = self.idx_map[uri];
+        self.clear_effective_props(idx);
+        self.dangling_edges.retain(|(from, _, _)| from != uri);
         let edges = self
             .graph
             .edges_directed(idx, Direction::Outgoing)
@@ -114,15 +140,143 @@ impl RenderCacheGraph {
     }
 
     /// 移除节点，同时移除节点上的边，同时删除对应的文件
+    ///
+    /// 其他节点指向该节点的边被暂存，节点重新创建时恢复
     pub fn remove_node(&mut self, uri: &Uri, root_uri: &Uri, target_root_uri: &Uri) -> RenderCache {
         let idx = self.idx_map[uri];
-        let cache = self.graph.remove_node(idx).unwrap();
+        self.clear_effective_props(idx);
+        self.dangling_edges.retain(|(from, _, _)| from != uri);
+        for edge in self.graph.edges_directed(idx, Direction::Incoming) {
+            if edge.source() != idx {
+                self.dangling_edges.push((
+                    self.url_map[&edge.source()].clone(),
+                    uri.clone(),
+                    edge.weight().clone(),
+                ));
+            }
+        }
         self.remove_node_file(uri, root_uri, target_root_uri);
+        // 移除节点时最后一个节点会被移动到被移除节点的位置
+        let last = NodeIndex::new(self.graph.node_count() - 1);
+        let cache = self.graph.remove_node(idx).unwrap();
         self.idx_map.remove(uri);
         self.url_map.remove(&idx);
+        if last != idx {
+            if let Some(last_uri) = self.url_map.remove(&last) {
+                self.idx_map.insert(last_uri.clone(), idx);
+                self.url_map.insert(idx, last_uri);
+            }
+        }
         cache
     }
 
+    /// 获取节点和边的统计信息
+    pub fn get_stats(&self) -> RenderCacheGraphStats {
+        let mut stats = RenderCacheGraphStats::default();
+        for (uri, idx) in &self.idx_map {
+            if self.url_map.get(idx) != Some(uri) {
+                stats.unknown_nodes += 1;
+                continue;
+            }
+            match self.graph.node_weight(*idx) {
+                Some(RenderCache::VueRenderCache(_)) => stats.vue_nodes += 1,
+                Some(RenderCache::TsRenderCache(_)) => stats.ts_nodes += 1,
+                Some(RenderCache::LibRenderCache(_)) => stats.lib_nodes += 1,
+                None => stats.unknown_nodes += 1,
+            }
+        }
+        for edge in self.graph.edge_weights() {
+            match edge {
+                Relationship::ExtendsRelationship(_) => stats.extends_edges += 1,
+                Relationship::RegisterRelationship(_) => stats.register_edges += 1,
+                Relationship::TransferRelationship(_) => stats.transfer_edges += 1,
+            }
+        }
+        stats.virtual_edges = self.virtual_edges.len();
+        stats
+    }
+
+    /// 将关系图输出为 Graphviz DOT 格式
+    ///
+    /// 节点以相对于根目录的路径标记，vue、ts、组件库节点分别为矩形、椭圆和组件形状
+    pub fn to_dot(&self, root_uri: &Uri) -> String {
+        let root = root_uri.as_str().trim_end_matches('/');
+        let escape = |label: &str| label.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut dot = "digraph RenderCacheGraph {\n".to_string();
+        for idx in self.graph.node_indices() {
+            let Some(uri) = self.url_map.get(&idx) else {
+                continue;
+            };
+            let uri = uri.as_str();
+            let label = uri
+                .strip_prefix(root)
+                .map_or(uri, |v| v.trim_start_matches('/'));
+            let shape = match &self.graph[idx] {
+                RenderCache::VueRenderCache(_) => "box",
+                RenderCache::TsRenderCache(_) => "ellipse",
+                RenderCache::LibRenderCache(_) => "component",
+            };
+            dot += &format!(
+                "  n{} [label=\"{}\", shape={}];\n",
+                idx.index(),
+                escape(label),
+                shape
+            );
+        }
+        for edge in self.graph.edge_references() {
+            let label = match edge.weight() {
+                Relationship::ExtendsRelationship(_) => "extends".to_string(),
+                Relationship::RegisterRelationship(relation) => {
+                    format!("register: {}", relation.registered_name)
+                }
+                Relationship::TransferRelationship(_) => "transfer".to_string(),
+            };
+            dot += &format!(
+                "  n{} -> n{} [label=\"{}\"];\n",
+                edge.source().index(),
+                edge.target().index(),
+                escape(&label)
+            );
+        }
+        dot += "}\n";
+        dot
+    }
+
+    /// 获取虚拟边指向的尚不存在的节点
+    pub fn get_missing_edge_targets(&self) -> Vec<Uri> {
+        let mut uris = vec![];
+        for (_, to, _) in &self.virtual_edges {
+            if !self.idx_map.contains_key(to) && !uris.contains(to) {
+                uris.push(to.clone());
+            }
+        }
+        uris
+    }
+
+    /// 获取通过继承、注册或转换关系直接导入当前节点的所有节点
+    pub fn get_importers(&self, uri: &Uri) -> Vec<&Uri> {
+        let Some(node) = self.idx_map.get(uri) else {
+            return vec![];
+        };
+        let mut result = vec![];
+        for edge in self.graph.edges_directed(*node, Direction::Incoming) {
+            let source = &self.url_map[&edge.source()];
+            if !result.contains(&source) {
+                result.push(source);
+            }
+        }
+        result
+    }
+
+    /// 获取所有 vue 节点的 uri
+    pub fn get_vue_uris(&self) -> Vec<Uri> {
+        self.graph
+            .node_indices()
+            .filter(|node| matches!(self.graph[*node], RenderCache::VueRenderCache(_)))
+            .map(|node| self.url_map[&node].clone())
+            .collect()
+    }
+
     /// 将所有虚拟边加入 graph
     pub fn flush(&mut self) {
         for (from, to, relation) in self.virtual_edges.take() {
@@ -131,12 +285,24 @@ impl RenderCacheGraph {
     }
 
     /// 更新上游节点版本
+    ///
+    /// 沿继承和转换关系继续向上游传播，使继承链上的所有节点都能感知祖先节点的变化
     pub fn update_incoming_node_version(&mut self, uri: &Uri) {
         let idx = self.idx_map[uri];
-        let edges = self.graph.edges_directed(idx, Direction::Incoming);
+        self.clear_effective_props(idx);
+        let mut visited = HashSet::from([idx]);
+        let mut queue = vec![idx];
         let mut nodes = vec![];
-        for edge in edges {
-            nodes.push(edge.source());
+        while let Some(cur) = queue.pop() {
+            for edge in self.graph.edges_directed(cur, Direction::Incoming) {
+                let source = edge.source();
+                if visited.insert(source) {
+                    nodes.push(source);
+                    if edge.weight().is_extends() || edge.weight().is_transfer() {
+                        queue.push(source);
+                    }
+                }
+            }
         }
         debug!("update_incoming_node_version: {}", nodes.len());
         for node in nodes {
@@ -147,6 +313,39 @@ impl RenderCacheGraph {
             }
         }
     }
+
+    /// 清空文件及继承自它的文件中展开后的属性缓存
+    pub fn clear_node_effective_props(&mut self, uri: &Uri) {
+        if let Some(idx) = self.idx_map.get(uri) {
+            self.clear_effective_props(*idx);
+        }
+    }
+
+    /// 清空节点及沿继承和转换关系的上游节点中展开后的属性缓存
+    fn clear_effective_props(&mut self, idx: NodeIndex) {
+        let mut visited = HashSet::from([idx]);
+        let mut queue = vec![idx];
+        while let Some(cur) = queue.pop() {
+            for edge in self.graph.edges_directed(cur, Direction::Incoming) {
+                if edge.weight().is_extends() || edge.weight().is_transfer() {
+                    if visited.insert(edge.source()) {
+                        queue.push(edge.source());
+                    }
+                }
+            }
+        }
+        for node in visited {
+            match self.graph.node_weight_mut(node) {
+                Some(RenderCache::VueRenderCache(cache)) => {
+                    cache.effective_props.take();
+                }
+                Some(RenderCache::TsRenderCache(cache)) => {
+                    cache.effective_props.take();
+                }
+                _ => {}
+            }
+        }
+    }
 }
 
 /// render
@@ -158,7 +357,8 @@ impl RenderCacheGraph {
             if let RenderCache::VueRenderCache(_) = cache {
                 let uri = &self.url_map[&node];
                 let content = self.get_node_render_content(uri).unwrap();
-                let target_path = Renderer::get_target_path(uri, root_uri, target_root_uri);
+                let target_path =
+                    Renderer::get_target_path(uri, root_uri, target_root_uri, cache.is_js());
                 tokio::spawn(async {
                     fs::write(target_path, content).await.unwrap();
                 });
@@ -174,16 +374,26 @@ impl RenderCacheGraph {
             RenderCache::VueRenderCache(_) => {
                 let uri = &self.url_map[&node];
                 let content = self.get_node_render_content(uri).unwrap();
-                let target_path = Renderer::get_target_path(uri, root_uri, target_root_uri);
+                let is_js = cache.is_js();
+                let target_path = Renderer::get_target_path(uri, root_uri, target_root_uri, is_js);
+                // 脚本语言变化后删除原有的渲染结果
+                let other_target_path =
+                    Renderer::get_target_path(uri, root_uri, target_root_uri, !is_js);
                 debug!("render_node: {}", target_path.to_string_lossy());
                 tokio::spawn(async {
-                    fs::write(target_path, content).await.unwrap();
+                    if let Err(err) = fs::write(&target_path, content).await {
+                        error!("error: {} - {}", target_path.to_string_lossy(), err);
+                        return;
+                    }
+                    if other_target_path.exists() {
+                        let _ = fs::remove_file(other_target_path).await;
+                    }
                 });
             }
             RenderCache::TsRenderCache(_) => {
                 // 如果不存在硬链接，那么增加
                 let uri = &self.url_map[&node];
-                let target_path = Renderer::get_target_path(uri, root_uri, target_root_uri);
+                let target_path = Renderer::get_target_path(uri, root_uri, target_root_uri, false);
                 if !target_path.exists() {
                     let src_path = util::to_file_path(uri);
                     tokio::spawn(async {
@@ -204,20 +414,42 @@ impl RenderCacheGraph {
         if let RenderCache::VueRenderCache(cache) = cache {
             if let Some(script) = &cache.script {
                 // 获取继承组件的 props
-                let mut props = cache.props.iter().map(|v| &v.name[..]).collect::<Vec<_>>();
                 let extends_props = self.get_extends_props(uri);
-                let mut extends_props = extends_props
+                let (readonly_props, props): (Vec<_>, Vec<_>) = cache
+                    .props
                     .iter()
+                    .chain(extends_props.iter())
+                    .partition(|v| v.is_readonly());
+                // 模版中不以 `$` 开头的全局属性不会加 `this.` 前缀，模版引用时同样从 this 中解构
+                let template_compile_result = cache.template_compile_result.get_content(None);
+                let global_properties = self
+                    .get_global_properties()
+                    .into_iter()
                     .map(|v| &v.name[..])
+                    .filter(|name| {
+                        !name.starts_with('$')
+                            && props.iter().chain(&readonly_props).all(|v| v.name != *name)
+                            && template_compile::is_identifier_referenced(
+                                template_compile_result,
+                                name,
+                            )
+                    })
                     .collect::<Vec<_>>();
-                props.append(&mut extends_props);
                 Some(combined_rendered_results::combined_rendered_results(
                     script.start_tag_end.unwrap(),
                     script.end_tag_start.unwrap(),
-                    &cache.template_compile_result.get_content(None),
-                    &props,
+                    template_compile_result,
+                    &props.iter().map(|v| &v.name[..]).collect::<Vec<_>>(),
+                    &readonly_props
+                        .iter()
+                        .map(|v| &v.name[..])
+                        .chain(global_properties)
+                        .collect::<Vec<_>>(),
+                    &css_server::get_style_modules_declaration(&cache.style_modules, cache.is_js()),
                     cache.render_insert_offset,
                     cache.document.get_content(None),
+                    cache.is_js(),
+                    &cache.class_declaration,
                 ))
             } else {
                 Some("".to_string())
@@ -231,9 +463,12 @@ impl RenderCacheGraph {
     fn remove_node_file(&self, uri: &Uri, root_uri: &Uri, target_root_uri: &Uri) {
         let node = self.idx_map[uri];
         let uri = &self.url_map[&node];
-        let target_path = Renderer::get_target_path(uri, root_uri, target_root_uri);
-        tokio::spawn(async {
-            fs::remove_file(target_path).await.unwrap();
+        let target_path =
+            Renderer::get_target_path(uri, root_uri, target_root_uri, self.graph[node].is_js());
+        tokio::spawn(async move {
+            if let Err(err) = fs::remove_file(&target_path).await {
+                error!("remove {} error: {}", target_path.to_string_lossy(), err);
+            }
         });
     }
 }
@@ -253,40 +488,146 @@ impl RenderCacheGraph {
         Some(&self.url_map[&node])
     }
 
-    /// 移除继承关系
-    pub fn remove_extends_edge(&mut self, uri: &Uri) {
+    /// 获取当前节点直接继承的节点及导出名称，按声明顺序排列
+    pub fn get_extends_nodes(&self, uri: &Uri) -> Vec<(&Uri, Option<String>)> {
         let idx = self.idx_map[uri];
-        let edge = self
+        let mut nodes = self
             .graph
             .edges_directed(idx, Direction::Outgoing)
-            .find(|v| v.weight().is_extends())
-            .map(|v| v.id());
-        if let Some(edge) = edge {
+            .filter(|v| v.weight().is_extends())
+            .map(|v| {
+                let export_name = v.weight().as_extends().export_name.clone();
+                (&self.url_map[&v.target()], export_name)
+            })
+            .collect::<Vec<_>>();
+        // 出边按添加顺序的逆序遍历
+        nodes.reverse();
+        nodes
+    }
+
+    /// 移除继承关系，包括混入产生的所有继承边
+    pub fn remove_extends_edges(&mut self, uri: &Uri) {
+        let idx = self.idx_map[uri];
+        self.clear_effective_props(idx);
+        self.dangling_edges
+            .retain(|(from, _, relation)| from != uri || !relation.is_extends());
+        let mut edges = self
+            .graph
+            .edges_directed(idx, Direction::Outgoing)
+            .filter(|v| v.weight().is_extends())
+            .map(|v| v.id())
+            .collect::<Vec<_>>();
+        // 删除边时最后一条边的索引会改变，因此从大到小删除
+        edges.sort_by(|a, b| b.cmp(a));
+        for edge in edges {
             self.graph.remove_edge(edge);
         }
     }
 
+    /// 获取继承当前组件的所有子组件，包括间接继承以及通过转换关系继承的组件
+    pub fn get_subtypes(&self, uri: &Uri) -> Vec<&Uri> {
+        let node = self.idx_map[uri];
+        let mut result = vec![];
+        let mut visited = HashSet::new();
+        // (节点, 组件在该节点中的导出名称)
+        let mut stack: Vec<(NodeIndex, Option<String>)> = vec![(node, None)];
+        while let Some((cur_node, export_name)) = stack.pop() {
+            if !visited.insert((cur_node, export_name.clone())) {
+                continue;
+            }
+            for edge in self.graph.edges_directed(cur_node, Direction::Incoming) {
+                match edge.weight() {
+                    Relationship::ExtendsRelationship(relation) => {
+                        if relation.export_name == export_name {
+                            let source = edge.source();
+                            if !result.contains(&&self.url_map[&source]) {
+                                result.push(&self.url_map[&source]);
+                            }
+                            stack.push((source, None));
+                        }
+                    }
+                    Relationship::TransferRelationship(relation) => {
+                        if relation.export_name == export_name {
+                            stack.push((edge.source(), relation.local.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        result
+    }
+
+    /// 获取 UI 库通过 `declare module 'vue/types/vue'` 扩展到 Vue 实例上的全局属性
+    pub fn get_global_properties(&self) -> Vec<&LibComponentProp> {
+        self.graph
+            .node_weights()
+            .filter_map(|cache| match cache {
+                RenderCache::LibRenderCache(cache) => Some(&cache.global_properties),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// 获取当前节点自身及沿继承链展开后的所有属性
+    /// 子组件中的同名属性会覆盖继承的属性
+    ///
+    /// 结果缓存在节点中，节点或祖先节点变化时清空
+    pub fn get_effective_props(&self, uri: &Uri) -> Vec<RenderCacheProp> {
+        let cell = match self.get(uri) {
+            Some(RenderCache::VueRenderCache(cache)) => &cache.effective_props,
+            Some(RenderCache::TsRenderCache(cache)) => &cache.effective_props,
+            _ => return vec![],
+        };
+        cell.get_or_init(|| self.compute_effective_props(uri))
+            .clone()
+    }
+
+    fn compute_effective_props(&self, uri: &Uri) -> Vec<RenderCacheProp> {
+        let mut props = match self.get(uri) {
+            Some(RenderCache::VueRenderCache(cache)) => cache.props.clone(),
+            Some(RenderCache::TsRenderCache(cache)) => match &cache.ts_component {
+                Some(ts_component) => ts_component.props.clone(),
+                None => return vec![],
+            },
+            _ => return vec![],
+        };
+        for prop in self.get_extends_props(uri) {
+            if props.iter().all(|v| v.name != prop.name) {
+                props.push(prop);
+            }
+        }
+        props
+    }
+
     /// 获取当前节点的所有继承属性
+    /// 混入时存在多个继承节点，按声明顺序深度优先展开
     fn get_extends_props(&self, uri: &Uri) -> Vec<RenderCacheProp> {
         let node = self.idx_map[uri];
         let mut extends_props = vec![];
-        let mut next_node = self.get_extends_node(node);
-        while let Some((cur_node, export_name)) = next_node {
+        let mut visited = HashSet::new();
+        let mut stack = vec![];
+        self.push_extends_nodes(&mut stack, node);
+        while let Some((cur_node, export_name)) = stack.pop() {
+            if !visited.insert((cur_node, export_name.clone())) {
+                continue;
+            }
             match &self.graph[cur_node] {
                 RenderCache::VueRenderCache(cache) => {
                     extends_props.append(&mut cache.props.clone());
-                    next_node = self.get_extends_node(cur_node);
+                    self.push_extends_nodes(&mut stack, cur_node);
                 }
                 RenderCache::TsRenderCache(cache) => {
                     // 尝试从当前文件获取下一个节点
                     if let Some(ts_component) = &cache.ts_component {
                         if export_name == None {
                             extends_props.append(&mut ts_component.props.clone());
-                            next_node = self.get_extends_node(cur_node);
+                            self.push_extends_nodes(&mut stack, cur_node);
                             continue;
                         } else if cache.local_exports.contains(&export_name) {
-                            // 从当前定义，但是不是组件，那么直接退出
-                            break;
+                            // 从当前定义，但是不是组件，那么直接跳过
+                            continue;
                         }
                     }
                     // 尝试从转换关系获取下一个节点
@@ -294,7 +635,7 @@ impl RenderCacheGraph {
                         self.get_transfer_node(&self.url_map[&cur_node], &export_name)
                     {
                         let transfer_node = self.idx_map[transfer_url];
-                        next_node = Some((transfer_node, export_name));
+                        stack.push((transfer_node, export_name));
                         continue;
                     }
                     // 尝试从星号导出获取下一个节点
@@ -303,27 +644,61 @@ impl RenderCacheGraph {
                         cur_node,
                         &export_name,
                     ) {
-                        next_node = Some((node, export_name));
-                    } else {
-                        // 未找到
-                        break;
+                        stack.push((node, export_name));
                     }
                 }
-                RenderCache::LibRenderCache(_) => {
-                    next_node = None;
-                }
+                RenderCache::LibRenderCache(_) => {}
             }
         }
         extends_props
     }
 
-    /// 获取继承的节点
-    fn get_extends_node(&self, node: NodeIndex) -> Option<(NodeIndex, Option<String>)> {
-        let mut edges = self.graph.edges_directed(node, Direction::Outgoing);
-        let extends_edge = edges.find(|edge| edge.weight().is_extends())?;
-        let export_name = extends_edge.weight().as_extends().export_name.clone();
-        let extends_node = extends_edge.target();
-        Some((extends_node, export_name))
+    /// 导出是否为组件，沿转换关系查找定义导出的节点
+    ///
+    /// 无法找到定义导出的节点时视为组件
+    pub fn is_component_export(&self, uri: &Uri, export_name: &Option<String>) -> bool {
+        let Some(&node) = self.idx_map.get(uri) else {
+            return true;
+        };
+        let mut visited = HashSet::new();
+        let mut cur = (node, export_name.clone());
+        while visited.insert(cur.clone()) {
+            let (cur_node, export_name) = &cur;
+            let RenderCache::TsRenderCache(cache) = &self.graph[*cur_node] else {
+                return true;
+            };
+            if cache.ts_component.is_some() && export_name.is_none() {
+                return true;
+            }
+            if cache.local_exports.contains(export_name) {
+                return false;
+            }
+            if let Some((transfer_url, export_name)) =
+                self.get_transfer_node(&self.url_map[cur_node], export_name)
+            {
+                cur = (self.idx_map[transfer_url], export_name);
+                continue;
+            }
+            if let Some(next) =
+                RenderCacheGraph::get_node_from_star_export(&self.graph, *cur_node, export_name)
+            {
+                cur = next;
+                continue;
+            }
+            return true;
+        }
+        true
+    }
+
+    /// 将继承的节点逆序压入栈中，使出栈顺序与声明顺序一致
+    fn push_extends_nodes(&self, stack: &mut Vec<(NodeIndex, Option<String>)>, node: NodeIndex) {
+        // 出边按添加顺序的逆序遍历，即为声明顺序的逆序
+        for edge in self.graph.edges_directed(node, Direction::Outgoing) {
+            if edge.weight().is_extends() {
+                let export_name = edge.weight().as_extends().export_name.clone();
+                stack.push((edge.target(), export_name));
+            }
+        }
     }
 }
 
@@ -332,6 +707,9 @@ impl RenderCacheGraph {
     /// 移除转换关系
     pub fn remove_transfers_edges(&mut self, uri: &Uri) {
         let idx = self.idx_map[uri];
+        self.clear_effective_props(idx);
+        self.dangling_edges
+            .retain(|(from, _, relation)| from != uri || !relation.is_transfer());
         let edges = self
             .graph
             .edges_directed(idx, Direction::Outgoing)
@@ -411,9 +789,44 @@ impl RenderCacheGraph {
         Some((&self.url_map[&edge.target()], edge.weight().as_register()))
     }
 
+    /// 获取注册了当前组件的所有父组件及注册的名称，包括通过转换关系注册的组件
+    pub fn get_register_parents(&self, uri: &Uri) -> Vec<(&Uri, &str)> {
+        let node = self.idx_map[uri];
+        let mut result = vec![];
+        let mut visited = HashSet::new();
+        // (节点, 组件在该节点中的导出名称)
+        let mut stack: Vec<(NodeIndex, Option<String>)> = vec![(node, None)];
+        while let Some((cur_node, export_name)) = stack.pop() {
+            if !visited.insert((cur_node, export_name.clone())) {
+                continue;
+            }
+            for edge in self.graph.edges_directed(cur_node, Direction::Incoming) {
+                match edge.weight() {
+                    Relationship::RegisterRelationship(relation) => {
+                        if relation.export_name == export_name && relation.prop.is_none() {
+                            result.push((
+                                &self.url_map[&edge.source()],
+                                relation.registered_name.as_str(),
+                            ));
+                        }
+                    }
+                    Relationship::TransferRelationship(relation) => {
+                        if relation.export_name == export_name {
+                            stack.push((edge.source(), relation.local.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        result
+    }
+
     /// 移除注册关系
     pub fn remove_registers_edges(&mut self, uri: &Uri) {
         let idx = self.idx_map[uri];
+        self.dangling_edges
+            .retain(|(from, _, relation)| from != uri || !relation.is_register());
         let edges = self
             .graph
             .edges_directed(idx, Direction::Outgoing)
@@ -434,6 +847,21 @@ impl Index<&Uri> for RenderCacheGraph {
     }
 }
 
+/// 图中节点和边的数量，用于诊断性能问题
+#[derive(Debug, Default, PartialEq)]
+pub struct RenderCacheGraphStats {
+    pub vue_nodes: usize,
+    pub ts_nodes: usize,
+    pub lib_nodes: usize,
+    /// 索引与图中节点不一致的节点
+    pub unknown_nodes: usize,
+    pub extends_edges: usize,
+    pub register_edges: usize,
+    pub transfer_edges: usize,
+    /// 尚未加入图中的边
+    pub virtual_edges: usize,
+}
+
 #[derive(Debug)]
 pub enum RenderCache {
     VueRenderCache(VueRenderCache),
@@ -442,20 +870,24 @@ pub enum RenderCache {
 }
 
 impl RenderCache {
-    /// 更新渲染缓存返回变更结果
+    /// 更新渲染缓存返回变更结果，`component_decorators` 为声明组件的装饰器名称
     pub fn update(
         &mut self,
         change: TextDocumentContentChangeEvent,
+        component_decorators: &[String],
     ) -> Option<RenderCacheUpdateResult> {
         match self {
-            RenderCache::VueRenderCache(vue_cache) => vue_cache.update(change),
-            RenderCache::TsRenderCache(ts_cache) => ts_cache.update(change),
+            RenderCache::VueRenderCache(vue_cache) => {
+                vue_cache.update(change, component_decorators)
+            }
+            RenderCache::TsRenderCache(ts_cache) => ts_cache.update(change, component_decorators),
             RenderCache::LibRenderCache(lib_cache) => {
                 error!("lib update: {} {:?}", lib_cache.name, change);
                 Some(RenderCacheUpdateResult {
                     changes: vec![change],
                     is_change: false,
                     extends_component: None,
+                    mixins: None,
                     registers: None,
                     transfers: None,
                 })
@@ -463,6 +895,11 @@ impl RenderCache {
         }
     }
 
+    /// 是否为脚本为 JavaScript 的 vue 节点
+    pub fn is_js(&self) -> bool {
+        matches!(self, RenderCache::VueRenderCache(cache) if cache.is_js())
+    }
+
     pub fn get_version(&self) -> Option<i32> {
         if let RenderCache::VueRenderCache(cache) = self {
             Some(cache.document.version())
@@ -501,6 +938,8 @@ pub struct RenderCacheUpdateResult {
     pub is_change: bool,
     /// 继承组件如果更新，返回更新后的继承组件
     pub extends_component: Option<Option<ExtendsComponent>>,
+    /// 混入组件如果更新，返回更新后的混入组件
+    pub mixins: Option<Vec<ExtendsComponent>>,
     /// 注册关系如果更新，返回更新后的注册关系
     pub registers: Option<Vec<RegisterComponent>>,
     /// 转换关系如果更新，返回更新后的转换关系
@@ -513,8 +952,22 @@ pub struct RenderCacheProp {
     pub range: (usize, usize),
     pub description: Option<Description>,
     pub typ: RenderCachePropType,
+    /// 成员的 TS 类型注解，prop 没有类型注解时为从默认值推断的类型
+    pub ts_type: Option<String>,
     /// 如果存在 @prop 装饰器，那么表示装饰器中的参数
     pub prop_params: Option<RenderCachePropParam>,
+    /// 如果存在 @Inject 装饰器，那么表示注入的参数
+    pub inject_params: Option<RenderCacheInjectParam>,
+    /// 如果存在 @Ref 装饰器，那么表示引用的参数
+    pub ref_params: Option<RenderCacheRefParam>,
+    /// 如果存在 @Model, @ModelSync, @VModel 装饰器，那么表示 model 的参数
+    pub model_params: Option<RenderCacheModelParam>,
+    /// 如果存在 @Emit 装饰器，那么表示触发的事件名称
+    pub emit_name: Option<String>,
+    /// 成员上的装饰器名称
+    pub decorators: Vec<String>,
+    /// 成员上所有装饰器的范围
+    pub decorators_range: Option<(usize, usize)>,
 }
 
 impl RenderCacheProp {
@@ -522,7 +975,31 @@ impl RenderCacheProp {
         self.name == other.name
             && self.description == other.description
             && self.typ == other.typ
+            && self.ts_type == other.ts_type
             && self.prop_params == other.prop_params
+            && self.inject_params == other.inject_params
+            && self.ref_params == other.ref_params
+            && self.model_params == other.model_params
+            && self.emit_name == other.emit_name
+            && self.decorators == other.decorators
+    }
+
+    /// 获取 prop 的 TS 类型，优先使用类型注解，其次使用装饰器参数中的 `type` 转换后的类型
+    pub fn get_prop_ts_type(&self) -> Option<String> {
+        self.ts_type.clone().or_else(|| {
+            let typ = self.prop_params.as_ref()?.typ.as_deref()?;
+            Some(util::get_constructor_ts_type(typ))
+        })
+    }
+
+    /// 是否存在指定的装饰器
+    pub fn has_decorator(&self, name: &str) -> bool {
+        self.decorators.iter().any(|v| v == name)
+    }
+
+    /// 是否是组件接收的 prop，对于组件自身是只读的
+    pub fn is_readonly(&self) -> bool {
+        self.has_decorator("Prop") || self.has_decorator("Model")
     }
 }
 
@@ -530,6 +1007,10 @@ impl RenderCacheProp {
 pub enum RenderCachePropType {
     Property,
     Method,
+    /// 计算属性，同时存在 setter 时可写
+    Computed {
+        writable: bool,
+    },
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -538,9 +1019,56 @@ pub struct RenderCachePropParam {
     /// 是否存在 default
     pub default: bool,
     pub required: bool,
+    /// 是否存在 validator
+    pub validator: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RenderCacheInjectParam {
+    /// 注入的 key，未指定时为成员名称
+    pub from: String,
+    /// 是否存在 default，存在时即使没有提供者也不会报错
+    pub default: bool,
+    /// key 是否为字符串字面量或成员名称，为变量或 Symbol 时无法确定提供者
+    pub is_literal_key: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RenderCacheRefParam {
+    /// 模版中的 ref 名称，未指定时为成员名称
+    pub name: String,
+    /// 类型注解是否为数组，位于 `v-for` 中的 ref 为数组
+    pub is_array: bool,
+    /// 声明的类型
+    pub typ: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RenderCacheModelParam {
+    /// model 属性名称，`@ModelSync` 为第一个参数，`@VModel` 为 `value`
+    pub prop: String,
+    /// 更新 model 的事件名称
+    pub event: String,
+    /// 声明的类型
+    pub typ: Option<String>,
+    /// 成员是否可写，`@ModelSync` 和 `@VModel` 生成带有 setter 的计算属性
+    pub writable: bool,
+}
+
+/// 组件的 model，即 `v-model` 绑定的属性和事件
+#[derive(Debug, PartialEq, Clone)]
+pub struct ComponentModel {
+    /// model 属性名称
+    pub prop: String,
+    /// 属性的类型
+    pub typ: Option<String>,
+    /// 更新 model 的事件名称
+    pub event: String,
+    /// 组件内是否可以直接赋值
+    pub writable: bool,
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub enum Relationship {
     ExtendsRelationship(ExtendsRelationship),
     RegisterRelationship(RegisterRelationship),
@@ -598,13 +1126,13 @@ impl Relationship {
 }
 
 /// 节点间的继承关系，指向被继承的节点
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub struct ExtendsRelationship {
     pub export_name: Option<String>,
 }
 
 /// 节点间的注册关系，指向被注册的节点
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub struct RegisterRelationship {
     /// 注册的名称
     pub registered_name: String,
@@ -617,7 +1145,7 @@ pub struct RegisterRelationship {
 }
 
 /// 节点间的中转关系，指向导入的节点
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub struct TransferRelationship {
     /// 当前文件导出时的名称
     pub local: Option<String>,
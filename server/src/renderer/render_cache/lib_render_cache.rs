@@ -1,19 +1,28 @@
 use html_languageservice::html_data::Description;
-use std::{fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 use tower_lsp::lsp_types::Location;
 use tracing::error;
 
 use lsp_textdocument::FullTextDocument;
 use swc_common::source_map::SmallPos;
-use swc_ecma_ast::{ClassMember, Decl, Expr, ModuleDecl, ModuleItem};
+use swc_ecma_ast::{
+    ClassDecl, ClassMember, Decl, Expr, ModuleDecl, ModuleItem, Pat, Stmt, TsEntityName,
+    TsModuleName, TsNamespaceBody, TsType, TsTypeElement, TsUnionOrIntersectionType, VarDecl,
+};
 use tower_lsp::lsp_types::{Range, Uri};
 
-use crate::{ast, util};
+use crate::{ast, renderer::multi_threaded_comment::MultiThreadedComments, util};
 
 #[derive(Debug)]
 pub struct LibRenderCache {
     pub name: String,
     pub components: Vec<LibComponent>,
+    /// 通过 `declare module 'vue/types/vue'` 扩展到 Vue 实例上的全局属性
+    pub global_properties: Vec<LibComponentProp>,
 }
 
 #[derive(Debug)]
@@ -27,79 +36,174 @@ pub struct LibComponent {
     pub props: Vec<LibComponentProp>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LibComponentProp {
     pub name: String,
     pub description: Option<Description>,
     pub location: Location,
 }
 
+/// `declare module` 和 `namespace` 的最大嵌套深度
+const MAX_MODULE_DEPTH: usize = 4;
+
+/// 声明组件常量时可以使用的组件类型
+const COMPONENT_TYPE_NAMES: [&str; 4] = [
+    "VueConstructor",
+    "Component",
+    "DefineComponent",
+    "ExtendedVue",
+];
+
+/// UI 库中组件类型声明的布局
+#[derive(Debug, PartialEq)]
+enum LibLayout {
+    /// 组件分别声明在 types 目录下的文件中，例如 element-ui
+    PerFile(PathBuf),
+    /// 所有组件在单个 lib/index.d.ts 中导出，例如 vuetify
+    SingleIndex(PathBuf),
+}
+
+/// 判断 UI 库的布局，不存在类型声明时返回 None
+///
+/// 优先使用 package.json 中 `types` 或 `typings` 指定的入口文件，
+/// 入口所在的子目录中存在其他声明文件时，组件分别声明在这些文件中，否则全部在入口文件中导出。
+/// 未指定入口时根据目录结构判断
+fn detect_lib_layout(lib_path: &Path) -> Option<LibLayout> {
+    if let Some(entry) = get_types_entry(lib_path) {
+        let dir = entry.parent()?;
+        let has_other_declarations = dir != lib_path
+            && fs::read_dir(dir).is_ok_and(|entries| {
+                entries.flatten().any(|v| {
+                    let path = v.path();
+                    path != entry && path.to_string_lossy().ends_with(".d.ts")
+                })
+            });
+        if has_other_declarations {
+            return Some(LibLayout::PerFile(dir.to_path_buf()));
+        }
+        return Some(LibLayout::SingleIndex(entry));
+    }
+    let index_path = lib_path.join("lib/index.d.ts");
+    if index_path.is_file() {
+        return Some(LibLayout::SingleIndex(index_path));
+    }
+    let types_path = lib_path.join("types");
+    if types_path.join("index.d.ts").is_file() {
+        return Some(LibLayout::PerFile(types_path));
+    }
+    None
+}
+
+/// 读取 package.json 中 `types` 或 `typings` 指定的类型声明入口文件，可以省略 `.d.ts` 后缀
+fn get_types_entry(lib_path: &Path) -> Option<PathBuf> {
+    let package = fs::read_to_string(lib_path.join("package.json")).ok()?;
+    let package = serde_json::from_str::<serde_json::Value>(&package).ok()?;
+    let types = package
+        .get("types")
+        .or_else(|| package.get("typings"))?
+        .as_str()?;
+    let path = lib_path.join(types);
+    if path.is_file() {
+        return Some(path);
+    }
+    let path = PathBuf::from(format!("{}.d.ts", path.to_string_lossy()));
+    path.is_file().then_some(path)
+}
+
 /// 解析特定格式的 UI 库，作为临时的代替方案
-/// 假设 UI 库满足下面的要求
-/// * 组件全部位于 types 目录下
-/// * 存在 types/index.d.ts 文件
+/// 根据 package.json 和目录结构选择解析方式
+/// * 只存在单个声明文件时，只解析该文件，组件可以是 class 或以组件类型声明的常量
+/// * 否则组件分别声明在入口文件所在目录下的文件中，例如 types 目录
 /// * 如果遍历 types 目录时是一个文件，那么取其中的 class 作为组件
 /// * 如果遍历 types 目录时是一个目录，那么存在静态属性的文件是主组件其他组件挂载到该组件下
 pub fn parse_specific_lib(uri: &Uri) -> LibRenderCache {
-    let mut components = vec![];
-    let mut file_path = util::to_file_path(uri);
-    let name = file_path
+    let lib_path = util::to_file_path(uri);
+    let name = lib_path
         .file_name()
         .map(|v| v.to_string_lossy().to_string())
         .unwrap_or(util::to_file_path_string(uri));
-    file_path.push("types/index.d.ts");
-    if file_path.is_file() {
-        file_path.pop();
-        if let Ok(entries) = fs::read_dir(file_path) {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if path.is_dir() {
-                        // 如果是目录，那么解析目录下的文件
-                        if let Ok(entries) = fs::read_dir(path) {
-                            for entry in entries {
-                                if let Ok(entry) = entry {
-                                    let path = entry.path();
-                                    if path.is_file() {
-                                        let result = parse_specific_file(&path);
-                                        if let Some(result) = result {
-                                            components.push(result);
-                                        }
-                                    }
+    let result = match detect_lib_layout(&lib_path) {
+        Some(LibLayout::PerFile(types_path)) => parse_per_file_lib(&types_path),
+        Some(LibLayout::SingleIndex(index_path)) => parse_specific_file(&index_path),
+        None => LibFileResult::default(),
+    };
+
+    // TODO: 获取继承的属性
+
+    let mut components = vec![];
+    for c in result.components {
+        components.push(c.0);
+    }
+    LibRenderCache {
+        name,
+        components,
+        global_properties: result.global_properties,
+    }
+}
+
+/// 解析 types 目录下的所有文件
+fn parse_per_file_lib(types_path: &Path) -> LibFileResult {
+    let mut result = LibFileResult::default();
+    if let Ok(entries) = fs::read_dir(types_path) {
+        for entry in entries {
+            if let Ok(entry) = entry {
+                let path = entry.path();
+                if path.is_dir() {
+                    // 如果是目录，那么解析目录下的文件
+                    if let Ok(entries) = fs::read_dir(path) {
+                        for entry in entries {
+                            if let Ok(entry) = entry {
+                                let path = entry.path();
+                                if path.is_file() {
+                                    result.append(parse_specific_file(&path));
                                 }
                             }
                         }
-                    } else {
-                        let result = parse_specific_file(&path);
-                        if let Some(result) = result {
-                            components.push(result);
-                        }
                     }
+                } else {
+                    result.append(parse_specific_file(&path));
                 }
             }
         }
     }
+    result
+}
 
-    // TODO: 获取继承的属性
+/// 单个文件的解析结果
+#[derive(Default)]
+struct LibFileResult {
+    /// (component, extends_component)
+    components: Vec<(LibComponent, Option<String>)>,
+    global_properties: Vec<LibComponentProp>,
+}
 
-    let mut result = vec![];
-    for c in components {
-        result.push(c.0);
-    }
-    LibRenderCache {
-        name,
-        components: result,
+impl LibFileResult {
+    fn append(&mut self, mut other: LibFileResult) {
+        self.components.append(&mut other.components);
+        self.global_properties.append(&mut other.global_properties);
     }
 }
 
 /// 解析特定格式的组件文件
 /// 假设满足以下条件
-/// * 组件导出为命名的 class
-/// * 一个文件最多只定义了一个组件
+/// * 组件导出为命名的 class，或者在 `declare module` 中声明的 class
+/// * 或者是以组件类型声明的常量，例如 `const VBtn: VueConstructor<VBtnProps>`
 /// * 继承的组件的标识符不变
-/// 返回值：（component, extends_component)
-fn parse_specific_file(path: &PathBuf) -> Option<(LibComponent, Option<String>)> {
-    let source = fs::read_to_string(path).unwrap();
+fn parse_specific_file(path: &PathBuf) -> LibFileResult {
+    match fs::read_to_string(path) {
+        Ok(source) => parse_specific_source(path, source),
+        Err(err) => {
+            error!(
+                "lib read error: {} {}",
+                path.as_os_str().to_string_lossy(),
+                err
+            );
+            LibFileResult::default()
+        }
+    }
+}
+
+fn parse_specific_source(path: &PathBuf, source: String) -> LibFileResult {
     let (module, comments) = ast::parse_source(&source, 0, source.len());
     if module.is_err() {
         error!(
@@ -107,70 +211,494 @@ fn parse_specific_file(path: &PathBuf) -> Option<(LibComponent, Option<String>)>
             path.as_os_str().to_string_lossy(),
             module.unwrap_err()
         );
-        return None;
-    }
-    for item in module.unwrap().body {
-        if let ModuleItem::ModuleDecl(module) = item {
-            if let ModuleDecl::ExportDecl(decl) = module {
-                if let Decl::Class(class_decl) = decl.decl {
-                    let class = &class_decl.class;
-                    // 继承组件
-                    let mut super_component = None;
-                    if let Some(super_class) = &class.super_class {
-                        if let Expr::Ident(ident) = super_class.as_ref() {
-                            let ident = ident.sym.to_string();
-                            if &ident != "Vue" {
-                                super_component = Some(ident);
-                            }
-                        }
+        return LibFileResult::default();
+    }
+    let module = module.unwrap();
+    let mut parser = LibFileParser {
+        path,
+        document: FullTextDocument::new("typescript".to_string(), 0, source),
+        comments: &comments,
+        result: LibFileResult::default(),
+        interfaces: HashMap::new(),
+        var_components: vec![],
+    };
+    for item in &module.body {
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(decl)) => {
+                parser.parse_decl(&decl.decl, 0, false, true)
+            }
+            ModuleItem::Stmt(Stmt::Decl(decl @ (Decl::TsModule(_) | Decl::TsInterface(_)))) => {
+                parser.parse_decl(decl, 0, false, false)
+            }
+            _ => {}
+        }
+    }
+    parser.finish()
+}
+
+struct LibFileParser<'a> {
+    path: &'a PathBuf,
+    document: FullTextDocument,
+    comments: &'a MultiThreadedComments,
+    result: LibFileResult,
+    /// 当前文件中声明的接口及其成员
+    interfaces: HashMap<String, Vec<LibComponentProp>>,
+    /// 以组件类型声明的常量及其属性引用的接口名称
+    var_components: Vec<(LibComponent, Vec<String>)>,
+}
+
+impl LibFileParser<'_> {
+    /// 解析声明，`is_vue_augmentation` 表示是否位于 `declare module 'vue/types/vue'` 中
+    ///
+    /// 只有导出的组件才会注册，组件为继承自其他组件的 class 或以组件类型声明的常量
+    fn parse_decl(
+        &mut self,
+        decl: &Decl,
+        depth: usize,
+        is_vue_augmentation: bool,
+        is_export: bool,
+    ) {
+        match decl {
+            Decl::Class(class_decl) => {
+                if is_export && !is_vue_augmentation && class_decl.class.super_class.is_some() {
+                    let component = self.parse_class_decl(class_decl);
+                    self.result.components.push(component);
+                }
+            }
+            Decl::TsInterface(interface) => {
+                if is_vue_augmentation && interface.id.sym == "Vue" {
+                    let mut props = self.parse_type_members(&interface.body.body);
+                    self.result.global_properties.append(&mut props);
+                } else {
+                    let props = self.parse_type_members(&interface.body.body);
+                    self.interfaces.insert(interface.id.sym.to_string(), props);
+                }
+            }
+            Decl::Var(var) => {
+                if is_export && !is_vue_augmentation {
+                    self.parse_var_decl(var)
+                }
+            }
+            Decl::TsModule(module) => {
+                if depth >= MAX_MODULE_DEPTH {
+                    return;
+                }
+                let is_vue_augmentation = match &module.id {
+                    TsModuleName::Str(name) => name.value == "vue/types/vue" || name.value == "vue",
+                    TsModuleName::Ident(_) => is_vue_augmentation,
+                };
+                let mut body = module.body.as_ref();
+                let mut depth = depth + 1;
+                // `namespace A.B {}` 的嵌套声明
+                while let Some(TsNamespaceBody::TsNamespaceDecl(namespace)) = body {
+                    if depth >= MAX_MODULE_DEPTH {
+                        return;
                     }
-                    let document = FullTextDocument::new("typescript".to_string(), 0, source);
-                    // 获取属性
-                    let mut props = vec![];
-                    let static_props = vec![];
-                    for member in &class.body {
-                        if let ClassMember::ClassProp(prop) = member {
-                            if prop.is_static {
-                                // TODO: 静态属性
-                            } else {
-                                let name = ast::get_class_prop_name(&prop);
-                                let description = ast::get_class_prop_description(&prop, &comments);
-                                if name.len() > 0 {
-                                    props.push(LibComponentProp {
-                                        name,
-                                        description,
-                                        location: Location {
-                                            uri: util::create_uri_from_path(&path),
-                                            range: Range {
-                                                start: document.position_at(prop.span.lo.to_u32()),
-                                                end: document.position_at(prop.span.hi.to_u32()),
-                                            },
-                                        },
-                                    });
-                                }
+                    body = Some(namespace.body.as_ref());
+                    depth += 1;
+                }
+                if let Some(TsNamespaceBody::TsModuleBlock(block)) = body {
+                    for item in &block.body {
+                        match item {
+                            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(decl)) => {
+                                self.parse_decl(&decl.decl, depth, is_vue_augmentation, true)
                             }
+                            ModuleItem::Stmt(Stmt::Decl(decl)) => {
+                                self.parse_decl(decl, depth, is_vue_augmentation, false)
+                            }
+                            _ => {}
                         }
                     }
-                    let name_location = Location {
-                        uri: util::create_uri_from_path(&path),
-                        range: Range::new(
-                            document.position_at(class.span.lo.to_u32()),
-                            document.position_at(class.span.hi.to_u32()),
-                        ),
-                    };
-                    return Some((
-                        LibComponent {
-                            name: class_decl.ident.sym.to_string(),
-                            name_location,
-                            description: ast::get_class_decl_description(&class_decl, &comments),
-                            static_props,
-                            props,
-                        },
-                        super_component,
-                    ));
                 }
             }
+            _ => {}
         }
     }
-    None
+
+    fn parse_class_decl(&self, class_decl: &ClassDecl) -> (LibComponent, Option<String>) {
+        let class = &class_decl.class;
+        let document = &self.document;
+        let path = self.path;
+        // 继承组件
+        let mut super_component = None;
+        if let Some(super_class) = &class.super_class {
+            if let Some(ident) = ast::get_super_class_ident(super_class) {
+                let ident = ident.sym.to_string();
+                if &ident != "Vue" {
+                    super_component = Some(ident);
+                }
+            }
+        }
+        // 获取属性
+        let mut props = vec![];
+        let static_props = vec![];
+        for member in &class.body {
+            if let ClassMember::ClassProp(prop) = member {
+                if prop.is_static {
+                    // TODO: 静态属性
+                } else {
+                    let name = ast::get_class_prop_name(&prop);
+                    let description = ast::get_class_prop_description(&prop, self.comments);
+                    if name.len() > 0 {
+                        props.push(LibComponentProp {
+                            name,
+                            description,
+                            location: Location {
+                                uri: util::create_uri_from_path(&path),
+                                range: Range {
+                                    start: document.position_at(prop.span.lo.to_u32()),
+                                    end: document.position_at(prop.span.hi.to_u32()),
+                                },
+                            },
+                        });
+                    }
+                }
+            }
+        }
+        let name_location = Location {
+            uri: util::create_uri_from_path(&path),
+            range: Range::new(
+                document.position_at(class.span.lo.to_u32()),
+                document.position_at(class.span.hi.to_u32()),
+            ),
+        };
+        (
+            LibComponent {
+                name: class_decl.ident.sym.to_string(),
+                name_location,
+                description: ast::get_class_decl_description(&class_decl, self.comments),
+                static_props,
+                props,
+            },
+            super_component,
+        )
+    }
+
+    /// 解析以组件类型声明的常量，例如 `const VBtn: VueConstructor<VBtnProps>`
+    /// 属性取自第一个类型参数中的类型字面量以及当前文件中声明的接口
+    fn parse_var_decl(&mut self, var: &VarDecl) {
+        for decl in &var.decls {
+            let Pat::Ident(ident) = &decl.name else {
+                continue;
+            };
+            let Some(type_ann) = &ident.type_ann else {
+                continue;
+            };
+            let TsType::TsTypeRef(type_ref) = type_ann.type_ann.as_ref() else {
+                continue;
+            };
+            let type_name = match &type_ref.type_name {
+                TsEntityName::Ident(ident) => &ident.sym,
+                TsEntityName::TsQualifiedName(name) => &name.right.sym,
+            };
+            if !COMPONENT_TYPE_NAMES.contains(&&type_name[..]) {
+                continue;
+            }
+            let Some(props_type) = type_ref.type_params.as_ref().and_then(|v| v.params.first())
+            else {
+                continue;
+            };
+            let mut props = vec![];
+            let mut interfaces = vec![];
+            self.collect_type_props(props_type, &mut props, &mut interfaces);
+            let name_location = Location {
+                uri: util::create_uri_from_path(self.path),
+                range: Range::new(
+                    self.document.position_at(decl.span.lo.to_u32()),
+                    self.document.position_at(decl.span.hi.to_u32()),
+                ),
+            };
+            self.var_components.push((
+                LibComponent {
+                    name: ident.id.sym.to_string(),
+                    name_location,
+                    description: None,
+                    static_props: vec![],
+                    props,
+                },
+                interfaces,
+            ));
+        }
+    }
+
+    /// 收集类型中的属性，类型引用只记录名称，在解析完成后从接口获取
+    fn collect_type_props(
+        &self,
+        ts_type: &TsType,
+        props: &mut Vec<LibComponentProp>,
+        interfaces: &mut Vec<String>,
+    ) {
+        match ts_type {
+            TsType::TsTypeLit(lit) => props.append(&mut self.parse_type_members(&lit.members)),
+            TsType::TsTypeRef(type_ref) => {
+                if let TsEntityName::Ident(ident) = &type_ref.type_name {
+                    interfaces.push(ident.sym.to_string());
+                }
+            }
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsIntersectionType(
+                ts_type,
+            )) => {
+                for ts_type in &ts_type.types {
+                    self.collect_type_props(ts_type, props, interfaces);
+                }
+            }
+            TsType::TsParenthesizedType(ts_type) => {
+                self.collect_type_props(&ts_type.type_ann, props, interfaces)
+            }
+            _ => {}
+        }
+    }
+
+    /// 获取类型成员中的属性和方法
+    fn parse_type_members(&self, members: &[TsTypeElement]) -> Vec<LibComponentProp> {
+        let mut props = vec![];
+        for member in members {
+            let (key, span) = match member {
+                TsTypeElement::TsPropertySignature(prop) => (&prop.key, prop.span),
+                TsTypeElement::TsMethodSignature(method) => (&method.key, method.span),
+                _ => continue,
+            };
+            let Expr::Ident(ident) = key.as_ref() else {
+                continue;
+            };
+            props.push(LibComponentProp {
+                name: ident.sym.to_string(),
+                description: ast::get_ts_type_element_description(member, self.comments),
+                location: Location {
+                    uri: util::create_uri_from_path(self.path),
+                    range: Range::new(
+                        self.document.position_at(span.lo.to_u32()),
+                        self.document.position_at(span.hi.to_u32()),
+                    ),
+                },
+            });
+        }
+        props
+    }
+
+    /// 从接口补全常量组件的属性，返回解析结果
+    fn finish(mut self) -> LibFileResult {
+        for (mut component, interfaces) in self.var_components {
+            for name in interfaces {
+                if let Some(props) = self.interfaces.get(&name) {
+                    component.props.extend(props.iter().cloned());
+                }
+            }
+            self.result.components.push((component, None));
+        }
+        self.result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::LibLayout;
+    use crate::util;
+
+    #[test]
+    fn generic_extends() {
+        let path = PathBuf::from("/path/project/node_modules/lib/types/button.d.ts");
+        let source = [
+            "export declare class Button extends UIComponent<ButtonProps> {",
+            "  size: string",
+            "}",
+        ]
+        .join("\n");
+        let result = super::parse_specific_source(&path, source);
+        let (component, extends) = &result.components[0];
+        assert_eq!(component.name, "Button");
+        assert_eq!(extends, &Some("UIComponent".to_string()));
+
+        let source = [
+            "export declare class Input extends (UIComponent<InputProps> as typeof UIComponent) {",
+            "  value: string",
+            "}",
+        ]
+        .join("\n");
+        let result = super::parse_specific_source(&path, source);
+        assert_eq!(result.components[0].1, Some("UIComponent".to_string()));
+    }
+
+    #[test]
+    fn declare_module() {
+        let path = PathBuf::from("/path/project/node_modules/lib/types/message.d.ts");
+        let source = [
+            "import Vue from 'vue';",
+            "declare module 'vue/types/vue' {",
+            "  interface Vue {",
+            "    $message: Message",
+            "    $confirm(message: string): Promise<void>",
+            "  }",
+            "}",
+            "declare module 'lib' {",
+            "  export class Message extends UIComponent {",
+            "    type: string",
+            "  }",
+            "  class Internal extends UIComponent {}",
+            "  export class MessageOptions {}",
+            "  namespace A.B.C.D {",
+            "    class Nested extends UIComponent {}",
+            "  }",
+            "}",
+        ]
+        .join("\n");
+        let result = super::parse_specific_source(&path, source);
+        let names = result
+            .components
+            .iter()
+            .map(|v| &v.0.name[..])
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Message"]);
+        assert_eq!(result.components[0].0.props[0].name, "type");
+        let names = result
+            .global_properties
+            .iter()
+            .map(|v| &v.name[..])
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["$message", "$confirm"]);
+    }
+
+    #[test]
+    fn per_file_layout() {
+        // element-ui 的目录结构
+        let dir = tempfile::tempdir().unwrap();
+        let lib_path = dir.path().join("element-ui");
+        std::fs::create_dir_all(lib_path.join("lib")).unwrap();
+        std::fs::create_dir_all(lib_path.join("types")).unwrap();
+        std::fs::write(
+            lib_path.join("package.json"),
+            r#"{ "name": "element-ui", "main": "lib/element-ui.common.js", "typings": "types/index.d.ts" }"#,
+        )
+        .unwrap();
+        std::fs::write(lib_path.join("lib/element-ui.common.js"), "").unwrap();
+        std::fs::write(
+            lib_path.join("types/index.d.ts"),
+            [
+                "export * from './element-ui'",
+                "import * as ElementUI from './element-ui'",
+                "export default ElementUI",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        std::fs::write(
+            lib_path.join("types/element-ui.d.ts"),
+            [
+                "import { ElButton } from './button'",
+                "/** Button Component */",
+                "export class Button extends ElButton {}",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        std::fs::write(
+            lib_path.join("types/component.d.ts"),
+            [
+                "import Vue from 'vue'",
+                "export declare class ElementUIComponent extends Vue {",
+                "  static install (vue: typeof Vue): void",
+                "}",
+                "export type ElementUIComponentSize = 'large' | 'medium' | 'small' | 'mini'",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        std::fs::write(
+            lib_path.join("types/button.d.ts"),
+            [
+                "import { ElementUIComponent, ElementUIComponentSize } from './component'",
+                "export type ButtonType = 'primary' | 'success' | 'warning' | 'danger' | 'info' | 'text'",
+                "/** Button Component */",
+                "export declare class ElButton extends ElementUIComponent {",
+                "  /** Button size */",
+                "  size: ElementUIComponentSize",
+                "  /** Button type */",
+                "  type: ButtonType",
+                "}",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        assert_eq!(
+            super::detect_lib_layout(&lib_path),
+            Some(LibLayout::PerFile(lib_path.join("types")))
+        );
+        let result = super::parse_specific_lib(&util::create_uri_from_path(&lib_path));
+        assert_eq!(result.name, "element-ui");
+        let button = result
+            .components
+            .iter()
+            .find(|v| v.name == "ElButton")
+            .unwrap();
+        let props = button.props.iter().map(|v| &v.name[..]).collect::<Vec<_>>();
+        assert_eq!(props, vec!["size", "type"]);
+    }
+
+    #[test]
+    fn single_index_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib_path = dir.path().join("vuetify");
+        std::fs::create_dir_all(lib_path.join("lib")).unwrap();
+        std::fs::create_dir_all(lib_path.join("types")).unwrap();
+        std::fs::write(
+            lib_path.join("package.json"),
+            r#"{ "name": "vuetify", "main": "dist/vuetify.js", "types": "lib/index" }"#,
+        )
+        .unwrap();
+        std::fs::write(lib_path.join("types/index.d.ts"), "").unwrap();
+        std::fs::write(
+            lib_path.join("lib/index.d.ts"),
+            [
+                "import Vue, { VueConstructor } from 'vue';",
+                "export interface VBtnProps {",
+                "  /** 按钮颜色 */",
+                "  color?: string",
+                "  block?: boolean",
+                "}",
+                "export declare const VBtn: VueConstructor<Vue & VBtnProps>;",
+                "export declare const VIcon: VueConstructor<{ dense?: boolean }>;",
+                "export declare const version: string;",
+                "export declare const store: Ref<{ count: number }>;",
+                "export declare const VCard: Vuetify.Component<{ flat?: boolean }>;",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        // package.json 中指定的入口所在目录没有其他声明文件
+        assert_eq!(
+            super::detect_lib_layout(&lib_path),
+            Some(LibLayout::SingleIndex(lib_path.join("lib/index.d.ts")))
+        );
+        let result = super::parse_specific_lib(&util::create_uri_from_path(&lib_path));
+        assert_eq!(result.name, "vuetify");
+        let names = result
+            .components
+            .iter()
+            .map(|v| &v.name[..])
+            .collect::<Vec<_>>();
+        // 非组件类型声明的常量不作为组件
+        assert_eq!(names, vec!["VBtn", "VIcon", "VCard"]);
+        let props = result.components[0]
+            .props
+            .iter()
+            .map(|v| &v.name[..])
+            .collect::<Vec<_>>();
+        assert_eq!(props, vec!["color", "block"]);
+        assert!(result.components[0].props[0].description.is_some());
+        assert_eq!(result.components[1].props[0].name, "dense");
+
+        // 没有 package.json 时根据目录结构判断
+        std::fs::remove_file(lib_path.join("package.json")).unwrap();
+        assert_eq!(
+            super::detect_lib_layout(&lib_path),
+            Some(LibLayout::SingleIndex(lib_path.join("lib/index.d.ts")))
+        );
+        std::fs::remove_file(lib_path.join("lib/index.d.ts")).unwrap();
+        assert_eq!(
+            super::detect_lib_layout(&lib_path),
+            Some(LibLayout::PerFile(lib_path.join("types")))
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use std::sync::OnceLock;
+
 use html_languageservice::html_data::Description;
 use lsp_textdocument::FullTextDocument;
 use swc_common::source_map::SmallPos;
@@ -25,6 +27,8 @@ pub struct TsRenderCache {
     pub ts_component: Option<TsComponent>,
     /// 从当前文件定义并导出的名称
     pub local_exports: Vec<Option<String>>,
+    /// 沿继承链展开后的属性缓存，节点或祖先节点变化时清空
+    pub effective_props: OnceLock<Vec<RenderCacheProp>>,
 }
 
 #[derive(Debug)]
@@ -38,10 +42,11 @@ impl TsRenderCache {
     pub fn update(
         &mut self,
         change: TextDocumentContentChangeEvent,
+        component_decorators: &[String],
     ) -> Option<RenderCacheUpdateResult> {
         self.document
             .update(&[change.clone()], self.document.version() + 1);
-        let result = parse_ts_file(&self.document);
+        let result = parse_ts_file(&self.document, component_decorators);
         self.local_exports = result.local_exports;
         if let Some(ts_component) = result.ts_component {
             self.ts_component = Some(TsComponent {
@@ -53,7 +58,8 @@ impl TsRenderCache {
                 changes: vec![change],
                 is_change: true,
                 extends_component: Some(ts_component.3),
-                registers: Some(ts_component.4),
+                mixins: Some(ts_component.4),
+                registers: Some(ts_component.5),
                 transfers: Some(result.transfers),
             })
         } else {
@@ -63,6 +69,7 @@ impl TsRenderCache {
                 changes: vec![change],
                 is_change: is_change_prop,
                 extends_component: None,
+                mixins: None,
                 registers: None,
                 transfers: Some(result.transfers),
             })
@@ -73,7 +80,10 @@ impl TsRenderCache {
 /// # 解析 ts 文件
 /// 如果 ts 文件默认导出组件，那么进行解析
 /// 如果不存在导入导出组件，那么返回 None
-pub fn parse_ts_file(document: &FullTextDocument) -> ParseTsFileResult {
+pub fn parse_ts_file(
+    document: &FullTextDocument,
+    component_decorators: &[String],
+) -> ParseTsFileResult {
     let source = document.get_content(None);
     let (module, comments) = ast::parse_source(source, 0, source.len());
     if let Err(e) = module {
@@ -88,19 +98,40 @@ pub fn parse_ts_file(document: &FullTextDocument) -> ParseTsFileResult {
     let mut ts_component = None;
     if let Some(ParseScriptResult {
         name_span,
+        functional: _,
         description,
         props,
         extends_component,
+        extends_range: _,
+        mixins,
+        options_mixins: _,
         registers,
+        reserved_members: _,
+        import_paths: _,
+        component_imports: _,
+        decorator_option_keys: _,
+        provide_keys: _,
+        inject_keys: _,
+        options_model: _,
+        local_type_names: _,
         render_insert_offset: _,
+        class_body_start: _,
+        class_nested_ranges: _,
         safe_update_range: _,
-    }) = parse_script::parse_module(&module, &comments, source)
+    }) = parse_script::parse_module(&module, &comments, source, component_decorators)
     {
         let name_range = Range::new(
             document.position_at(name_span.lo.to_u32()),
             document.position_at(name_span.hi.to_u32()),
         );
-        ts_component = Some((name_range, description, props, extends_component, registers));
+        ts_component = Some((
+            name_range,
+            description,
+            props,
+            extends_component,
+            mixins,
+            registers,
+        ));
     }
     let (local_exports, transfers) = ast::get_local_exports_and_transfers(&module);
     ParseTsFileResult {
@@ -115,7 +146,11 @@ pub fn parse_ts_file(document: &FullTextDocument) -> ParseTsFileResult {
 /// * 如果是从其他文件导出，那么返回新的 path 和导出名称
 /// * 如果未找到指定的导出，并且存在所有导出，那么返回所有导出的列表
 /// * 如果未找到指定的导出，那么返回 None
-pub async fn _parse_ts_file_export(uri: &Uri, export_name: &Option<String>) -> TsFileExportResult {
+pub async fn _parse_ts_file_export(
+    uri: &Uri,
+    export_name: &Option<String>,
+    component_decorators: &[String],
+) -> TsFileExportResult {
     let document = Renderer::get_document_from_file(uri).await;
     if let Err(e) = document {
         error!("parse_ts_file_export error {}: {}", uri.as_str(), e);
@@ -128,7 +163,7 @@ pub async fn _parse_ts_file_export(uri: &Uri, export_name: &Option<String>) -> T
         error!("parse_ts_file_export error {}: {:?}", uri.as_str(), e);
         return TsFileExportResult::_None;
     }
-    ast::_get_export_from_module(&module.unwrap(), export_name)
+    ast::_get_export_from_module(&module.unwrap(), export_name, component_decorators)
 }
 
 pub struct ParseTsFileResult {
@@ -137,6 +172,7 @@ pub struct ParseTsFileResult {
         Option<Description>,
         Vec<RenderCacheProp>,
         Option<ExtendsComponent>,
+        Vec<ExtendsComponent>,
         Vec<RegisterComponent>,
     )>,
     /// 从当前文件定义的导出
@@ -1,3 +1,5 @@
+use std::sync::OnceLock;
+
 use html_languageservice::{html_data::Description, parser::html_document::Node};
 use lsp_textdocument::FullTextDocument;
 use swc_common::source_map::SmallPos;
@@ -5,11 +7,12 @@ use tower_lsp::lsp_types::{Position, Range, TextDocumentContentChangeEvent};
 use tracing::debug;
 
 use crate::{
+    css_server::{self, StyleModule},
     lazy::REG_SINGLE_BRACKET,
     renderer::{
         combined_rendered_results, parse_document,
         parse_script::{self, ExtendsComponent, ParseScriptResult, RegisterComponent},
-        template_compile::{self, CompileMapping},
+        template_compile::{self, CompileMapping, SlotProps},
     },
 };
 
@@ -24,43 +27,115 @@ pub struct VueRenderCache {
     pub template: Option<Node>,
     pub script: Option<Node>,
     pub style: Vec<Node>,
+    /// 自定义块，如 `<i18n>`，只记录位置，不参与渲染
+    pub custom_blocks: Vec<Node>,
+    /// 使用 CSS Modules 的样式块
+    pub style_modules: Vec<StyleModule>,
+    /// 是否为函数式组件
+    pub functional: bool,
     // 解析模版
     pub name_range: (usize, usize),
     pub description: Option<Description>,
     pub template_compile_result: FullTextDocument,
     pub mapping: CompileMapping,
+    /// 模版编译使用的子组件作用域插槽属性，增量编译时沿用
+    pub slot_props: SlotProps,
     /// 解析脚本得到的属性
     pub props: Vec<RenderCacheProp>,
     pub render_insert_offset: usize,
+    /// 类体左花括号的位置
+    pub class_body_start: usize,
+    /// 类体中成员的装饰器、参数、方法体和初始值等嵌套结构的范围
+    pub class_nested_ranges: Vec<(usize, usize)>,
+    /// 继承的组件及 `extends` 子句中父类表达式的范围
+    pub extends_clause: Option<(ExtendsComponent, (usize, usize))>,
+    /// `@Component({ mixins: [A, B] })` 中混入组件的标识符
+    pub options_mixins: Vec<String>,
+    /// 追加在渲染结果末尾的类的类型声明
+    pub class_declaration: String,
+    /// 脚本是否存在语法错误，存在时组件信息不再更新
+    pub script_syntax_error: bool,
+    /// 脚本中导入的标识符及声明的类型名称，只在当前文件中可用
+    pub local_type_names: Vec<String>,
+    /// 使用 Vue 保留名称的属性和数据的名称范围
+    pub reserved_members: Vec<(usize, usize)>,
+    /// 所有导入路径及路径字符串的范围
+    pub import_paths: Vec<(String, (usize, usize))>,
+    /// 作为组件使用的导入路径及路径字符串的范围
+    pub component_imports: Vec<(String, (usize, usize))>,
+    /// `@Prop`, `@Model` 等装饰器选项对象中的键及其范围
+    pub decorator_option_keys: Vec<(String, (usize, usize))>,
+    /// `@Component` 选项对象中 `provide` 提供的 key 及其范围
+    pub provide_keys: Vec<(String, (usize, usize))>,
+    /// `@Component` 选项对象中 `inject` 注入的 key 及其范围
+    pub inject_keys: Vec<(String, (usize, usize))>,
+    /// `@Component({ model: { prop, event } })` 中的 model 属性名称和事件名称
+    pub options_model: Option<(String, String)>,
     /// 安全更新范围，处于此范围的更新无需重新解析脚本
     pub safe_update_range: Vec<(usize, usize)>,
+    /// 沿继承链展开后的属性缓存，节点或祖先节点变化时清空
+    pub effective_props: OnceLock<Vec<RenderCacheProp>>,
 }
 
 impl VueRenderCache {
+    /// 脚本是否为 JavaScript
+    pub fn is_js(&self) -> bool {
+        self.script
+            .as_ref()
+            .is_some_and(parse_document::is_js_script)
+    }
+
+    /// 设置子组件的作用域插槽属性，发生变化时重新编译模版，返回是否重新编译
+    pub fn set_slot_props(&mut self, slot_props: SlotProps) -> bool {
+        if self.slot_props == slot_props {
+            return false;
+        }
+        self.slot_props = slot_props;
+        let Some(template) = &self.template else {
+            return false;
+        };
+        let (template_compile_result, mapping) = template_compile::template_compile(
+            template,
+            self.document.get_content(None),
+            self.functional,
+            self.is_js(),
+            &self.slot_props,
+            &self.style_modules,
+        );
+        self.template_compile_result =
+            FullTextDocument::new("typescript".to_string(), 0, template_compile_result);
+        self.mapping = mapping;
+        true
+    }
+
     /// 更新，如果更新失败需要重新解析，那么返回 None
     pub fn update(
         &mut self,
         change: TextDocumentContentChangeEvent,
+        component_decorators: &[String],
     ) -> Option<RenderCacheUpdateResult> {
         let range = change.range.unwrap();
         let range_start = self.document.offset_at(range.start) as usize;
         let range_end = self.document.offset_at(range.end) as usize;
         let range_length = range_end - range_start;
-        // 如果变更处于 style，那么 range 位置向下移动一行
-        let style_range = Range {
-            start: Position {
-                line: range.start.line + 1,
-                character: range.start.character,
-            },
-            end: Position {
-                line: range.end.line + 1,
-                character: range.end.character,
-            },
+        // 变更跨越 render 方法的插入位置时无法转换，进行全量渲染
+        if range_start < self.render_insert_offset && self.render_insert_offset < range_end {
+            return None;
+        }
+        // 变更在渲染结果中的范围，位于插入位置之后时需要移动，与节点的顺序无关
+        let render_range = if range_start < self.render_insert_offset {
+            range
+        } else {
+            Range::new(
+                self.get_render_position(range.start),
+                self.get_render_position(range.end),
+            )
         };
         // 更新缓存文档
         self.document
             .update(&[change.clone()], self.document.version() + 1);
         let source = &self.document.get_content(None).to_string();
+        let is_js = self.is_js();
         // 节点需要增加的偏移量
         let incremental = change.text.len() as isize - range_length as isize;
         // 1. 如果变更处于 template 节点
@@ -87,9 +162,26 @@ impl VueRenderCache {
 
                 if let Some(node) = node {
                     *template = node;
+                    // ref 变更导致类的类型声明变更后进行全量渲染
+                    let class_declaration = combined_rendered_results::get_class_declaration(
+                        &source[self.name_range.0..self.name_range.1],
+                        &self.options_mixins,
+                        &template_compile::get_template_refs(template, source),
+                        self.props.iter().any(|v| v.name == "$refs"),
+                        is_js,
+                    );
+                    if class_declaration != self.class_declaration {
+                        return None;
+                    }
                     // 进行模版编译
-                    let (template_compile_result, mapping) =
-                        template_compile::template_compile(&template, source);
+                    let (template_compile_result, mapping) = template_compile::template_compile(
+                        &template,
+                        source,
+                        self.functional,
+                        is_js,
+                        &self.slot_props,
+                        &self.style_modules,
+                    );
                     let old_template_compile_result_chars_count =
                         self.template_compile_result
                             .get_content(None)
@@ -108,7 +200,7 @@ impl VueRenderCache {
                         changes: vec![
                             // 模版对应位置填充空格
                             TextDocumentContentChangeEvent {
-                                range: change.range,
+                                range: Some(render_range),
                                 range_length: change.range_length,
                                 text: combined_rendered_results::get_fill_space_source(
                                     &change.text,
@@ -131,6 +223,7 @@ impl VueRenderCache {
                         ],
                         is_change: false,
                         extends_component: None,
+                        mixins: None,
                         registers: None,
                         transfers: None,
                     });
@@ -141,7 +234,7 @@ impl VueRenderCache {
                         changes: vec![
                             // 模版对应位置填充空格
                             TextDocumentContentChangeEvent {
-                                range: change.range,
+                                range: Some(render_range),
                                 range_length: change.range_length,
                                 text: combined_rendered_results::get_fill_space_source(
                                     &change.text,
@@ -152,6 +245,7 @@ impl VueRenderCache {
                         ],
                         is_change: false,
                         extends_component: None,
+                        mixins: None,
                         registers: None,
                         transfers: None,
                     });
@@ -177,29 +271,73 @@ impl VueRenderCache {
                 ) {
                     debug!("safe_update");
                     return Some(RenderCacheUpdateResult {
-                        changes: vec![change],
+                        changes: vec![TextDocumentContentChangeEvent {
+                            range: Some(render_range),
+                            ..change
+                        }],
                         is_change: false,
                         extends_component: None,
+                        mixins: None,
                         registers: None,
                         transfers: None,
                     });
                 } else {
+                    let result = parse_script::try_parse_script(
+                        source,
+                        script.start_tag_end.unwrap(),
+                        script.end_tag_start.unwrap(),
+                        component_decorators,
+                    );
+                    self.script_syntax_error = result.is_err();
                     if let Some(ParseScriptResult {
                         name_span,
+                        functional,
                         description,
                         props,
                         render_insert_offset,
+                        class_body_start,
+                        class_nested_ranges,
                         extends_component,
+                        extends_range,
+                        mixins,
+                        options_mixins,
                         registers,
+                        reserved_members,
+                        import_paths,
+                        component_imports,
+                        decorator_option_keys,
+                        provide_keys,
+                        inject_keys,
+                        options_model,
+                        local_type_names,
                         safe_update_range,
-                    }) = parse_script::parse_script(
-                        source,
-                        script.start_tag_end.unwrap(),
-                        script.end_tag_start.unwrap(),
-                    ) {
+                    }) = result.ok().flatten()
+                    {
                         debug!("parse_script success");
+                        // 函数式组件变更后需要重新编译模版，进行全量渲染
+                        if functional != self.functional {
+                            return None;
+                        }
+                        // 类的类型声明变更后进行全量渲染
+                        let class_declaration = combined_rendered_results::get_class_declaration(
+                            &source[name_span.lo.to_usize()..name_span.hi.to_usize()],
+                            &options_mixins,
+                            &self
+                                .template
+                                .as_ref()
+                                .map(|v| template_compile::get_template_refs(v, source))
+                                .unwrap_or_default(),
+                            props.iter().any(|v| v.name == "$refs"),
+                            is_js,
+                        );
+                        if class_declaration != self.class_declaration {
+                            return None;
+                        }
+                        self.options_mixins = options_mixins;
                         // 尝试`解析脚本` 成功
                         self.render_insert_offset = render_insert_offset;
+                        self.class_body_start = class_body_start;
+                        self.class_nested_ranges = class_nested_ranges;
                         self.name_range = (name_span.lo.to_usize(), name_span.hi.to_usize());
                         let is_description_change = self.description != description;
                         self.description = description;
@@ -216,45 +354,33 @@ impl VueRenderCache {
                             }
                             is_change
                         };
-                        let mut changes = vec![change];
+                        // 属性分别以 `let` 和 `const` 从 this 中解构，并且包含继承的属性和全局属性，
+                        // 无法在原位置替换，属性变更后进行全量渲染
                         if is_props_change {
-                            let old_props_length = self
-                                .props
-                                .iter()
-                                .map(|v| v.name.clone())
-                                .collect::<Vec<_>>()
-                                .join(",")
-                                .len() as u32;
-                            let Position { line, character } = self
-                                .document
-                                .position_at(self.render_insert_offset as u32 + 1);
-                            // 属性变更
-                            changes.push(TextDocumentContentChangeEvent {
-                                range: Some(Range {
-                                    start: Position {
-                                        line,
-                                        character: character + 23,
-                                    },
-                                    end: Position {
-                                        line,
-                                        character: character + 23 + old_props_length,
-                                    },
-                                }),
-                                range_length: Some(old_props_length),
-                                text: props
-                                    .iter()
-                                    .map(|v| v.name.clone())
-                                    .collect::<Vec<_>>()
-                                    .join(","),
-                            });
+                            return None;
                         }
+                        let changes = vec![TextDocumentContentChangeEvent {
+                            range: Some(render_range),
+                            ..change
+                        }];
                         self.props = props;
+                        self.extends_clause = extends_component.clone().zip(extends_range);
+                        self.reserved_members = reserved_members;
+                        self.import_paths = import_paths;
+                        self.component_imports = component_imports;
+                        self.decorator_option_keys = decorator_option_keys;
+                        self.provide_keys = provide_keys;
+                        self.inject_keys = inject_keys;
+                        let is_model_change = self.options_model != options_model;
+                        self.options_model = options_model;
+                        self.local_type_names = local_type_names;
 
                         self.safe_update_range = safe_update_range;
                         return Some(RenderCacheUpdateResult {
                             changes,
-                            is_change: is_description_change || is_props_change,
+                            is_change: is_description_change || is_model_change,
                             extends_component: Some(extends_component),
+                            mixins: Some(mixins),
                             registers: Some(registers),
                             transfers: None,
                         });
@@ -263,9 +389,13 @@ impl VueRenderCache {
                         // 解析失败
                         self.safe_update_range = vec![];
                         return Some(RenderCacheUpdateResult {
-                            changes: vec![change],
+                            changes: vec![TextDocumentContentChangeEvent {
+                                range: Some(render_range),
+                                ..change
+                            }],
                             is_change: false,
                             extends_component: None,
+                            mixins: None,
                             registers: None,
                             transfers: None,
                         });
@@ -285,26 +415,116 @@ impl VueRenderCache {
         if is_in_style {
             // 变更处于 style，将变更转换为空格后输出
             self.move_offset(range_start, incremental);
+            let mut changes = vec![
+                // 模版对应位置填充空格
+                TextDocumentContentChangeEvent {
+                    range: Some(render_range),
+                    range_length: change.range_length,
+                    text: combined_rendered_results::get_fill_space_source(&change.text, 0, 0),
+                },
+            ];
+            // CSS Modules 变更
+            let style_modules = css_server::get_style_modules(source, &self.style);
+            // 注入的名称变化时模版需要重新编译
+            if style_modules
+                .iter()
+                .map(|v| &v.name)
+                .ne(self.style_modules.iter().map(|v| &v.name))
+            {
+                return None;
+            }
+            let old_declaration =
+                css_server::get_style_modules_declaration(&self.style_modules, is_js);
+            let declaration = css_server::get_style_modules_declaration(&style_modules, is_js);
+            if self.script.is_some() && declaration != old_declaration {
+                let old_declaration_length = old_declaration.len() as u32;
+                let Position { line, character } =
+                    self.document.position_at(self.render_insert_offset as u32);
+                // 声明位于 render 方法的方法体开头
+                let character = character
+                    + combined_rendered_results::get_render_method_prefix(is_js).len() as u32;
+                // 位置基于更新后的文档，需要在填充空格之后应用，style 位于 script 之前时行号可能变化
+                changes.push(TextDocumentContentChangeEvent {
+                    range: Some(Range {
+                        start: Position { line, character },
+                        end: Position {
+                            line,
+                            character: character + old_declaration_length,
+                        },
+                    }),
+                    range_length: Some(old_declaration_length),
+                    text: declaration,
+                });
+            }
+            self.style_modules = style_modules;
             return Some(RenderCacheUpdateResult {
-                changes: vec![
-                    // 模版对应位置填充空格
-                    TextDocumentContentChangeEvent {
-                        range: Some(style_range),
-                        range_length: change.range_length,
-                        text: combined_rendered_results::get_fill_space_source(&change.text, 0, 0),
-                    },
-                ],
+                changes,
+                is_change: false,
+                extends_component: None,
+                mixins: None,
+                registers: None,
+                transfers: None,
+            });
+        }
+
+        // 4. 如果变更位于自定义块的内容中，将变更转换为空格后输出
+        let is_in_custom_block = self.custom_blocks.iter().any(|block| {
+            let (Some(start_tag_end), Some(end_tag_start), Some(tag)) =
+                (block.start_tag_end, block.end_tag_start, &block.tag)
+            else {
+                return false;
+            };
+            // 变更后的内容中出现结束标签时，块的范围发生变化
+            let end = (end_tag_start as isize + incremental) as usize;
+            start_tag_end <= range_start
+                && range_end < end_tag_start
+                && !source[start_tag_end..end].contains(&format!("</{}", tag))
+        });
+        if is_in_custom_block {
+            self.move_offset(range_start, incremental);
+            return Some(RenderCacheUpdateResult {
+                changes: vec![TextDocumentContentChangeEvent {
+                    range: Some(render_range),
+                    range_length: change.range_length,
+                    text: combined_rendered_results::get_fill_space_source(&change.text, 0, 0),
+                }],
                 is_change: false,
                 extends_component: None,
+                mixins: None,
                 registers: None,
                 transfers: None,
             });
         }
 
-        // 4. 如果变更处于节点边界，返回 None 进行全量渲染
+        // 5. 如果变更处于节点边界，返回 None 进行全量渲染
         None
     }
 
+    /// 获取原文件中位于 render_insert_offset 之后的位置在渲染结果中的位置，要求 document 未更新
+    ///
+    /// 渲染结果在 render_insert_offset 处插入 render 方法并换行，
+    /// 之后的内容向下移动一行，同一行的内容移动到 template_compile_result 之后
+    fn get_render_position(&self, position: Position) -> Position {
+        let insert_position = self.document.position_at(self.render_insert_offset as u32);
+        if position.line == insert_position.line {
+            let template_compile_result_chars_count = self
+                .template_compile_result
+                .get_content(None)
+                .chars()
+                .count() as u32;
+            Position {
+                line: position.line + 1,
+                character: position.character - insert_position.character
+                    + template_compile_result_chars_count,
+            }
+        } else {
+            Position {
+                line: position.line + 1,
+                character: position.character,
+            }
+        }
+    }
+
     /// 从 offset 之后的位置开始移动 incremental，为正向后移动，为负向前移动
     /// 要求 document 已更新
     fn move_offset(&mut self, offset: usize, incremental: isize) {
@@ -346,6 +566,10 @@ impl VueRenderCache {
         for style in &mut self.style {
             move_node(style, offset, incremental);
         }
+        // 移动自定义块
+        for block in &mut self.custom_blocks {
+            move_node(block, offset, incremental);
+        }
         // 移动 name_range
         if offset < self.name_range.0 {
             move_it(&mut self.name_range.0, incremental);
@@ -366,10 +590,70 @@ impl VueRenderCache {
                 move_it(&mut prop.range.1, incremental);
             }
         }
+        // 移动 style_modules
+        for module in &mut self.style_modules {
+            for class in &mut module.classes {
+                if offset < class.1 {
+                    move_it(&mut class.1, incremental);
+                }
+            }
+        }
+        // 移动 reserved_members
+        for item in &mut self.reserved_members {
+            if offset < item.0 {
+                move_it(&mut item.0, incremental);
+                move_it(&mut item.1, incremental);
+            }
+        }
+        // 移动 import_paths
+        for (_, range) in &mut self.import_paths {
+            if offset < range.0 {
+                move_it(&mut range.0, incremental);
+                move_it(&mut range.1, incremental);
+            }
+        }
+        // 移动 extends_clause
+        if let Some((_, range)) = &mut self.extends_clause {
+            if offset < range.0 {
+                move_it(&mut range.0, incremental);
+                move_it(&mut range.1, incremental);
+            }
+        }
+        // 移动 component_imports
+        for (_, range) in &mut self.component_imports {
+            if offset < range.0 {
+                move_it(&mut range.0, incremental);
+                move_it(&mut range.1, incremental);
+            }
+        }
+        // 移动 decorator_option_keys, provide_keys, inject_keys
+        for (_, range) in self
+            .decorator_option_keys
+            .iter_mut()
+            .chain(&mut self.provide_keys)
+            .chain(&mut self.inject_keys)
+        {
+            if offset < range.0 {
+                move_it(&mut range.0, incremental);
+                move_it(&mut range.1, incremental);
+            }
+        }
         // 移动 render_insert_offset
         if offset < self.render_insert_offset {
             move_it(&mut self.render_insert_offset, incremental);
         }
+        // 移动类体的范围
+        if offset < self.class_body_start {
+            move_it(&mut self.class_body_start, incremental);
+        }
+        for item in &mut self.class_nested_ranges {
+            if offset < item.0 {
+                move_it(&mut item.0, incremental);
+            }
+            if offset < item.1 {
+                move_it(&mut item.1, incremental);
+            }
+        }
         // 移动 safe_update_range
         for item in &mut self.safe_update_range {
             if offset < item.0 {
@@ -383,32 +667,62 @@ impl VueRenderCache {
 }
 
 /// 解析 vue 组件
-pub fn parse_vue_file(document: &FullTextDocument) -> ParseVueFileResult {
+pub fn parse_vue_file(
+    document: &FullTextDocument,
+    component_decorators: &[String],
+) -> ParseVueFileResult {
     // 解析文档
-    let (template, script, style) = parse_document::parse_document(&document);
+    let (template, script, style, custom_blocks) = parse_document::parse_document(&document);
 
     let source = document.get_content(None);
     let mut parse_script_result = None;
+    let mut script_syntax_error = false;
     if let Some(script) = &script {
         // 解析脚本
-        parse_script_result = parse_script::parse_script(
+        match parse_script::try_parse_script(
             source,
             script.start_tag_end.unwrap(),
             script.end_tag_start.unwrap(),
-        );
+            component_decorators,
+        ) {
+            Ok(result) => parse_script_result = result,
+            Err(_) => script_syntax_error = true,
+        }
     }
     let result = parse_script_result.unwrap_or_default();
+    let is_js = script.as_ref().is_some_and(parse_document::is_js_script);
+    let class_declaration = combined_rendered_results::get_class_declaration(
+        &source[result.name_span.lo.to_usize()..result.name_span.hi.to_usize()],
+        &result.options_mixins,
+        &template
+            .as_ref()
+            .map(|v| template_compile::get_template_refs(v, source))
+            .unwrap_or_default(),
+        result.props.iter().any(|v| v.name == "$refs"),
+        is_js,
+    );
+    let style_modules = css_server::get_style_modules(source, &style);
     let mut template_compile_result = "".to_string();
     let mut mapping = vec![];
     if let Some(template) = &template {
         // 模版编译
-        (template_compile_result, mapping) = template_compile::template_compile(&template, source);
+        (template_compile_result, mapping) = template_compile::template_compile(
+            &template,
+            source,
+            result.functional,
+            is_js,
+            &SlotProps::new(),
+            &style_modules,
+        );
     }
 
     ParseVueFileResult {
         template,
         script,
         style,
+        custom_blocks,
+        style_modules,
+        functional: result.functional,
         name_range: (
             result.name_span.lo.to_usize(),
             result.name_span.hi.to_usize(),
@@ -416,10 +730,25 @@ pub fn parse_vue_file(document: &FullTextDocument) -> ParseVueFileResult {
         description: result.description,
         props: result.props,
         render_insert_offset: result.render_insert_offset,
+        class_body_start: result.class_body_start,
+        class_nested_ranges: result.class_nested_ranges,
         template_compile_result,
         mapping,
         extends_component: result.extends_component,
+        extends_range: result.extends_range,
+        mixins: result.mixins,
+        options_mixins: result.options_mixins,
+        class_declaration,
+        script_syntax_error,
+        local_type_names: result.local_type_names,
         registers: result.registers,
+        reserved_members: result.reserved_members,
+        import_paths: result.import_paths,
+        component_imports: result.component_imports,
+        decorator_option_keys: result.decorator_option_keys,
+        provide_keys: result.provide_keys,
+        inject_keys: result.inject_keys,
+        options_model: result.options_model,
         safe_update_range: result.safe_update_range,
     }
 }
@@ -429,15 +758,33 @@ pub struct ParseVueFileResult {
     pub template: Option<Node>,
     pub script: Option<Node>,
     pub style: Vec<Node>,
+    pub custom_blocks: Vec<Node>,
+    pub style_modules: Vec<StyleModule>,
+    pub functional: bool,
     pub name_range: (usize, usize),
     pub description: Option<Description>,
     /// 渲染得到的属性
     pub props: Vec<RenderCacheProp>,
     pub render_insert_offset: usize,
+    pub class_body_start: usize,
+    pub class_nested_ranges: Vec<(usize, usize)>,
     pub template_compile_result: String,
     pub mapping: CompileMapping,
     pub extends_component: Option<ExtendsComponent>,
+    pub extends_range: Option<(usize, usize)>,
+    pub mixins: Vec<ExtendsComponent>,
+    pub options_mixins: Vec<String>,
+    pub class_declaration: String,
+    pub script_syntax_error: bool,
+    pub local_type_names: Vec<String>,
     pub registers: Vec<RegisterComponent>,
+    pub reserved_members: Vec<(usize, usize)>,
+    pub import_paths: Vec<(String, (usize, usize))>,
+    pub component_imports: Vec<(String, (usize, usize))>,
+    pub decorator_option_keys: Vec<(String, (usize, usize))>,
+    pub provide_keys: Vec<(String, (usize, usize))>,
+    pub inject_keys: Vec<(String, (usize, usize))>,
+    pub options_model: Option<(String, String)>,
     pub safe_update_range: Vec<(usize, usize)>,
 }
 
@@ -483,72 +830,80 @@ mod tests {
     use swc_common::source_map::SmallPos;
     use tower_lsp::lsp_types::{Position, Range, TextDocumentContentChangeEvent};
 
-    use crate::renderer::{
-        combined_rendered_results, parse_document, parse_script, template_compile,
+    use crate::{
+        ast,
+        renderer::{combined_rendered_results, parse_document, parse_script, template_compile},
     };
 
     use super::VueRenderCache;
 
     fn assert_update(changes: &[TextDocumentContentChangeEvent]) {
-        let mut document = FullTextDocument::new(
-            "vue".to_string(),
-            0,
-            [
-                // 0    5   10   15   20   25   30   35   40   45   50   55   60
-                r#"<template>"#,
-                r#"  <div>"#,
-                r#"    <MyComponent1 :title="title"></MyComponent1>"#, // value expr
-                r#"    <div>{{ content }}</div>"#,                     // content expr
-                r#"    <MyComponent2 v-if="condition1" />"#,           // condition expr
-                r#"    <Empty v-else :description="'text'" />"#,
-                r#"  </div>"#,
-                r#"</template>"#,
-                r#"<script lang="ts">"#,
-                r#"import Vue from "vue";"#,
-                r#"import { Component, Model, Prop } from "vue-property-decorator";"#,
-                r#"import { Empty } from "ant-design-vue";"#,
-                r#"import MyComponent1 from "./my-component1.vue";"#,
-                r#"import MyComponent2 from "./my-component2.vue";"#,
-                r#"@Component({"#,
-                r#"  components: {"#,
-                r#"    Empty,"#,
-                r#"    MyComponent1,"#,
-                r#"    MyComponent2,"#,
-                r#"  },"#,
-                r#"})"#,
-                r#"export default class App extends Vue {"#,
-                r#"  @Model("change", { type: Number, required: true })"#,
-                r#"  private value!: number;"#,
-                r#"  @Prop({ type: String, default: "" })"#,
-                r#"  private prop1!: string;"#,
-                r#"  private prop2 = "";"#,
-                r#"  public prop3 = "";"#,
-                r#"  private get prop4() {"#,
-                r#"    return this.value === 1;"#,
-                r#"  }"#,
-                r#"  created() {"#,
-                r#"    this.calc();"#,
-                r#"  }"#,
-                r#"  private calc() {"#,
-                r#"    123 + 456;"#,
-                r#"  }"#,
-                r#"}"#,
-                r#"</script>"#,
-                r#"<style>"#,
-                r#".root {"#,
-                r#"  display: flex;"#,
-                r#"}"#,
-                r#"</style>"#,
-            ]
-            .join("\n"),
-        );
+        assert_update_source(get_update_source(), changes);
+    }
+
+    fn get_update_source() -> String {
+        [
+            // 0    5   10   15   20   25   30   35   40   45   50   55   60
+            r#"<template>"#,
+            r#"  <div>"#,
+            r#"    <MyComponent1 :title="title"></MyComponent1>"#, // value expr
+            r#"    <div>{{ content }}</div>"#,                     // content expr
+            r#"    <MyComponent2 v-if="condition1" />"#,           // condition expr
+            r#"    <Empty v-else :description="'text'" />"#,
+            r#"  </div>"#,
+            r#"</template>"#,
+            r#"<script lang="ts">"#,
+            r#"import Vue from "vue";"#,
+            r#"import { Component, Model, Prop } from "vue-property-decorator";"#,
+            r#"import { Empty } from "ant-design-vue";"#,
+            r#"import MyComponent1 from "./my-component1.vue";"#,
+            r#"import MyComponent2 from "./my-component2.vue";"#,
+            r#"@Component({"#,
+            r#"  components: {"#,
+            r#"    Empty,"#,
+            r#"    MyComponent1,"#,
+            r#"    MyComponent2,"#,
+            r#"  },"#,
+            r#"})"#,
+            r#"export default class App extends Vue {"#,
+            r#"  @Model("change", { type: Number, required: true })"#,
+            r#"  private value!: number;"#,
+            r#"  @Prop({ type: String, default: "" })"#,
+            r#"  private prop1!: string;"#,
+            r#"  private prop2 = "";"#,
+            r#"  public prop3 = "";"#,
+            r#"  private get prop4() {"#,
+            r#"    return this.value === 1;"#,
+            r#"  }"#,
+            r#"  created() {"#,
+            r#"    this.calc();"#,
+            r#"  }"#,
+            r#"  private calc() {"#,
+            r#"    123 + 456;"#,
+            r#"  }"#,
+            r#"}"#,
+            r#"</script>"#,
+            r#"<style module>"#,
+            r#".root {"#,
+            r#"  display: flex;"#,
+            r#"}"#,
+            r#"</style>"#,
+        ]
+        .join("\n")
+    }
+
+    fn assert_update_source(source: String, changes: &[TextDocumentContentChangeEvent]) {
+        let mut document = FullTextDocument::new("vue".to_string(), 0, source);
         let mut cache = create_vue_render_cache(&document);
         let mut old_render_result = get_render_content(&cache);
 
         // update
         for (i, change) in changes.iter().enumerate() {
             document.update(&[change.clone()], i as i32 + 1);
-            let render_changes = cache.update(change.clone()).unwrap().changes;
+            let render_changes = cache
+                .update(change.clone(), &ast::get_default_component_decorators())
+                .unwrap()
+                .changes;
             let render_result = get_render_content(&cache);
 
             // old_render_result + changes = render_result
@@ -567,6 +922,8 @@ mod tests {
         assert_eq!(cache.template, expected.template);
         assert_eq!(cache.script, expected.script);
         assert_eq!(cache.style, expected.style);
+        assert_eq!(cache.custom_blocks, expected.custom_blocks);
+        assert_eq!(cache.style_modules, expected.style_modules);
         assert_eq!(cache.name_range, expected.name_range);
         assert_eq!(cache.description, expected.description);
         assert_eq!(
@@ -576,26 +933,62 @@ mod tests {
         assert_eq!(cache.mapping, expected.mapping);
         assert_eq!(cache.props, expected.props);
         assert_eq!(cache.render_insert_offset, expected.render_insert_offset);
+        assert_eq!(cache.class_body_start, expected.class_body_start);
+        assert_eq!(cache.class_nested_ranges, expected.class_nested_ranges);
+        assert_eq!(cache.extends_clause, expected.extends_clause);
+        assert_eq!(cache.options_mixins, expected.options_mixins);
+        assert_eq!(cache.class_declaration, expected.class_declaration);
+        assert_eq!(cache.script_syntax_error, expected.script_syntax_error);
+        assert_eq!(cache.local_type_names, expected.local_type_names);
+        assert_eq!(cache.reserved_members, expected.reserved_members);
+        assert_eq!(cache.import_paths, expected.import_paths);
+        assert_eq!(cache.component_imports, expected.component_imports);
+        assert_eq!(cache.decorator_option_keys, expected.decorator_option_keys);
+        assert_eq!(cache.provide_keys, expected.provide_keys);
+        assert_eq!(cache.inject_keys, expected.inject_keys);
         assert_eq!(cache.safe_update_range, expected.safe_update_range);
     }
 
     fn create_vue_render_cache(document: &FullTextDocument) -> VueRenderCache {
         let source = document.get_content(None);
-        let (template, script, style) = parse_document::parse_document(&document);
+        let (template, script, style, custom_blocks) = parse_document::parse_document(&document);
         let mut result = None;
+        let mut script_syntax_error = false;
         if let Some(script) = &script {
-            result = parse_script::parse_script(
+            match parse_script::try_parse_script(
                 source,
                 script.start_tag_end.unwrap(),
                 script.end_tag_start.unwrap(),
-            );
+                &ast::get_default_component_decorators(),
+            ) {
+                Ok(v) => result = v,
+                Err(_) => script_syntax_error = true,
+            }
         }
         let result = result.unwrap_or_default();
+        let is_js = script.as_ref().is_some_and(parse_document::is_js_script);
+        let class_declaration = combined_rendered_results::get_class_declaration(
+            &source[result.name_span.lo.to_usize()..result.name_span.hi.to_usize()],
+            &result.options_mixins,
+            &template
+                .as_ref()
+                .map(|v| template_compile::get_template_refs(v, source))
+                .unwrap_or_default(),
+            result.props.iter().any(|v| v.name == "$refs"),
+            is_js,
+        );
+        let style_modules = css_server::get_style_modules(source, &style);
         let mut template_compile_result = String::new();
         let mut mapping = vec![];
         if let Some(template) = &template {
-            (template_compile_result, mapping) =
-                template_compile::template_compile(&template, source);
+            (template_compile_result, mapping) = template_compile::template_compile(
+                &template,
+                source,
+                result.functional,
+                is_js,
+                &SlotProps::new(),
+                &style_modules,
+            );
         }
         VueRenderCache {
             document: FullTextDocument::new(
@@ -603,9 +996,12 @@ mod tests {
                 document.version(),
                 source.to_string(),
             ),
+            style_modules,
+            functional: result.functional,
             template,
             script,
             style,
+            custom_blocks,
             name_range: (
                 result.name_span.lo.to_usize(),
                 result.name_span.hi.to_usize(),
@@ -617,9 +1013,25 @@ mod tests {
                 template_compile_result,
             ),
             mapping,
+            slot_props: SlotProps::new(),
             props: result.props,
             render_insert_offset: result.render_insert_offset,
+            class_body_start: result.class_body_start,
+            class_nested_ranges: result.class_nested_ranges,
+            extends_clause: result.extends_component.zip(result.extends_range),
+            options_mixins: result.options_mixins,
+            class_declaration,
+            script_syntax_error,
+            local_type_names: result.local_type_names,
+            reserved_members: result.reserved_members,
+            import_paths: result.import_paths,
+            component_imports: result.component_imports,
+            decorator_option_keys: result.decorator_option_keys,
+            provide_keys: result.provide_keys,
+            inject_keys: result.inject_keys,
+            options_model: result.options_model,
             safe_update_range: result.safe_update_range,
+            effective_props: OnceLock::new(),
         }
     }
 
@@ -629,9 +1041,23 @@ mod tests {
                 script.start_tag_end.unwrap(),
                 script.end_tag_start.unwrap(),
                 &cache.template_compile_result.get_content(None),
-                &cache.props.iter().map(|v| &v.name[..]).collect::<Vec<_>>(),
+                &cache
+                    .props
+                    .iter()
+                    .filter(|v| !v.is_readonly())
+                    .map(|v| &v.name[..])
+                    .collect::<Vec<_>>(),
+                &cache
+                    .props
+                    .iter()
+                    .filter(|v| v.is_readonly())
+                    .map(|v| &v.name[..])
+                    .collect::<Vec<_>>(),
+                &css_server::get_style_modules_declaration(&cache.style_modules, cache.is_js()),
                 cache.render_insert_offset,
                 cache.document.get_content(None),
+                cache.is_js(),
+                &cache.class_declaration,
             )
         } else {
             String::new()
@@ -942,7 +1368,7 @@ mod tests {
                 },
             }),
             range_length: Some(40),
-            text: "".to_string(),
+            text: "    this.calc();\n  }\n\n  private calc() {\n".to_string(),
         }]);
         // 处于安全更新范围外
         assert_update(&[TextDocumentContentChangeEvent {
@@ -957,7 +1383,7 @@ mod tests {
                 },
             }),
             range_length: Some(0),
-            text: "private prop5 = \"\";\n".to_string(),
+            text: "\n".to_string(),
         }]);
     }
 
@@ -1010,10 +1436,32 @@ mod tests {
                 },
             }),
             range_length: Some(0),
-            text: "private prop5 = 1;\n".to_string(),
+            text: "\n\n".to_string(),
         }]);
     }
 
+    #[test]
+    fn script_props_change_update() {
+        // 属性变更后无法在原位置替换，返回 None 进行全量渲染
+        let change = |start: u32, end: u32, text: &str| TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(start, 0), Position::new(end, 0))),
+            range_length: None,
+            text: text.to_string(),
+        };
+        for change in [
+            // 添加属性
+            change(28, 28, "  private prop5 = 1;\n"),
+            // 删除方法
+            change(34, 37, ""),
+        ] {
+            let document = FullTextDocument::new("vue".to_string(), 0, get_update_source());
+            let mut cache = create_vue_render_cache(&document);
+            assert!(cache
+                .update(change, &ast::get_default_component_decorators())
+                .is_none());
+        }
+    }
+
     #[test]
     fn script_add_utf8_char() {
         assert_update(&[
@@ -1079,4 +1527,86 @@ mod tests {
             text: "-container".to_string(),
         }]);
     }
+
+    #[test]
+    fn style_before_script_update() {
+        let change = |line: u32, character: u32, text: &str| TextDocumentContentChangeEvent {
+            range: Some(Range::new(
+                Position::new(line, character),
+                Position::new(line, character),
+            )),
+            range_length: Some(0),
+            text: text.to_string(),
+        };
+        assert_update_source(
+            [
+                r#"<style module>"#,
+                r#".root {"#,
+                r#"  display: flex;"#,
+                r#"}"#,
+                r#"</style>"#,
+                r#"<script lang="ts">"#,
+                r#"import Vue from "vue";"#,
+                r#"import { Component } from "vue-property-decorator";"#,
+                r#"@Component"#,
+                r#"export default class App extends Vue {"#,
+                r#"  private content = "";"#,
+                r#"}"#,
+                r#"</script>"#,
+                r#"<template>"#,
+                r#"  <div :class="$style.root">{{ content }}</div>"#,
+                r#"</template>"#,
+            ]
+            .join("\n"),
+            &[
+                // style 位于 script 之前，不需要移动
+                change(1, 5, "-container"),
+                change(2, 16, "\n}\n.title {\n  color: red;"),
+                // template 位于 script 之后，需要向下移动一行
+                change(17, 38, " "),
+                change(17, 31, "\n"),
+            ],
+        );
+    }
+
+    #[test]
+    fn custom_block_update() {
+        let change = |line: u32, character: u32, text: &str| TextDocumentContentChangeEvent {
+            range: Some(Range::new(
+                Position::new(line, character),
+                Position::new(line, character),
+            )),
+            range_length: Some(0),
+            text: text.to_string(),
+        };
+        assert_update_source(
+            [
+                r#"<i18n lang="json">"#,
+                r#"{ "en": { "hello": "Hello" } }"#,
+                r#"</i18n>"#,
+                r#"<template>"#,
+                r#"  <div>{{ content }}</div>"#,
+                r#"</template>"#,
+                r#"<script lang="ts">"#,
+                r#"import Vue from "vue";"#,
+                r#"import { Component } from "vue-property-decorator";"#,
+                r#"@Component"#,
+                r#"export default class App extends Vue {"#,
+                r#"  private content = "";"#,
+                r#"}"#,
+                r#"</script>"#,
+                r#"<docs>"#,
+                r#"# App"#,
+                r#"</docs>"#,
+            ]
+            .join("\n"),
+            &[
+                // 位于 script 之前
+                change(1, 28, ", \"zh\": { \"hello\": \"<b>你好</b>\" }"),
+                change(1, 0, "\n"),
+                // 位于 script 之后，需要向下移动一行
+                change(16, 5, "\n\n<Example />"),
+            ],
+        );
+    }
 }
@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// Vue 实例上保留的属性和方法名称
+    static ref RESERVED_NAMES: HashSet<&'static str> = HashSet::from([
+        "$el",
+        "$data",
+        "$props",
+        "$options",
+        "$parent",
+        "$root",
+        "$children",
+        "$refs",
+        "$slots",
+        "$scopedSlots",
+        "$isServer",
+        "$ssrContext",
+        "$vnode",
+        "$attrs",
+        "$listeners",
+        "$watch",
+        "$set",
+        "$delete",
+        "$on",
+        "$once",
+        "$off",
+        "$emit",
+        "$mount",
+        "$forceUpdate",
+        "$destroy",
+        "$nextTick",
+        "$createElement",
+        "_uid",
+        "_isVue",
+        "_self",
+        "_data",
+        "_props",
+        "_vnode",
+        "_watcher",
+        "_watchers",
+        "_events",
+        "_renderProxy",
+    ]);
+}
+
+/// 获取保留名称的提示信息，如果不是保留名称，返回 None
+///
+/// 以 `$` 或 `_` 开头的属性不会被 Vue 代理到实例上
+pub fn get_reserved_name_message(name: &str) -> Option<String> {
+    if RESERVED_NAMES.contains(name) {
+        Some(format!("'{}' is a reserved Vue instance property.", name))
+    } else if name.starts_with('$') || name.starts_with('_') {
+        Some(format!(
+            "'{}' starts with a reserved prefix, properties starting with '$' or '_' are not proxied by Vue.",
+            name
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn reserved_name_message() {
+        assert!(super::get_reserved_name_message("$el")
+            .is_some_and(|v| v.contains("reserved Vue instance property")));
+        assert!(super::get_reserved_name_message("_uid")
+            .is_some_and(|v| v.contains("reserved Vue instance property")));
+        assert!(super::get_reserved_name_message("$custom")
+            .is_some_and(|v| v.contains("reserved prefix")));
+        assert!(super::get_reserved_name_message("_private")
+            .is_some_and(|v| v.contains("reserved prefix")));
+        assert_eq!(super::get_reserved_name_message("title"), None);
+    }
+}
@@ -1,23 +1,25 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::Deref;
 use std::sync::Arc;
 
-use html_languageservice::html_data::{IAttributeData, ITagData, IValueData};
+use html_languageservice::html_data::{Description, IAttributeData, ITagData, IValueData};
 use html_languageservice::language_facts::data_provider::{
     generate_documentation, GenerateDocumentationItem, GenerateDocumentationSetting,
     HTMLDataProviderContent, IHTMLDataProvider,
 };
+use html_languageservice::parser::html_document::Node;
 use html_languageservice::participant::{
     HtmlAttributeValueContext, HtmlContentContext, ICompletionParticipant,
 };
 use regex::Regex;
 use tower_lsp::lsp_types::{
     CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, InsertTextFormat,
-    Position, Range, TextEdit, Uri,
+    MarkupContent, MarkupKind, Position, Range, TextEdit, Uri,
 };
 use tracing::debug;
 
-use crate::util;
+use crate::{convert, util};
 
 use super::render_cache::RenderCache;
 use super::Renderer;
@@ -33,77 +35,35 @@ impl Renderer {
         }
         debug!("create TagsProvider");
         let mut tags = vec![];
+        let mut details = HashMap::new();
         // 获取当前节点注册的组件
-        let registers = self.render_cache.get_registers(uri);
+        let registers = self
+            .render_cache
+            .get_registers(uri)
+            .into_iter()
+            .map(|(name, export_name, prop, uri)| (name, export_name, prop, uri.clone()))
+            .collect::<Vec<_>>();
         for (register_name, mut export_name, prop, mut register_uri) in registers {
-            let mut cache = &self.render_cache[register_uri];
+            // 注册的组件所在的节点及组件描述
+            let mut component = None;
             loop {
-                match cache {
+                match &self.render_cache[&register_uri] {
                     RenderCache::VueRenderCache(cache) => {
-                        let mut attributes = vec![];
-                        for prop in &cache.props {
-                            if prop.prop_params.is_some() {
-                                attributes.push(IAttributeData {
-                                    name: prop.name.clone(),
-                                    description: prop.description.clone(),
-                                    value_set: None,
-                                    values: None,
-                                    references: None,
-                                });
-                                attributes.push(IAttributeData {
-                                    name: format!(":{}", prop.name),
-                                    description: prop.description.clone(),
-                                    value_set: None,
-                                    values: None,
-                                    references: None,
-                                });
-                            }
-                        }
-                        tags.push(ITagData {
-                            name: register_name.clone(),
-                            description: cache.description.clone(),
-                            attributes,
-                            references: None,
-                            void: None,
-                        });
+                        component = Some((register_uri.clone(), cache.description.clone()));
                         break;
                     }
                     RenderCache::TsRenderCache(ts_cache) => {
-                        if let Some(ts_component) = &ts_cache.ts_component {
-                            let mut attributes = vec![];
-                            for prop in &ts_component.props {
-                                if prop.prop_params.is_some() {
-                                    attributes.push(IAttributeData {
-                                        name: prop.name.clone(),
-                                        description: prop.description.clone(),
-                                        value_set: None,
-                                        values: None,
-                                        references: None,
-                                    });
-                                    attributes.push(IAttributeData {
-                                        name: format!(":{}", prop.name),
-                                        description: prop.description.clone(),
-                                        value_set: None,
-                                        values: None,
-                                        references: None,
-                                    });
-                                }
-                            }
-                            tags.push(ITagData {
-                                name: register_name.clone(),
-                                description: ts_component.description.clone(),
-                                attributes,
-                                references: None,
-                                void: None,
-                            });
+                        // 只有默认导出才是当前文件中的组件，具名导出需要沿转换关系继续查找
+                        if let (None, Some(ts_component)) = (&export_name, &ts_cache.ts_component) {
+                            component =
+                                Some((register_uri.clone(), ts_component.description.clone()));
                             break;
                         } else {
                             if let Some((transfer_uri, name)) = self
                                 .render_cache
-                                .get_transfer_node(register_uri, &export_name)
+                                .get_transfer_node(&register_uri, &export_name)
                             {
-                                cache = &self.render_cache[transfer_uri];
-                                register_uri = transfer_uri;
+                                register_uri = transfer_uri.clone();
                                 export_name = name;
                             } else {
                                 break;
@@ -155,15 +115,64 @@ impl Renderer {
                     }
                 }
             }
+            // 使用沿继承链展开后的属性，必填属性排在前面
+            if let Some((component_uri, description)) = component {
+                let mut attributes = vec![];
+                let mut props = self
+                    .get_effective_props(&component_uri)
+                    .into_iter()
+                    .filter_map(|v| v.prop_params.clone().map(|params| (v, params)))
+                    .collect::<Vec<_>>();
+                props.sort_by_key(|(_, params)| !params.required);
+                for (prop, params) in props {
+                    // 同时提供驼峰和短横线两种形式
+                    let mut names = vec![prop.name.clone()];
+                    let kebab_name = util::to_kebab_case(&prop.name);
+                    if kebab_name != prop.name {
+                        names.push(kebab_name);
+                    }
+                    for name in names {
+                        for name in [name.clone(), format!(":{}", name)] {
+                            details.insert(
+                                (register_name.clone(), name.clone()),
+                                AttributeDetail {
+                                    typ: params.typ.clone(),
+                                    required: params.required,
+                                    validator: params.validator,
+                                },
+                            );
+                            let description = if params.validator {
+                                append_validator_note(prop.description.clone())
+                            } else {
+                                prop.description.clone()
+                            };
+                            attributes.push(IAttributeData {
+                                name,
+                                description,
+                                value_set: None,
+                                values: None,
+                                references: None,
+                            });
+                        }
+                    }
+                }
+                tags.push(ITagData {
+                    name: register_name,
+                    description: append_module_path(description, &component_uri),
+                    attributes,
+                    references: None,
+                    void: None,
+                });
+            }
         }
         // TODO: 获取继承节点注册的组件
-        let provider = ArcTagsProvider::new(util::to_file_path_string(uri), tags, version);
+        let provider = ArcTagsProvider::new(util::to_file_path_string(uri), tags, details, version);
         self.provider_map.insert(uri.clone(), provider.clone());
         provider
     }
 
     fn get_document_version(&self, uri: &Uri) -> Option<i32> {
-        let cache = &self.render_cache[uri];
+        let cache = self.render_cache.get(uri)?;
         if let RenderCache::VueRenderCache(cache) = cache {
             Some(cache.document.version())
         } else {
@@ -172,11 +181,56 @@ impl Renderer {
     }
 }
 
+/// 在组件描述后追加组件所在的源文件路径，与脚本中的悬停信息保持一致
+pub(super) fn append_module_path(
+    description: Option<Description>,
+    uri: &Uri,
+) -> Option<Description> {
+    let module = convert::get_module_markdown(&util::to_file_path_string(uri));
+    let value = match description {
+        Some(Description::MarkupContent(markup)) => format!("{}{}", markup.value, module),
+        Some(Description::String(value)) => format!("{}\n{}", value, module),
+        None => module,
+    };
+    Some(Description::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    }))
+}
+
+/// 在属性说明后追加存在 validator 的提示
+fn append_validator_note(description: Option<Description>) -> Option<Description> {
+    let note = "*has validator*";
+    let value = match description {
+        Some(Description::MarkupContent(markup)) => format!("{}\n\n{}", markup.value, note),
+        Some(Description::String(value)) => format!("{}\n\n{}", value, note),
+        None => note.to_string(),
+    };
+    Some(Description::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    }))
+}
+
+/// 获取属性对应的短横线形式的 prop 名称，如 `:titleText.sync` 对应 `title-text`
+pub(super) fn get_prop_name_of_attribute(name: &str) -> String {
+    let name = name
+        .strip_prefix("v-bind:")
+        .or_else(|| name.strip_prefix(':'))
+        .unwrap_or(name);
+    util::to_kebab_case(name.split('.').next().unwrap_or(name))
+}
+
 pub struct ArcTagsProvider(Arc<TagsProvider>);
 
 impl ArcTagsProvider {
-    pub fn new(id: String, tags: Vec<ITagData>, version: Option<i32>) -> Self {
-        ArcTagsProvider(Arc::new(TagsProvider::new(id, tags, version)))
+    pub fn new(
+        id: String,
+        tags: Vec<ITagData>,
+        details: HashMap<(String, String), AttributeDetail>,
+        version: Option<i32>,
+    ) -> Self {
+        ArcTagsProvider(Arc::new(TagsProvider::new(id, tags, details, version)))
     }
 }
 
@@ -194,21 +248,72 @@ impl Clone for ArcTagsProvider {
     }
 }
 
+/// 组件属性的补充信息
+#[derive(Clone, Debug)]
+pub struct AttributeDetail {
+    pub typ: Option<String>,
+    pub required: bool,
+    /// 是否存在 validator
+    pub validator: bool,
+}
+
 #[derive(Clone)]
 pub struct TagsProvider {
     id: String,
     tags: Vec<ITagData>,
+    /// (标签名, 属性名) -> 属性的补充信息
+    details: HashMap<(String, String), AttributeDetail>,
     version: Option<i32>,
 }
 
 impl TagsProvider {
-    pub fn new(id: String, tags: Vec<ITagData>, version: Option<i32>) -> TagsProvider {
-        TagsProvider { id, version, tags }
+    pub fn new(
+        id: String,
+        tags: Vec<ITagData>,
+        details: HashMap<(String, String), AttributeDetail>,
+        version: Option<i32>,
+    ) -> TagsProvider {
+        TagsProvider {
+            id,
+            version,
+            tags,
+            details,
+        }
     }
 
     pub fn version(&self) -> Option<i32> {
         return self.version;
     }
+
+    /// 获取标签上尚未传递的属性
+    ///
+    /// 已存在任意形式（驼峰、短横线、`:`、`v-bind:` 或带修饰符）的同名属性时跳过，光标所在的属性除外
+    pub fn get_absent_attributes(
+        &self,
+        tag: &str,
+        node: &Node,
+        offset: usize,
+    ) -> Vec<&IAttributeData> {
+        let exist_attrs = node
+            .attributes
+            .iter()
+            .filter(|(name, attr)| !(attr.offset <= offset && offset <= attr.offset + name.len()))
+            .map(|(name, _)| get_prop_name_of_attribute(name))
+            .collect::<Vec<_>>();
+        let Some(tag_data) = self.tags.iter().find(|t| t.name == tag) else {
+            return vec![];
+        };
+        tag_data
+            .attributes
+            .iter()
+            .filter(|attr| !exist_attrs.contains(&get_prop_name_of_attribute(&attr.name)))
+            .collect()
+    }
+
+    /// 获取组件属性的补充信息
+    pub fn get_attribute_detail(&self, tag: &str, attr: &str) -> Option<&AttributeDetail> {
+        self.details.get(&(tag.to_string(), attr.to_string()))
+    }
 }
 
 impl Debug for TagsProvider {
@@ -239,27 +344,7 @@ impl IHTMLDataProvider for ArcTagsProvider {
             .html_document
             .find_node_at(content.offset, &mut vec![])
             .unwrap();
-        let exist_attrs = node.attribute_names();
-        let tag_data = self.tags.iter().find(|t| t.name == tag);
-        let mut result = vec![];
-        if let Some(tag_data) = tag_data {
-            for attr in &tag_data.attributes {
-                if exist_attrs.contains(&&attr.name) {
-                    continue;
-                }
-                if attr.name.starts_with(":")
-                    && exist_attrs
-                        .iter()
-                        .map(|v| &v[..])
-                        .collect::<Vec<&str>>()
-                        .contains(&&attr.name[1..])
-                {
-                    continue;
-                }
-                result.push(attr);
-            }
-        }
-        result
+        self.get_absent_attributes(tag, &node, content.offset)
     }
 
     fn provide_values(&self, _tag: &str, _attribute: &str) -> Vec<&IValueData> {
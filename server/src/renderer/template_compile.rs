@@ -1,17 +1,119 @@
+use std::collections::HashMap;
+
 use html_languageservice::parser::html_document::Node;
 use multi_line_stream::MultiLineStream;
 
-use crate::lazy::{REG_DOUBLE_BRACES, REG_V_FOR_WITH_INDEX};
+use crate::{
+    css_server::StyleModule,
+    lazy::{REG_DOUBLE_BRACES, REG_V_FOR_WITH_INDEX},
+    util, vue_data,
+};
 
 /// 模版编译，返回 template_compile_result, mapping
-pub fn template_compile(template: &Node, source: &str) -> (String, CompileMapping) {
-    let mut result = TemplateCompileResult::new();
-    compile_node(template, source, &mut result);
+///
+/// 函数式组件没有实例，模版中的标识符不加 `this.` 前缀
+///
+/// 存在多个根元素时只编译第一个根元素及其 `v-else-if`/`v-else` 分支
+///
+/// 脚本为 JavaScript 时不输出类型断言
+///
+/// 作用域插槽的变量类型取自 `slot_props`，未知的插槽或变量为 `any`
+///
+/// `style_modules` 中以 `$` 开头的名称在 render 方法中声明，不加 `this.` 前缀
+pub fn template_compile(
+    template: &Node,
+    source: &str,
+    functional: bool,
+    is_js: bool,
+    slot_props: &SlotProps,
+    style_modules: &[StyleModule],
+) -> (String, CompileMapping) {
+    let mut result = TemplateCompileResult::new(functional, is_js, slot_props, style_modules);
+    let extra_roots = get_extra_roots(template)
+        .iter()
+        .map(|v| v.start)
+        .collect::<Vec<_>>();
+    compile_node(template, source, &mut result, &extra_roots);
     (result.render, result.mapping)
 }
 
-fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult) {
-    let mut close_str = "";
+/// 获取模版中多余的根元素
+///
+/// 第一个根元素以及与其通过 `v-if`/`v-else-if`/`v-else` 连接的元素为有效根元素
+pub fn get_extra_roots(template: &Node) -> Vec<&Node> {
+    let mut extra_roots = vec![];
+    let mut roots = template.children.iter().filter(|v| v.tag.is_some());
+    let Some(first) = roots.next() else {
+        return extra_roots;
+    };
+    let mut in_chain = first.attributes.contains_key("v-if");
+    for root in roots {
+        if in_chain && root.attributes.contains_key("v-else-if") {
+            continue;
+        }
+        if in_chain && root.attributes.contains_key("v-else") {
+            in_chain = false;
+            continue;
+        }
+        in_chain = false;
+        extra_roots.push(root);
+    }
+    extra_roots
+}
+
+/// 是否存在没有参数的 `v-bind="object"` 或 `:="object"`，即通过对象展开传递属性
+pub fn has_spread_bind(node: &Node) -> bool {
+    node.attributes.keys().any(|key| {
+        key.strip_prefix("v-bind")
+            .or_else(|| key.strip_prefix(':'))
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// 获取属性值在文档中的起始偏移量，属性值包含引号
+///
+/// 跳过属性名称之后的 `=` 及其两侧的空白
+pub fn get_attr_value_offset(source: &str, attr_offset: usize, name: &str) -> usize {
+    let rest = source[attr_offset + name.len()..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest).trim_start();
+    source.len() - rest.len()
+}
+
+/// 模版中的 ref
+#[derive(Debug, PartialEq)]
+pub struct TemplateRef {
+    pub name: String,
+    /// ref 值的范围，不包含引号
+    pub range: (usize, usize),
+    /// 是否位于 `v-for` 中，位于 `v-for` 中的 ref 为数组
+    pub in_v_for: bool,
+}
+
+/// 获取模版中静态的 ref，按在模版中的顺序排列
+pub fn get_template_refs(template: &Node, source: &str) -> Vec<TemplateRef> {
+    let mut refs = vec![];
+    let mut nodes = vec![(template, false)];
+    while let Some((node, in_v_for)) = nodes.pop() {
+        let in_v_for = in_v_for || node.attributes.contains_key("v-for");
+        if let Some(value) = node.attributes.get("ref") {
+            let value_offset = get_attr_value_offset(source, value.offset, "ref") + 1;
+            if let Some(value) = &value.value {
+                if value.len() > 1 && (value.starts_with('"') || value.starts_with('\'')) {
+                    refs.push(TemplateRef {
+                        name: value[1..value.len() - 1].to_string(),
+                        range: (value_offset, value_offset + value.len() - 2),
+                        in_v_for,
+                    });
+                }
+            }
+        }
+        nodes.extend(node.children.iter().rev().map(|child| (child, in_v_for)));
+    }
+    refs
+}
+
+fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult, skip: &[usize]) {
+    let mut close_str = String::new();
 
     let attrs = node.attribute_names();
 
@@ -20,13 +122,13 @@ fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult) {
     let v_if_key = "v-if";
     if attrs.iter().find(|v| **v == v_if_key).is_some() {
         let value = node.attributes.get(v_if_key).unwrap();
-        let value_offset = value.offset + v_if_key.len() + 2;
+        let value_offset = get_attr_value_offset(source, value.offset, v_if_key) + 1;
         if let Some(value) = &value.value {
             if value.starts_with(r#"""#) && value.ends_with(r#"""#) && value.len() > 1 {
                 result.add_wrap("if(");
                 result.add_fragment(&value[1..value.len() - 1], value_offset);
                 result.add_wrap("){");
-                close_str = "}";
+                close_str.push('}');
                 skip_util_v_if = true;
             }
         }
@@ -37,13 +139,13 @@ fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult) {
     let v_else_if_key = "v-else-if";
     if attrs.iter().find(|v| **v == v_else_if_key).is_some() {
         let value = node.attributes.get(v_else_if_key).unwrap();
-        let value_offset = value.offset + v_else_if_key.len() + 2;
+        let value_offset = get_attr_value_offset(source, value.offset, v_else_if_key) + 1;
         if let Some(value) = &value.value {
             if value.starts_with(r#"""#) && value.ends_with(r#"""#) && value.len() > 1 {
                 result.add_wrap("else if(");
                 result.add_fragment(&value[1..value.len() - 1], value_offset);
                 result.add_wrap("){");
-                close_str = "}";
+                close_str.push('}');
                 skip_util_v_else_if = true;
             }
         }
@@ -53,7 +155,7 @@ fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult) {
     let v_else_key = "v-else";
     if attrs.iter().find(|v| **v == v_else_key).is_some() {
         result.add_wrap("else{");
-        close_str = "}";
+        close_str.push('}');
     }
 
     // v-for
@@ -63,21 +165,24 @@ fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult) {
         if let Some(value) = &value.value {
             if value.starts_with(r#"""#) && value.ends_with(r#"""#) && value.len() > 1 {
                 let value = &value[1..value.len() - 1];
-                if let Some((left, right)) = value.split_once(" in ") {
-                    if REG_V_FOR_WITH_INDEX.is_match(left) {
-                        result.add_wrap(&format!("for(const __item__ of {right})"));
-                        result.add_wrap("{");
-                        close_str = "}"
+                if let Some((left, right)) = split_v_for(value) {
+                    if result.is_js {
+                        result.add_wrap(&format!("for(const __item__ of {right}){{"));
+                        close_str.push('}');
                     } else {
-                        result.add_wrap(&format!("for(const __item__ of {right})"));
-                        result.add_wrap("{");
-                        close_str = "}";
+                        let count = REG_V_FOR_WITH_INDEX
+                            .captures(left)
+                            .map_or(1, |caps| caps.iter().skip(1).flatten().count());
+                        result.add_wrap(&get_v_for_header(right, count));
+                        close_str.push_str("}}");
                     }
                 }
             }
         }
     }
 
+    // 作用域插槽的值及其偏移，在其他属性之后处理，仅作用于子节点
+    let mut slot_scope = None;
     for key in node.attribute_names_by_order() {
         let value = node.attributes.get(key).unwrap();
         if key.starts_with(":")
@@ -86,21 +191,26 @@ fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult) {
             || (key.starts_with("v-") && key != v_if_key && key != v_else_if_key)
             || ["slot-scope"].contains(&&key[..])
         {
-            let value_offset = value.offset + key.len() + 2;
+            let value_offset = get_attr_value_offset(source, value.offset, key) + 1;
             if let Some(value) = &value.value {
                 if value.starts_with(r#"""#) && value.ends_with(r#"""#) && value.len() > 1 {
                     let value = &value[1..value.len() - 1];
                     if key == v_for_key {
-                        if let Some((left, right)) = value.split_once(" in ") {
+                        if let Some((left, right)) = split_v_for(value) {
                             if let Some(caps) = REG_V_FOR_WITH_INDEX.captures(left) {
-                                let item = caps.get(1).unwrap();
-                                let index = caps.get(2).unwrap();
-                                result.add_wrap("const ");
-                                result.add_fragment(item.as_str(), value_offset + item.start());
-                                result.add_wrap(" = __item__;");
-                                result.add_wrap("const ");
-                                result.add_fragment(index.as_str(), value_offset + index.start());
-                                result.add_wrap(" = 0 as number;");
+                                // (value, key, index)
+                                let is_js = result.is_js;
+                                for (i, alias) in caps.iter().skip(1).flatten().enumerate() {
+                                    result.add_wrap("const ");
+                                    result
+                                        .add_fragment(alias.as_str(), value_offset + alias.start());
+                                    result.add_wrap(match (i, is_js) {
+                                        (0, _) => " = __item__;",
+                                        (_, true) => " = 0;",
+                                        (1, false) => " = __key__;",
+                                        _ => " = __index__;",
+                                    });
+                                }
                             } else {
                                 result.add_wrap("const ");
                                 result.add_fragment(left, value_offset);
@@ -114,45 +224,26 @@ fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult) {
                             result.add_fragment(value, value_offset);
                             result.add_wrap(");");
                         }
-                    } else if key == "v-slot" || key.starts_with("#") || key.starts_with("v-slot:")
+                    } else if key == "v-slot"
+                        || key.starts_with("#")
+                        || key.starts_with("v-slot:")
+                        || key == "slot-scope"
                     {
-                        if value.starts_with("{") && value.ends_with("}") {
-                            result.add_wrap("{const ");
-                        } else {
-                            result.add_wrap("{const {");
-                        }
-                        result.add_fragment(value, value_offset);
-                        if value.starts_with("{") && value.ends_with("}") {
-                            result.add_wrap(" = {} as Record<string, any>;");
-                        } else {
-                            result.add_wrap("} = {} as Record<string, any>;");
-                        }
-                        if close_str == "}" {
-                            close_str = "}}";
-                        } else {
-                            close_str = "}";
-                        }
-                    } else if key == "slot-scope" {
-                        if value.starts_with("{") && value.ends_with("}") {
-                            result.add_wrap("{const ");
-                        } else {
-                            result.add_wrap("{const {");
-                        }
-                        result.add_fragment(value, value_offset);
-                        if value.starts_with("{") && value.ends_with("}") {
-                            result.add_wrap(" = {} as Record<string, any>;");
-                        } else {
-                            result.add_wrap("} = {} as Record<string, any>;");
-                        }
-                        if close_str == "}" {
-                            close_str = "}}";
-                        } else {
-                            close_str = "}";
-                        }
+                        // `v-slot` 可以直接位于组件标签上，作为默认插槽的简写
+                        slot_scope = Some((value, value_offset, get_slot_name(node, key)));
                     } else if (key.starts_with("@") || key.starts_with("v-on:"))
                         && !value.contains("=>")
                     {
-                        result.add_wrap("(()=>{");
+                        let event_type = if result.is_js {
+                            None
+                        } else {
+                            get_native_event_type(node, key)
+                        };
+                        if let Some(event_type) = event_type {
+                            result.add_wrap(&format!("(($event: {event_type})=>{{"));
+                        } else {
+                            result.add_wrap("(()=>{");
+                        }
                         result.add_fragment(value, value_offset);
                         result.add_wrap("});");
                     } else if !skip_util_v_if && !skip_util_v_else_if {
@@ -169,15 +260,60 @@ fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult) {
         }
     }
 
+    if let Some((value, value_offset, slot_name)) = slot_scope {
+        if value.starts_with("{") && value.ends_with("}") {
+            result.add_wrap("{const ");
+        } else {
+            result.add_wrap("{const {");
+        }
+        result.add_fragment(value, value_offset);
+        if !(value.starts_with("{") && value.ends_with("}")) {
+            result.add_wrap("}");
+        }
+        if result.is_js {
+            result.add_wrap(" = {};");
+        } else {
+            // `<template v-slot>` 的插槽属于父级组件，`v-slot` 位于组件标签上时属于组件自身
+            let tag = match node.tag.as_deref() {
+                Some("template") => result.tags.last().map(|v| &v[..]),
+                tag => tag,
+            };
+            let props = tag.and_then(|tag| result.get_slot_props(tag, &slot_name));
+            if let Some(props) = props {
+                let props = props
+                    .iter()
+                    .map(|(name, typ)| {
+                        if name
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+                        {
+                            format!(" {name}: {typ};")
+                        } else {
+                            format!(" \"{name}\": {typ};")
+                        }
+                    })
+                    .collect::<String>();
+                result.add_wrap(&format!(" = {{}} as {{ [key: string]: any;{props} }};"));
+            } else {
+                result.add_wrap(" = {} as Record<string, any>;");
+            }
+        }
+        close_str.push('}');
+    }
+
+    result.tags.push(node.tag.clone().unwrap_or_default());
     let mut start = node.start_tag_end;
     for child in &node.children {
         // 子节点前的文本
         if let Some(start) = start {
             compile_text(start, child.start, source, result);
         }
-        compile_node(child, source, result);
+        if !skip.contains(&child.start) {
+            compile_node(child, source, result, &[]);
+        }
         start = Some(child.end);
     }
+    result.tags.pop();
     // 最后一个子节点后的文本
     if let Some(end) = node.end_tag_start {
         if let Some(start) = start {
@@ -188,8 +324,53 @@ fn compile_node(node: &Node, source: &str, result: &mut TemplateCompileResult) {
     }
 
     if close_str.len() > 0 {
-        result.add_wrap(close_str);
+        result.add_wrap(&close_str);
+    }
+}
+
+/// 获取原生元素事件绑定中 `$event` 的 DOM 事件类型
+///
+/// 组件上的事件仅在使用 `.native` 修饰符时为原生事件
+fn get_native_event_type(node: &Node, key: &str) -> Option<&'static str> {
+    let key = key
+        .strip_prefix('@')
+        .or_else(|| key.strip_prefix("v-on:"))?;
+    let mut parts = key.split('.');
+    let name = parts.next()?;
+    let is_native = parts.any(|v| v == "native")
+        || node.tag.as_deref().is_some_and(|tag| {
+            tag != "template"
+                && !vue_data::is_builtin_component(tag)
+                && tag
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        });
+    if !is_native {
+        return None;
     }
+    get_dom_event_type(name)
+}
+
+/// 常见 DOM 事件名称对应的事件类型
+fn get_dom_event_type(name: &str) -> Option<&'static str> {
+    let event_type = match name {
+        "click" | "dblclick" | "contextmenu" | "auxclick" => "MouseEvent",
+        "keydown" | "keyup" | "keypress" => "KeyboardEvent",
+        "input" | "change" | "select" | "reset" | "scroll" | "load" | "error" => "Event",
+        "submit" => "SubmitEvent",
+        "focus" | "blur" | "focusin" | "focusout" => "FocusEvent",
+        "wheel" => "WheelEvent",
+        "copy" | "cut" | "paste" => "ClipboardEvent",
+        "compositionstart" | "compositionupdate" | "compositionend" => "CompositionEvent",
+        name if name.starts_with("mouse") => "MouseEvent",
+        name if name.starts_with("drag") || name == "drop" => "DragEvent",
+        name if name.starts_with("touch") => "TouchEvent",
+        name if name.starts_with("pointer") => "PointerEvent",
+        name if name.starts_with("animation") => "AnimationEvent",
+        name if name.starts_with("transition") => "TransitionEvent",
+        _ => return None,
+    };
+    Some(event_type)
 }
 
 fn compile_text(start: usize, end: usize, source: &str, result: &mut TemplateCompileResult) {
@@ -218,24 +399,89 @@ fn compile_text(start: usize, end: usize, source: &str, result: &mut TemplateCom
     }
 }
 
+/// 获取作用域插槽所属的插槽名称，未指定时为 `default`
+fn get_slot_name(node: &Node, key: &str) -> String {
+    let name = if let Some(name) = key.strip_prefix("#") {
+        Some(name.to_string())
+    } else if let Some(name) = key.strip_prefix("v-slot:") {
+        Some(name.to_string())
+    } else if key == "slot-scope" {
+        node.attributes
+            .get("slot")
+            .and_then(|v| v.value.as_ref())
+            .filter(|v| v.len() > 1)
+            .map(|v| v[1..v.len() - 1].to_string())
+    } else {
+        None
+    };
+    name.filter(|v| v.len() > 0)
+        .unwrap_or("default".to_string())
+}
+
+/// 组件的作用域插槽属性，组件名称 -> 插槽名称 -> Vec<(属性名称, 类型)>
+pub type SlotProps = HashMap<String, HashMap<String, Vec<(String, String)>>>;
+
 /// 模版编译结果
-struct TemplateCompileResult {
+struct TemplateCompileResult<'a> {
     /// 编译输出的 render 方法
     pub render: String,
     /// 编译前后的映射关系
     pub mapping: CompileMapping,
     offset: usize,
+    /// 是否为函数式组件
+    functional: bool,
+    /// 脚本是否为 JavaScript
+    is_js: bool,
+    /// 组件的作用域插槽属性
+    slot_props: &'a SlotProps,
+    /// CSS Modules 注入的名称
+    style_modules: &'a [StyleModule],
+    /// 祖先节点的标签，用于确定 `<template v-slot>` 所属的组件
+    tags: Vec<String>,
 }
 
-impl TemplateCompileResult {
-    pub fn new() -> Self {
+impl<'a> TemplateCompileResult<'a> {
+    pub fn new(
+        functional: bool,
+        is_js: bool,
+        slot_props: &'a SlotProps,
+        style_modules: &'a [StyleModule],
+    ) -> Self {
         TemplateCompileResult {
             render: String::new(),
             mapping: vec![],
             offset: 0,
+            functional,
+            is_js,
+            slot_props,
+            style_modules,
+            tags: vec![],
         }
     }
 
+    /// `item` 是否以 CSS Modules 注入的名称开头，`item` 不包含 `$`
+    fn is_style_module(&self, item: &str) -> bool {
+        self.style_modules.iter().any(|module| {
+            module
+                .name
+                .strip_prefix('$')
+                .is_some_and(|name| is_identifier(item, name))
+        })
+    }
+
+    /// 获取组件插槽的属性，组件名称兼容短横线命名
+    fn get_slot_props(&self, tag: &str, slot_name: &str) -> Option<&'a Vec<(String, String)>> {
+        let slot_props = self.slot_props;
+        let slots = slot_props.get(tag).or_else(|| {
+            let tag = util::to_kebab_case(tag);
+            slot_props
+                .iter()
+                .find(|(name, _)| util::to_kebab_case(name) == tag)
+                .map(|(_, slots)| slots)
+        })?;
+        slots.get(slot_name).filter(|v| v.len() > 0)
+    }
+
     fn add_wrap(&mut self, target: &str) {
         self.render += &target.replace("\r", " ").replace("\n", " ");
         self.offset += target.len();
@@ -260,8 +506,13 @@ impl TemplateCompileResult {
         let prefix = "this.";
         let mut is_add = !first.ends_with(".");
         for item in split {
-            // 循环中除了 $event 的每项都需要加前缀
-            if is_add && !item.starts_with("event") {
+            // 循环中除了 $event 和 CSS Modules 注入的名称的每项都需要加前缀
+            // 单独的 `$` 同样加前缀，以便补全 `$props`、`$emit` 等实例属性
+            if is_add
+                && !self.functional
+                && !is_identifier(item, "event")
+                && !self.is_style_module(item)
+            {
                 self.add_wrap(prefix);
             }
             self.render += "$";
@@ -274,23 +525,106 @@ impl TemplateCompileResult {
     }
 }
 
+/// # 获取 ts 中 `v-for` 的循环头
+///
+/// 被迭代的值可以是数组、对象、数字或 Map、Set 等可迭代对象，
+/// 由 tsserver 从 `__iter__` 推断循环变量 (value, key, index) 的类型，
+/// `count` 是循环变量的数量，结果需要使用 `}}` 闭合
+fn get_v_for_header(right: &str, count: usize) -> String {
+    let mut header = format!(
+        "{{const __iter__ = {right};type __Item__<T> = 0 extends 1 & T ? any : T extends number ? number : T extends Iterable<infer I> ? I : T extends object ? T[keyof T] : never;"
+    );
+    if count > 1 {
+        header.push_str("type __Key__<T> = 0 extends 1 & T ? number : T extends number | Iterable<any> ? number : keyof T;");
+    }
+    header.push_str(match count {
+        1 => "for(const __item__ of [] as __Item__<typeof __iter__>[]){",
+        2 => "for(const [__item__, __key__] of [] as [__Item__<typeof __iter__>, __Key__<typeof __iter__>][]){",
+        _ => "for(const [__item__, __key__, __index__] of [] as [__Item__<typeof __iter__>, __Key__<typeof __iter__>, number][]){",
+    });
+    header
+}
+
+/// 按 ` in ` 或 ` of ` 分割 `v-for` 的值，返回循环变量和被迭代的表达式
+///
+/// 分隔符长度均为 4，被迭代表达式的偏移为 `left.len() + 4`
+pub fn split_v_for(value: &str) -> Option<(&str, &str)> {
+    let index = [value.find(" in "), value.find(" of ")]
+        .into_iter()
+        .flatten()
+        .min()?;
+    Some((&value[..index], &value[index + 4..]))
+}
+
+/// `item` 是否以指定的完整标识符开头，`item` 不包含 `$`
+///
+/// 用于识别 `$event` 和 CSS Modules 注入的 `$style` 等名称，而不误伤 `$eventBus` 等实例属性
+fn is_identifier(item: &str, name: &str) -> bool {
+    item.strip_prefix(name)
+        .is_some_and(|rest| !rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_'))
+}
+
+/// 编译结果中是否直接引用了指定的标识符，不包括成员访问 `a.name` 和更长的标识符
+pub fn is_identifier_referenced(compile_result: &str, name: &str) -> bool {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    compile_result.match_indices(name).any(|(index, _)| {
+        !compile_result[..index].ends_with(|c: char| is_ident_char(c) || c == '.')
+            && !compile_result[index + name.len()..].starts_with(is_ident_char)
+    })
+}
+
 /// 映射表，Vec<(character, 原位置, 长度)>
 pub type CompileMapping = Vec<(usize, usize, usize)>;
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use html_languageservice::{parser::html_parse, HTMLDataManager};
 
-    use super::template_compile;
+    use crate::css_server::StyleModule;
+
+    use super::{get_template_refs, template_compile, SlotProps, TemplateRef};
+
+    const V_FOR_ITEM_TYPE: &str = "type __Item__<T> = 0 extends 1 & T ? any : T extends number ? number : T extends Iterable<infer I> ? I : T extends object ? T[keyof T] : never;";
+    const V_FOR_KEY_TYPE: &str = "type __Key__<T> = 0 extends 1 & T ? number : T extends number | Iterable<any> ? number : keyof T;";
 
     fn assert_render(template: &str, expected: &str, expected_mapping: &[(usize, usize, usize)]) {
         let html_document =
             html_parse::parse_html_document(template, "html", &HTMLDataManager::default(), true);
-        let (render, mapping) = template_compile(&html_document.roots[0], template);
+        let style_modules = [StyleModule {
+            name: "$style".to_string(),
+            classes: vec![],
+        }];
+        let (render, mapping) = template_compile(
+            &html_document.roots[0],
+            template,
+            false,
+            false,
+            &SlotProps::new(),
+            &style_modules,
+        );
         assert_eq!(render, expected);
         assert_eq!(mapping, expected_mapping.to_vec());
     }
 
+    #[test]
+    fn functional() {
+        let template = r#"<div :title="$attrs.title"></div>"#;
+        let html_document =
+            html_parse::parse_html_document(template, "html", &HTMLDataManager::default(), true);
+        let (render, mapping) = template_compile(
+            &html_document.roots[0],
+            template,
+            true,
+            false,
+            &SlotProps::new(),
+            &[],
+        );
+        assert_eq!(render, "($attrs.title);");
+        assert_eq!(mapping, vec![(1, 13, 12)]);
+    }
+
     #[test]
     fn empty_props() {
         assert_render("<template></template>", "", &[]);
@@ -361,15 +695,17 @@ mod tests {
         assert_render(
             r#"<TabPane :key="item.task.id" v-for="item in tabLists" :closable="true" class="content-tab-pane"></TabPane>"#,
             &[
-                "for(const __item__ of tabLists){",
+                "{const __iter__ = tabLists;",
+                V_FOR_ITEM_TYPE,
+                "for(const __item__ of [] as __Item__<typeof __iter__>[]){",
                 "(item.task.id);",
                 "const item = __item__;",
                 "(tabLists);",
                 "(true);",
-                "}",
+                "}}",
             ]
             .join(""),
-            &[(33, 15, 12), (53, 36, 4), (70, 44, 8), (81, 65, 4)],
+            &[(228, 15, 12), (248, 36, 4), (265, 44, 8), (276, 65, 4)],
         );
     }
 
@@ -378,15 +714,88 @@ mod tests {
         assert_render(
             r#"<div :key="index" v-for="(item, index) in list"></div>"#,
             &[
-                "for(const __item__ of list){",
+                "{const __iter__ = list;",
+                V_FOR_ITEM_TYPE,
+                V_FOR_KEY_TYPE,
+                "for(const [__item__, __key__] of [] as [__Item__<typeof __iter__>, __Key__<typeof __iter__>][]){",
                 "(index);",
                 "const item = __item__;",
-                "const index = 0 as number;",
+                "const index = __key__;",
+                "(list);",
+                "}}",
+            ]
+            .join(""),
+            &[(360, 11, 5), (373, 26, 4), (395, 32, 5), (412, 42, 4)],
+        );
+    }
+
+    #[test]
+    fn directive_for_of() {
+        assert_render(
+            r#"<div v-for="item of list"></div>"#,
+            &[
+                "{const __iter__ = list;",
+                V_FOR_ITEM_TYPE,
+                "for(const __item__ of [] as __Item__<typeof __iter__>[]){",
+                "const item = __item__;",
                 "(list);",
-                "}",
+                "}}",
             ]
             .join(""),
-            &[(29, 11, 5), (42, 26, 4), (64, 32, 5), (85, 42, 4)],
+            &[(229, 12, 4), (246, 20, 4)],
+        );
+    }
+
+    #[test]
+    fn directive_for_number_range() {
+        assert_render(
+            r#"<span v-for="n in 10" :key="n"></span>"#,
+            &[
+                "{const __iter__ = 10;",
+                V_FOR_ITEM_TYPE,
+                "for(const __item__ of [] as __Item__<typeof __iter__>[]){",
+                "const n = __item__;",
+                "(10);",
+                "(n);",
+                "}}",
+            ]
+            .join(""),
+            &[(227, 13, 1), (241, 18, 2), (246, 28, 1)],
+        );
+    }
+
+    #[test]
+    fn directive_for_object() {
+        assert_render(
+            r#"<div v-for="(value, key, index) in object" :key="key"></div>"#,
+            &[
+                "{const __iter__ = object;",
+                V_FOR_ITEM_TYPE,
+                V_FOR_KEY_TYPE,
+                "for(const [__item__, __key__, __index__] of [] as [__Item__<typeof __iter__>, __Key__<typeof __iter__>, number][]){",
+                "const value = __item__;",
+                "const key = __key__;",
+                "const index = __index__;",
+                "(object);",
+                "(key);",
+                "}}",
+            ]
+            .join(""),
+            &[(386, 13, 5), (409, 20, 3), (429, 25, 5), (448, 35, 6), (457, 49, 3)],
+        );
+    }
+
+    #[test]
+    fn multiple_roots() {
+        assert_render(
+            r#"<template><div :title="title"></div><span :title="text"></span></template>"#,
+            "(title);",
+            &[(1, 23, 5)],
+        );
+        assert_render(
+            r#"<template><div v-if="a"></div><div v-else-if="b"></div><div v-else></div><p :title="c"></p></template>"#,
+            "if(a){}else if(b){}else{}",
+            &[(3, 21, 1), (15, 46, 1)],
         );
     }
 
@@ -427,6 +836,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn directive_slot_on_component() {
+        assert_render(
+            r#"<template><Child v-slot="{ x }" :title="title">{{ x }}</Child></template>"#,
+            "(title);{const { x } = {} as Record<string, any>;( x );}",
+            &[(1, 40, 5), (15, 25, 5), (50, 49, 3)],
+        );
+        assert_render(
+            r#"<template><Child #default="{ x }">{{ x }}</Child></template>"#,
+            "{const { x } = {} as Record<string, any>;( x );}",
+            &[(7, 27, 5), (42, 36, 3)],
+        );
+    }
+
+    #[test]
+    fn directive_slot_typed() {
+        let slot_props = SlotProps::from([(
+            "MyChild".to_string(),
+            HashMap::from([(
+                "default".to_string(),
+                vec![("item".to_string(), "string".to_string())],
+            )]),
+        )]);
+        let template = r#"<template><MyChild><template #row="{ item }">{{ item }}</template></MyChild></template>"#;
+        let html_document =
+            html_parse::parse_html_document(template, "html", &HTMLDataManager::default(), true);
+        let (render, _) = template_compile(
+            &html_document.roots[0],
+            template,
+            false,
+            false,
+            &slot_props,
+            &[],
+        );
+        assert_eq!(
+            render,
+            "{const { item } = {} as Record<string, any>;( item );}"
+        );
+
+        let template =
+            r#"<template><my-child v-slot="{ item, other }">{{ other }}</my-child></template>"#;
+        let html_document =
+            html_parse::parse_html_document(template, "html", &HTMLDataManager::default(), true);
+        let (render, mapping) = template_compile(
+            &html_document.roots[0],
+            template,
+            false,
+            false,
+            &slot_props,
+            &[],
+        );
+        assert_eq!(
+            render,
+            "{const { item, other } = {} as { [key: string]: any; item: string; };( other );}"
+        );
+        assert_eq!(mapping, vec![(7, 28, 15), (70, 47, 7)]);
+    }
+
+    #[test]
+    fn javascript() {
+        let template = r#"<template><Child #default="{ x }">{{ x }}</Child></template>"#;
+        let html_document =
+            html_parse::parse_html_document(template, "html", &HTMLDataManager::default(), true);
+        let (render, mapping) = template_compile(
+            &html_document.roots[0],
+            template,
+            false,
+            true,
+            &SlotProps::new(),
+            &[],
+        );
+        assert_eq!(render, "{const { x } = {};( x );}");
+        assert_eq!(mapping, vec![(7, 27, 5), (19, 36, 3)]);
+    }
+
     #[test]
     fn directive_slot_scope() {
         assert_render(
@@ -450,13 +934,13 @@ mod tests {
     fn event() {
         assert_render(
             r#"<div @click="onClick"></div>"#,
-            "(()=>{onClick});",
-            &[(6, 13, 7)],
+            "(($event: MouseEvent)=>{onClick});",
+            &[(24, 13, 7)],
         );
         assert_render(
             r#"<div @click="onClick()"></div>"#,
-            "(()=>{onClick()});",
-            &[(6, 13, 9)],
+            "(($event: MouseEvent)=>{onClick()});",
+            &[(24, 13, 9)],
         );
         assert_render(
             r#"<div @click="e => onClick(e)"></div>"#,
@@ -465,8 +949,55 @@ mod tests {
         );
         assert_render(
             r#"<div @click="value = 'xxx'"></div>"#,
-            "(()=>{value = 'xxx'});",
-            &[(6, 13, 13)],
+            "(($event: MouseEvent)=>{value = 'xxx'});",
+            &[(24, 13, 13)],
+        );
+    }
+
+    #[test]
+    fn native_event_type() {
+        // 原生元素事件中的 `$event` 为对应的 DOM 事件类型
+        assert_render(
+            r#"<button @click="onClick($event)"></button>"#,
+            "(($event: MouseEvent)=>{onClick($event)});",
+            &[(24, 16, 8), (32, 24, 7)],
+        );
+        assert_render(
+            r#"<input @keydown.enter="onKeydown($event)" />"#,
+            "(($event: KeyboardEvent)=>{onKeydown($event)});",
+            &[(27, 23, 10), (37, 33, 7)],
+        );
+        assert_render(
+            r#"<input v-on:input="onInput" />"#,
+            "(($event: Event)=>{onInput});",
+            &[(19, 19, 7)],
+        );
+        assert_render(
+            r#"<form @submit.prevent="onSubmit"></form>"#,
+            "(($event: SubmitEvent)=>{onSubmit});",
+            &[(25, 23, 8)],
+        );
+        // 组件上的事件仅在 `.native` 时为原生事件
+        assert_render(
+            r#"<MyButton @click="onClick"></MyButton>"#,
+            "(()=>{onClick});",
+            &[(6, 18, 7)],
+        );
+        assert_render(
+            r#"<my-button @click.native="onClick"></my-button>"#,
+            "(($event: MouseEvent)=>{onClick});",
+            &[(24, 26, 7)],
+        );
+        assert_render(
+            r#"<div @custom="onCustom"></div>"#,
+            "(()=>{onCustom});",
+            &[(6, 14, 8)],
+        );
+        // 内置组件不是原生元素
+        assert_render(
+            r#"<keep-alive @click="onClick"></keep-alive>"#,
+            "(()=>{onClick});",
+            &[(6, 20, 7)],
         );
     }
 
@@ -484,8 +1015,33 @@ mod tests {
         );
         assert_render(
             r#"<div @click="onClick($event)"></div>"#,
-            "(()=>{onClick($event)});",
-            &[(6, 13, 8), (14, 21, 7)],
+            "(($event: MouseEvent)=>{onClick($event)});",
+            &[(24, 13, 8), (32, 21, 7)],
+        );
+        assert_render(
+            r#"<div :class="$style.root"></div>"#,
+            "($style.root);",
+            &[(1, 13, 11)],
+        );
+        assert_render(
+            r#"<div @click="$eventBus.emit($event)"></div>"#,
+            "(($event: MouseEvent)=>{this.$eventBus.emit($event)});",
+            &[(29, 13, 15), (44, 28, 7)],
+        );
+    }
+
+    #[test]
+    fn instance_property_completion() {
+        assert_render("<div>{{ $ }}</div>", "( this.$ );", &[(1, 7, 1), (7, 8, 2)]);
+        assert_render(
+            "<div>{{ this.$ }}</div>",
+            "( this.$ );",
+            &[(1, 7, 6), (7, 13, 2)],
+        );
+        assert_render(
+            r#"<div :title="$props.title"></div>"#,
+            "(this.$props.title);",
+            &[(6, 13, 12)],
         );
     }
 
@@ -507,4 +1063,62 @@ mod tests {
             &[(1, 7, 5), (9, 34, 7)],
         );
     }
+
+    #[test]
+    fn style_modules() {
+        let template = r#"<div :class="[$css.root, $style.root]"></div>"#;
+        let html_document =
+            html_parse::parse_html_document(template, "html", &HTMLDataManager::default(), true);
+        let style_modules = [StyleModule {
+            name: "$css".to_string(),
+            classes: vec![],
+        }];
+        let (render, _) = template_compile(
+            &html_document.roots[0],
+            template,
+            false,
+            false,
+            &SlotProps::new(),
+            &style_modules,
+        );
+        // 只有声明的模块名称不加前缀
+        assert_eq!(render, "([$css.root, this.$style.root]);");
+    }
+
+    #[test]
+    fn attr_value_offset() {
+        let source = r#"<div ref="a" ref = 'b' ref
+=c></div>"#;
+        assert_eq!(super::get_attr_value_offset(source, 5, "ref"), 9);
+        assert_eq!(super::get_attr_value_offset(source, 13, "ref"), 19);
+        assert_eq!(super::get_attr_value_offset(source, 23, "ref"), 28);
+    }
+
+    #[test]
+    fn template_refs() {
+        let template = r#"<template><div ref="root"><p v-for="item in list"><span ref="items"></span></p><input ref="input"></div></template>"#;
+        let html_document =
+            html_parse::parse_html_document(template, "html", &HTMLDataManager::default(), true);
+        let refs = get_template_refs(&html_document.roots[0], template);
+        assert_eq!(
+            refs,
+            vec![
+                TemplateRef {
+                    name: "root".to_string(),
+                    range: (20, 24),
+                    in_v_for: false,
+                },
+                TemplateRef {
+                    name: "items".to_string(),
+                    range: (61, 66),
+                    in_v_for: true,
+                },
+                TemplateRef {
+                    name: "input".to_string(),
+                    range: (91, 96),
+                    in_v_for: false,
+                },
+            ]
+        );
+    }
 }
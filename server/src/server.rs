@@ -1,9 +1,11 @@
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
 
 use core::fmt::Debug;
-use html_languageservice::html_data::HTMLDataV1;
+use html_languageservice::html_data::{Description, HTMLDataV1};
 use html_languageservice::language_facts::data_provider::{HTMLDataProvider, IHTMLDataProvider};
 use html_languageservice::parser::html_document::Node;
 use html_languageservice::parser::html_scanner::TokenType;
@@ -14,36 +16,124 @@ use lsp_textdocument::TextDocuments;
 use serde_json::{json, Value};
 use std::time;
 use tokio::join;
+use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::{Mutex, RwLock};
-use tower_lsp::jsonrpc::Result;
+use tower_lsp::jsonrpc::{self, Result};
 use tower_lsp::lsp_types::notification::DidChangeConfiguration;
 use tower_lsp::lsp_types::notification::{
     DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification,
 };
-use tower_lsp::lsp_types::request::WorkDoneProgressCreate;
+use tower_lsp::lsp_types::request::{
+    GotoImplementationParams, GotoImplementationResponse, WorkDoneProgressCreate,
+};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
-use tracing::{debug, info, instrument};
+use tracing::{debug, error, info, instrument};
 
 use crate::css_server::CssServer;
 use crate::diagnostics::DiagnosticsManager;
-use crate::renderer::{PositionType, Renderer};
-use crate::ts_server::TsServer;
+use crate::log::LogLevelHandle;
+use crate::renderer::{PositionType, Renderer, DEFAULT_MAX_FILE_SIZE};
+use crate::ts_server::{self, TsServer, DEFAULT_CHANGE_DELAY, DEFAULT_REQUEST_TIMEOUT};
 use crate::util;
-use crate::vue_data::VueDataProvider;
+use crate::vue_data::{self, VueDataProvider};
+
+/// 增量渲染后在后台每次创建节点的未修改文件数量
+const LAZY_FILES_BATCH_SIZE: usize = 50;
+
+/// tsserver 意外退出后连续自动重启的最大次数
+const MAX_TS_SERVER_RESTART_COUNT: u32 = 5;
+
+/// 第 count 次自动重启前等待的时间，从 1 秒开始倍增，最多 16 秒
+fn get_ts_server_restart_delay(count: u32) -> time::Duration {
+    time::Duration::from_secs(1 << (count.max(1) - 1).min(4))
+}
+
+/// tsserver 连续自动重启的计数
+struct TsServerRestartCounter {
+    count: u32,
+    last_restart: time::Instant,
+}
+
+impl TsServerRestartCounter {
+    fn new(now: time::Instant) -> TsServerRestartCounter {
+        TsServerRestartCounter {
+            count: 0,
+            last_restart: now,
+        }
+    }
+
+    /// 返回本次重启的次数及重启前等待的时间，达到次数上限时返回 None
+    ///
+    /// 距离上次重启稳定运行一段时间后重新计数
+    fn next(&mut self, now: time::Instant) -> Option<(u32, time::Duration)> {
+        if now.duration_since(self.last_restart) > time::Duration::from_secs(60) {
+            self.count = 0;
+        }
+        if self.count >= MAX_TS_SERVER_RESTART_COUNT {
+            return None;
+        }
+        self.count += 1;
+        Some((self.count, get_ts_server_restart_delay(self.count)))
+    }
+
+    /// 记录重启完成的时间
+    fn restarted(&mut self, now: time::Instant) {
+        self.last_restart = now;
+    }
+}
+
+/// 初始化选项中是否启用 CSS 服务，未设置时启用
+fn is_css_enabled_in_options(options: Option<&Value>) -> bool {
+    options
+        .and_then(|v| v.get("cssEnabled"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+/// 请求模版表达式映射后的位置，结果为空且位置位于映射边界时使用边界内相邻的位置重试
+async fn request_with_adjacent_retry<T, Fut>(
+    position: Position,
+    adjacent_positions: Vec<Position>,
+    is_empty: impl Fn(&Result<T>) -> bool,
+    request: impl Fn(Position) -> Fut,
+) -> Result<T>
+where
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let mut result = request(position).await;
+    for position in adjacent_positions {
+        if !is_empty(&result) {
+            break;
+        }
+        result = request(position).await;
+    }
+    result
+}
 
 pub struct VueLspServer {
     is_shared: bool,
     client: Client,
     text_documents: Arc<RwLock<TextDocuments>>,
     data_manager: Mutex<HTMLDataManager>,
-    _diagnostics: DiagnosticsManager,
+    diagnostics: DiagnosticsManager,
+    /// 模版诊断在 DiagnosticsManager 中的来源序号
+    template_diagnostics_index: usize,
+    template_diagnostics_tx: Sender<(Uri, Option<i32>, Vec<Diagnostic>)>,
     html_server: Mutex<HTMLLanguageService>,
-    ts_server: RwLock<TsServer>,
-    css_server: CssServer,
+    ts_server: Arc<RwLock<TsServer>>,
+    /// CSS 服务，只在初始化时启用才启动，重新配置时只能停用
+    css_server: RwLock<Option<CssServer>>,
+    /// CSS 诊断在 DiagnosticsManager 中的来源序号
+    css_diagnostics_index: usize,
+    css_diagnostics_tx: Sender<(Uri, Option<i32>, Vec<Diagnostic>)>,
     renderer: Arc<Mutex<Renderer>>,
     vue_data_provider: VueDataProvider,
     custom_data: StdMutex<Option<HTMLDataV1>>,
+    /// 保存前是否整理模版
+    format_on_save: AtomicBool,
+    /// 日志等级句柄，未设置时无法在运行时修改日志等级
+    log_level_handle: Option<LogLevelHandle>,
 }
 
 impl VueLspServer {
@@ -61,11 +151,19 @@ impl VueLspServer {
         };
         let mut diagnostics = DiagnosticsManager::new(client.clone());
         let renderer = Arc::new(Mutex::new(Renderer::new()));
-        let ts_server = RwLock::new(TsServer::new(
+        let (ts_exit_tx, ts_exit_rx) = mpsc::channel(1);
+        let ts_server = Arc::new(RwLock::new(TsServer::new(
             client.clone(),
             Arc::clone(&renderer),
             diagnostics.register(),
-        ));
+            ts_exit_tx,
+        )));
+        VueLspServer::watch_ts_server(
+            client.clone(),
+            Arc::clone(&ts_server),
+            Arc::clone(&text_documents),
+            ts_exit_rx,
+        );
         let html_server = HTMLLanguageService::new(&HTMLLanguageServiceOptions {
             case_sensitive: Some(true),
             ..Default::default()
@@ -74,22 +172,65 @@ impl VueLspServer {
         let html_server = Mutex::new(html_server);
         let vue_data_provider = VueDataProvider::new();
         let custom_data = StdMutex::new(None);
-        let css_server = CssServer::new(client.clone(), diagnostics.register());
+        let css_diagnostics_index = diagnostics.registered_count();
+        let css_diagnostics_tx = diagnostics.register();
+        let template_diagnostics_index = diagnostics.registered_count();
+        let template_diagnostics_tx = diagnostics.register();
         VueLspServer {
             is_shared,
             client,
             text_documents,
             data_manager,
-            _diagnostics: diagnostics,
+            diagnostics,
+            template_diagnostics_index,
+            template_diagnostics_tx,
             html_server,
             ts_server,
-            css_server,
+            css_server: RwLock::new(None),
+            css_diagnostics_index,
+            css_diagnostics_tx,
             renderer,
             vue_data_provider,
             custom_data,
+            format_on_save: AtomicBool::new(false),
+            log_level_handle: None,
         }
     }
 
+    /// 设置日志等级句柄，用于在运行时修改日志等级
+    pub fn with_log_level_handle(mut self, log_level_handle: LogLevelHandle) -> VueLspServer {
+        self.log_level_handle = Some(log_level_handle);
+        self
+    }
+
+    /// 获取模版、保留名称、组件导入及继承相关的诊断信息
+    async fn get_template_diagnostics(&self, uri: &Uri) -> Vec<Diagnostic> {
+        let (mut diagnostics, import_files) = {
+            let renderer = self.renderer.lock().await;
+            let mut diagnostics = renderer.get_template_diagnostics(uri);
+            diagnostics.append(&mut renderer.get_reserved_name_diagnostics(uri));
+            diagnostics.append(&mut renderer.get_extends_diagnostics(uri));
+            diagnostics.append(&mut renderer.get_template_lang_diagnostics(uri));
+            diagnostics.append(&mut renderer.get_inject_diagnostics(uri));
+            diagnostics.append(&mut renderer.get_script_parse_diagnostics(uri));
+            (diagnostics, renderer.get_component_import_files(uri))
+        };
+        // 检查导入的文件是否存在时不持有渲染器的锁
+        diagnostics.append(&mut Renderer::get_unresolved_import_diagnostics(
+            import_files,
+        ));
+        diagnostics
+    }
+
+    /// 发布模版、保留名称、组件导入及脚本解析相关的诊断信息
+    async fn publish_template_diagnostics(&self, uri: &Uri, version: Option<i32>) {
+        let diagnostics = self.get_template_diagnostics(uri).await;
+        let _ = self
+            .template_diagnostics_tx
+            .send((uri.clone(), version, diagnostics))
+            .await;
+    }
+
     /// 在进行 html 服务器相关的操作前调用
     async fn update_html_languageservice(&self, uri: &Uri) {
         debug!("(Vue2TsDecoratorServer/update_html_languageservice)");
@@ -119,14 +260,67 @@ impl VueLspServer {
     }
 
     async fn get_configure(&self) {
-        let custom_data = self
+        let configuration = self
             .client
-            .configuration(vec![ConfigurationItem {
-                scope_uri: None,
-                section: Some("vue-property-decorator.html.data".to_string()),
-            }])
+            .configuration(vec![
+                ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("vue-property-decorator.html.data".to_string()),
+                },
+                ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("vue-property-decorator.render.exclude".to_string()),
+                },
+                ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("vue-property-decorator.format.onSave".to_string()),
+                },
+                ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("vue-property-decorator.tsserver.requestTimeout".to_string()),
+                },
+                ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("vue-property-decorator.tsserver.changeDelay".to_string()),
+                },
+                ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("vue-property-decorator.css.enabled".to_string()),
+                },
+            ])
             .await
             .unwrap();
+        self.set_render_exclude(configuration.get(1)).await;
+        self.format_on_save.store(
+            configuration
+                .get(2)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            Ordering::Relaxed,
+        );
+        let ts_server = self.ts_server.read().await;
+        ts_server.set_request_timeout(
+            configuration
+                .get(3)
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_REQUEST_TIMEOUT),
+        );
+        ts_server.set_change_delay(
+            configuration
+                .get(4)
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_CHANGE_DELAY),
+        );
+        drop(ts_server);
+        // 停用后关闭 CSS 服务并清除其诊断，重新启用需要重启
+        if configuration.get(5).and_then(|v| v.as_bool()) == Some(false) {
+            let css_server = self.css_server.write().await.take();
+            if let Some(css_server) = css_server {
+                css_server.shutdown().await;
+                self.diagnostics.clear(self.css_diagnostics_index).await;
+            }
+        }
+        let custom_data = &configuration;
         if custom_data[0].is_object() {
             if custom_data[0].as_object().unwrap().is_empty() {
                 *self.custom_data.lock().unwrap() = None;
@@ -161,10 +355,150 @@ impl VueLspServer {
         }
     }
 
+    /// 设置不进行渲染的文件，新排除的文件将不再更新
+    ///
+    /// 已打开的文件被排除后向 tsserver 和 css 服务发送关闭通知
+    async fn set_render_exclude(&self, value: Option<&Value>) {
+        let patterns = match value {
+            Some(Value::Array(_)) => {
+                serde_json::from_value::<Vec<String>>(value.unwrap().clone()).unwrap_or_default()
+            }
+            _ => vec![],
+        };
+        let open_uris = {
+            let text_documents = self.text_documents.read().await;
+            text_documents
+                .documents()
+                .keys()
+                .filter(|uri| VueLspServer::is_uri_valid(uri))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let (result, excluded_uris) = {
+            let mut renderer = self.renderer.lock().await;
+            let open_uris = open_uris
+                .into_iter()
+                .filter(|uri| !renderer.is_render_excluded(uri))
+                .collect::<Vec<_>>();
+            let result = renderer.set_render_exclude(&patterns);
+            let excluded_uris = open_uris
+                .into_iter()
+                .filter(|uri| renderer.is_render_excluded(uri))
+                .collect::<Vec<_>>();
+            (result, excluded_uris)
+        };
+        for uri in excluded_uris {
+            self.close_document(DidCloseTextDocumentParams {
+                text_document: TextDocumentIdentifier { uri },
+            })
+            .await;
+        }
+        if let Err(e) = result {
+            self.client
+                .show_message(
+                    MessageType::WARNING,
+                    format!(
+                        "Parse configuration `vue-property-decorator.render.exclude` error: {}",
+                        e
+                    ),
+                )
+                .await;
+        }
+    }
+
+    /// 向 tsserver 和 css 服务发送关闭文件通知，并清除模版诊断
+    async fn close_document(&self, params: DidCloseTextDocumentParams) {
+        let css_params = params.clone();
+        join!(
+            async {
+                let uri = params.text_document.uri.clone();
+                self.ts_server.read().await.did_close(params).await;
+                let _ = self.template_diagnostics_tx.send((uri, None, vec![])).await;
+            },
+            async {
+                let css_server = self.css_server.read().await;
+                let Some(css_server) = css_server.as_ref() else {
+                    return;
+                };
+                let uri = css_params.text_document.uri.clone();
+                let html_document = { self.renderer.lock().await.get_html_document(&uri) };
+                if let Some(html_document) = html_document {
+                    css_server.did_close(css_params, &html_document).await;
+                }
+            }
+        );
+    }
+
+    /// 是否启用 CSS 服务
+    async fn is_css_enabled(&self) -> bool {
+        self.css_server.read().await.is_some()
+    }
+
+    /// 是否处理 uri，排除配置中不进行渲染的文件
+    async fn is_uri_enabled(&self, uri: &Uri) -> bool {
+        VueLspServer::is_uri_valid(uri) && !self.renderer.lock().await.is_render_excluded(uri)
+    }
+
+    /// tsserver 意外退出后自动重启，连续重启次数有上限且每次重启前等待的时间递增
+    fn watch_ts_server(
+        client: Client,
+        ts_server: Arc<RwLock<TsServer>>,
+        text_documents: Arc<RwLock<TextDocuments>>,
+        mut exit_rx: Receiver<()>,
+    ) {
+        tokio::spawn(async move {
+            let mut counter = TsServerRestartCounter::new(time::Instant::now());
+            while let Some(()) = exit_rx.recv().await {
+                let Some((count, delay)) = counter.next(time::Instant::now()) else {
+                    client
+                        .show_message(
+                            MessageType::ERROR,
+                            "tsserver exited unexpectedly too many times, please restart it manually.",
+                        )
+                        .await;
+                    continue;
+                };
+                tokio::time::sleep(delay).await;
+                client
+                    .show_message(
+                        MessageType::WARNING,
+                        format!(
+                            "tsserver exited unexpectedly, restarting ({}/{}).",
+                            count, MAX_TS_SERVER_RESTART_COUNT
+                        ),
+                    )
+                    .await;
+                // 复制文档后立即释放锁，重启期间不阻塞文档的修改
+                let documents = TsServer::get_documents_snapshot(&*text_documents.read().await);
+                ts_server.write().await.recover(&documents).await;
+                counter.restarted(time::Instant::now());
+            }
+        });
+    }
+
     /// 是否处理 uri
     fn is_uri_valid(uri: &Uri) -> bool {
         !util::to_file_path_string(uri).contains("/node_modules/")
     }
+
+    /// 开启语义标记的增量请求，增量结果由当前服务器计算，无需 tsserver 支持
+    fn enable_semantic_tokens_delta(
+        provider: SemanticTokensServerCapabilities,
+    ) -> SemanticTokensServerCapabilities {
+        let full = Some(SemanticTokensFullOptions::Delta { delta: Some(true) });
+        match provider {
+            SemanticTokensServerCapabilities::SemanticTokensOptions(options) => {
+                SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
+                    full,
+                    ..options
+                })
+            }
+            SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(mut options) => {
+                options.semantic_tokens_options.full = full;
+                SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options)
+            }
+        }
+    }
 }
 
 impl Debug for VueLspServer {
@@ -189,6 +523,60 @@ impl LanguageServer for VueLspServer {
                 });
             }
             let root_uri = &folders[0].uri;
+            // 初始化时还不能获取配置，由客户端通过 initializationOptions 传入
+            let render_exclude = params
+                .initialization_options
+                .as_ref()
+                .and_then(|v| v.get("renderExclude"));
+            self.set_render_exclude(render_exclude).await;
+            // 客户端拉取诊断时不再推送诊断
+            let pull_diagnostics = params
+                .initialization_options
+                .as_ref()
+                .and_then(|v| v.get("pullDiagnostics"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let refresh_support = params
+                .capabilities
+                .workspace
+                .as_ref()
+                .and_then(|v| v.diagnostic.as_ref())
+                .and_then(|v| v.refresh_support)
+                .unwrap_or(false);
+            self.diagnostics
+                .set_pull_mode(pull_diagnostics, refresh_support);
+            let incremental_render = params
+                .initialization_options
+                .as_ref()
+                .and_then(|v| v.get("incrementalRender"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            self.renderer
+                .lock()
+                .await
+                .set_incremental_render(incremental_render);
+            let max_file_size = params
+                .initialization_options
+                .as_ref()
+                .and_then(|v| v.get("maxFileSize"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_MAX_FILE_SIZE);
+            self.renderer.lock().await.set_max_file_size(max_file_size);
+            let component_decorators = params
+                .initialization_options
+                .as_ref()
+                .and_then(|v| v.get("componentDecorators"))
+                .and_then(|v| v.as_array())
+                .map(|v| {
+                    v.iter()
+                        .filter_map(|v| v.as_str().map(|v| v.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            self.renderer
+                .lock()
+                .await
+                .set_component_decorators(component_decorators);
             self.renderer
                 .lock()
                 .await
@@ -202,7 +590,24 @@ impl LanguageServer for VueLspServer {
                         .unwrap(),
                 )
                 .await;
-            self.css_server.initialize(params.clone()).await.unwrap();
+            // 增量渲染时未修改的文件在后台分批创建节点，使反向关系完整
+            let renderer = Arc::clone(&self.renderer);
+            tokio::spawn(async move {
+                while renderer
+                    .lock()
+                    .await
+                    .materialize_lazy_files(LAZY_FILES_BATCH_SIZE)
+                    .await
+                {
+                    tokio::task::yield_now().await;
+                }
+            });
+            if is_css_enabled_in_options(params.initialization_options.as_ref()) {
+                let css_server =
+                    CssServer::new(self.client.clone(), self.css_diagnostics_tx.clone());
+                css_server.initialize(params.clone()).await.unwrap();
+                *self.css_server.write().await = Some(css_server);
+            }
             let result = self.ts_server.write().await.initialize(params).await?;
             let file_operation = Some(FileOperationRegistrationOptions {
                 filters: vec![FileOperationFilter {
@@ -216,6 +621,13 @@ impl LanguageServer for VueLspServer {
             let mut commands = vec![
                 "vue-property-decorator-extension.restart.tsserver".to_string(),
                 "vue-property-decorator-extension.clean.cache.and.restart".to_string(),
+                "vue-property-decorator-extension.rerender.document".to_string(),
+                "vue-property-decorator-extension.show.rendered.range".to_string(),
+                "vue-property-decorator-extension.find.usages".to_string(),
+                "vue-property-decorator-extension.status".to_string(),
+                "vue-property-decorator-extension.dump.graph".to_string(),
+                "vue-property-decorator-extension.reveal.source".to_string(),
+                "vue-property-decorator-extension.set.logLevel".to_string(),
             ];
             if let Some(execute_command_provider) = result.capabilities.execute_command_provider {
                 let mut ts_commands = execute_command_provider
@@ -233,17 +645,45 @@ impl LanguageServer for VueLspServer {
                     version: Some("1.0.0".to_string()),
                 }),
                 capabilities: ServerCapabilities {
-                    position_encoding: result.capabilities.position_encoding,
-                    text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                        TextDocumentSyncKind::INCREMENTAL,
+                    position_encoding: Some(PositionEncodingKind::UTF16),
+                    text_document_sync: Some(TextDocumentSyncCapability::Options(
+                        TextDocumentSyncOptions {
+                            open_close: Some(true),
+                            change: Some(TextDocumentSyncKind::INCREMENTAL),
+                            will_save_wait_until: Some(true),
+                            save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                            ..Default::default()
+                        },
                     )),
                     hover_provider: result.capabilities.hover_provider,
                     completion_provider: result.capabilities.completion_provider,
                     definition_provider: result.capabilities.definition_provider,
                     references_provider: result.capabilities.references_provider,
+                    implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
+                    selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+                    call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
                     document_symbol_provider: result.capabilities.document_symbol_provider,
-                    semantic_tokens_provider: result.capabilities.semantic_tokens_provider,
+                    document_link_provider: Some(DocumentLinkOptions {
+                        resolve_provider: Some(false),
+                        work_done_progress_options: WorkDoneProgressOptions::default(),
+                    }),
+                    document_formatting_provider: result.capabilities.document_formatting_provider,
+                    semantic_tokens_provider: result
+                        .capabilities
+                        .semantic_tokens_provider
+                        .map(VueLspServer::enable_semantic_tokens_delta),
                     code_action_provider: result.capabilities.code_action_provider,
+                    code_lens_provider: Some(CodeLensOptions {
+                        resolve_provider: Some(true),
+                    }),
+                    diagnostic_provider: pull_diagnostics.then(|| {
+                        DiagnosticServerCapabilities::Options(DiagnosticOptions {
+                            identifier: Some("vue-property-decorator".to_string()),
+                            inter_file_dependencies: true,
+                            workspace_diagnostics: false,
+                            work_done_progress_options: WorkDoneProgressOptions::default(),
+                        })
+                    }),
                     workspace: Some(WorkspaceServerCapabilities {
                         workspace_folders: result
                             .capabilities
@@ -279,7 +719,9 @@ impl LanguageServer for VueLspServer {
     #[instrument]
     async fn initialized(&self, _params: InitializedParams) {
         info!("start");
-        self.css_server.initialized().await;
+        if let Some(css_server) = self.css_server.read().await.as_ref() {
+            css_server.initialized().await;
+        }
         self.ts_server.read().await.initialized().await;
         self.get_configure().await;
         self.client
@@ -295,7 +737,7 @@ impl LanguageServer for VueLspServer {
 
     #[instrument]
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
-        if !VueLspServer::is_uri_valid(&params.text_document.uri) {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
             return;
         }
         info!("start");
@@ -332,12 +774,18 @@ impl LanguageServer for VueLspServer {
                 let ts_server = self.ts_server.read().await;
                 debug!("did_open:lock ts_server");
                 ts_server.did_open(&uri, document).await;
+                self.publish_template_diagnostics(&uri, Some(document.version()))
+                    .await;
                 info!("did_open:done {:?}", start_time.elapsed());
             },
             async {
+                let css_server = self.css_server.read().await;
+                let Some(css_server) = css_server.as_ref() else {
+                    return;
+                };
                 let html_document = { self.renderer.lock().await.get_html_document(&uri) };
                 if let Some(html_document) = html_document {
-                    self.css_server.did_open(params, &html_document).await;
+                    css_server.did_open(params, &html_document).await;
                 }
             }
         );
@@ -345,7 +793,7 @@ impl LanguageServer for VueLspServer {
 
     #[instrument]
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
-        if !VueLspServer::is_uri_valid(&params.text_document.uri) {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
             return;
         }
         info!("start");
@@ -367,11 +815,17 @@ impl LanguageServer for VueLspServer {
                 let ts_server = self.ts_server.read().await;
                 debug!("lock ts_server");
                 ts_server.did_change(params, &document).await;
+                self.publish_template_diagnostics(&uri, Some(document.version()))
+                    .await;
             },
             async {
+                let css_server = self.css_server.read().await;
+                let Some(css_server) = css_server.as_ref() else {
+                    return;
+                };
                 let html_document = { self.renderer.lock().await.get_html_document(&uri) };
                 if let Some(html_document) = html_document {
-                    self.css_server
+                    css_server
                         .did_change(css_params, &document, &html_document)
                         .await;
                 }
@@ -383,7 +837,7 @@ impl LanguageServer for VueLspServer {
 
     #[instrument]
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
-        if !VueLspServer::is_uri_valid(&params.text_document.uri) {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
             return;
         }
         info!("start");
@@ -395,26 +849,35 @@ impl LanguageServer for VueLspServer {
                 &serde_json::to_value(&params).unwrap(),
             );
         }
-        let css_params = params.clone();
-        join!(
-            async {
-                self.ts_server.read().await.did_close(params).await;
-            },
-            async {
-                let uri = css_params.text_document.uri.clone();
-                let html_document = { self.renderer.lock().await.get_html_document(&uri) };
-                if let Some(html_document) = html_document {
-                    self.css_server.did_close(css_params, &html_document).await;
-                }
-            }
-        );
+        self.close_document(params).await;
         info!("done {:?}", start_time.elapsed());
     }
 
+    /// 保存前整理模版，只修改模版部分，避免与 tsserver 和 css 的格式化冲突
+    #[instrument]
+    async fn will_save_wait_until(
+        &self,
+        params: WillSaveTextDocumentParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let uri = &params.text_document.uri;
+        if !self.format_on_save.load(Ordering::Relaxed)
+            || !Renderer::is_vue_component(uri)
+            || !self.is_uri_enabled(uri).await
+        {
+            return Ok(None);
+        }
+        let edits = self.renderer.lock().await.get_template_format_edits(uri);
+        if edits.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(edits))
+        }
+    }
+
     #[instrument]
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
         let uri = &params.text_document.uri;
-        if !VueLspServer::is_uri_valid(uri) {
+        if !self.is_uri_enabled(uri).await {
             return;
         }
         info!("start");
@@ -447,15 +910,31 @@ impl LanguageServer for VueLspServer {
     }
 
     async fn will_rename_files(&self, params: RenameFilesParams) -> Result<Option<WorkspaceEdit>> {
-        {
+        // 根据依赖关系更新导入被重命名文件的路径，tsserver 无法感知组件之间的全部导入
+        let import_edits = {
             let mut uris = vec![];
+            let mut import_edits: HashMap<Uri, Vec<TextEdit>> = HashMap::new();
+            let mut renderer = self.renderer.lock().await;
             for file in &params.files {
-                let uri = Uri::from_str(&file.new_uri).unwrap();
-                uris.push(uri);
+                let old_uri = Uri::from_str(&file.old_uri).unwrap();
+                let new_uri = Uri::from_str(&file.new_uri).unwrap();
+                for (uri, mut edits) in renderer.get_rename_import_edits(&old_uri, &new_uri) {
+                    import_edits.entry(uri).or_default().append(&mut edits);
+                }
+                uris.push(new_uri);
+            }
+            renderer.will_create_files(uris);
+            import_edits
+        };
+        let mut response = self.ts_server.read().await.will_rename_files(params).await;
+        if !import_edits.is_empty() {
+            if let Ok(None) = response {
+                response = Ok(Some(WorkspaceEdit::default()));
+            }
+            if let Ok(Some(workspace_edit)) = &mut response {
+                util::merge_workspace_edit(workspace_edit, import_edits);
             }
-            self.renderer.lock().await.will_create_files(uris);
         }
-        let response = self.ts_server.read().await.will_rename_files(params).await;
         response
     }
 
@@ -509,7 +988,10 @@ impl LanguageServer for VueLspServer {
 
     #[instrument]
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
-        if !VueLspServer::is_uri_valid(&params.text_document_position_params.text_document.uri) {
+        if !self
+            .is_uri_enabled(&params.text_document_position_params.text_document.uri)
+            .await
+        {
             return Ok(None);
         }
         info!("start");
@@ -517,31 +999,73 @@ impl LanguageServer for VueLspServer {
         let mut hover = Ok(None);
         let uri = &params.text_document_position_params.text_document.uri;
         let position = &params.text_document_position_params.position;
-        let typ = {
+        let (typ, dynamic_hover, prop_hover) = {
             let renderer = self.renderer.lock().await;
-            renderer.get_position_type(uri, position)
+            (
+                renderer.get_position_type(uri, position),
+                renderer.get_dynamic_component_hover(uri, position),
+                renderer.get_component_prop_hover(uri, position),
+            )
         };
         if let Some(typ) = typ {
             match typ {
+                // 动态组件 `:is` 的字符串字面量显示对应组件的信息
+                PositionType::TemplateExpr(_) | PositionType::Template
+                    if dynamic_hover.is_some() =>
+                {
+                    hover = Ok(dynamic_hover);
+                }
+                // 组件标签的属性优先显示 prop 的信息，而不是同名的 HTML 全局属性
+                PositionType::Template if prop_hover.is_some() => {
+                    hover = Ok(prop_hover);
+                }
                 PositionType::Script => {
                     info!("In script");
-                    hover = self
-                        .ts_server
-                        .read()
-                        .await
-                        .hover(params.text_document_position_params)
-                        .await;
+                    let option_hover = {
+                        let renderer = self.renderer.lock().await;
+                        renderer
+                            .get_decorator_option_hover(uri, position)
+                            .or_else(|| renderer.get_lifecycle_hook_hover(uri, position))
+                    };
+                    if let Some(option_hover) = option_hover {
+                        hover = Ok(Some(option_hover));
+                    } else {
+                        hover = self
+                            .ts_server
+                            .read()
+                            .await
+                            .hover(params.text_document_position_params)
+                            .await;
+                    }
                 }
                 PositionType::TemplateExpr(pos) => {
                     info!("In template expr");
-                    let mut params = params.clone();
-                    params.text_document_position_params.position = pos;
-                    hover = self
-                        .ts_server
-                        .read()
-                        .await
-                        .hover(params.text_document_position_params)
-                        .await;
+                    let (adjacent_positions, is_synthetic) = {
+                        let renderer = self.renderer.lock().await;
+                        (
+                            renderer.get_adjacent_mapping_positions(uri, &pos),
+                            renderer.is_in_synthetic_region(uri, &pos),
+                        )
+                    };
+                    // 合成的内容在原文件中没有对应，不提供悬停信息
+                    if is_synthetic {
+                        info!("done {:?}", start_time.elapsed());
+                        return Ok(None);
+                    }
+                    let params = &params.text_document_position_params;
+                    let ts_server = &*self.ts_server.read().await;
+                    hover = request_with_adjacent_retry(
+                        pos,
+                        adjacent_positions,
+                        |hover: &Result<Option<Hover>>| matches!(hover, Ok(None)),
+                        move |position| {
+                            ts_server.hover(TextDocumentPositionParams {
+                                position,
+                                ..params.clone()
+                            })
+                        },
+                    )
+                    .await;
                 }
                 PositionType::Template => {
                     info!("In template");
@@ -567,12 +1091,15 @@ impl LanguageServer for VueLspServer {
                 }
                 PositionType::Style => {
                     info!("In style");
+                    let css_server = self.css_server.read().await;
                     let html_document = {
                         let renderer = self.renderer.lock().await;
                         renderer.get_html_document(uri)
                     };
-                    if let Some(html_document) = html_document {
-                        hover = self.css_server.hover(params, &html_document).await;
+                    if let (Some(css_server), Some(html_document)) =
+                        (css_server.as_ref(), html_document)
+                    {
+                        hover = css_server.hover(params, &html_document).await;
                     }
                 }
             }
@@ -583,7 +1110,10 @@ impl LanguageServer for VueLspServer {
 
     #[instrument]
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
-        if !VueLspServer::is_uri_valid(&params.text_document_position.text_document.uri) {
+        if !self
+            .is_uri_enabled(&params.text_document_position.text_document.uri)
+            .await
+        {
             return Ok(None);
         }
         info!("start");
@@ -597,60 +1127,283 @@ impl LanguageServer for VueLspServer {
             renderer.get_position_type(uri, position)
         };
         if let Some(typ) = typ {
-            /// 添加额外参数
-            fn add_extra_params(list: &mut Vec<CompletionItem>, uri: &Uri) {
+            /// 添加额外参数，模版表达式中的补全项额外标记 in_template
+            fn add_extra_params(list: &mut Vec<CompletionItem>, uri: &Uri, in_template: bool) {
                 for item in list {
                     if let Some(data) = &mut item.data {
                         if data.is_object() {
                             if let Some(data) = data.as_object_mut() {
                                 data.insert("from_ts_server".to_string(), Value::Bool(true));
                                 data.insert("original_uri".to_string(), json!(uri));
+                                if in_template {
+                                    data.insert("in_template".to_string(), Value::Bool(true));
+                                }
                             }
                         }
+                    } else if in_template {
+                        item.data = Some(json!({
+                            "from_ts_server": true,
+                            "original_uri": json!(uri),
+                            "in_template": true
+                        }));
                     } else {
                         item.data = Some(json!({
                             "from_ts_server": true,
                             "original_uri": json!(uri)
                         }));
                     }
+                    // 模版表达式中不需要 "this."
+                    if in_template {
+                        ts_server::strip_this_prefix(item);
+                    }
+                }
+            }
+            /// 补全结果是否为空
+            fn is_completion_empty(completion: &Result<Option<CompletionResponse>>) -> bool {
+                match completion {
+                    Ok(Some(CompletionResponse::Array(list))) => list.is_empty(),
+                    Ok(Some(CompletionResponse::List(list))) => list.items.is_empty(),
+                    Ok(None) => true,
+                    Err(_) => false,
                 }
             }
             /// 给每项的 data 中加入标记表示来自 ts 服务器的补全
-            fn completion_add_flag(completion: &mut Result<Option<CompletionResponse>>, uri: &Uri) {
+            fn completion_add_flag(
+                completion: &mut Result<Option<CompletionResponse>>,
+                uri: &Uri,
+                in_template: bool,
+            ) {
                 if let Ok(Some(completion)) = completion {
                     match completion {
                         CompletionResponse::Array(list) => {
-                            add_extra_params(list, uri);
+                            add_extra_params(list, uri, in_template);
                         }
                         CompletionResponse::List(list) => {
-                            add_extra_params(&mut list.items, uri);
+                            add_extra_params(&mut list.items, uri, in_template);
                         }
                     }
                 }
             }
+            /// 已注册组件名称的补全项
+            fn get_component_items(components: Vec<String>) -> Vec<CompletionItem> {
+                components
+                    .into_iter()
+                    .map(|name| CompletionItem {
+                        label: name,
+                        kind: Some(CompletionItemKind::CLASS),
+                        ..Default::default()
+                    })
+                    .collect()
+            }
+            /// 合并额外的补全项，跳过已经存在的同名项
+            fn merge_completion_items(
+                completion: &mut Result<Option<CompletionResponse>>,
+                items: Vec<CompletionItem>,
+            ) {
+                if let Ok(None) = completion {
+                    *completion = Ok(Some(CompletionResponse::Array(items)));
+                    return;
+                }
+                let list = match completion {
+                    Ok(Some(CompletionResponse::Array(list))) => list,
+                    Ok(Some(CompletionResponse::List(list))) => &mut list.items,
+                    _ => return,
+                };
+                for item in items {
+                    if list.iter().all(|v| v.label != item.label) {
+                        list.push(item);
+                    }
+                }
+            }
             match typ {
                 PositionType::Script => {
                     let uri = uri.clone();
+                    let hooks = {
+                        let renderer = self.renderer.lock().await;
+                        renderer.get_lifecycle_hook_completions(&uri, position)
+                    };
                     debug!("lock ts_server await");
                     let ts_server = self.ts_server.read().await;
                     debug!("lock ts_server");
                     completion = ts_server.completion(params).await;
-                    completion_add_flag(&mut completion, &uri);
+                    completion_add_flag(&mut completion, &uri, false);
+                    // 类体中成员名称处补充生命周期钩子
+                    if !hooks.is_empty() {
+                        let items = hooks
+                            .into_iter()
+                            .map(|(name, description)| CompletionItem {
+                                label: name.to_string(),
+                                kind: Some(CompletionItemKind::METHOD),
+                                detail: Some("(lifecycle hook)".to_string()),
+                                documentation: Some(Documentation::MarkupContent(MarkupContent {
+                                    kind: MarkupKind::Markdown,
+                                    value: description.to_string(),
+                                })),
+                                insert_text: Some(format!("{}() {{\n\t$0\n}}", name)),
+                                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                                ..Default::default()
+                            })
+                            .collect();
+                        merge_completion_items(&mut completion, items);
+                    }
                 }
                 PositionType::TemplateExpr(pos) => {
-                    let mut params = params.clone();
-                    params.text_document_position.position = pos;
+                    let (
+                        methods,
+                        is_in_v_for_alias,
+                        components,
+                        adjacent_positions,
+                        is_synthetic,
+                        global_properties,
+                    ) = {
+                        let renderer = self.renderer.lock().await;
+                        (
+                            renderer.get_event_handler_methods(uri, position),
+                            renderer.is_in_v_for_alias(uri, position),
+                            renderer.get_component_is_completions(uri, position),
+                            renderer.get_adjacent_mapping_positions(uri, &pos),
+                            renderer.is_in_synthetic_region(uri, &pos),
+                            renderer.get_global_property_completions(uri, position),
+                        )
+                    };
+                    // 循环变量为声明，合成的内容在原文件中没有对应，均不需要补全
+                    if is_in_v_for_alias || is_synthetic {
+                        info!("done {:?}", start_time.elapsed());
+                        return Ok(None);
+                    }
+                    // `:is` 的字符串字面量中提示已注册的组件
+                    if let Some(components) = components {
+                        info!("done {:?}", start_time.elapsed());
+                        return Ok(Some(CompletionResponse::Array(get_component_items(
+                            components,
+                        ))));
+                    }
                     debug!("lock ts_server await");
-                    let ts_server = self.ts_server.read().await;
+                    let ts_server = &*self.ts_server.read().await;
                     debug!("lock ts_server");
-                    completion = ts_server.completion(params).await;
-                    completion_add_flag(&mut completion, uri);
+                    let params = &params;
+                    completion = request_with_adjacent_retry(
+                        pos,
+                        adjacent_positions,
+                        is_completion_empty,
+                        move |position| {
+                            let mut params = params.clone();
+                            params.text_document_position.position = position;
+                            ts_server.completion(params)
+                        },
+                    )
+                    .await;
+                    completion_add_flag(&mut completion, uri, true);
+                    // 补充 UI 库扩展的全局属性
+                    if !global_properties.is_empty() {
+                        let items = global_properties
+                            .into_iter()
+                            .map(|prop| CompletionItem {
+                                label: prop.name,
+                                kind: Some(CompletionItemKind::PROPERTY),
+                                documentation: prop.description.map(|v| match v {
+                                    Description::String(value) => Documentation::String(value),
+                                    Description::MarkupContent(value) => {
+                                        Documentation::MarkupContent(value)
+                                    }
+                                }),
+                                ..Default::default()
+                            })
+                            .collect();
+                        merge_completion_items(&mut completion, items);
+                    }
+                    // 事件绑定中优先提示组件的方法
+                    if let Some(methods) = methods {
+                        let mut items = methods
+                            .into_iter()
+                            .map(|method| CompletionItem {
+                                label: method.name.clone(),
+                                kind: Some(CompletionItemKind::METHOD),
+                                sort_text: Some(format!("0{}", method.name)),
+                                ..Default::default()
+                            })
+                            .collect::<Vec<_>>();
+                        let (ts_items, is_incomplete) = match completion? {
+                            Some(CompletionResponse::Array(list)) => (list, false),
+                            Some(CompletionResponse::List(list)) => {
+                                (list.items, list.is_incomplete)
+                            }
+                            None => (vec![], false),
+                        };
+                        for item in ts_items {
+                            if items.iter().all(|v| v.label != item.label) {
+                                items.push(item);
+                            }
+                        }
+                        completion = Ok(Some(CompletionResponse::List(CompletionList {
+                            is_incomplete,
+                            items,
+                        })));
+                    }
                 }
                 PositionType::Template => {
-                    self.update_html_languageservice(uri).await;
-                    let html_document = {
+                    // 指令修饰符
+                    let modifiers = {
+                        let text_documents = self.text_documents.read().await;
+                        text_documents.get_document(uri).and_then(|document| {
+                            let offset = document.offset_at(*position) as usize;
+                            vue_data::get_directive_modifiers(document.get_content(None), offset)
+                        })
+                    };
+                    if let Some(modifiers) = modifiers {
+                        let items = modifiers
+                            .into_iter()
+                            .map(|(name, description)| CompletionItem {
+                                label: name.to_string(),
+                                kind: Some(CompletionItemKind::KEYWORD),
+                                documentation: Some(Documentation::String(description.to_string())),
+                                ..Default::default()
+                            })
+                            .collect();
+                        info!("done {:?}", start_time.elapsed());
+                        return Ok(Some(CompletionResponse::Array(items)));
+                    }
+                    // `ref` 的值提示尚未使用的 `@Ref` 名称，`<component>` 的 `is` 的值提示已注册的组件
+                    let (refs, components, is_allowed) = {
                         let renderer = self.renderer.lock().await;
-                        renderer.get_html_document(uri)
+                        (
+                            renderer.get_ref_value_completions(uri, position),
+                            renderer.get_component_is_completions(uri, position),
+                            renderer.is_template_completion_allowed(uri, position),
+                        )
+                    };
+                    if let Some(components) = components {
+                        info!("done {:?}", start_time.elapsed());
+                        return Ok(Some(CompletionResponse::Array(get_component_items(
+                            components,
+                        ))));
+                    }
+                    if let Some(refs) = refs {
+                        let items = refs
+                            .into_iter()
+                            .filter_map(|prop| prop.ref_params)
+                            .map(|params| CompletionItem {
+                                label: params.name,
+                                kind: Some(CompletionItemKind::REFERENCE),
+                                detail: params.typ,
+                                ..Default::default()
+                            })
+                            .collect();
+                        info!("done {:?}", start_time.elapsed());
+                        return Ok(Some(CompletionResponse::Array(items)));
+                    }
+                    // 标签名或属性名的单词中间不提供补全
+                    if !is_allowed {
+                        info!("done {:?}", start_time.elapsed());
+                        return Ok(None);
+                    }
+                    self.update_html_languageservice(uri).await;
+                    let (html_document, tags_provider) = {
+                        let mut renderer = self.renderer.lock().await;
+                        (
+                            renderer.get_html_document(uri),
+                            renderer.get_tags_provider(uri).await,
+                        )
                     };
                     if let Some(html_document) = html_document {
                         let document_context = DefaultDocumentContext {};
@@ -660,7 +1413,7 @@ impl LanguageServer for VueLspServer {
                         let text_documents = self.text_documents.read().await;
                         debug!("lock all");
                         let text_document = text_documents.get_document(uri).unwrap();
-                        let html_result = html_server.do_complete(
+                        let mut html_result = html_server.do_complete(
                             text_document,
                             position,
                             &html_document,
@@ -668,16 +1421,42 @@ impl LanguageServer for VueLspServer {
                             None,
                             &data_manager,
                         );
+                        // 组件属性显示类型，必填属性排在前面
+                        let offset = text_document.offset_at(*position) as usize;
+                        let tag = html_document
+                            .find_node_at(offset, &mut vec![])
+                            .and_then(|node| node.tag.clone());
+                        if let Some(tag) = tag {
+                            for item in &mut html_result.items {
+                                if let Some(detail) =
+                                    tags_provider.get_attribute_detail(&tag, &item.label)
+                                {
+                                    item.detail = match (&detail.typ, detail.validator) {
+                                        (Some(typ), true) => {
+                                            Some(format!("{} (has validator)", typ))
+                                        }
+                                        (None, true) => Some("(has validator)".to_string()),
+                                        (typ, false) => typ.clone(),
+                                    };
+                                    if detail.required {
+                                        item.sort_text = Some(format!("0{}", item.label));
+                                    }
+                                }
+                            }
+                        }
                         completion = Ok(Some(CompletionResponse::List(html_result)));
                     }
                 }
                 PositionType::Style => {
+                    let css_server = self.css_server.read().await;
                     let html_document = {
                         let renderer = self.renderer.lock().await;
                         renderer.get_html_document(uri)
                     };
-                    if let Some(html_document) = html_document {
-                        completion = self.css_server.completion(params, &html_document).await;
+                    if let (Some(css_server), Some(html_document)) =
+                        (css_server.as_ref(), html_document)
+                    {
+                        completion = css_server.completion(params, &html_document).await;
                     }
                 }
             }
@@ -689,14 +1468,15 @@ impl LanguageServer for VueLspServer {
 
     #[instrument]
     async fn completion_resolve(&self, mut params: CompletionItem) -> Result<CompletionItem> {
-        /// 判断是否来自 ts_server 并且移除标记，返回原始 uri
-        fn get_original_uri(params: &mut CompletionItem) -> Option<Value> {
+        /// 判断是否来自 ts_server 并且移除标记，返回原始 uri 及是否位于模版表达式中
+        fn get_original_uri(params: &mut CompletionItem) -> Option<(Value, bool)> {
             let data = params.data.as_mut()?;
             if data.is_object() {
                 let data = data.as_object_mut()?;
                 if data.contains_key("from_ts_server") {
                     data.remove("from_ts_server");
-                    Some(data.remove("original_uri").unwrap())
+                    let in_template = data.remove("in_template").is_some();
+                    Some((data.remove("original_uri").unwrap(), in_template))
                 } else {
                     None
                 }
@@ -705,11 +1485,15 @@ impl LanguageServer for VueLspServer {
             }
         }
         let original_uri = get_original_uri(&mut params);
-        if let Some(original_uri) = original_uri {
+        if let Some((original_uri, in_template)) = original_uri {
             self.ts_server
                 .read()
                 .await
-                .completion_resolve(params, serde_json::from_value(original_uri).unwrap())
+                .completion_resolve(
+                    params,
+                    serde_json::from_value(original_uri).unwrap(),
+                    in_template,
+                )
                 .await
         } else {
             Ok(params)
@@ -721,7 +1505,10 @@ impl LanguageServer for VueLspServer {
         &self,
         params: GotoDefinitionParams,
     ) -> Result<Option<GotoDefinitionResponse>> {
-        if !VueLspServer::is_uri_valid(&params.text_document_position_params.text_document.uri) {
+        if !self
+            .is_uri_enabled(&params.text_document_position_params.text_document.uri)
+            .await
+        {
             return Ok(None);
         }
         info!("start");
@@ -739,23 +1526,70 @@ impl LanguageServer for VueLspServer {
             match typ {
                 PositionType::Script => {
                     debug!("Script");
-                    definition = self
-                        .ts_server
-                        .read()
-                        .await
-                        .goto_definition(params, false)
-                        .await;
+                    let mut locations = {
+                        let renderer = self.renderer.lock().await;
+                        let locations = renderer.get_decorator_template_locations(uri, position);
+                        if locations.is_empty() {
+                            renderer.get_inject_provider_locations(uri, position)
+                        } else {
+                            locations
+                        }
+                    };
+                    if locations.len() == 1 {
+                        definition = Ok(Some(GotoDefinitionResponse::Scalar(locations.remove(0))));
+                    } else if locations.len() > 1 {
+                        definition = Ok(Some(GotoDefinitionResponse::Array(locations)));
+                    } else {
+                        definition = self
+                            .ts_server
+                            .read()
+                            .await
+                            .goto_definition(params, false)
+                            .await;
+                    }
                 }
                 PositionType::TemplateExpr(pos) => {
                     debug!("TemplateExpr");
-                    let mut params = params.clone();
-                    params.text_document_position_params.position = pos;
-                    definition = self
-                        .ts_server
-                        .read()
-                        .await
-                        .goto_definition(params, true)
+                    let location = {
+                        let renderer = self.renderer.lock().await;
+                        renderer
+                            .get_dynamic_component_location(uri, position)
+                            .or_else(|| renderer.get_style_module_location(uri, position))
+                    };
+                    if let Some(location) = location {
+                        definition = Ok(Some(GotoDefinitionResponse::Scalar(location)));
+                    } else {
+                        let (adjacent_positions, is_synthetic) = {
+                            let renderer = self.renderer.lock().await;
+                            (
+                                renderer.get_adjacent_mapping_positions(uri, &pos),
+                                renderer.is_in_synthetic_region(uri, &pos),
+                            )
+                        };
+                        // 合成的内容在原文件中没有对应，不跳转
+                        if is_synthetic {
+                            info!("done {:?}", start_time.elapsed());
+                            return Ok(None);
+                        }
+                        let ts_server = &*self.ts_server.read().await;
+                        let params = &params;
+                        definition = request_with_adjacent_retry(
+                            pos,
+                            adjacent_positions,
+                            |definition: &Result<Option<GotoDefinitionResponse>>| match definition {
+                                Ok(Some(GotoDefinitionResponse::Array(list))) => list.is_empty(),
+                                Ok(Some(GotoDefinitionResponse::Link(list))) => list.is_empty(),
+                                Ok(Some(GotoDefinitionResponse::Scalar(_))) | Err(_) => false,
+                                Ok(None) => true,
+                            },
+                            move |position| {
+                                let mut params = params.clone();
+                                params.text_document_position_params.position = position;
+                                ts_server.goto_definition(params, true)
+                            },
+                        )
                         .await;
+                    }
                 }
                 PositionType::Template => {
                     debug!("Template");
@@ -787,8 +1621,22 @@ impl LanguageServer for VueLspServer {
                                             definition =
                                                 Ok(Some(GotoDefinitionResponse::Scalar(location)));
                                         }
+                                    } else if let Some(location) =
+                                        renderer.get_dynamic_component_location(uri, position)
+                                    {
+                                        definition =
+                                            Ok(Some(GotoDefinitionResponse::Scalar(location)));
                                     } else {
-                                        let tag = node.tag.as_ref().unwrap().clone();
+                                        // 动态组件的属性使用 `:is` 指定的组件查找
+                                        let source = renderer
+                                            .get_document(uri)
+                                            .map_or("", |v| v.get_content(None));
+                                        let tag =
+                                            Renderer::get_dynamic_component_tag(&node, source)
+                                                .map(|(tag, _)| tag)
+                                                .unwrap_or_else(|| {
+                                                    node.tag.as_ref().unwrap().clone()
+                                                });
                                         let mut attr = None;
                                         for (attr_name, node_attr) in &node.attributes {
                                             if node_attr.offset <= offset
@@ -822,19 +1670,216 @@ impl LanguageServer for VueLspServer {
         definition
     }
 
+    #[instrument]
+    async fn goto_implementation(
+        &self,
+        params: GotoImplementationParams,
+    ) -> Result<Option<GotoImplementationResponse>> {
+        if !self
+            .is_uri_enabled(&params.text_document_position_params.text_document.uri)
+            .await
+        {
+            return Ok(None);
+        }
+        info!("start");
+        let start_time = time::Instant::now();
+        let uri = &params.text_document_position_params.text_document.uri;
+        let position = &params.text_document_position_params.position;
+
+        let renderer = self.renderer.lock().await;
+        let typ = renderer.get_position_type(uri, position);
+        if !matches!(typ, Some(PositionType::Script)) {
+            return Ok(None);
+        }
+        // 位于组件类名或属性上时，返回继承当前组件的子组件，没有找到时由 tsserver 处理
+        let locations = renderer.get_implementation_locations(uri, position);
+        drop(renderer);
+        let result = if let Some(locations) = locations {
+            Ok(Some(GotoImplementationResponse::Array(locations)))
+        } else {
+            self.ts_server
+                .read()
+                .await
+                .goto_implementation(params)
+                .await
+        };
+
+        info!("done {:?}", start_time.elapsed());
+        result
+    }
+
     #[instrument]
     async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
-        if !VueLspServer::is_uri_valid(&params.text_document_position.text_document.uri) {
+        if !self
+            .is_uri_enabled(&params.text_document_position.text_document.uri)
+            .await
+        {
             return Ok(None);
         }
         info!("start");
         let start_time = time::Instant::now();
+        // 提供的 key 在后代组件中注入的位置
+        let mut injectors = {
+            let uri = &params.text_document_position.text_document.uri;
+            let position = &params.text_document_position.position;
+            let renderer = self.renderer.lock().await;
+            match renderer.get_position_type(uri, position) {
+                Some(PositionType::Script) => {
+                    renderer.get_provide_injector_locations(uri, position)
+                }
+                _ => vec![],
+            }
+        };
         debug!("lock ts_server await");
         let ts_server = self.ts_server.read().await;
         debug!("lock ts_server");
         let result = ts_server.references(params).await;
         info!("done {:?}", start_time.elapsed());
-        result
+        if injectors.is_empty() {
+            return result;
+        }
+        let mut locations = result?.unwrap_or_default();
+        locations.append(&mut injectors);
+        Ok(Some(locations))
+    }
+
+    #[instrument]
+    async fn selection_range(
+        &self,
+        params: SelectionRangeParams,
+    ) -> Result<Option<Vec<SelectionRange>>> {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
+            return Ok(None);
+        }
+        info!("start");
+        let start_time = time::Instant::now();
+        let uri = &params.text_document.uri;
+        // 非 vue 文件直接从 tsserver 获取选择范围
+        let types = if Renderer::is_vue_component(uri) {
+            let renderer = self.renderer.lock().await;
+            params
+                .positions
+                .iter()
+                .map(|position| renderer.get_position_type(uri, position))
+                .collect::<Vec<_>>()
+        } else {
+            params
+                .positions
+                .iter()
+                .map(|_| Some(PositionType::Script))
+                .collect()
+        };
+        // 模版表达式和脚本中的位置从 tsserver 获取选择范围
+        let ts_positions = params
+            .positions
+            .iter()
+            .zip(&types)
+            .filter_map(|(position, typ)| match typ {
+                Some(PositionType::TemplateExpr(pos)) => Some(*pos),
+                Some(PositionType::Script) => Some(*position),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut ts_ranges = if ts_positions.is_empty() {
+            vec![]
+        } else {
+            self.ts_server
+                .read()
+                .await
+                .selection_range(uri, ts_positions)
+                .await?
+        }
+        .into_iter();
+        // 样式中的位置从 css server 获取选择范围
+        let style_positions = params
+            .positions
+            .iter()
+            .zip(&types)
+            .filter(|(_, typ)| matches!(typ, Some(PositionType::Style)))
+            .map(|(position, _)| *position)
+            .collect::<Vec<_>>();
+        let css_server = self.css_server.read().await;
+        let style_target = match css_server.as_ref() {
+            Some(css_server) if !style_positions.is_empty() => self
+                .renderer
+                .lock()
+                .await
+                .get_html_document(uri)
+                .map(|html_document| (css_server, html_document)),
+            _ => None,
+        };
+        let mut style_ranges = if let Some((css_server, html_document)) = style_target {
+            let params = SelectionRangeParams {
+                text_document: params.text_document.clone(),
+                positions: style_positions,
+                work_done_progress_params: params.work_done_progress_params.clone(),
+                partial_result_params: params.partial_result_params.clone(),
+            };
+            css_server
+                .selection_range(params, &html_document)
+                .await?
+                .unwrap_or_default()
+        } else {
+            vec![]
+        }
+        .into_iter();
+        let renderer = self.renderer.lock().await;
+        let mut result = vec![];
+        for (position, typ) in params.positions.iter().zip(types) {
+            let ranges = match typ {
+                Some(PositionType::TemplateExpr(_) | PositionType::Script) => {
+                    ts_ranges.next().unwrap_or_default()
+                }
+                Some(PositionType::Style) => {
+                    if let Some(selection_range) = style_ranges.next() {
+                        result.push(selection_range);
+                        continue;
+                    }
+                    vec![]
+                }
+                _ => vec![],
+            };
+            result.push(renderer.get_selection_range(uri, position, &ranges));
+        }
+        info!("done {:?}", start_time.elapsed());
+        Ok(Some(result))
+    }
+
+    #[instrument]
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        if !Renderer::is_vue_component(uri) || !self.is_uri_enabled(uri).await {
+            return Ok(None);
+        }
+        let position = &params.text_document_position_params.position;
+        let renderer = self.renderer.lock().await;
+        // 仅支持位于 `@Prop` 属性上，传入调用为父组件模版中传值的属性
+        Ok(renderer
+            .get_prop_call_hierarchy_item(uri, position)
+            .map(|item| vec![item]))
+    }
+
+    #[instrument]
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        let item = &params.item;
+        let renderer = self.renderer.lock().await;
+        Ok(Some(
+            renderer.get_prop_incoming_calls(&item.uri, &item.name),
+        ))
+    }
+
+    #[instrument]
+    async fn outgoing_calls(
+        &self,
+        _params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        Ok(None)
     }
 
     #[instrument]
@@ -842,7 +1887,7 @@ impl LanguageServer for VueLspServer {
         &self,
         params: DocumentSymbolParams,
     ) -> Result<Option<DocumentSymbolResponse>> {
-        if !VueLspServer::is_uri_valid(&params.text_document.uri) {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
             return Ok(None);
         }
         info!("start");
@@ -873,17 +1918,99 @@ impl LanguageServer for VueLspServer {
             if let Ok(Some(DocumentSymbolResponse::Nested(response))) = response {
                 script.children = Some(response);
             }
+            // 组件的 props, events 和 watchers
+            let mut component_symbols = {
+                let renderer = self.renderer.lock().await;
+                renderer.get_component_symbols(uri)
+            };
+            if component_symbols.len() > 0 {
+                script
+                    .children
+                    .get_or_insert_with(Vec::new)
+                    .append(&mut component_symbols);
+            }
         }
         info!("done {:?}", start_time.elapsed());
         Ok(Some(DocumentSymbolResponse::Nested(document_symbol_list)))
     }
 
+    #[instrument]
+    async fn document_link(&self, params: DocumentLinkParams) -> Result<Option<Vec<DocumentLink>>> {
+        let uri = &params.text_document.uri;
+        if !Renderer::is_vue_component(uri) || !self.is_uri_enabled(uri).await {
+            return Ok(None);
+        }
+        let renderer = self.renderer.lock().await;
+        Ok(Some(renderer.get_import_links(uri)))
+    }
+
+    #[instrument]
+    async fn document_link_resolve(&self, params: DocumentLink) -> Result<DocumentLink> {
+        // 导入路径在 document_link 中已经解析，原样返回
+        Ok(params)
+    }
+
+    #[instrument]
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        let uri = &params.text_document.uri;
+        if !Renderer::is_vue_component(uri) || !self.is_uri_enabled(uri).await {
+            return Ok(None);
+        }
+        let renderer = self.renderer.lock().await;
+        // 使用次数在 code_lens_resolve 中计算
+        Ok(renderer
+            .get_component_counts(uri)
+            .map(|(range, props, events)| {
+                vec![CodeLens {
+                    range,
+                    command: None,
+                    data: Some(json!({
+                        "uri": uri,
+                        "props": props,
+                        "events": events,
+                    })),
+                }]
+            }))
+    }
+
+    #[instrument]
+    async fn code_lens_resolve(&self, mut params: CodeLens) -> Result<CodeLens> {
+        let Some(data) = params.data.take() else {
+            return Ok(params);
+        };
+        let Some(uri) = serde_json::from_value::<Uri>(data["uri"].clone()).ok() else {
+            return Ok(params);
+        };
+        let props = data["props"].as_u64().unwrap_or(0);
+        let events = data["events"].as_u64().unwrap_or(0);
+        let usages = self.renderer.lock().await.get_component_usages(&uri);
+        let plural = |count: u64, word: &str| {
+            if count == 1 {
+                format!("{} {}", count, word)
+            } else {
+                format!("{} {}s", count, word)
+            }
+        };
+        params.command = Some(Command {
+            title: format!(
+                "{} · {} · used in {}",
+                plural(props, "prop"),
+                plural(events, "event"),
+                plural(usages.len() as u64, "place")
+            ),
+            // 由客户端转换参数后调用 editor.action.showReferences
+            command: "vue-property-decorator-extension.show.references".to_string(),
+            arguments: Some(vec![json!(uri), json!(params.range.start), json!(usages)]),
+        });
+        Ok(params)
+    }
+
     #[instrument]
     async fn semantic_tokens_full(
         &self,
         params: SemanticTokensParams,
     ) -> Result<Option<SemanticTokensResult>> {
-        if !VueLspServer::is_uri_valid(&params.text_document.uri) {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
             return Ok(None);
         }
         info!("start");
@@ -896,12 +2023,30 @@ impl LanguageServer for VueLspServer {
         result
     }
 
+    #[instrument]
+    async fn semantic_tokens_full_delta(
+        &self,
+        params: SemanticTokensDeltaParams,
+    ) -> Result<Option<SemanticTokensFullDeltaResult>> {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
+            return Ok(None);
+        }
+        info!("start");
+        let start_time = time::Instant::now();
+        debug!("lock ts_server await");
+        let ts_server = self.ts_server.read().await;
+        debug!("lock ts_server");
+        let result = ts_server.semantic_tokens_full_delta(params).await;
+        info!("done {:?}", start_time.elapsed());
+        result
+    }
+
     #[instrument]
     async fn semantic_tokens_range(
         &self,
         params: SemanticTokensRangeParams,
     ) -> Result<Option<SemanticTokensRangeResult>> {
-        if !VueLspServer::is_uri_valid(&params.text_document.uri) {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
             return Ok(None);
         }
         info!("start");
@@ -914,21 +2059,126 @@ impl LanguageServer for VueLspServer {
         result
     }
 
+    /// 拉取诊断，tsserver 和 css 服务器的诊断使用已缓存的结果，模版诊断重新计算
+    #[instrument]
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        let uri = &params.text_document.uri;
+        let mut items = vec![];
+        if self.is_uri_enabled(uri).await {
+            info!("start");
+            let start_time = time::Instant::now();
+            items = self
+                .diagnostics
+                .get_diagnostics(uri, self.template_diagnostics_index);
+            items.append(&mut self.get_template_diagnostics(uri).await);
+            info!("done {:?}", start_time.elapsed());
+        }
+        Ok(DocumentDiagnosticReportResult::Report(
+            DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: None,
+                    items,
+                },
+            }),
+        ))
+    }
+
     #[instrument]
     async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
-        if !VueLspServer::is_uri_valid(&params.text_document.uri) {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
             return Ok(None);
         }
         info!("start");
         let start_time = time::Instant::now();
+        let uri = params.text_document.uri.clone();
+        let convert_edits = {
+            let renderer = self.renderer.lock().await;
+            renderer.get_convert_to_class_edits(&uri, &params.range)
+        };
         debug!("lock ts_server await");
         let ts_server = self.ts_server.read().await;
         debug!("lock ts_server");
-        let result = ts_server.code_action(params).await;
+        let mut result = ts_server.code_action(params).await;
+        // 选项式组件转换为类组件
+        if let Some(edits) = convert_edits {
+            let mut actions = result.ok().flatten().unwrap_or_default();
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title: "Convert to class component".to_string(),
+                kind: Some(CodeActionKind::REFACTOR_REWRITE),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri, edits)])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }));
+            result = Ok(Some(actions));
+        }
         info!("done {:?}", start_time.elapsed());
         result
     }
 
+    #[instrument]
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        if !self.is_uri_enabled(&params.text_document.uri).await {
+            return Ok(None);
+        }
+        info!("start");
+        let start_time = time::Instant::now();
+        let uri = &params.text_document.uri;
+        let html_document = {
+            let renderer = self.renderer.lock().await;
+            renderer.get_html_document(uri)
+        };
+        let css_params = params.clone();
+        let (ts_result, css_result) = join!(
+            async {
+                debug!("lock ts_server await");
+                let ts_server = self.ts_server.read().await;
+                debug!("lock ts_server");
+                ts_server.formatting(params).await
+            },
+            async {
+                let css_server = self.css_server.read().await;
+                let Some(css_server) = css_server.as_ref() else {
+                    return Ok(None);
+                };
+                if let Some(html_document) = &html_document {
+                    let text_documents = self.text_documents.read().await;
+                    if let Some(document) = text_documents.get_document(uri) {
+                        return css_server
+                            .formatting(css_params, document, html_document)
+                            .await;
+                    }
+                }
+                Ok(None)
+            }
+        );
+        // 合并各部分的修改，并移除重叠的修改
+        let mut edits = ts_result?.unwrap_or_default();
+        match css_result {
+            Ok(css_edits) => edits.append(&mut css_edits.unwrap_or_default()),
+            // 样式格式化失败时仍然应用脚本部分的修改
+            Err(err) => error!("css formatting error: {:?}", err),
+        }
+        edits.sort_by(|a, b| a.range.start.cmp(&b.range.start));
+        let mut result: Vec<TextEdit> = vec![];
+        for edit in edits {
+            if result
+                .last()
+                .is_some_and(|last| edit.range.start < last.range.end)
+            {
+                continue;
+            }
+            result.push(edit);
+        }
+        info!("done {:?}", start_time.elapsed());
+        Ok(Some(result))
+    }
+
     async fn execute_command(&self, mut params: ExecuteCommandParams) -> Result<Option<Value>> {
         let text_documents = self.text_documents.read().await;
         if params.command == "vue-property-decorator-extension.restart.tsserver" {
@@ -954,6 +2204,124 @@ impl LanguageServer for VueLspServer {
                 .await;
             self.ts_server.write().await.restart(&text_documents).await;
             Ok(None)
+        } else if params.command == "vue-property-decorator-extension.rerender.document" {
+            // 参数为需要重新渲染的文档 uri，如果未提供，那么重新渲染所有打开的文档
+            let uris = if let Some(uri) = params
+                .arguments
+                .first()
+                .and_then(|v| serde_json::from_value::<Uri>(v.clone()).ok())
+            {
+                vec![uri]
+            } else {
+                text_documents.documents().keys().cloned().collect()
+            };
+            for uri in uris {
+                if let Some(document) = text_documents.get_document(&uri) {
+                    let change = { self.renderer.lock().await.rerender(&uri, document).await };
+                    if let Some(change) = change {
+                        debug!("lock ts_server await");
+                        let ts_server = self.ts_server.read().await;
+                        debug!("lock ts_server");
+                        ts_server.did_save(change).await;
+                    }
+                }
+            }
+            self.client.semantic_tokens_refresh().await.unwrap();
+            Ok(None)
+        } else if params.command == "vue-property-decorator-extension.show.rendered.range" {
+            // 参数为文档 uri 和原文件中的范围
+            let uri = params
+                .arguments
+                .first()
+                .and_then(|v| serde_json::from_value::<Uri>(v.clone()).ok());
+            let range = params
+                .arguments
+                .get(1)
+                .and_then(|v| serde_json::from_value::<Range>(v.clone()).ok());
+            if let (Some(uri), Some(range)) = (uri, range) {
+                let renderer = self.renderer.lock().await;
+                if let Some((content, position)) =
+                    renderer.get_node_render_content_range(&uri, &range)
+                {
+                    return Ok(Some(json!({
+                        "content": content,
+                        "position": position,
+                    })));
+                }
+            }
+            Ok(None)
+        } else if params.command == "vue-property-decorator-extension.find.usages" {
+            // 参数为组件的 uri，返回使用该组件的标签位置
+            let uri = params
+                .arguments
+                .first()
+                .and_then(|v| serde_json::from_value::<Uri>(v.clone()).ok());
+            if let Some(uri) = uri {
+                let locations = self.renderer.lock().await.get_component_usages(&uri);
+                return Ok(Some(json!(locations)));
+            }
+            Ok(None)
+        } else if params.command == "vue-property-decorator-extension.status" {
+            let renderer = self.renderer.lock().await;
+            let stats = renderer.get_render_cache_stats();
+            Ok(Some(json!({
+                "nodes": {
+                    "vue": stats.vue_nodes,
+                    "ts": stats.ts_nodes,
+                    "lib": stats.lib_nodes,
+                    "unknown": stats.unknown_nodes,
+                },
+                "edges": {
+                    "extends": stats.extends_edges,
+                    "register": stats.register_edges,
+                    "transfer": stats.transfer_edges,
+                    "virtual": stats.virtual_edges,
+                },
+                "libraries": renderer.get_library_count(),
+                "willCreateFiles": renderer.get_will_create_files_count(),
+                "lazyFiles": renderer.get_lazy_file_count(),
+                "positionEncoding": PositionEncodingKind::UTF16.as_str(),
+                "isShared": self.is_shared,
+                "cssEnabled": self.is_css_enabled().await,
+            })))
+        } else if params.command == "vue-property-decorator-extension.dump.graph" {
+            // 返回渲染缓存图的 Graphviz DOT 格式
+            let dot = self.renderer.lock().await.dump_graph();
+            Ok(Some(Value::String(dot)))
+        } else if params.command == "vue-property-decorator-extension.reveal.source" {
+            // 参数为渲染目录中的文件 uri，返回对应的原文件 uri
+            let uri = params
+                .arguments
+                .first()
+                .and_then(|v| serde_json::from_value::<Uri>(v.clone()).ok())
+                .ok_or_else(|| jsonrpc::Error::invalid_params("Missing uri argument"))?;
+            let source_uri = self.renderer.lock().await.get_source_uri(&uri);
+            match source_uri {
+                Some(source_uri) => Ok(Some(json!(source_uri))),
+                None => Err(jsonrpc::Error::invalid_params(format!(
+                    "`{}` is not in the rendered directory",
+                    uri
+                ))),
+            }
+        } else if params.command == "vue-property-decorator-extension.set.logLevel" {
+            // 参数为日志等级，如 `debug`
+            let level = params.arguments.first().and_then(|v| v.as_str());
+            let result = match (&self.log_level_handle, level) {
+                (Some(handle), Some(level)) => handle.set_level(level),
+                (None, _) => Err("Log level cannot be changed at runtime".to_string()),
+                (_, None) => Err("Missing log level argument".to_string()),
+            };
+            match result {
+                Ok(level) => {
+                    self.client
+                        .show_message(MessageType::INFO, format!("Log level set to `{}`", level))
+                        .await;
+                }
+                Err(message) => {
+                    self.client.show_message(MessageType::ERROR, message).await;
+                }
+            }
+            Ok(None)
         } else {
             params.command = params
                 .command
@@ -964,6 +2332,127 @@ impl LanguageServer for VueLspServer {
 
     async fn shutdown(&self) -> Result<()> {
         self.ts_server.read().await.shutdown().await;
+        if let Some(css_server) = self.css_server.read().await.as_ref() {
+            css_server.shutdown().await;
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use lsp_textdocument::FullTextDocument;
+    use serde_json::json;
+    use tower_lsp::lsp_types::{Position, Uri};
+
+    use crate::renderer::Renderer;
+
+    /// 插值是模版的最后一个标记时，只有映射边界上的位置使用相邻位置重试
+    #[tokio::test]
+    async fn adjacent_retry() {
+        let mut renderer = Renderer::new();
+        let uri = Uri::from_str("file:///path/project/src/last.vue").unwrap();
+        renderer.create_node_from_document(
+            &uri,
+            FullTextDocument::new(
+                "vue".to_string(),
+                0,
+                [
+                    "<template>",
+                    "  <div>{{ title }}</div>",
+                    "</template>",
+                    "<script lang=\"ts\">",
+                    "import { Component, Vue } from 'vue-property-decorator';",
+                    "@Component",
+                    "export default class Last extends Vue {",
+                    "  private title = '';",
+                    "}",
+                    "</script>",
+                ]
+                .join("\n"),
+            ),
+        );
+        let request = |position: Position| {
+            let uri = &uri;
+            let renderer = &renderer;
+            async move {
+                let pos = renderer.get_mapping_position(uri, &position).unwrap();
+                let requested = Mutex::new(vec![]);
+                // 只有部分位置有结果
+                let result = super::request_with_adjacent_retry(
+                    pos,
+                    renderer.get_adjacent_mapping_positions(uri, &pos),
+                    |result: &tower_lsp::jsonrpc::Result<Option<u32>>| matches!(result, Ok(None)),
+                    |pos| {
+                        requested.lock().unwrap().push(pos.character);
+                        async move { Ok((3..=7).contains(&pos.character).then_some(pos.character)) }
+                    },
+                )
+                .await;
+                (result.unwrap(), requested.into_inner().unwrap())
+            }
+        };
+        // 位于映射的结尾时向左重试
+        assert_eq!(request(Position::new(1, 16)).await, (Some(7), vec![8, 7]));
+        // 位于映射内部时不重试
+        assert_eq!(request(Position::new(1, 10)).await, (None, vec![2]));
+    }
+
+    #[test]
+    fn css_enabled_in_options() {
+        assert!(super::is_css_enabled_in_options(None));
+        assert!(super::is_css_enabled_in_options(Some(&json!({}))));
+        assert!(super::is_css_enabled_in_options(Some(
+            &json!({ "cssEnabled": true })
+        )));
+        // 停用时不启动 CSS 服务
+        assert!(!super::is_css_enabled_in_options(Some(
+            &json!({ "cssEnabled": false })
+        )));
+    }
+
+    #[test]
+    fn ts_server_restart_counter() {
+        let start = Instant::now();
+        let mut counter = super::TsServerRestartCounter::new(start);
+        for count in 1..=super::MAX_TS_SERVER_RESTART_COUNT {
+            assert_eq!(
+                counter.next(start),
+                Some((count, super::get_ts_server_restart_delay(count)))
+            );
+            counter.restarted(start);
+        }
+        // 连续重启达到上限后不再重启
+        assert_eq!(counter.next(start), None);
+        assert_eq!(counter.next(start + Duration::from_secs(30)), None);
+        // 上次重启后稳定运行一段时间，重新计数
+        assert_eq!(
+            counter.next(start + Duration::from_secs(61)),
+            Some((1, Duration::from_secs(1)))
+        );
+    }
+
+    #[test]
+    fn ts_server_restart_delay() {
+        assert_eq!(
+            super::get_ts_server_restart_delay(1),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            super::get_ts_server_restart_delay(2),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            super::get_ts_server_restart_delay(5),
+            Duration::from_secs(16)
+        );
+        assert_eq!(
+            super::get_ts_server_restart_delay(10),
+            Duration::from_secs(16)
+        );
+    }
+}
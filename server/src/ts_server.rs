@@ -1,11 +1,19 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicI32, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_lsp_client::{LspServer, ServerMessage};
 use lsp_textdocument::{FullTextDocument, TextDocuments};
-use notification::{DidCloseTextDocument, Progress};
+use notification::{Cancel, DidCloseTextDocument, Progress};
 use request::{
     ApplyWorkspaceEdit, CodeActionRequest, Completion, DocumentSymbolRequest, ExecuteCommand,
-    GotoDefinition, ResolveCompletionItem, SemanticTokensFullRequest, SemanticTokensRangeRequest,
+    Formatting, GotoDefinition, GotoImplementation, GotoImplementationParams,
+    GotoImplementationResponse, ResolveCompletionItem, SelectionRangeRequest,
+    SemanticTokensFullDeltaRequest, SemanticTokensFullRequest, SemanticTokensRangeRequest,
     WillRenameFiles,
 };
 use serde_json::{json, Value};
@@ -24,10 +32,11 @@ use tower_lsp::{
         *,
     },
 };
-use tracing::debug;
+use tracing::{debug, error, warn};
 
 use crate::convert::{ConvertBack, ConvertOptions, ConvertTo};
 use crate::renderer::Renderer;
+use crate::util;
 
 /// # TsServer
 /// * 将请求转换格式后发送到 tsserver，然后将返回的响应转换为适合的格式
@@ -38,6 +47,106 @@ pub struct TsServer {
     initialize_params: InitializeParams,
     renderer: Arc<Mutex<Renderer>>,
     tx: Sender<(Uri, Option<i32>, Vec<Diagnostic>)>,
+    /// tsserver 是否支持语义标记的增量请求
+    semantic_tokens_delta: bool,
+    /// 每个文档最近一次的语义标记，用于计算增量结果
+    semantic_tokens_cache: Mutex<HashMap<Uri, SemanticTokensCache>>,
+    /// tsserver 未返回 result_id 时使用的自增 id
+    semantic_tokens_id: AtomicU32,
+    /// 每次启动 tsserver 时递增，用于区分主动重启和意外退出
+    generation: Arc<AtomicU32>,
+    /// tsserver 意外退出时发送通知
+    exit_tx: Sender<()>,
+    /// 请求 tsserver 的超时时间，单位为毫秒，为 0 时不限制
+    request_timeout: AtomicU64,
+    /// 下一个请求的 id，用于超时后通知 tsserver 取消请求
+    ///
+    /// 与 async-lsp-client 的分配方式一致，每个进程从 0 开始按发送顺序递增，0 为 initialize 请求
+    next_request_id: AtomicI32,
+    /// 文档修改的次数，请求等待期间发生修改时超时返回 `ContentModified`
+    change_count: AtomicU64,
+    /// 尚未转发到 tsserver 的文档修改
+    pending_changes: Arc<Mutex<PendingChanges>>,
+    /// 合并文档修改的时间窗口，单位为毫秒，为 0 时立即转发
+    change_delay: AtomicU64,
+    /// 启动 tsserver 使用的 node 可执行文件
+    node_path: String,
+}
+
+/// 默认的 tsserver 请求超时时间，单位为毫秒
+pub const DEFAULT_REQUEST_TIMEOUT: u64 = 10000;
+
+/// 默认的合并文档修改的时间窗口，单位为毫秒
+pub const DEFAULT_CHANGE_DELAY: u64 = 30;
+
+/// 等待合并后转发的文档修改
+struct PendingChange {
+    id: u64,
+    params: DidChangeTextDocumentParams,
+}
+
+/// 等待合并后转发的文档修改队列，按开始合并的顺序排列，每个渲染后的文件最多一项
+#[derive(Default)]
+struct PendingChanges {
+    changes: Vec<PendingChange>,
+    /// 每次开始合并修改时递增，用于区分过期的定时任务
+    next_id: u64,
+}
+
+impl PendingChanges {
+    /// 合并到同一文件等待转发的修改中，没有等待转发的修改时原样返回
+    fn merge(
+        &mut self,
+        params: DidChangeTextDocumentParams,
+    ) -> Option<DidChangeTextDocumentParams> {
+        let uri = &params.text_document.uri;
+        match self
+            .changes
+            .iter_mut()
+            .find(|v| &v.params.text_document.uri == uri)
+        {
+            Some(change) => {
+                merge_change(&mut change.params, params);
+                None
+            }
+            None => Some(params),
+        }
+    }
+
+    /// 开始合并修改，返回用于定时转发的 id
+    fn insert(&mut self, params: DidChangeTextDocumentParams) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.changes.push(PendingChange { id, params });
+        id
+    }
+
+    /// 取出定时任务对应的修改，已经被提前转发或者重新开始合并时返回 None
+    fn take(&mut self, uri: &Uri, id: u64) -> Option<DidChangeTextDocumentParams> {
+        let index = self
+            .changes
+            .iter()
+            .position(|v| &v.params.text_document.uri == uri && v.id == id)?;
+        Some(self.changes.remove(index).params)
+    }
+
+    /// 按顺序取出所有等待转发的修改
+    fn drain(&mut self) -> Vec<DidChangeTextDocumentParams> {
+        self.changes.drain(..).map(|v| v.params).collect()
+    }
+
+    fn clear(&mut self) {
+        self.changes.clear();
+    }
+}
+
+/// 最近一次返回给客户端的语义标记
+struct SemanticTokensCache {
+    result_id: Option<String>,
+    /// tsserver 返回的渲染文件中的标记
+    shadow: Vec<SemanticToken>,
+    /// 转换到原文件后的标记
+    tokens: Vec<SemanticToken>,
 }
 
 impl TsServer {
@@ -45,14 +154,108 @@ impl TsServer {
         client: Client,
         renderer: Arc<Mutex<Renderer>>,
         tx: Sender<(Uri, Option<i32>, Vec<Diagnostic>)>,
+        exit_tx: Sender<()>,
+    ) -> TsServer {
+        TsServer::with_node_path(client, renderer, tx, exit_tx, "node".to_string())
+    }
+
+    /// 使用指定的 node 可执行文件启动 tsserver
+    fn with_node_path(
+        client: Client,
+        renderer: Arc<Mutex<Renderer>>,
+        tx: Sender<(Uri, Option<i32>, Vec<Diagnostic>)>,
+        exit_tx: Sender<()>,
+        node_path: String,
     ) -> TsServer {
-        let server = TsServer::spawn(client.clone(), Arc::clone(&renderer), tx.clone());
+        let generation = Arc::new(AtomicU32::new(0));
+        let server = TsServer::spawn(
+            client.clone(),
+            Arc::clone(&renderer),
+            tx.clone(),
+            Arc::clone(&generation),
+            exit_tx.clone(),
+            &node_path,
+        );
         TsServer {
             client,
             server,
             renderer,
             initialize_params: InitializeParams::default(),
             tx,
+            semantic_tokens_delta: false,
+            semantic_tokens_cache: Mutex::new(HashMap::new()),
+            semantic_tokens_id: AtomicU32::new(0),
+            generation,
+            exit_tx,
+            request_timeout: AtomicU64::new(DEFAULT_REQUEST_TIMEOUT),
+            next_request_id: AtomicI32::new(1),
+            change_count: AtomicU64::new(0),
+            pending_changes: Arc::new(Mutex::new(PendingChanges::default())),
+            change_delay: AtomicU64::new(DEFAULT_CHANGE_DELAY),
+            node_path,
+        }
+    }
+
+    /// 设置请求 tsserver 的超时时间，单位为毫秒，为 0 时不限制
+    pub fn set_request_timeout(&self, timeout: u64) {
+        self.request_timeout.store(timeout, Ordering::Relaxed);
+    }
+
+    /// 设置合并文档修改的时间窗口，单位为毫秒，为 0 时立即转发
+    pub fn set_change_delay(&self, delay: u64) {
+        self.change_delay.store(delay, Ordering::Relaxed);
+    }
+
+    /// 将所有等待合并的文档修改立即转发到 tsserver
+    ///
+    /// 发送请求和其他文档通知前调用，保证 tsserver 中的文档与渲染结果一致
+    async fn flush_pending_changes(&self) {
+        let mut pending_changes = self.pending_changes.lock().await;
+        for params in pending_changes.drain() {
+            self.server
+                .send_notification::<DidChangeTextDocument>(params)
+                .await;
+        }
+    }
+
+    /// 发送请求到 tsserver，超过配置的时间未响应时通知 tsserver 取消请求，
+    /// 等待期间文档发生修改时返回 `ContentModified` 错误，否则返回 `RequestCancelled` 错误
+    ///
+    /// `workspace/willRenameFiles` 和 `workspace/executeCommand` 会产生修改，超时后仍可能生效，不限制时间；
+    /// 客户端取消请求时，tower-lsp 会丢弃处理请求的 future，等待随之结束
+    async fn request_with_timeout<R: Request>(&self, params: R::Params) -> Result<R::Result> {
+        self.flush_pending_changes().await;
+        let timeout = self.request_timeout.load(Ordering::Relaxed);
+        let id = self.next_request_id.fetch_add(1, Ordering::SeqCst);
+        let request = self.server.send_request::<R>(params);
+        if timeout == 0 || [WillRenameFiles::METHOD, ExecuteCommand::METHOD].contains(&R::METHOD) {
+            return request.await;
+        }
+        let change_count = self.change_count.load(Ordering::SeqCst);
+        match tokio::time::timeout(Duration::from_millis(timeout), request).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("{} timed out after {}ms", R::METHOD, timeout);
+                self.server
+                    .send_notification::<Cancel>(CancelParams {
+                        id: NumberOrString::Number(id),
+                    })
+                    .await;
+                let code = if self.change_count.load(Ordering::SeqCst) != change_count {
+                    jsonrpc::ErrorCode::ContentModified
+                } else {
+                    jsonrpc::ErrorCode::RequestCancelled
+                };
+                Err(jsonrpc::Error {
+                    code,
+                    message: std::borrow::Cow::Owned(format!(
+                        "tsserver request `{}` timed out after {}ms",
+                        R::METHOD,
+                        timeout
+                    )),
+                    data: None,
+                })
+            }
         }
     }
 
@@ -60,6 +263,9 @@ impl TsServer {
         client: Client,
         renderer: Arc<Mutex<Renderer>>,
         tx: Sender<(Uri, Option<i32>, Vec<Diagnostic>)>,
+        generation: Arc<AtomicU32>,
+        exit_tx: Sender<()>,
+        node_path: &str,
     ) -> LspServer {
         let exe_path = std::env::current_exe().unwrap();
         let mut path = exe_path.parent().unwrap().to_path_buf();
@@ -68,8 +274,9 @@ impl TsServer {
         }
         path.push("typescript-language-server.mjs");
 
-        let (server, mut rx) = LspServer::new("node", [path.to_str().unwrap(), "--stdio"]);
+        let (server, mut rx) = LspServer::new(node_path, [path.to_str().unwrap(), "--stdio"]);
         let server_ = server.clone();
+        let current_generation = generation.load(Ordering::SeqCst);
 
         tokio::spawn(async move {
             loop {
@@ -82,6 +289,10 @@ impl TsServer {
                     break;
                 }
             }
+            // 非主动重启导致的退出
+            if generation.load(Ordering::SeqCst) == current_generation {
+                let _ = exit_tx.send(()).await;
+            }
         });
 
         server_
@@ -89,49 +300,197 @@ impl TsServer {
 
     /// 重启 ts 服务器
     pub async fn restart(&mut self, text_documents: &TextDocuments) {
-        self.server.shutdown().await.unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        if let Err(err) = self.server.shutdown().await {
+            error!("tsserver shutdown error: {:?}", err);
+        }
         self.server.exit().await;
+        let documents = TsServer::get_documents_snapshot(text_documents);
+        self.respawn(&documents).await;
+    }
+
+    /// 在 ts 服务器意外退出后重新启动，并重新打开已跟踪的文档
+    pub async fn recover(&mut self, documents: &[(Uri, FullTextDocument)]) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.respawn(documents).await;
+    }
+
+    /// 复制已跟踪的文档，重新打开文档时不需要持有文档的锁
+    pub fn get_documents_snapshot(text_documents: &TextDocuments) -> Vec<(Uri, FullTextDocument)> {
+        text_documents
+            .documents()
+            .iter()
+            .map(|(uri, document)| {
+                (
+                    uri.clone(),
+                    FullTextDocument::new(
+                        document.language_id().to_string(),
+                        document.version(),
+                        document.get_content(None).to_string(),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    async fn respawn(&mut self, documents: &[(Uri, FullTextDocument)]) {
         let client = self.client.clone();
         let renderer = self.renderer.clone();
-        let server = TsServer::spawn(client, renderer, self.tx.clone());
+        let server = TsServer::spawn(
+            client,
+            renderer,
+            self.tx.clone(),
+            Arc::clone(&self.generation),
+            self.exit_tx.clone(),
+            &self.node_path,
+        );
         self.server = server;
-        self.server
-            .initialize(self.initialize_params.clone())
-            .await
-            .unwrap();
+        self.next_request_id.store(1, Ordering::SeqCst);
+        // 重启后 tsserver 的 result_id 不再有效
+        self.semantic_tokens_cache.lock().await.clear();
+        // 重启后会重新打开文档，未转发的修改不再需要
+        self.pending_changes.lock().await.clear();
+        if let Err(err) = self.server.initialize(self.initialize_params.clone()).await {
+            // 新的进程同样退出时会再次通知，由调用方决定是否继续重启
+            error!("tsserver initialize error: {:?}", err);
+            return;
+        }
         self.server.initialized(InitializedParams {}).await;
-        for (uri, document) in text_documents.documents() {
+        for (uri, document) in documents {
             self.did_open(uri, document).await;
         }
     }
 
+    /// 初始化 tsserver
+    ///
+    /// `initializationOptions` 中的 `nodePath` 和 `tsserverPath` 指定自定义的 node 和 TypeScript，
+    /// 相对路径基于项目根目录，无效时使用默认值并提示
     pub async fn initialize(&mut self, params: InitializeParams) -> Result<InitializeResult> {
+        let root_path = params
+            .workspace_folders
+            .as_ref()
+            .and_then(|folders| folders.first())
+            .map(|folder| util::to_file_path(&folder.uri));
+        let get_option = |key: &str| {
+            params
+                .initialization_options
+                .as_ref()
+                .and_then(|v| v.get(key))
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string())
+        };
+        let node_path = get_option("nodePath");
+        let tsserver_path = get_option("tsserverPath");
+        if let Some(node_path) = node_path {
+            if let Some(path) = resolve_custom_path(root_path.as_deref(), &node_path, None) {
+                self.node_path = path.to_string_lossy().to_string();
+                // 已启动的服务器尚未初始化，直接使用新的 node 重新启动
+                self.generation.fetch_add(1, Ordering::SeqCst);
+                self.server.exit().await;
+                self.server = TsServer::spawn(
+                    self.client.clone(),
+                    Arc::clone(&self.renderer),
+                    self.tx.clone(),
+                    Arc::clone(&self.generation),
+                    self.exit_tx.clone(),
+                    &self.node_path,
+                );
+                self.next_request_id.store(1, Ordering::SeqCst);
+            } else {
+                self.show_invalid_path_warning("node.path", &node_path)
+                    .await;
+            }
+        }
+        let tsserver_path = if let Some(tsserver_path) = tsserver_path {
+            let path =
+                resolve_custom_path(root_path.as_deref(), &tsserver_path, Some("tsserver.js"));
+            if path.is_none() {
+                self.show_invalid_path_warning("tsserver.path", &tsserver_path)
+                    .await;
+            }
+            path
+        } else {
+            None
+        };
+        let mut initialization_options = json!({
+            "locale": params.locale,
+        });
+        if let Some(path) = tsserver_path {
+            initialization_options["tsserver"] = json!({
+                "path": path.to_string_lossy(),
+            });
+        }
         self.initialize_params = InitializeParams {
             process_id: Some(std::process::id()),
-            capabilities: params.capabilities.clone(),
+            capabilities: ClientCapabilities {
+                // 渲染缓存中的位置与偏移量转换均按 UTF-16 计算，与 tsserver 保持一致
+                general: Some(GeneralClientCapabilities {
+                    position_encodings: Some(vec![PositionEncodingKind::UTF16]),
+                    ..params.capabilities.general.clone().unwrap_or_default()
+                }),
+                ..params.capabilities.clone()
+            },
             workspace_folders: params.workspace_folders.clone(),
-            initialization_options: Some(json!({
-                "locale": params.locale,
-            })),
+            initialization_options: Some(initialization_options),
             work_done_progress_params: WorkDoneProgressParams {
                 work_done_token: None,
             },
             ..params.clone()
         };
-        self.server.initialize(self.initialize_params.clone()).await
+        let result = self.server.initialize(self.initialize_params.clone()).await;
+        if let Ok(result) = &result {
+            self.semantic_tokens_delta = result
+                .capabilities
+                .semantic_tokens_provider
+                .as_ref()
+                .is_some_and(|provider| {
+                    let options = match provider {
+                        SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+                        SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
+                            options,
+                        ) => &options.semantic_tokens_options,
+                    };
+                    matches!(
+                        options.full,
+                        Some(SemanticTokensFullOptions::Delta { delta: Some(true) })
+                    )
+                });
+        }
+        result
     }
 
     pub async fn initialized(&self) {
         self.server.initialized(InitializedParams {}).await;
     }
 
+    /// 提示自定义路径无效，将使用内置的版本
+    async fn show_invalid_path_warning(&self, key: &str, path: &str) {
+        warn!("invalid {}: {}", key, path);
+        self.client
+            .show_message(
+                MessageType::WARNING,
+                format!(
+                    "Configuration `vue-property-decorator.{}` is invalid: `{}` does not exist, the bundled one is used instead.",
+                    key, path
+                ),
+            )
+            .await;
+    }
+
     pub async fn did_open(&self, uri: &Uri, document: &FullTextDocument) {
+        self.flush_pending_changes().await;
         let renderer = self.renderer.lock().await;
         let options = &ConvertOptions {
             renderer: Some(&renderer),
             ..Default::default()
         };
         let target_uri = uri.clone().convert_to(options).await;
+        let language_id = if renderer.is_js_component(uri) {
+            "javascript"
+        } else {
+            "typescript"
+        };
         drop(renderer);
         if Renderer::is_vue_component(uri) {
             if let Ok(document) = Renderer::get_document_from_file(&target_uri).await {
@@ -139,7 +498,7 @@ impl TsServer {
                     .send_notification::<DidOpenTextDocument>(DidOpenTextDocumentParams {
                         text_document: TextDocumentItem {
                             uri: target_uri,
-                            language_id: "typescript".to_string(),
+                            language_id: language_id.to_string(),
                             version: document.version(),
                             text: document.get_content(None).to_string(),
                         },
@@ -150,7 +509,7 @@ impl TsServer {
                     .send_notification::<DidOpenTextDocument>(DidOpenTextDocumentParams {
                         text_document: TextDocumentItem {
                             uri: target_uri,
-                            language_id: "typescript".to_string(),
+                            language_id: language_id.to_string(),
                             version: document.version(),
                             text: document.get_content(None).to_string(),
                         },
@@ -171,26 +530,91 @@ impl TsServer {
         }
     }
 
+    /// 更新渲染结果并将修改转发到 tsserver
+    ///
+    /// 渲染结果总是立即更新，时间窗口内同一文件的连续修改合并后再转发
     pub async fn did_change(
         &self,
         params: DidChangeTextDocumentParams,
         document: &FullTextDocument,
     ) {
+        self.change_count.fetch_add(1, Ordering::SeqCst);
         let uri = params.text_document.uri.clone();
         let mut renderer = self.renderer.lock().await;
-        let params = renderer.update(&uri, params, document);
+        let was_js = renderer.is_js_component(&uri);
+        let params = renderer.update(&uri, params, document).await;
         let options = &ConvertOptions {
             renderer: Some(&renderer),
             ..Default::default()
         };
+        // 脚本语言变化后渲染结果的路径随之变化，关闭原文档并打开新文档
+        if renderer.is_js_component(&uri) != was_js {
+            let target_uri = uri.clone().convert_to(options).await;
+            let text = renderer.get_render_content(&uri).unwrap_or_default();
+            drop(renderer);
+            let target = target_uri.as_str();
+            let old_target_uri = Uri::from_str(&format!(
+                "{}{}",
+                &target[..target.len() - 2],
+                if was_js { "js" } else { "ts" }
+            ))
+            .unwrap();
+            self.flush_pending_changes().await;
+            self.server
+                .send_notification::<DidCloseTextDocument>(DidCloseTextDocumentParams {
+                    text_document: TextDocumentIdentifier {
+                        uri: old_target_uri,
+                    },
+                })
+                .await;
+            self.server
+                .send_notification::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+                    text_document: TextDocumentItem {
+                        uri: target_uri,
+                        language_id: if was_js { "typescript" } else { "javascript" }.to_string(),
+                        version: params.text_document.version,
+                        text,
+                    },
+                })
+                .await;
+            return;
+        }
         let params = params.convert_to(options).await;
         drop(renderer);
-        self.server
-            .send_notification::<DidChangeTextDocument>(params)
-            .await;
+        let target_uri = params.text_document.uri.clone();
+        let mut pending_changes = self.pending_changes.lock().await;
+        let Some(params) = pending_changes.merge(params) else {
+            return;
+        };
+        let delay = self.change_delay.load(Ordering::Relaxed);
+        if delay == 0 {
+            self.server
+                .send_notification::<DidChangeTextDocument>(params)
+                .await;
+            return;
+        }
+        let id = pending_changes.insert(params);
+        drop(pending_changes);
+        let pending_changes = Arc::clone(&self.pending_changes);
+        let server = self.server.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            let mut pending_changes = pending_changes.lock().await;
+            // 已经被提前转发或者属于重启前的服务器时忽略
+            if let Some(params) = pending_changes.take(&target_uri, id) {
+                server
+                    .send_notification::<DidChangeTextDocument>(params)
+                    .await;
+            }
+        });
     }
 
     pub async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        self.flush_pending_changes().await;
+        self.semantic_tokens_cache
+            .lock()
+            .await
+            .remove(&params.text_document.uri);
         let renderer = self.renderer.lock().await;
         let params = params
             .convert_to(&ConvertOptions {
@@ -205,6 +629,7 @@ impl TsServer {
     }
 
     pub async fn did_save(&self, params: DidChangeTextDocumentParams) {
+        self.flush_pending_changes().await;
         let renderer = self.renderer.lock().await;
         let options = &ConvertOptions {
             renderer: Some(&renderer),
@@ -228,7 +653,7 @@ impl TsServer {
         };
         let params = params.convert_to(options).await;
         drop(renderer);
-        let response = self.server.send_request::<WillRenameFiles>(params).await;
+        let response = self.request_with_timeout::<WillRenameFiles>(params).await;
         let renderer = self.renderer.lock().await;
         let options = &ConvertOptions {
             renderer: Some(&renderer),
@@ -247,8 +672,7 @@ impl TsServer {
         let params = params.convert_to(options).await;
         drop(renderer);
         let response = self
-            .server
-            .send_request::<HoverRequest>(HoverParams {
+            .request_with_timeout::<HoverRequest>(HoverParams {
                 text_document_position_params: params,
                 work_done_progress_params: WorkDoneProgressParams {
                     work_done_token: None,
@@ -275,7 +699,7 @@ impl TsServer {
 
         debug!("send_request");
         let start_time = std::time::Instant::now();
-        let result = self.server.send_request::<Completion>(params).await;
+        let result = self.request_with_timeout::<Completion>(params).await;
         debug!("request time: {:?}", start_time.elapsed());
 
         let renderer = self.renderer.lock().await;
@@ -286,10 +710,12 @@ impl TsServer {
         result.convert_back(options).await
     }
 
+    /// 解析补全项，`in_template` 为 true 时表示补全项来自模版表达式
     pub async fn completion_resolve(
         &self,
         params: CompletionItem,
         original_uri: Uri,
+        in_template: bool,
     ) -> Result<CompletionItem> {
         let renderer = self.renderer.lock().await;
         let options = &ConvertOptions {
@@ -302,8 +728,7 @@ impl TsServer {
         debug!("send_request");
         let start_time = std::time::Instant::now();
         let result = self
-            .server
-            .send_request::<ResolveCompletionItem>(params)
+            .request_with_timeout::<ResolveCompletionItem>(params)
             .await;
         debug!("request time: {:?}", start_time.elapsed());
 
@@ -316,6 +741,11 @@ impl TsServer {
         // 处理 "this." 后面补全多了 "this." 的问题
         let mut result = result.convert_back(options).await?;
         drop(renderer);
+        // 模版表达式中直接访问组件成员，不需要 "this."
+        if in_template {
+            strip_this_prefix(&mut result);
+            return Ok(result);
+        }
         if let Some(data) = &result.data {
             if data.is_object() {
                 let data = data.as_object().unwrap();
@@ -374,7 +804,7 @@ impl TsServer {
 
         debug!("send_request");
         let start_time = std::time::Instant::now();
-        let response = self.server.send_request::<GotoDefinition>(params).await;
+        let response = self.request_with_timeout::<GotoDefinition>(params).await;
         debug!("request time: {:?}", start_time.elapsed());
 
         let renderer = self.renderer.lock().await;
@@ -382,35 +812,36 @@ impl TsServer {
             uri: Some(&uri),
             renderer: Some(&renderer),
         };
-        let response = response.convert_back(options).await?;
+        let response = response?;
 
         if is_in_template {
             if let Some(response) = response {
+                // 位于 render 区域且无法映射到模版的结果（如模版中声明的变量）继续跳转到其定义
+                let params = |uri: Uri, position: Position| GotoDefinitionParams {
+                    text_document_position_params: TextDocumentPositionParams {
+                        text_document: TextDocumentIdentifier { uri },
+                        position,
+                    },
+                    work_done_progress_params: WorkDoneProgressParams {
+                        work_done_token: None,
+                    },
+                    partial_result_params: PartialResultParams {
+                        partial_result_token: None,
+                    },
+                };
                 match response {
                     GotoDefinitionResponse::Array(array) => {
                         let mut result = vec![];
-                        for mut item in array {
-                            if let Some(range) = renderer.get_original_range(&item.uri, &item.range)
+                        for item in array {
+                            if self
+                                .is_unmapped_render_range(&item.uri, &item.range, options)
+                                .await
                             {
-                                item.range = range;
-                                result.push(item);
-                            } else if !renderer.is_position_valid(&item.uri, &item.range.start) {
                                 let response = self
-                                    .server
-                                    .send_request::<GotoDefinition>(GotoDefinitionParams {
-                                        text_document_position_params: TextDocumentPositionParams {
-                                            text_document: TextDocumentIdentifier {
-                                                uri: item.uri.clone().convert_to(options).await,
-                                            },
-                                            position: item.range.start,
-                                        },
-                                        work_done_progress_params: WorkDoneProgressParams {
-                                            work_done_token: None,
-                                        },
-                                        partial_result_params: PartialResultParams {
-                                            partial_result_token: None,
-                                        },
-                                    })
+                                    .request_with_timeout::<GotoDefinition>(params(
+                                        item.uri,
+                                        item.range.start,
+                                    ))
                                     .await;
                                 if let Ok(Some(GotoDefinitionResponse::Array(mut value))) =
                                     response.convert_back(options).await
@@ -418,127 +849,128 @@ impl TsServer {
                                     result.append(&mut value);
                                 }
                             } else {
-                                result.push(item);
+                                result.push(item.convert_back(options).await);
                             }
                         }
                         return Ok(Some(GotoDefinitionResponse::Array(result)));
                     }
                     GotoDefinitionResponse::Link(link) => {
                         let mut result: Vec<LocationLink> = vec![];
-                        for mut item in link {
-                            // 处理 origin_selection_range
-                            if let Some(origin_selection_range) = item.origin_selection_range {
-                                item.origin_selection_range =
-                                    renderer.get_original_range(&uri, &origin_selection_range);
-                            }
-                            if let Some(target_selection_range) =
-                                renderer.get_original_range(&uri, &item.target_selection_range)
+                        for item in link {
+                            let values = if self
+                                .is_unmapped_render_range(
+                                    &item.target_uri,
+                                    &item.target_selection_range,
+                                    options,
+                                )
+                                .await
                             {
-                                item.target_selection_range = target_selection_range;
-                                item.target_range = target_selection_range;
-                                result.push(item);
-                            } else if !renderer.is_position_valid(
-                                &item.target_uri,
-                                &item.target_selection_range.start,
-                            ) {
                                 let response = self
-                                    .server
-                                    .send_request::<GotoDefinition>(GotoDefinitionParams {
-                                        text_document_position_params: TextDocumentPositionParams {
-                                            text_document: TextDocumentIdentifier {
-                                                uri: item.target_uri.convert_to(options).await,
-                                            },
-                                            position: item.target_selection_range.start,
-                                        },
-                                        work_done_progress_params: WorkDoneProgressParams {
-                                            work_done_token: None,
-                                        },
-                                        partial_result_params: PartialResultParams {
-                                            partial_result_token: None,
-                                        },
-                                    })
+                                    .request_with_timeout::<GotoDefinition>(params(
+                                        item.target_uri.clone(),
+                                        item.target_selection_range.start,
+                                    ))
                                     .await;
-                                if let Ok(Some(GotoDefinitionResponse::Link(mut value))) =
+                                let Ok(Some(GotoDefinitionResponse::Link(mut value))) =
                                     response.convert_back(options).await
-                                {
-                                    for v in &mut value {
-                                        // 重置 origin_selection_range 的值
-                                        if let Some(origin_selection_range) =
-                                            item.origin_selection_range
-                                        {
-                                            v.origin_selection_range = renderer
-                                                .get_original_range(&uri, &origin_selection_range);
-                                        }
-                                    }
-
-                                    for v in value {
-                                        // 如果 result 中已经存在了相同的 target_range，则不再添加
-                                        if result
-                                            .iter()
-                                            .find(|vv| {
-                                                vv.target_uri == v.target_uri
-                                                    && vv.target_range == v.target_range
-                                            })
-                                            .is_none()
-                                        {
-                                            result.push(v);
-                                        }
-                                    }
-                                }
-                            } else {
+                                else {
+                                    continue;
+                                };
                                 // 重置 origin_selection_range 的值
-                                if let Some(origin_selection_range) = item.origin_selection_range {
-                                    item.origin_selection_range =
-                                        renderer.get_original_range(&uri, &origin_selection_range);
+                                let origin_selection_range =
+                                    item.convert_back(options).await.origin_selection_range;
+                                for v in &mut value {
+                                    v.origin_selection_range = origin_selection_range;
                                 }
+                                value
+                            } else {
+                                vec![item.convert_back(options).await]
+                            };
+                            for v in values {
                                 // 如果 result 中已经存在了相同的 target_range，则不再添加
                                 if result
                                     .iter()
-                                    .find(|v| {
-                                        v.target_uri == item.target_uri
-                                            && v.target_range == item.target_range
+                                    .find(|vv| {
+                                        vv.target_uri == v.target_uri
+                                            && vv.target_range == v.target_range
                                     })
                                     .is_none()
                                 {
-                                    result.push(item);
+                                    result.push(v);
                                 }
                             }
                         }
                         return Ok(Some(GotoDefinitionResponse::Link(result)));
                     }
-                    GotoDefinitionResponse::Scalar(mut location) => {
-                        if let Some(range) =
-                            renderer.get_original_range(&location.uri, &location.range)
-                        {
-                            location.range = range;
-                            return Ok(Some(GotoDefinitionResponse::Scalar(location)));
-                        } else if !renderer.is_position_valid(&location.uri, &location.range.start)
+                    GotoDefinitionResponse::Scalar(location) => {
+                        if self
+                            .is_unmapped_render_range(&location.uri, &location.range, options)
+                            .await
                         {
                             return self
-                                .server
-                                .send_request::<GotoDefinition>(GotoDefinitionParams {
-                                    text_document_position_params: TextDocumentPositionParams {
-                                        text_document: TextDocumentIdentifier { uri: location.uri },
-                                        position: location.range.start,
-                                    },
-                                    work_done_progress_params: WorkDoneProgressParams {
-                                        work_done_token: None,
-                                    },
-                                    partial_result_params: PartialResultParams {
-                                        partial_result_token: None,
-                                    },
-                                })
+                                .request_with_timeout::<GotoDefinition>(params(
+                                    location.uri,
+                                    location.range.start,
+                                ))
                                 .await
                                 .convert_back(options)
                                 .await;
                         } else {
-                            return Ok(Some(GotoDefinitionResponse::Scalar(location)));
+                            return Ok(Some(GotoDefinitionResponse::Scalar(
+                                location.convert_back(options).await,
+                            )));
                         }
                     }
                 }
             }
         }
-        Ok(response)
+        Ok(response.convert_back(options).await)
+    }
+
+    /// 渲染文件中的范围是否位于插入的 render 区域且无法映射到模版
+    async fn is_unmapped_render_range(
+        &self,
+        uri: &Uri,
+        range: &Range,
+        options: &ConvertOptions<'_>,
+    ) -> bool {
+        let renderer = options.renderer.unwrap();
+        let uri = uri.clone().convert_back(options).await;
+        renderer.is_in_render_region(&uri, &range.start)
+            && renderer.get_original_range(&uri, range).is_none()
+    }
+
+    pub async fn goto_implementation(
+        &self,
+        params: GotoImplementationParams,
+    ) -> Result<Option<GotoImplementationResponse>> {
+        let uri = params
+            .text_document_position_params
+            .text_document
+            .uri
+            .clone();
+
+        let renderer = self.renderer.lock().await;
+        let options = &ConvertOptions {
+            uri: Some(&uri),
+            renderer: Some(&renderer),
+        };
+        let params = params.convert_to(options).await;
+        drop(renderer);
+
+        debug!("send_request");
+        let start_time = std::time::Instant::now();
+        let response = self
+            .request_with_timeout::<GotoImplementation>(params)
+            .await;
+        debug!("request time: {:?}", start_time.elapsed());
+
+        let renderer = self.renderer.lock().await;
+        let options = &ConvertOptions {
+            uri: Some(&uri),
+            renderer: Some(&renderer),
+        };
+        response.convert_back(options).await
     }
 
     pub async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
@@ -554,7 +986,7 @@ impl TsServer {
         debug!("send_request");
         let start_time = std::time::Instant::now();
 
-        let result = self.server.send_request::<References>(params).await;
+        let result = self.request_with_timeout::<References>(params).await;
         debug!("request time: {:?}", start_time.elapsed());
 
         let renderer = self.renderer.lock().await;
@@ -572,8 +1004,7 @@ impl TsServer {
                     let start_time = std::time::Instant::now();
 
                     let res = self
-                        .server
-                        .send_request::<References>(ReferenceParams {
+                        .request_with_timeout::<References>(ReferenceParams {
                             text_document_position: TextDocumentPositionParams {
                                 text_document: TextDocumentIdentifier {
                                     uri: item.uri.convert_to(options).await,
@@ -607,6 +1038,65 @@ impl TsServer {
         }
     }
 
+    /// 获取选择范围，`positions` 是渲染文件中的位置
+    ///
+    /// 返回每个位置由内到外转换回原文件的范围，无法转换的范围被丢弃
+    pub async fn selection_range(
+        &self,
+        uri: &Uri,
+        positions: Vec<Position>,
+    ) -> Result<Vec<Vec<Range>>> {
+        let renderer = self.renderer.lock().await;
+        let options = &ConvertOptions {
+            uri: Some(uri),
+            renderer: Some(&renderer),
+        };
+        let params = SelectionRangeParams {
+            text_document: TextDocumentIdentifier {
+                uri: uri.clone().convert_to(options).await,
+            },
+            positions,
+            work_done_progress_params: WorkDoneProgressParams {
+                work_done_token: None,
+            },
+            partial_result_params: PartialResultParams {
+                partial_result_token: None,
+            },
+        };
+        drop(renderer);
+
+        debug!("send_request");
+        let start_time = std::time::Instant::now();
+        let response = self
+            .request_with_timeout::<SelectionRangeRequest>(params)
+            .await?;
+        debug!("request time: {:?}", start_time.elapsed());
+
+        let renderer = self.renderer.lock().await;
+        // 非 vue 文件与 tsserver 中的内容一致，无需转换
+        let is_vue = Renderer::is_vue_component(uri);
+        let mut result = vec![];
+        for selection_range in response.unwrap_or_default() {
+            let mut ranges = vec![];
+            let mut current = Some(&selection_range);
+            while let Some(item) = current {
+                let range = if is_vue {
+                    renderer
+                        .get_original_range(uri, &item.range)
+                        .or_else(|| renderer.get_original_script_range(uri, &item.range))
+                } else {
+                    Some(item.range)
+                };
+                if let Some(range) = range {
+                    ranges.push(range);
+                }
+                current = item.parent.as_deref();
+            }
+            result.push(ranges);
+        }
+        Ok(result)
+    }
+
     pub async fn document_symbol(
         &self,
         params: DocumentSymbolParams,
@@ -623,8 +1113,7 @@ impl TsServer {
         let start_time = std::time::Instant::now();
 
         let result = self
-            .server
-            .send_request::<DocumentSymbolRequest>(params)
+            .request_with_timeout::<DocumentSymbolRequest>(params)
             .await;
         debug!("request time: {:?}", start_time.elapsed());
 
@@ -652,17 +1141,146 @@ impl TsServer {
         debug!("send_request");
         let start_time = std::time::Instant::now();
         let result = self
-            .server
-            .send_request::<SemanticTokensFullRequest>(params)
+            .request_with_timeout::<SemanticTokensFullRequest>(params)
             .await;
         debug!("request time: {:?}", start_time.elapsed());
 
+        let shadow = match &result {
+            Ok(Some(SemanticTokensResult::Tokens(tokens))) => Some(tokens.clone()),
+            _ => None,
+        };
         let renderer = self.renderer.lock().await;
         let options = &ConvertOptions {
             uri: Some(&uri),
             renderer: Some(&renderer),
         };
-        result.convert_back(options).await
+        let result = result.convert_back(options).await;
+        drop(renderer);
+
+        // 缓存结果，以便后续的增量请求
+        if let (Some(shadow), Ok(Some(SemanticTokensResult::Tokens(tokens)))) = (shadow, &result) {
+            let result_id = self.get_semantic_tokens_result_id(shadow.result_id);
+            self.semantic_tokens_cache.lock().await.insert(
+                uri,
+                SemanticTokensCache {
+                    result_id: result_id.clone(),
+                    shadow: shadow.data,
+                    tokens: tokens.data.clone(),
+                },
+            );
+            return Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+                result_id,
+                data: tokens.data.clone(),
+            })));
+        }
+        result
+    }
+
+    /// 获取语义标记的增量结果
+    ///
+    /// 如果 tsserver 支持增量请求，那么将增量应用到缓存的标记上，否则请求全部标记。
+    /// 转换到原文件后，与上次返回给客户端的标记比较得到增量结果
+    pub async fn semantic_tokens_full_delta(
+        &self,
+        params: SemanticTokensDeltaParams,
+    ) -> Result<Option<SemanticTokensFullDeltaResult>> {
+        let uri = params.text_document.uri.clone();
+        let previous_result_id = params.previous_result_id.clone();
+        let renderer = self.renderer.lock().await;
+        let options = &ConvertOptions {
+            uri: Some(&uri),
+            renderer: Some(&renderer),
+        };
+        let params = params.convert_to(options).await;
+        drop(renderer);
+
+        debug!("send_request");
+        let start_time = std::time::Instant::now();
+        let full_params = SemanticTokensParams {
+            work_done_progress_params: params.work_done_progress_params.clone(),
+            partial_result_params: params.partial_result_params.clone(),
+            text_document: params.text_document.clone(),
+        };
+        let mut shadow = None;
+        if self.semantic_tokens_delta {
+            match self
+                .request_with_timeout::<SemanticTokensFullDeltaRequest>(params)
+                .await?
+            {
+                Some(SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                    shadow = Some((tokens.result_id, tokens.data));
+                }
+                Some(SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                    let cache = self.semantic_tokens_cache.lock().await;
+                    shadow = cache
+                        .get(&uri)
+                        .filter(|cache| cache.result_id.as_ref() == Some(&previous_result_id))
+                        .map(|cache| {
+                            (
+                                delta.result_id,
+                                util::apply_semantic_tokens_edits(&cache.shadow, delta.edits),
+                            )
+                        });
+                }
+                _ => {}
+            }
+        }
+        // 不支持增量请求，或者缓存与增量结果不匹配时，请求全部标记
+        if shadow.is_none() {
+            if let Some(SemanticTokensResult::Tokens(tokens)) = self
+                .request_with_timeout::<SemanticTokensFullRequest>(full_params)
+                .await?
+            {
+                shadow = Some((tokens.result_id, tokens.data));
+            }
+        }
+        debug!("request time: {:?}", start_time.elapsed());
+        let Some((result_id, shadow)) = shadow else {
+            return Ok(None);
+        };
+
+        let renderer = self.renderer.lock().await;
+        let options = &ConvertOptions {
+            uri: Some(&uri),
+            renderer: Some(&renderer),
+        };
+        let tokens = shadow.clone().convert_back(options).await;
+        drop(renderer);
+
+        let result_id = self.get_semantic_tokens_result_id(result_id);
+        let mut cache = self.semantic_tokens_cache.lock().await;
+        let result = match cache.get(&uri) {
+            Some(cache) if cache.result_id.as_ref() == Some(&previous_result_id) => {
+                SemanticTokensFullDeltaResult::TokensDelta(SemanticTokensDelta {
+                    result_id: result_id.clone(),
+                    edits: util::diff_semantic_tokens(&cache.tokens, &tokens),
+                })
+            }
+            _ => SemanticTokensFullDeltaResult::Tokens(SemanticTokens {
+                result_id: result_id.clone(),
+                data: tokens.clone(),
+            }),
+        };
+        cache.insert(
+            uri,
+            SemanticTokensCache {
+                result_id,
+                shadow,
+                tokens,
+            },
+        );
+        Ok(Some(result))
+    }
+
+    /// 优先使用 tsserver 返回的 result_id，否则生成新的 id
+    fn get_semantic_tokens_result_id(&self, result_id: Option<String>) -> Option<String> {
+        result_id.or_else(|| {
+            Some(
+                self.semantic_tokens_id
+                    .fetch_add(1, Ordering::Relaxed)
+                    .to_string(),
+            )
+        })
     }
 
     pub async fn semantic_tokens_range(
@@ -681,8 +1299,7 @@ impl TsServer {
         debug!("send_request");
         let start_time = std::time::Instant::now();
         let result = self
-            .server
-            .send_request::<SemanticTokensRangeRequest>(params)
+            .request_with_timeout::<SemanticTokensRangeRequest>(params)
             .await;
         debug!("request time: {:?}", start_time.elapsed());
 
@@ -709,16 +1326,54 @@ impl TsServer {
 
         debug!("send_request");
         let start_time = std::time::Instant::now();
-        let response = self.server.send_request::<CodeActionRequest>(params).await;
+        let response = self.request_with_timeout::<CodeActionRequest>(params).await;
         debug!("request time: {:?}", start_time.elapsed());
 
         response.convert_back(&ConvertOptions::default()).await
     }
 
+    /// 格式化脚本，排除插入的 render 区域和脚本之外的修改
+    pub async fn formatting(
+        &self,
+        params: DocumentFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let uri = params.text_document.uri.clone();
+        let renderer = self.renderer.lock().await;
+        let options = &ConvertOptions {
+            uri: Some(&uri),
+            renderer: Some(&renderer),
+        };
+        let params = params.convert_to(options).await;
+        drop(renderer);
+
+        debug!("send_request");
+        let start_time = std::time::Instant::now();
+        let response = self.request_with_timeout::<Formatting>(params).await;
+        debug!("request time: {:?}", start_time.elapsed());
+
+        if !Renderer::is_vue_component(&uri) {
+            return response;
+        }
+        let renderer = self.renderer.lock().await;
+        Ok(response?.map(|edits| {
+            edits
+                .into_iter()
+                .filter_map(|edit| {
+                    let range = renderer.get_original_script_range(&uri, &edit.range)?;
+                    Some(TextEdit {
+                        range,
+                        new_text: edit.new_text,
+                    })
+                })
+                .collect()
+        }))
+    }
+
     pub async fn execute_command(&self, params: ExecuteCommandParams) -> Result<Option<Value>> {
-        self.server
-            .send_request::<ExecuteCommand>(params.convert_to(&ConvertOptions::default()).await)
-            .await
+        self.request_with_timeout::<ExecuteCommand>(
+            params.convert_to(&ConvertOptions::default()).await,
+        )
+        .await
     }
 
     /// 处理来自服务器的消息
@@ -816,7 +1471,300 @@ impl TsServer {
     }
 
     pub async fn shutdown(&self) {
-        self.server.shutdown().await.unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        if let Err(err) = self.server.shutdown().await {
+            error!("tsserver shutdown error: {:?}", err);
+        }
         self.server.exit().await;
     }
 }
+
+/// 解析配置的自定义路径，相对路径基于项目根目录，路径不存在时返回 None
+///
+/// 指定 `file_name` 时，路径可以是包含该文件的目录
+fn resolve_custom_path(
+    root: Option<&Path>,
+    path: &str,
+    file_name: Option<&str>,
+) -> Option<PathBuf> {
+    let mut path = PathBuf::from(path);
+    if path.is_relative() {
+        // 只有文件名的 node 等可执行文件从 PATH 中查找
+        if file_name.is_none() && path.components().count() == 1 {
+            return find_executable(path.as_os_str(), &std::env::var_os("PATH")?);
+        }
+        path = root?.join(path);
+    }
+    if let Some(file_name) = file_name {
+        if path.is_dir() {
+            path.push(file_name);
+        }
+    }
+    path.is_file().then_some(path)
+}
+
+/// 在 `paths` 列出的目录中查找可执行文件，Windows 下同时查找 `.exe` 后缀的文件
+fn find_executable(name: &OsStr, paths: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(paths).find_map(|dir| {
+        let path = dir.join(name);
+        if path.is_file() {
+            return Some(path);
+        }
+        if cfg!(windows) {
+            let path = path.with_extension("exe");
+            if path.is_file() {
+                return Some(path);
+            }
+        }
+        None
+    })
+}
+
+/// 将新的修改追加到等待转发的修改中
+///
+/// 增量修改按顺序应用，因此直接追加即可，版本使用最新的版本
+fn merge_change(pending: &mut DidChangeTextDocumentParams, params: DidChangeTextDocumentParams) {
+    pending.text_document.version = params.text_document.version;
+    pending.content_changes.extend(params.content_changes);
+}
+
+/// 移除补全项插入文本中开头的 "this."
+///
+/// 模版表达式中直接访问组件成员，tsserver 提供的 `this.method` 需要去掉 "this."
+pub fn strip_this_prefix(item: &mut CompletionItem) {
+    fn strip(text: &mut String) {
+        if let Some(rest) = text.strip_prefix("this.") {
+            *text = rest.to_string();
+        }
+    }
+    if let Some(insert_text) = &mut item.insert_text {
+        strip(insert_text);
+    }
+    if let Some(filter_text) = &mut item.filter_text {
+        strip(filter_text);
+    }
+    match &mut item.text_edit {
+        Some(CompletionTextEdit::Edit(edit)) => strip(&mut edit.new_text),
+        Some(CompletionTextEdit::InsertAndReplace(edit)) => strip(&mut edit.new_text),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use tokio::sync::{mpsc, Mutex};
+    use tower_lsp::jsonrpc::Result;
+    use tower_lsp::lsp_types::{
+        CompletionItem, CompletionTextEdit, DidChangeTextDocumentParams, InitializeParams,
+        InitializeResult, Position, Range, TextDocumentContentChangeEvent, TextEdit, Uri,
+        VersionedTextDocumentIdentifier,
+    };
+    use tower_lsp::{LanguageServer, LspService};
+
+    use crate::renderer::Renderer;
+
+    use super::TsServer;
+
+    struct NoopServer;
+
+    #[tower_lsp::async_trait]
+    impl LanguageServer for NoopServer {
+        async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+            Ok(InitializeResult::default())
+        }
+
+        async fn shutdown(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// 使用立即退出的进程模拟 tsserver 被终止，重启失败时不会 panic 并继续通知退出
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn recover_after_exit() {
+        let mut client = None;
+        let (_service, _socket) = LspService::new(|c| {
+            client = Some(c);
+            NoopServer
+        });
+        let (tx, _rx) = mpsc::channel(1);
+        let (exit_tx, mut exit_rx) = mpsc::channel(1);
+        let mut ts_server = TsServer::with_node_path(
+            client.unwrap(),
+            Arc::new(Mutex::new(Renderer::new())),
+            tx,
+            exit_tx,
+            "true".to_string(),
+        );
+        let timeout = Duration::from_secs(5);
+        assert_eq!(
+            tokio::time::timeout(timeout, exit_rx.recv()).await,
+            Ok(Some(()))
+        );
+        assert!(tokio::time::timeout(timeout, ts_server.recover(&[]))
+            .await
+            .is_ok());
+        assert_eq!(
+            tokio::time::timeout(timeout, exit_rx.recv()).await,
+            Ok(Some(()))
+        );
+    }
+
+    #[test]
+    fn resolve_custom_path() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let root = root_dir.path();
+        let lib = root.join("node_modules/typescript/lib");
+        std::fs::create_dir_all(&lib).unwrap();
+        std::fs::write(lib.join("tsserver.js"), "").unwrap();
+        assert_eq!(
+            super::resolve_custom_path(
+                Some(root),
+                "node_modules/typescript/lib",
+                Some("tsserver.js")
+            ),
+            Some(lib.join("tsserver.js"))
+        );
+        assert_eq!(
+            super::resolve_custom_path(Some(root), "node_modules/tsc/lib", Some("tsserver.js")),
+            None
+        );
+        // 只有文件名时从 PATH 中查找，找不到时无效
+        assert_eq!(
+            super::resolve_custom_path(Some(root), "vue-decorator-missing-node", None),
+            None
+        );
+        let bin = root.join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("my-node"), "").unwrap();
+        let paths = std::env::join_paths([root.join("missing"), bin.clone()]).unwrap();
+        assert_eq!(
+            super::find_executable("my-node".as_ref(), &paths),
+            Some(bin.join("my-node"))
+        );
+        assert_eq!(super::find_executable("node".as_ref(), &paths), None);
+    }
+
+    #[test]
+    fn merge_change() {
+        let uri = Uri::from_str("file:///path/.~$project/src/App.vue.ts").unwrap();
+        let change = |version: i32, text: &str| DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: uri.clone(),
+                version,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: Some(Range::new(Position::new(0, 0), Position::new(0, 0))),
+                range_length: None,
+                text: text.to_string(),
+            }],
+        };
+        let mut pending = change(1, "a");
+        super::merge_change(&mut pending, change(2, "b"));
+        super::merge_change(&mut pending, change(3, "c"));
+        assert_eq!(pending.text_document.version, 3);
+        assert_eq!(
+            pending
+                .content_changes
+                .iter()
+                .map(|v| v.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn pending_changes() {
+        let app_uri = Uri::from_str("file:///path/.~$project/src/App.vue.ts").unwrap();
+        let home_uri = Uri::from_str("file:///path/.~$project/src/Home.vue.ts").unwrap();
+        let change = |uri: &Uri, version: i32, text: &str| DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: uri.clone(),
+                version,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: Some(Range::new(Position::new(0, 0), Position::new(0, 0))),
+                range_length: None,
+                text: text.to_string(),
+            }],
+        };
+        let summary = |params: &DidChangeTextDocumentParams| {
+            (
+                params.text_document.uri.clone(),
+                params.text_document.version,
+                params
+                    .content_changes
+                    .iter()
+                    .map(|v| v.text.clone())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let mut pending = super::PendingChanges::default();
+        // 两个文件的修改交替到达
+        let params = pending.merge(change(&app_uri, 1, "a")).unwrap();
+        let app_id = pending.insert(params);
+        let params = pending.merge(change(&home_uri, 1, "x")).unwrap();
+        let home_id = pending.insert(params);
+        assert!(pending.merge(change(&app_uri, 2, "b")).is_none());
+        assert!(pending.merge(change(&home_uri, 2, "y")).is_none());
+        assert!(pending.merge(change(&app_uri, 3, "c")).is_none());
+        assert!(pending.take(&app_uri, home_id).is_none());
+
+        // 发送请求前按开始合并的顺序转发，每个文件的修改保持原有顺序
+        assert_eq!(
+            pending.drain().iter().map(summary).collect::<Vec<_>>(),
+            vec![
+                (
+                    app_uri.clone(),
+                    3,
+                    vec!["a".to_string(), "b".to_string(), "c".to_string()]
+                ),
+                (home_uri.clone(), 2, vec!["x".to_string(), "y".to_string()]),
+            ]
+        );
+
+        // 提前转发后，之前的定时任务不再转发新的修改
+        let params = pending.merge(change(&app_uri, 4, "d")).unwrap();
+        let new_app_id = pending.insert(params);
+        assert!(pending.take(&app_uri, app_id).is_none());
+        assert_eq!(
+            pending.take(&app_uri, new_app_id).as_ref().map(summary),
+            Some((app_uri.clone(), 4, vec!["d".to_string()]))
+        );
+        assert!(pending.drain().is_empty());
+    }
+
+    #[test]
+    fn strip_this_prefix() {
+        let mut item = CompletionItem {
+            label: "handleClick".to_string(),
+            insert_text: Some("this.handleClick".to_string()),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                range: Range::new(Position::new(0, 10), Position::new(0, 12)),
+                new_text: "this.handleClick".to_string(),
+            })),
+            ..Default::default()
+        };
+        super::strip_this_prefix(&mut item);
+        assert_eq!(item.insert_text.as_deref(), Some("handleClick"));
+        assert_eq!(
+            item.text_edit,
+            Some(CompletionTextEdit::Edit(TextEdit {
+                range: Range::new(Position::new(0, 10), Position::new(0, 12)),
+                new_text: "handleClick".to_string(),
+            }))
+        );
+
+        let mut item = CompletionItem {
+            label: "title".to_string(),
+            insert_text: Some("title".to_string()),
+            ..Default::default()
+        };
+        super::strip_this_prefix(&mut item);
+        assert_eq!(item.insert_text.as_deref(), Some("title"));
+    }
+}
@@ -1,10 +1,14 @@
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
     str::FromStr,
 };
 
 use percent_encoding::{self, AsciiSet, CONTROLS};
-use tower_lsp::lsp_types::Uri;
+use tower_lsp::lsp_types::{
+    DocumentChangeOperation, DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier,
+    Range, SemanticToken, SemanticTokensEdit, TextDocumentEdit, TextEdit, Uri, WorkspaceEdit,
+};
 
 const ASCII_SET: &AsciiSet = &CONTROLS.add(b' ').add(b'$').add(b':');
 
@@ -39,11 +43,176 @@ pub fn to_file_path_string(uri: &Uri) -> String {
     to_file_path(uri).to_string_lossy().to_string()
 }
 
+/// 驼峰命名转换为短横线命名，例如 `titleText` 转换为 `title-text`
+pub fn to_kebab_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.char_indices() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                result.push('-');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// 将 prop 的 `type` 中的构造函数转换为 TS 类型，例如 `String` 转换为 `string`
+///
+/// 多个构造函数 `[String, Number]` 转换为联合类型，其他名称（如自定义的类）保持不变
+pub fn get_constructor_ts_type(typ: &str) -> String {
+    if let Some(items) = typ.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return items
+            .split(',')
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .map(get_constructor_ts_type)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    match typ {
+        "String" => "string",
+        "Number" => "number",
+        "Boolean" => "boolean",
+        "Symbol" => "symbol",
+        "Array" => "any[]",
+        "Object" => "Record<string, any>",
+        name => name,
+    }
+    .to_string()
+}
+
+/// 将语义标记的增量编辑应用到标记上
+///
+/// 编辑的位置以整数为单位，每个标记占 5 个整数，编辑的范围不一定与标记对齐
+pub fn apply_semantic_tokens_edits(
+    tokens: &[SemanticToken],
+    mut edits: Vec<SemanticTokensEdit>,
+) -> Vec<SemanticToken> {
+    let flatten = |token: &SemanticToken| {
+        [
+            token.delta_line,
+            token.delta_start,
+            token.length,
+            token.token_type,
+            token.token_modifiers_bitset,
+        ]
+    };
+    let mut data = tokens.iter().flat_map(flatten).collect::<Vec<_>>();
+    // 从后往前应用，避免前面的编辑影响后面编辑的位置
+    edits.sort_by(|a, b| b.start.cmp(&a.start));
+    for edit in edits {
+        let start = (edit.start as usize).min(data.len());
+        let end = (start + edit.delete_count as usize).min(data.len());
+        let inserted = edit.data.unwrap_or_default();
+        data.splice(start..end, inserted.iter().flat_map(flatten));
+    }
+    data.chunks_exact(5)
+        .map(|v| SemanticToken {
+            delta_line: v[0],
+            delta_start: v[1],
+            length: v[2],
+            token_type: v[3],
+            token_modifiers_bitset: v[4],
+        })
+        .collect()
+}
+
+/// 计算两组语义标记的差异，返回将 old 转换为 new 的增量编辑
+pub fn diff_semantic_tokens(
+    old: &[SemanticToken],
+    new: &[SemanticToken],
+) -> Vec<SemanticTokensEdit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    if prefix == old.len() && prefix == new.len() {
+        return vec![];
+    }
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    vec![SemanticTokensEdit {
+        start: (prefix * 5) as u32,
+        delete_count: ((old.len() - prefix - suffix) * 5) as u32,
+        data: Some(new[prefix..new.len() - suffix].to_vec()),
+    }]
+}
+
+/// 将文本编辑合并到工作区编辑中，跳过与同一文件中已有编辑范围重叠的编辑
+pub fn merge_workspace_edit(
+    workspace_edit: &mut WorkspaceEdit,
+    changes: HashMap<Uri, Vec<TextEdit>>,
+) {
+    fn is_overlap(a: &Range, b: &Range) -> bool {
+        a == b || (a.start < b.end && b.start < a.end)
+    }
+    for (uri, edits) in changes {
+        let document_edits = match &workspace_edit.document_changes {
+            Some(DocumentChanges::Edits(document_edits)) => document_edits.iter().collect(),
+            Some(DocumentChanges::Operations(operations)) => operations
+                .iter()
+                .filter_map(|v| match v {
+                    DocumentChangeOperation::Edit(edit) => Some(edit),
+                    DocumentChangeOperation::Op(_) => None,
+                })
+                .collect::<Vec<_>>(),
+            None => {
+                let list = workspace_edit
+                    .changes
+                    .get_or_insert_with(HashMap::new)
+                    .entry(uri)
+                    .or_default();
+                for edit in edits {
+                    if list.iter().all(|v| !is_overlap(&v.range, &edit.range)) {
+                        list.push(edit);
+                    }
+                }
+                continue;
+            }
+        };
+        let existing = document_edits
+            .iter()
+            .filter(|v| v.text_document.uri == uri)
+            .flat_map(|v| v.edits.iter())
+            .map(|v| match v {
+                OneOf::Left(edit) => edit.range,
+                OneOf::Right(edit) => edit.text_edit.range,
+            })
+            .collect::<Vec<_>>();
+        let edits = edits
+            .into_iter()
+            .filter(|edit| existing.iter().all(|range| !is_overlap(range, &edit.range)))
+            .map(OneOf::Left)
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            continue;
+        }
+        let document_edit = TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+            edits,
+        };
+        match &mut workspace_edit.document_changes {
+            Some(DocumentChanges::Edits(document_edits)) => document_edits.push(document_edit),
+            Some(DocumentChanges::Operations(operations)) => {
+                operations.push(DocumentChangeOperation::Edit(document_edit))
+            }
+            None => {}
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::{path::PathBuf, str::FromStr};
+    use std::{collections::HashMap, path::PathBuf, str::FromStr};
 
-    use tower_lsp::lsp_types::Uri;
+    use tower_lsp::lsp_types::{
+        DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier, Position, Range,
+        SemanticToken, SemanticTokensEdit, TextDocumentEdit, TextEdit, Uri, WorkspaceEdit,
+    };
 
     use crate::util::to_file_path;
 
@@ -68,9 +237,116 @@ mod tests {
         assert_path("file:///home/user/file%20.md", "/home/user/file .md");
     }
 
+    #[test]
+    fn semantic_tokens_delta() {
+        let token = |delta_line: u32, delta_start: u32| SemanticToken {
+            delta_line,
+            delta_start,
+            length: 1,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        };
+        let old = vec![token(0, 1), token(1, 2), token(0, 3), token(2, 0)];
+        let new = vec![
+            token(0, 1),
+            token(1, 5),
+            token(0, 6),
+            token(0, 3),
+            token(2, 0),
+        ];
+        let edits = super::diff_semantic_tokens(&old, &new);
+        assert_eq!(
+            edits,
+            vec![SemanticTokensEdit {
+                start: 5,
+                delete_count: 5,
+                data: Some(vec![token(1, 5), token(0, 6)]),
+            }]
+        );
+        assert_eq!(super::apply_semantic_tokens_edits(&old, edits), new);
+        assert_eq!(super::diff_semantic_tokens(&new, &new), vec![]);
+
+        // 编辑范围跨越标记的边界
+        let edits = vec![SemanticTokensEdit {
+            start: 7,
+            delete_count: 5,
+            data: Some(vec![SemanticToken {
+                delta_line: 1,
+                delta_start: 0,
+                length: 4,
+                token_type: 1,
+                token_modifiers_bitset: 0,
+            }]),
+        }];
+        let mut expected = old.clone();
+        expected[1].token_modifiers_bitset = 4;
+        expected[2].delta_line = 1;
+        expected[2].delta_start = 0;
+        assert_eq!(super::apply_semantic_tokens_edits(&old, edits), expected);
+    }
+
+    #[test]
+    fn kebab_case() {
+        assert_eq!(super::to_kebab_case("title"), "title");
+        assert_eq!(super::to_kebab_case("titleText"), "title-text");
+        assert_eq!(super::to_kebab_case("isHTMLContent"), "is-h-t-m-l-content");
+    }
+
+    #[test]
+    fn constructor_ts_type() {
+        assert_eq!(super::get_constructor_ts_type("Number"), "number");
+        assert_eq!(
+            super::get_constructor_ts_type("Object"),
+            "Record<string, any>"
+        );
+        assert_eq!(
+            super::get_constructor_ts_type("[String, Array]"),
+            "string | any[]"
+        );
+        assert_eq!(super::get_constructor_ts_type("User"), "User");
+    }
+
     #[test]
     fn windows() {
         assert_path("file:///d%3A/code/project", "d:/code/project");
         assert_uri("d:/code/project", "file:///d%3A/code/project");
     }
+
+    #[test]
+    fn merge_workspace_edit() {
+        let uri = Uri::from_str("file:///path/project/index.vue").unwrap();
+        let edit = |line: u32, text: &str| TextEdit {
+            range: Range::new(Position::new(line, 1), Position::new(line, 5)),
+            new_text: text.to_string(),
+        };
+        let mut workspace_edit = WorkspaceEdit::default();
+        super::merge_workspace_edit(
+            &mut workspace_edit,
+            HashMap::from([(uri.clone(), vec![edit(0, "a")])]),
+        );
+        assert_eq!(
+            workspace_edit.changes.as_ref().unwrap()[&uri],
+            vec![edit(0, "a")]
+        );
+        // 跳过范围重叠的编辑
+        let mut workspace_edit = WorkspaceEdit {
+            document_changes: Some(DocumentChanges::Edits(vec![TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier {
+                    uri: uri.clone(),
+                    version: Some(1),
+                },
+                edits: vec![OneOf::Left(edit(0, "a"))],
+            }])),
+            ..Default::default()
+        };
+        super::merge_workspace_edit(
+            &mut workspace_edit,
+            HashMap::from([(uri.clone(), vec![edit(0, "b"), edit(1, "c")])]),
+        );
+        let Some(DocumentChanges::Edits(document_edits)) = workspace_edit.document_changes else {
+            panic!();
+        };
+        assert_eq!(document_edits.len(), 2);
+        assert_eq!(document_edits[1].edits, vec![OneOf::Left(edit(1, "c"))]);
+    }
 }
@@ -4,6 +4,8 @@ use html_languageservice::language_facts::data_provider::{
     HTMLDataProvider, HTMLDataProviderContent, IHTMLDataProvider,
 };
 
+use crate::util;
+
 #[derive(Clone)]
 pub struct VueDataProvider(Arc<HTMLDataProvider>);
 
@@ -38,15 +40,137 @@ impl IHTMLDataProvider for VueDataProvider {
         self.0.provide_attributes(tag, content)
     }
 
+    /// 内置组件也可以使用大驼峰形式，如 `<Transition mode="">`
     fn provide_values(
         &self,
         tag: &str,
         attribute: &str,
     ) -> Vec<&html_languageservice::html_data::IValueData> {
-        self.0.provide_values(tag, attribute)
+        self.0.provide_values(&util::to_kebab_case(tag), attribute)
+    }
+}
+
+/// Vue 内置组件，不需要注册，解析标签和模版诊断时忽略
+pub static BUILTIN_COMPONENTS: &[&str] = &[
+    "component",
+    "transition",
+    "transition-group",
+    "keep-alive",
+    "slot",
+];
+
+/// 标签是否为内置组件，可以是短横线形式或大驼峰形式
+pub fn is_builtin_component(tag: &str) -> bool {
+    BUILTIN_COMPONENTS.contains(&util::to_kebab_case(tag).as_str())
+}
+
+/// 获取光标前指令的可用修饰符及其说明，已使用的修饰符会被排除
+///
+/// 仅当光标位于形如 `@click.` 或 `v-model.lazy.` 的属性名中时返回
+pub fn get_directive_modifiers(
+    source: &str,
+    offset: usize,
+) -> Option<Vec<(&'static str, &'static str)>> {
+    let before = source.get(..offset)?;
+    let start = before
+        .rfind(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '=' | '"' | '\''))
+        .map(|i| i + 1)?;
+    // 属性名应该以空白字符开始
+    if !before[..start].ends_with(char::is_whitespace) {
+        return None;
     }
+    let mut parts = before[start..].split('.');
+    let directive = parts.next()?;
+    let mut used = parts.collect::<Vec<_>>();
+    used.pop()?;
+    let modifiers = if directive.starts_with('@') || directive.starts_with("v-on:") {
+        EVENT_MODIFIERS
+    } else if directive == "v-model" {
+        V_MODEL_MODIFIERS
+    } else if directive.starts_with(':') || directive.starts_with("v-bind:") {
+        V_BIND_MODIFIERS
+    } else {
+        return None;
+    };
+    Some(
+        modifiers
+            .iter()
+            .filter(|(name, _)| !used.contains(name))
+            .copied()
+            .collect(),
+    )
 }
 
+static EVENT_MODIFIERS: &[(&str, &str)] = &[
+    ("stop", "call `event.stopPropagation()`"),
+    ("prevent", "call `event.preventDefault()`"),
+    (
+        "self",
+        "only trigger handler if event was dispatched from this element",
+    ),
+    ("capture", "add event listener in capture mode"),
+    ("once", "trigger handler at most once"),
+    ("passive", "attaches a DOM event with `{ passive: true }`"),
+    (
+        "native",
+        "listen for a native event on the root element of component",
+    ),
+    (
+        "exact",
+        "allows control of the exact combination of system modifiers needed to trigger an event",
+    ),
+    (
+        "ctrl",
+        "only trigger handler when the `Ctrl` key is pressed",
+    ),
+    ("alt", "only trigger handler when the `Alt` key is pressed"),
+    (
+        "shift",
+        "only trigger handler when the `Shift` key is pressed",
+    ),
+    (
+        "meta",
+        "only trigger handler when the `Meta` key is pressed",
+    ),
+    ("enter", "only trigger handler on `Enter` key"),
+    ("tab", "only trigger handler on `Tab` key"),
+    (
+        "delete",
+        "only trigger handler on `Delete` or `Backspace` key",
+    ),
+    ("esc", "only trigger handler on `Esc` key"),
+    ("space", "only trigger handler on `Space` key"),
+    ("up", "only trigger handler on `Up` key"),
+    ("down", "only trigger handler on `Down` key"),
+    (
+        "left",
+        "only trigger handler on `Left` key or left mouse button",
+    ),
+    (
+        "right",
+        "only trigger handler on `Right` key or right mouse button",
+    ),
+    ("middle", "only trigger handler for middle mouse button"),
+];
+
+static V_MODEL_MODIFIERS: &[(&str, &str)] = &[
+    ("lazy", "listen to `change` events instead of `input`"),
+    ("number", "cast valid input string to numbers"),
+    ("trim", "trim input"),
+];
+
+static V_BIND_MODIFIERS: &[(&str, &str)] = &[
+    ("prop", "bind as a DOM property instead of an attribute"),
+    (
+        "camel",
+        "transform the kebab-case attribute name into camelCase",
+    ),
+    (
+        "sync",
+        "a syntax sugar that expands into a `v-on` handler for updating the bound value",
+    ),
+];
+
 static VUE_DATA: &str = r##"{
     "version": 1,
     "tags": [
@@ -85,6 +209,7 @@ static VUE_DATA: &str = r##"{
                 {
                     "name": "appear",
                     "description": "Whether to apply transition on initial render. Default: false",
+                    "valueSet": "v",
                     "references": [
                         {
                             "name": "API Reference",
@@ -105,6 +230,16 @@ static VUE_DATA: &str = r##"{
                 {
                     "name": "type",
                     "description": "The event, \"transition\" or \"animation\", to determine end timing. Default: the type that has a longer duration.",
+                    "values": [
+                        {
+                            "name": "transition",
+                            "description": "Use the `transitionend` event to determine end timing."
+                        },
+                        {
+                            "name": "animation",
+                            "description": "Use the `animationend` event to determine end timing."
+                        }
+                    ],
                     "references": [
                         {
                             "name": "API Reference",
@@ -115,6 +250,16 @@ static VUE_DATA: &str = r##"{
                 {
                     "name": "mode",
                     "description": "Controls the timing sequence of leaving/entering transitions. Available modes are \"out-in\" and \"in-out\"; Defaults to simultaneous.",
+                    "values": [
+                        {
+                            "name": "out-in",
+                            "description": "Current element transitions out first, then when complete, the new element transitions in."
+                        },
+                        {
+                            "name": "in-out",
+                            "description": "New element transitions in first, then when complete, the current element transitions out."
+                        }
+                    ],
                     "references": [
                         {
                             "name": "API Reference",
@@ -173,6 +318,7 @@ static VUE_DATA: &str = r##"{
                 {
                     "name": "appear",
                     "description": "Whether to apply transition on initial render. Default: false",
+                    "valueSet": "v",
                     "references": [
                         {
                             "name": "API Reference",
@@ -193,6 +339,16 @@ static VUE_DATA: &str = r##"{
                 {
                     "name": "type",
                     "description": "The event, \"transition\" or \"animation\", to determine end timing. Default: the type that has a longer duration.",
+                    "values": [
+                        {
+                            "name": "transition",
+                            "description": "Use the `transitionend` event to determine end timing."
+                        },
+                        {
+                            "name": "animation",
+                            "description": "Use the `animationend` event to determine end timing."
+                        }
+                    ],
                     "references": [
                         {
                             "name": "API Reference",
@@ -312,6 +468,7 @@ static VUE_DATA: &str = r##"{
                 {
                     "name": "replace",
                     "description": "Setting replace prop will call `router.replace()` instead of `router.push()` when clicked, so the navigation will not leave a history record.",
+                    "valueSet": "v",
                     "references": [
                         {
                             "name": "API Reference",
@@ -322,6 +479,7 @@ static VUE_DATA: &str = r##"{
                 {
                     "name": "append",
                     "description": "Setting append prop always appends the relative path to the current path. For example, assuming we are navigating from /a to a relative link b, without append we will end up at /b, but with append we will end up at /a/b.",
+                    "valueSet": "v",
                     "references": [
                         {
                             "name": "API Reference",
@@ -352,6 +510,7 @@ static VUE_DATA: &str = r##"{
                 {
                     "name": "exact",
                     "description": "Force the link into \"exact match mode\".",
+                    "valueSet": "v",
                     "references": [
                         {
                             "name": "API Reference",
@@ -382,6 +541,29 @@ static VUE_DATA: &str = r##"{
                 {
                     "name": "aria-current-value",
                     "description": "Configure the value of `aria-current` when the link is active with exact match. It must be one of the [allowed values for `aria-current`](https://www.w3.org/TR/wai-aria-1.2/#aria-current) in the ARIA spec. In most cases, the default of `page` should be the best fit.",
+                    "values": [
+                        {
+                            "name": "page"
+                        },
+                        {
+                            "name": "step"
+                        },
+                        {
+                            "name": "location"
+                        },
+                        {
+                            "name": "date"
+                        },
+                        {
+                            "name": "time"
+                        },
+                        {
+                            "name": "true"
+                        },
+                        {
+                            "name": "false"
+                        }
+                    ],
                     "references": [
                         {
                             "name": "API Reference",
@@ -592,3 +774,72 @@ static VUE_DATA: &str = r##"{
         }
     ]
 }"##;
+
+#[cfg(test)]
+mod tests {
+    use html_languageservice::language_facts::data_provider::IHTMLDataProvider;
+
+    fn get_value_names(tag: &str, attribute: &str) -> Vec<String> {
+        super::VueDataProvider::new()
+            .provide_values(tag, attribute)
+            .into_iter()
+            .map(|value| value.name.clone())
+            .collect()
+    }
+    fn get_modifier_names(source: &str) -> Option<Vec<&'static str>> {
+        super::get_directive_modifiers(source, source.len())
+            .map(|modifiers| modifiers.into_iter().map(|(name, _)| name).collect())
+    }
+
+    #[test]
+    fn directive_modifiers() {
+        assert_eq!(
+            get_modifier_names(r#"<input v-model."#),
+            Some(vec!["lazy", "number", "trim"])
+        );
+        assert_eq!(
+            get_modifier_names(r#"<input v-model.trim."#),
+            Some(vec!["lazy", "number"])
+        );
+        assert!(get_modifier_names(r#"<div @click."#)
+            .unwrap()
+            .contains(&"stop"));
+        assert!(get_modifier_names(r#"<div v-on:click.stop."#)
+            .is_some_and(|names| !names.contains(&"stop") && names.contains(&"prevent")));
+        assert_eq!(
+            get_modifier_names(r#"<my-comp :title."#),
+            Some(vec!["prop", "camel", "sync"])
+        );
+        // 不是修饰符的位置
+        assert_eq!(get_modifier_names(r#"<div @click"#), None);
+        assert_eq!(get_modifier_names(r#"<div title="a."#), None);
+        assert_eq!(get_modifier_names(r#"<div v-if."#), None);
+    }
+
+    #[test]
+    fn builtin_components() {
+        assert!(super::is_builtin_component("keep-alive"));
+        assert!(super::is_builtin_component("KeepAlive"));
+        assert!(super::is_builtin_component("transition-group"));
+        assert!(!super::is_builtin_component("my-component"));
+    }
+
+    #[test]
+    fn builtin_attribute_values() {
+        assert_eq!(
+            get_value_names("transition", "mode"),
+            vec!["out-in", "in-out"]
+        );
+        assert_eq!(
+            get_value_names("Transition", "mode"),
+            vec!["out-in", "in-out"]
+        );
+        assert_eq!(
+            get_value_names("transition-group", "type"),
+            vec!["transition", "animation"]
+        );
+        assert!(get_value_names("transition-group", "mode").is_empty());
+        assert!(get_value_names("router-link", "aria-current-value").contains(&"page".to_string()));
+        assert!(get_value_names("router-link", "replace").is_empty());
+    }
+}